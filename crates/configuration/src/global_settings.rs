@@ -7,22 +7,24 @@ use std::{
 };
 
 use multiaddr::Multiaddr;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 use crate::{
     shared::{
-        errors::{ConfigError, FieldError},
-        helpers::{merge_errors, merge_errors_vecs},
-        types::Duration,
+        errors::{ConfigError, FieldError, ValidationError},
+        helpers::{merge_errors, merge_errors_vecs, merge_unique},
+        types::{Duration, Image, Port},
     },
     utils::{default_node_spawn_timeout, default_timeout},
 };
 
 /// Global settings applied to an entire network.
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
 pub struct GlobalSettings {
     /// Global bootnodes to use (we will then add more)
     #[serde(skip_serializing_if = "std::vec::Vec::is_empty", default)]
+    #[schemars(with = "Vec<String>")]
     bootnodes_addresses: Vec<Multiaddr>,
     // TODO: parse both case in zombienet node version to avoid renamed ?
     /// Global spawn timeout
@@ -39,6 +41,108 @@ pub struct GlobalSettings {
     /// Used to reuse the same files (database) from a previous run,
     /// also note that we will override the content of some of those files.
     base_dir: Option<PathBuf>,
+    /// Range of ports to use when auto-assigning the nodes ports
+    /// (instead of random ones).
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    port_range: Option<PortRange>,
+    /// Max amount of concurrent spawning tasks (building the parachains artifacts and
+    /// spawning the nodes)
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    spawn_concurrency: Option<usize>,
+    /// Policy the images references should follow (e.g. pinned by digest)
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    image_policy: Option<ImagePolicy>,
+    /// Scheme to use in the nodes endpoints (e.g. `wss` when fronted by a TLS proxy)
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    rpc_scheme: Option<RpcScheme>,
+    /// Path prefix to use in the nodes endpoints (e.g. when fronted by an ingress)
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    rpc_path_prefix: Option<String>,
+    /// Image to use for all the remote chain-spec generation (instead of the chains images)
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    spec_builder_image: Option<Image>,
+    /// Command template to use for all the chain-spec generation (instead of the chains ones)
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    spec_builder_command: Option<String>,
+    /// Max size (in bytes) of the nodes logs before rotating them (native provider)
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    log_max_bytes: Option<u64>,
+    /// Amount of rotated log files to keep per node (native provider)
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    log_max_files: Option<usize>,
+    /// Prefix of the namespace name (e.g. a team or ci run id), instead of `zombie`
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    namespace_prefix: Option<String>,
+    /// Grace period (in seconds) between `SIGTERM` and `SIGKILL` when stopping the nodes (native provider)
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    stop_grace: Option<Duration>,
+}
+
+/// Amount of rotated log files kept per node, if not set.
+const DEFAULT_LOG_MAX_FILES: usize = 5;
+
+/// Max length of the namespace prefix, the name (`<prefix>-<uuid>`) should fit in a DNS label (63 chars).
+const NAMESPACE_PREFIX_MAX_LEN: usize = 26;
+
+/// The scheme used to reach the nodes endpoints.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum RpcScheme {
+    /// Plain connections (`ws://` for rpc and `http://` for metrics).
+    #[default]
+    Ws,
+    /// Secure connections (`wss://` for rpc and `https://` for metrics).
+    Wss,
+}
+
+impl RpcScheme {
+    /// Scheme to use for the rpc (websocket) endpoint.
+    pub fn ws_scheme(&self) -> &'static str {
+        match self {
+            RpcScheme::Ws => "ws",
+            RpcScheme::Wss => "wss",
+        }
+    }
+
+    /// Scheme to use for the metrics (http) endpoint.
+    pub fn http_scheme(&self) -> &'static str {
+        match self {
+            RpcScheme::Ws => "http",
+            RpcScheme::Wss => "https",
+        }
+    }
+}
+
+/// The policy the images references (`default_image` / node `image`) should follow.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ImagePolicy {
+    /// Images can be referenced by tag (e.g. `docker.io/parity/polkadot:latest`).
+    #[default]
+    AllowTags,
+    /// Images should be pinned by digest (e.g. `docker.io/parity/polkadot@sha256:<digest>`).
+    RequireDigest,
+}
+
+/// A range of ports `[base, base + stride)`.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct PortRange {
+    #[schemars(range(min = 1, max = 65535))]
+    base: Port,
+    #[schemars(range(min = 1, max = 65535))]
+    stride: u16,
+}
+
+impl PortRange {
+    /// First port of the range.
+    pub fn base(&self) -> Port {
+        self.base
+    }
+
+    /// Amount of ports in the range.
+    pub fn stride(&self) -> u16 {
+        self.stride
+    }
 }
 
 impl GlobalSettings {
@@ -67,6 +171,96 @@ impl GlobalSettings {
     pub fn base_dir(&self) -> Option<&Path> {
         self.base_dir.as_deref()
     }
+
+    /// Range of ports to use when auto-assigning the nodes ports.
+    pub fn port_range(&self) -> Option<&PortRange> {
+        self.port_range.as_ref()
+    }
+
+    /// Max amount of concurrent spawning tasks.
+    pub fn spawn_concurrency(&self) -> Option<usize> {
+        self.spawn_concurrency
+    }
+
+    /// Policy the images references should follow (default to [`ImagePolicy::AllowTags`]).
+    pub fn image_policy(&self) -> ImagePolicy {
+        self.image_policy.unwrap_or_default()
+    }
+
+    /// Scheme to use in the nodes endpoints (default to [`RpcScheme::Ws`]).
+    pub fn rpc_scheme(&self) -> RpcScheme {
+        self.rpc_scheme.unwrap_or_default()
+    }
+
+    /// Path prefix (e.g. `/my-network`) to use in the nodes endpoints.
+    pub fn rpc_path_prefix(&self) -> Option<&str> {
+        self.rpc_path_prefix.as_deref()
+    }
+
+    /// Image to use for all the remote chain-spec generation, overriding the chains images.
+    pub fn spec_builder_image(&self) -> Option<&Image> {
+        self.spec_builder_image.as_ref()
+    }
+
+    /// Command template to use for all the chain-spec generation, overriding the chains commands.
+    pub fn spec_builder_command(&self) -> Option<&str> {
+        self.spec_builder_command.as_deref()
+    }
+
+    /// Max size (in bytes) of the nodes logs before rotating them, `None` disables the rotation.
+    pub fn log_max_bytes(&self) -> Option<u64> {
+        self.log_max_bytes
+    }
+
+    /// Amount of rotated log files to keep per node (default to 5).
+    pub fn log_max_files(&self) -> usize {
+        self.log_max_files.unwrap_or(DEFAULT_LOG_MAX_FILES)
+    }
+
+    /// Prefix of the namespace name (`<prefix>-<random>`).
+    pub fn namespace_prefix(&self) -> Option<&str> {
+        self.namespace_prefix.as_deref()
+    }
+
+    /// Grace period in seconds to let the nodes exit after `SIGTERM` when stopping them,
+    /// `None` kills them right away.
+    pub fn stop_grace(&self) -> Option<Duration> {
+        self.stop_grace
+    }
+
+    /// Merge the `overlay` settings into these ones, the `overlay` values win
+    /// unless they are unset (or hold the default value).
+    pub(crate) fn merge(self, overlay: GlobalSettings) -> Self {
+        Self {
+            bootnodes_addresses: merge_unique(
+                self.bootnodes_addresses,
+                overlay.bootnodes_addresses,
+            ),
+            network_spawn_timeout: if overlay.network_spawn_timeout == default_timeout() {
+                self.network_spawn_timeout
+            } else {
+                overlay.network_spawn_timeout
+            },
+            node_spawn_timeout: if overlay.node_spawn_timeout == default_node_spawn_timeout() {
+                self.node_spawn_timeout
+            } else {
+                overlay.node_spawn_timeout
+            },
+            local_ip: overlay.local_ip.or(self.local_ip),
+            base_dir: overlay.base_dir.or(self.base_dir),
+            port_range: overlay.port_range.or(self.port_range),
+            spawn_concurrency: overlay.spawn_concurrency.or(self.spawn_concurrency),
+            image_policy: overlay.image_policy.or(self.image_policy),
+            rpc_scheme: overlay.rpc_scheme.or(self.rpc_scheme),
+            rpc_path_prefix: overlay.rpc_path_prefix.or(self.rpc_path_prefix),
+            spec_builder_image: overlay.spec_builder_image.or(self.spec_builder_image),
+            spec_builder_command: overlay.spec_builder_command.or(self.spec_builder_command),
+            log_max_bytes: overlay.log_max_bytes.or(self.log_max_bytes),
+            log_max_files: overlay.log_max_files.or(self.log_max_files),
+            namespace_prefix: overlay.namespace_prefix.or(self.namespace_prefix),
+            stop_grace: overlay.stop_grace.or(self.stop_grace),
+        }
+    }
 }
 
 impl Default for GlobalSettings {
@@ -77,10 +271,44 @@ impl Default for GlobalSettings {
             node_spawn_timeout: default_node_spawn_timeout(),
             local_ip: Default::default(),
             base_dir: Default::default(),
+            port_range: Default::default(),
+            spawn_concurrency: Default::default(),
+            image_policy: Default::default(),
+            rpc_scheme: Default::default(),
+            rpc_path_prefix: Default::default(),
+            spec_builder_image: Default::default(),
+            spec_builder_command: Default::default(),
+            log_max_bytes: Default::default(),
+            log_max_files: Default::default(),
+            namespace_prefix: Default::default(),
+            stop_grace: Default::default(),
         }
     }
 }
 
+// The namespace prefix should be a valid DNS label (as required by k8s), leaving room for
+// the random suffix.
+pub(crate) fn ensure_namespace_prefix(namespace_prefix: &str) -> Result<(), anyhow::Error> {
+    let is_alphanumeric = |c: char| c.is_ascii_lowercase() || c.is_ascii_digit();
+    let is_valid = !namespace_prefix.is_empty()
+        && namespace_prefix.len() <= NAMESPACE_PREFIX_MAX_LEN
+        && namespace_prefix
+            .chars()
+            .all(|c| is_alphanumeric(c) || c == '-')
+        && namespace_prefix.starts_with(is_alphanumeric)
+        && namespace_prefix.ends_with(is_alphanumeric);
+
+    if !is_valid {
+        return Err(ValidationError::InvalidDnsLabel(
+            namespace_prefix.to_string(),
+            NAMESPACE_PREFIX_MAX_LEN,
+        )
+        .into());
+    }
+
+    Ok(())
+}
+
 /// A global settings builder, used to build [`GlobalSettings`] declaratively with fields validation.
 pub struct GlobalSettingsBuilder {
     config: GlobalSettings,
@@ -96,6 +324,17 @@ impl Default for GlobalSettingsBuilder {
                 node_spawn_timeout: default_node_spawn_timeout(),
                 local_ip: None,
                 base_dir: None,
+                port_range: None,
+                spawn_concurrency: None,
+                image_policy: None,
+                rpc_scheme: None,
+                rpc_path_prefix: None,
+                spec_builder_image: None,
+                spec_builder_command: None,
+                log_max_bytes: None,
+                log_max_files: None,
+                namespace_prefix: None,
+                stop_grace: None,
             },
             errors: vec![],
         }
@@ -189,6 +428,214 @@ impl GlobalSettingsBuilder {
         )
     }
 
+    /// Set the range of ports `[base, base + stride)` used to auto-assign the nodes ports
+    /// (sequentially, in the order nodes are defined) instead of random ones.
+    pub fn with_port_range(self, base: Port, stride: u16) -> Self {
+        let error = if stride == 0 {
+            Some(ValidationError::CantBeZero())
+        } else if base as u32 + stride as u32 > u16::MAX as u32 + 1 {
+            Some(ValidationError::PortRangeOverflow(base, stride))
+        } else {
+            None
+        };
+
+        match error {
+            Some(error) => Self::transition(
+                self.config,
+                merge_errors(self.errors, FieldError::PortRange(error.into()).into()),
+            ),
+            None => Self::transition(
+                GlobalSettings {
+                    port_range: Some(PortRange { base, stride }),
+                    ..self.config
+                },
+                self.errors,
+            ),
+        }
+    }
+
+    /// Set the max amount of concurrent spawning tasks.
+    /// NOTE: values above the provider max (`ProviderCapabilities::max_spawn_concurrency`)
+    /// are clamped to that max (with a warning) when the network is spawned.
+    pub fn with_spawn_concurrency(self, spawn_concurrency: usize) -> Self {
+        if spawn_concurrency == 0 {
+            Self::transition(
+                self.config,
+                merge_errors(
+                    self.errors,
+                    FieldError::SpawnConcurrency(ValidationError::CantBeZero().into()).into(),
+                ),
+            )
+        } else {
+            Self::transition(
+                GlobalSettings {
+                    spawn_concurrency: Some(spawn_concurrency),
+                    ..self.config
+                },
+                self.errors,
+            )
+        }
+    }
+
+    /// Set the policy the images references should follow, enforced when building the network config.
+    pub fn with_image_policy(self, image_policy: ImagePolicy) -> Self {
+        Self::transition(
+            GlobalSettings {
+                image_policy: Some(image_policy),
+                ..self.config
+            },
+            self.errors,
+        )
+    }
+
+    /// Set the scheme to use in the nodes endpoints (e.g. `wss` when fronted by a TLS proxy).
+    pub fn with_rpc_scheme(self, rpc_scheme: RpcScheme) -> Self {
+        Self::transition(
+            GlobalSettings {
+                rpc_scheme: Some(rpc_scheme),
+                ..self.config
+            },
+            self.errors,
+        )
+    }
+
+    /// Set the path prefix to use in the nodes endpoints (e.g. when fronted by an ingress).
+    /// Leading and trailing slashes are normalized, so `my-network/` is used as `/my-network`.
+    pub fn with_rpc_path_prefix(self, rpc_path_prefix: impl Into<String>) -> Self {
+        let rpc_path_prefix: String = rpc_path_prefix.into();
+        let trimmed = rpc_path_prefix.trim().trim_matches('/');
+
+        Self::transition(
+            GlobalSettings {
+                rpc_path_prefix: (!trimmed.is_empty()).then(|| format!("/{trimmed}")),
+                ..self.config
+            },
+            self.errors,
+        )
+    }
+
+    /// Set the image to use for all the remote chain-spec generation (e.g. a pinned
+    /// `chain-spec-builder` image), regardless of the image used to run the nodes.
+    pub fn with_spec_builder_image<T>(self, image: T) -> Self
+    where
+        T: TryInto<Image>,
+        T::Error: Error + Send + Sync + 'static,
+    {
+        match image.try_into() {
+            Ok(image) => Self::transition(
+                GlobalSettings {
+                    spec_builder_image: Some(image),
+                    ..self.config
+                },
+                self.errors,
+            ),
+            Err(error) => Self::transition(
+                self.config,
+                merge_errors(
+                    self.errors,
+                    FieldError::SpecBuilderImage(error.into()).into(),
+                ),
+            ),
+        }
+    }
+
+    /// Set the command template to use for all the chain-spec generation (e.g. a pinned
+    /// `chain-spec-builder`), regardless of the chains commands. The `{{chainName}}` and
+    /// `{{disableBootnodes}}` tokens are replaced, `{{mainCommand}}` is not supported.
+    pub fn with_spec_builder_command(self, command: impl Into<String>) -> Self {
+        Self::transition(
+            GlobalSettings {
+                spec_builder_command: Some(command.into()),
+                ..self.config
+            },
+            self.errors,
+        )
+    }
+
+    /// Set the max size (in bytes) of the nodes logs, once exceeded the log is rotated
+    /// (`<node>.log` is copied to `<node>.log.1` and truncated, keeping the last
+    /// [`Self::with_log_max_files`] files).
+    ///
+    /// NOTE: only supported by the native provider, the docker/k8s providers delegate
+    /// the logs rotation to the container runtime. The node keeps writing to its open log,
+    /// so the lines written between the copy and the truncation can be lost.
+    pub fn with_log_max_bytes(self, log_max_bytes: u64) -> Self {
+        if log_max_bytes == 0 {
+            Self::transition(
+                self.config,
+                merge_errors(
+                    self.errors,
+                    FieldError::LogMaxBytes(ValidationError::CantBeZero().into()).into(),
+                ),
+            )
+        } else {
+            Self::transition(
+                GlobalSettings {
+                    log_max_bytes: Some(log_max_bytes),
+                    ..self.config
+                },
+                self.errors,
+            )
+        }
+    }
+
+    /// Set the amount of rotated log files to keep per node (`0` only keeps the current log).
+    pub fn with_log_max_files(self, log_max_files: usize) -> Self {
+        Self::transition(
+            GlobalSettings {
+                log_max_files: Some(log_max_files),
+                ..self.config
+            },
+            self.errors,
+        )
+    }
+
+    /// Set the grace period (in seconds) to let the nodes exit cleanly (e.g. flushing their db)
+    /// when they are stopped (`pause`/`restart`/`destroy`). The nodes get a `SIGTERM` and are
+    /// only killed if they are still running after it.
+    ///
+    /// NOTE: only supported by the native provider.
+    pub fn with_stop_grace(self, stop_grace: Duration) -> Self {
+        if stop_grace == 0 {
+            Self::transition(
+                self.config,
+                merge_errors(
+                    self.errors,
+                    FieldError::StopGrace(ValidationError::CantBeZero().into()).into(),
+                ),
+            )
+        } else {
+            Self::transition(
+                GlobalSettings {
+                    stop_grace: Some(stop_grace),
+                    ..self.config
+                },
+                self.errors,
+            )
+        }
+    }
+
+    /// Set the prefix of the namespace name (e.g. a team or ci run id), the namespace is
+    /// then named `<prefix>-<random>` by all the providers. The prefix should be a valid
+    /// DNS label (as required by k8s) of at most 26 chars.
+    pub fn with_namespace_prefix(self, namespace_prefix: impl Into<String>) -> Self {
+        let namespace_prefix: String = namespace_prefix.into();
+
+        match ensure_namespace_prefix(&namespace_prefix) {
+            Ok(_) => Self::transition(
+                GlobalSettings {
+                    namespace_prefix: Some(namespace_prefix),
+                    ..self.config
+                },
+                self.errors,
+            ),
+            Err(error) => Self::transition(
+                self.config,
+                merge_errors(self.errors, FieldError::NamespacePrefix(error).into()),
+            ),
+        }
+    }
+
     /// Seals the builder and returns a [`GlobalSettings`] if there are no validation errors, else returns errors.
     pub fn build(self) -> Result<GlobalSettings, Vec<anyhow::Error>> {
         if !self.errors.is_empty() {
@@ -245,6 +692,70 @@ mod tests {
         );
     }
 
+    #[test]
+    fn global_settings_config_builder_with_rpc_scheme_and_path_prefix_should_succeeds() {
+        let global_settings_config = GlobalSettingsBuilder::new()
+            .with_rpc_scheme(RpcScheme::Wss)
+            .with_rpc_path_prefix("my-network/")
+            .build()
+            .unwrap();
+
+        assert_eq!(global_settings_config.rpc_scheme(), RpcScheme::Wss);
+        assert_eq!(global_settings_config.rpc_scheme().http_scheme(), "https");
+        assert_eq!(
+            global_settings_config.rpc_path_prefix(),
+            Some("/my-network")
+        );
+        assert_eq!(
+            GlobalSettingsBuilder::new().build().unwrap().rpc_scheme(),
+            RpcScheme::Ws
+        );
+    }
+
+    #[test]
+    fn global_settings_config_builder_with_spec_builder_image_should_succeeds() {
+        let global_settings_config = GlobalSettingsBuilder::new()
+            .with_spec_builder_image("docker.io/parity/chain-spec-builder:latest")
+            .build()
+            .unwrap();
+
+        assert_eq!(
+            global_settings_config
+                .spec_builder_image()
+                .unwrap()
+                .as_str(),
+            "docker.io/parity/chain-spec-builder:latest"
+        );
+    }
+
+    #[test]
+    fn global_settings_config_builder_with_spec_builder_command_should_succeeds() {
+        let global_settings_config = GlobalSettingsBuilder::new()
+            .with_spec_builder_command("chain-spec-builder create --chain {{chainName}}")
+            .build()
+            .unwrap();
+
+        assert_eq!(
+            global_settings_config.spec_builder_command(),
+            Some("chain-spec-builder create --chain {{chainName}}")
+        );
+    }
+
+    #[test]
+    fn global_settings_config_builder_should_fails_and_returns_an_error_if_spec_builder_image_is_invalid(
+    ) {
+        let errors = GlobalSettingsBuilder::new()
+            .with_spec_builder_image("invalid.image")
+            .build()
+            .unwrap_err();
+
+        assert_eq!(errors.len(), 1);
+        assert_eq!(
+            errors.first().unwrap().to_string(),
+            "global_settings.spec_builder_image: 'invalid.image' doesn't match regex '^([ip]|[hostname]/)?[tag_name]:[tag_version]?$'"
+        );
+    }
+
     #[test]
     fn global_settings_config_builder_should_succeeds_when_node_spawn_timeout_is_missing() {
         let global_settings_config = GlobalSettingsBuilder::new()
@@ -311,6 +822,32 @@ mod tests {
         );
     }
 
+    #[test]
+    fn global_settings_config_builder_should_succeeds_with_ipv6_local_ip_and_bootnodes() {
+        let global_settings_config = GlobalSettingsBuilder::new()
+            .with_bootnodes_addresses(vec![
+                "/ip6/fd00::10/tcp/30333/ws",
+                "/ip4/51.144.222.10/tcp/2333",
+            ])
+            .with_local_ip("fd00::1")
+            .build()
+            .unwrap();
+
+        let bootnodes_addresses: Vec<Multiaddr> = vec![
+            "/ip6/fd00::10/tcp/30333/ws".try_into().unwrap(),
+            "/ip4/51.144.222.10/tcp/2333".try_into().unwrap(),
+        ];
+        assert_eq!(
+            global_settings_config.bootnodes_addresses(),
+            bootnodes_addresses.iter().collect::<Vec<_>>()
+        );
+        assert!(global_settings_config.local_ip().unwrap().is_ipv6());
+        assert_eq!(
+            global_settings_config.local_ip().unwrap().to_string(),
+            "fd00::1"
+        );
+    }
+
     #[test]
     fn global_settings_builder_should_fails_and_returns_an_error_if_local_ip_is_invalid() {
         let errors = GlobalSettingsBuilder::new()
@@ -348,4 +885,134 @@ mod tests {
             "global_settings.local_ip: invalid IP address syntax"
         );
     }
+
+    #[test]
+    fn global_settings_builder_should_succeeds_with_port_range() {
+        let global_settings_config = GlobalSettingsBuilder::new()
+            .with_port_range(30000, 100)
+            .build()
+            .unwrap();
+
+        let port_range = global_settings_config.port_range().unwrap();
+        assert_eq!(port_range.base(), 30000);
+        assert_eq!(port_range.stride(), 100);
+    }
+
+    #[test]
+    fn global_settings_builder_should_fails_and_returns_an_error_if_spawn_concurrency_is_zero() {
+        let errors = GlobalSettingsBuilder::new()
+            .with_spawn_concurrency(0)
+            .build()
+            .unwrap_err();
+
+        assert_eq!(errors.len(), 1);
+        assert_eq!(
+            errors.first().unwrap().to_string(),
+            "global_settings.spawn_concurrency: can't be zero"
+        );
+    }
+
+    #[test]
+    fn global_settings_builder_should_succeeds_with_log_rotation() {
+        let global_settings_config = GlobalSettingsBuilder::new()
+            .with_log_max_bytes(10 * 1024 * 1024)
+            .with_log_max_files(2)
+            .build()
+            .unwrap();
+
+        assert_eq!(
+            global_settings_config.log_max_bytes(),
+            Some(10 * 1024 * 1024)
+        );
+        assert_eq!(global_settings_config.log_max_files(), 2);
+
+        let global_settings_config = GlobalSettingsBuilder::new().build().unwrap();
+        assert_eq!(global_settings_config.log_max_bytes(), None);
+        assert_eq!(global_settings_config.log_max_files(), 5);
+    }
+
+    #[test]
+    fn global_settings_builder_should_fails_and_returns_an_error_if_log_max_bytes_is_zero() {
+        let errors = GlobalSettingsBuilder::new()
+            .with_log_max_bytes(0)
+            .build()
+            .unwrap_err();
+
+        assert_eq!(errors.len(), 1);
+        assert_eq!(
+            errors.first().unwrap().to_string(),
+            "global_settings.log_max_bytes: can't be zero"
+        );
+    }
+
+    #[test]
+    fn global_settings_builder_should_succeeds_with_stop_grace() {
+        let global_settings = GlobalSettingsBuilder::new()
+            .with_stop_grace(10)
+            .build()
+            .unwrap();
+
+        assert_eq!(global_settings.stop_grace(), Some(10));
+
+        let errors = GlobalSettingsBuilder::new()
+            .with_stop_grace(0)
+            .build()
+            .unwrap_err();
+
+        assert_eq!(
+            errors.first().unwrap().to_string(),
+            "global_settings.stop_grace: can't be zero"
+        );
+    }
+
+    #[test]
+    fn global_settings_builder_should_succeeds_with_namespace_prefix() {
+        let global_settings = GlobalSettingsBuilder::new()
+            .with_namespace_prefix("ci-run-1234")
+            .build()
+            .unwrap();
+
+        assert_eq!(global_settings.namespace_prefix(), Some("ci-run-1234"));
+    }
+
+    #[test]
+    fn global_settings_builder_should_fails_and_returns_an_error_if_namespace_prefix_is_invalid() {
+        for prefix in ["", "My-Team", "team_a", "-team", "team-", &"a".repeat(27)] {
+            let errors = GlobalSettingsBuilder::new()
+                .with_namespace_prefix(prefix)
+                .build()
+                .unwrap_err();
+
+            assert_eq!(errors.len(), 1);
+            assert_eq!(
+                errors.first().unwrap().to_string(),
+                format!("global_settings.namespace_prefix: '{prefix}' should be a DNS label of at most 26 chars (lowercase alphanumerics or '-', starting and ending with an alphanumeric)")
+            );
+        }
+    }
+
+    #[test]
+    fn global_settings_builder_should_fails_and_returns_an_error_if_port_range_is_invalid() {
+        let errors = GlobalSettingsBuilder::new()
+            .with_port_range(30000, 0)
+            .build()
+            .unwrap_err();
+
+        assert_eq!(errors.len(), 1);
+        assert_eq!(
+            errors.first().unwrap().to_string(),
+            "global_settings.port_range: can't be zero"
+        );
+
+        let errors = GlobalSettingsBuilder::new()
+            .with_port_range(65500, 100)
+            .build()
+            .unwrap_err();
+
+        assert_eq!(errors.len(), 1);
+        assert_eq!(
+            errors.first().unwrap().to_string(),
+            "global_settings.port_range: [65500, 65500 + 100) exceeds the max port"
+        );
+    }
 }
@@ -1,13 +1,16 @@
 use std::marker::PhantomData;
 
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 use crate::shared::{macros::states, types::ParaId};
 
 /// HRMP channel configuration, with fine-grained configuration options.
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
 pub struct HrmpChannelConfig {
+    #[schemars(range(min = 1))]
     sender: ParaId,
+    #[schemars(range(min = 1))]
     recipient: ParaId,
     max_capacity: u32,
     max_message_size: u32,
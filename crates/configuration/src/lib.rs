@@ -55,15 +55,15 @@
 //!     })
 //!     .with_hrmp_channel(|hrmp_channel1| {
 //!         hrmp_channel1
-//!             .with_sender(1)
-//!             .with_recipient(2)
+//!             .with_sender(1000)
+//!             .with_recipient(2000)
 //!             .with_max_capacity(200)
 //!             .with_max_message_size(500)
 //!     })
 //!     .with_hrmp_channel(|hrmp_channel2| {
 //!         hrmp_channel2
-//!             .with_sender(2)
-//!             .with_recipient(1)
+//!             .with_sender(2000)
+//!             .with_recipient(1000)
 //!             .with_max_capacity(100)
 //!             .with_max_message_size(250)
 //!     })
@@ -86,7 +86,9 @@ mod relaychain;
 pub mod shared;
 mod utils;
 
-pub use global_settings::{GlobalSettings, GlobalSettingsBuilder};
+pub use global_settings::{
+    GlobalSettings, GlobalSettingsBuilder, ImagePolicy, PortRange, RpcScheme,
+};
 pub use hrmp_channel::{HrmpChannelConfig, HrmpChannelConfigBuilder};
 pub use network::{NetworkConfig, NetworkConfigBuilder};
 pub use parachain::{
@@ -1,29 +1,42 @@
-use std::{cell::RefCell, fs, marker::PhantomData, rc::Rc};
+use std::{
+    cell::RefCell,
+    fs,
+    marker::PhantomData,
+    path::{Path, PathBuf},
+    rc::Rc,
+};
 
 use anyhow::anyhow;
 use regex::Regex;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use support::constants::{
-    NO_ERR_DEF_BUILDER, RELAY_NOT_NONE, RW_FAILED, THIS_IS_A_BUG, VALIDATION_CHECK, VALID_REGEX,
+    NO_ERR_DEF_BUILDER, RELAY_NOT_NONE, SCHEMA_SHOULD_SERIALIZE, THIS_IS_A_BUG, VALIDATION_CHECK,
+    VALID_REGEX,
 };
-use tracing::trace;
+use tracing::{trace, warn};
 
 use crate::{
-    global_settings::{GlobalSettings, GlobalSettingsBuilder},
+    global_settings::{
+        ensure_namespace_prefix, GlobalSettings, GlobalSettingsBuilder, ImagePolicy,
+    },
     hrmp_channel::{self, HrmpChannelConfig, HrmpChannelConfigBuilder},
     parachain::{self, ParachainConfig, ParachainConfigBuilder},
     relaychain::{self, RelaychainConfig, RelaychainConfigBuilder},
     shared::{
-        errors::{ConfigError, ValidationError},
-        helpers::{merge_errors, merge_errors_vecs},
+        errors::{ConfigError, FieldError, MergeError, ValidationError},
+        helpers::{
+            ensure_genesis_raw_entries, ensure_node_name_unique, ensure_port_unique, merge_by_key,
+            merge_errors, merge_errors_vecs, override_args, zombie_token_references,
+        },
         macros::states,
-        node::NodeConfig,
-        types::{Arg, AssetLocation, Chain, Command, Image, ValidationContext},
+        node::{ensure_node_fields, NodeConfig},
+        types::{Arg, AssetLocation, Chain, Command, Image, ParaId, ValidationContext},
     },
 };
 
 /// A network configuration, composed of a relaychain, parachains and HRMP channels.
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
 pub struct NetworkConfig {
     #[serde(rename = "settings", default = "GlobalSettings::default")]
     global_settings: GlobalSettings,
@@ -67,15 +80,27 @@ impl NetworkConfig {
         Ok(re.replace_all(&toml_string, "$u128_value").to_string())
     }
 
-    /// A helper function to load a network configuration from a TOML file.
-    pub fn load_from_toml(path: &str) -> Result<NetworkConfig, anyhow::Error> {
-        let file_str = fs::read_to_string(path).expect(&format!("{} {}", RW_FAILED, THIS_IS_A_BUG));
-        let re: Regex = Regex::new(r"(?<field_name>(initial_)?balance)\s+=\s+(?<u128_value>\d+)")
+    /// The JSON schema of the network configuration (`toml` definitions), e.g. to validate
+    /// the definitions or provide completions in an editor.
+    pub fn json_schema() -> serde_json::Value {
+        // Same as in `dump_to_toml`, the u128 defaults should be raw integers.
+        let re = Regex::new(r#""U128%(?<u128_value>\d+)""#)
             .expect(&format!("{} {}", VALID_REGEX, THIS_IS_A_BUG));
+        let schema = serde_json::to_string(&schemars::schema_for!(NetworkConfig))
+            .expect(&format!("{} {}", SCHEMA_SHOULD_SERIALIZE, THIS_IS_A_BUG));
+
+        serde_json::from_str(&re.replace_all(&schema, "$u128_value"))
+            .expect(&format!("{} {}", SCHEMA_SHOULD_SERIALIZE, THIS_IS_A_BUG))
+    }
 
-        let toml_text = re.replace_all(&file_str, "$field_name = \"$u128_value\"");
-        trace!("toml text to parse: {}", toml_text);
-        let mut network_config: NetworkConfig = toml::from_str(&toml_text)?;
+    /// A helper function to load a network configuration from a TOML file.
+    ///
+    /// The file can extend a base configuration with a top-level `extends = "<path>"` key,
+    /// the base is loaded first and this configuration is layered on top of it (following
+    /// the [`NetworkConfig::merge`] rules). Relative paths are resolved against the directory
+    /// of the extending file and `extends` cycles are rejected.
+    pub fn load_from_toml(path: &str) -> Result<NetworkConfig, anyhow::Error> {
+        let mut network_config = load_toml_with_extends(Path::new(path), &mut vec![])?;
         trace!("parsed config {network_config:#?}");
 
         // All unwraps below are safe, because we ensure that the relaychain is not None at this point
@@ -140,9 +165,7 @@ impl NetworkConfig {
                 node.db_snapshot.clone_from(&relaychain_default_db_snapshot);
             }
 
-            if !default_args.is_empty() && node.args().is_empty() {
-                node.set_args(default_args.clone());
-            }
+            apply_default_args(node, &default_args);
         }
 
         for para in parachains.iter_mut() {
@@ -176,14 +199,14 @@ impl NetworkConfig {
                         .clone_from(&parachain_default_db_snapshot);
                 }
 
-                if !default_args.is_empty() && collator.args().is_empty() {
-                    collator.set_args(default_args.clone());
-                }
+                apply_default_args(collator, &default_args);
             }
 
-            para.collators = collators;
+            para.set_collators(collators);
         }
 
+        network_config.parachains = parachains;
+
         network_config
             .relaychain
             .as_mut()
@@ -200,8 +223,774 @@ impl NetworkConfig {
             }
         });
 
+        ensure_checks(common_checks(&network_config))?;
+
         Ok(network_config)
     }
+
+    /// Validate an already loaded network configuration (e.g. from a `toml` file), without
+    /// spawning anything. Runs the checks of the builders (chains, images and commands values,
+    /// fields like the genesis raw entries, relay args, namespace prefix, keystore keys,
+    /// injected files, listen addresses, rpc methods and syncing mode, unique nodes names,
+    /// ports and parachains ids, image policy, hrmp channels endpoints and relay rpc nodes)
+    /// and ensures the `{{ZOMBIE:<node>:<field>}}` tokens in the nodes args/env reference
+    /// nodes of the config. All the errors are returned at once.
+    ///
+    /// [`NetworkConfig::load_from_toml`] already runs the image policy, hrmp channels
+    /// endpoints, relay rpc nodes and tokens checks (failing on the first one with errors),
+    /// the other checks are only run here.
+    ///
+    /// # Example:
+    ///
+    /// ```
+    /// use zombienet_configuration::NetworkConfig;
+    ///
+    /// let network_config =
+    ///     NetworkConfig::load_from_toml("./testing/snapshots/0000-small-network.toml").unwrap();
+    ///
+    /// assert!(network_config.validate().is_ok());
+    /// ```
+    pub fn validate(&self) -> Result<(), Vec<anyhow::Error>> {
+        if self.relaychain.is_none() {
+            return Err(vec![anyhow!("Relay chain does not exist.")]);
+        }
+
+        let errors = [
+            ensure_values(self),
+            ensure_fields(self),
+            ensure_unique_nodes(self),
+            ensure_unique_para_ids(self),
+        ]
+        .into_iter()
+        .chain(common_checks(self).into_iter().map(|(_, errors)| errors))
+        .flatten()
+        .collect::<Vec<_>>();
+
+        if !errors.is_empty() {
+            return Err(errors);
+        }
+
+        Ok(())
+    }
+
+    /// A helper function to load the network configuration of a previous run from its
+    /// `zombie.json` state file, e.g. to re-spawn the same network.
+    ///
+    /// The state file links the configuration (recovered from the spawned network) that is
+    /// stored next to it, nodes keep the ports used in the previous run. Chain-spec paths
+    /// that no longer exist are reported as warnings, the spawn will fail if still missing.
+    pub fn load_from_zombie_json(path: &str) -> Result<NetworkConfig, anyhow::Error> {
+        let path = Path::new(path);
+        let file_str = fs::read_to_string(path)
+            .map_err(|err| anyhow!("Can't read state file {}: {err}", path.to_string_lossy()))?;
+        let zombie_json: serde_json::Value = serde_json::from_str(&file_str)?;
+
+        let Some(config_file) = zombie_json["config"].as_str() else {
+            Err(anyhow!(
+                "State file {} doesn't link a network config",
+                path.to_string_lossy()
+            ))?
+        };
+        let config_path = path.parent().unwrap_or(Path::new("")).join(config_file);
+        let network_config = NetworkConfig::load_from_toml(&config_path.to_string_lossy())?;
+
+        let chain_specs = network_config
+            .parachains()
+            .into_iter()
+            .filter_map(|para| para.chain_spec_path())
+            .chain(network_config.relaychain().chain_spec_path());
+        for location in chain_specs {
+            if let AssetLocation::FilePath(chain_spec_path) = location {
+                if !chain_spec_path.exists() {
+                    warn!(
+                        "⚠️ chain-spec {} referenced by {} no longer exists",
+                        chain_spec_path.to_string_lossy(),
+                        path.to_string_lossy()
+                    );
+                }
+            }
+        }
+
+        Ok(network_config)
+    }
+
+    /// Merge an `overlay` configuration into this one, allowing to keep a base configuration
+    /// and layer environment-specific tweaks (e.g. images or args) on top of it.
+    ///
+    /// The merge rules are:
+    /// - scalar values from the `overlay` win, unless they are unset (or hold the default value);
+    /// - args lists are appended, options already present (matched by name) get their value replaced;
+    /// - relaychain nodes and parachain collators are matched by `name` (within the same chain),
+    ///   the matched ones are merged and the others are appended;
+    /// - parachains are matched by `id` and HRMP channels by `(sender, recipient)`;
+    /// - nodes values inherited from the chain defaults follow the merged defaults.
+    ///
+    /// The merge is ambiguous (and fails) if a node name or parachain id is defined more than once
+    /// in any of the configurations, or if a node belongs to different chains in each of them.
+    ///
+    /// # Example:
+    ///
+    /// ```
+    /// use zombienet_configuration::NetworkConfigBuilder;
+    ///
+    /// let base = NetworkConfigBuilder::new()
+    ///     .with_relaychain(|relaychain| {
+    ///         relaychain
+    ///             .with_chain("rococo-local")
+    ///             .with_default_command("polkadot")
+    ///             .with_node(|node| node.with_name("alice"))
+    ///             .with_node(|node| node.with_name("bob"))
+    ///     })
+    ///     .build()
+    ///     .unwrap();
+    ///
+    /// let overlay = NetworkConfigBuilder::new()
+    ///     .with_relaychain(|relaychain| {
+    ///         relaychain
+    ///             .with_chain("rococo-local")
+    ///             .with_default_image("docker.io/parity/polkadot:latest")
+    ///             .with_node(|node| {
+    ///                 node.with_name("alice")
+    ///                     .with_args(vec!["-lruntime=debug".into()])
+    ///             })
+    ///     })
+    ///     .build()
+    ///     .unwrap();
+    ///
+    /// let network_config = base.merge(overlay).unwrap();
+    /// let alice = network_config.relaychain().nodes()[0];
+    ///
+    /// assert_eq!(alice.args(), vec![&"-lruntime=debug".into()]);
+    /// assert_eq!(
+    ///     alice.image().unwrap().as_str(),
+    ///     "docker.io/parity/polkadot:latest"
+    /// );
+    /// ```
+    pub fn merge(self, overlay: NetworkConfig) -> Result<NetworkConfig, Vec<anyhow::Error>> {
+        let errors = merge_errors_vecs(
+            ensure_mergeable(&self, "base"),
+            ensure_mergeable(&overlay, "overlay"),
+        );
+
+        if !errors.is_empty() {
+            return Err(errors);
+        }
+
+        let overlay_locations = nodes_locations(&overlay);
+        let errors = nodes_locations(&self)
+            .into_iter()
+            .filter_map(|(name, location)| {
+                overlay_locations
+                    .iter()
+                    .find(|(overlay_name, overlay_location)| {
+                        *overlay_name == name && *overlay_location != location
+                    })
+                    .map(|(_, overlay_location)| {
+                        MergeError::NodeChainMismatch(
+                            name.to_string(),
+                            location,
+                            overlay_location.clone(),
+                        )
+                        .into()
+                    })
+            })
+            .collect::<Vec<anyhow::Error>>();
+
+        if !errors.is_empty() {
+            return Err(errors);
+        }
+
+        Ok(NetworkConfig {
+            global_settings: self.global_settings.merge(overlay.global_settings),
+            relaychain: match (self.relaychain, overlay.relaychain) {
+                (Some(base), Some(overlay)) => Some(base.merge(overlay)),
+                (base, overlay) => overlay.or(base),
+            },
+            parachains: merge_by_key(
+                self.parachains,
+                overlay.parachains,
+                |parachain| parachain.id(),
+                |parachain, overlay_parachain| match parachain {
+                    Some(parachain) => parachain.merge(overlay_parachain),
+                    None => overlay_parachain,
+                },
+            ),
+            hrmp_channels: merge_by_key(
+                self.hrmp_channels,
+                overlay.hrmp_channels,
+                |channel| (channel.sender(), channel.recipient()),
+                |_, overlay_channel| overlay_channel,
+            ),
+        })
+    }
+}
+
+/// The top-level directive of a TOML config to extend a base config.
+#[derive(Deserialize)]
+struct Extends {
+    extends: Option<String>,
+}
+
+/// Load the network configuration (as is, without applying the chains defaults) from the TOML
+/// file at `path`, resolving the `extends` directive recursively. The files in `visited` are
+/// the ones currently being loaded, used to detect cycles.
+fn load_toml_with_extends(
+    path: &Path,
+    visited: &mut Vec<PathBuf>,
+) -> Result<NetworkConfig, anyhow::Error> {
+    let file_str = fs::read_to_string(path)
+        .map_err(|err| anyhow!("Can't read config file {}: {err}", path.to_string_lossy()))?;
+
+    let canonical_path = fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+    if let Some(index) = visited
+        .iter()
+        .position(|visited| *visited == canonical_path)
+    {
+        let cycle = visited[index..]
+            .iter()
+            .chain([&canonical_path])
+            .map(|path| path.to_string_lossy())
+            .collect::<Vec<_>>()
+            .join(" -> ");
+        Err(MergeError::ExtendsCycle(
+            path.to_string_lossy().to_string(),
+            cycle,
+        ))?
+    }
+    visited.push(canonical_path);
+
+    let re: Regex = Regex::new(r"(?<field_name>(initial_)?balance)\s+=\s+(?<u128_value>\d+)")
+        .expect(&format!("{} {}", VALID_REGEX, THIS_IS_A_BUG));
+
+    let toml_text = re.replace_all(&file_str, "$field_name = \"$u128_value\"");
+    trace!("toml text to parse: {}", toml_text);
+    let network_config: NetworkConfig = toml::from_str(&toml_text)?;
+    let Extends { extends } = toml::from_str(&toml_text)?;
+
+    let network_config = if let Some(base_path) = extends {
+        let base_path = path.parent().unwrap_or(Path::new("")).join(base_path);
+        let base = load_toml_with_extends(&base_path, visited)?;
+
+        base.merge(network_config).map_err(|errors| {
+            let errors = errors
+                .iter()
+                .map(|error| error.to_string())
+                .collect::<Vec<_>>()
+                .join(", ");
+            anyhow!(
+                "Can't extend {} from {}: {errors}",
+                path.to_string_lossy(),
+                base_path.to_string_lossy()
+            )
+        })?
+    } else {
+        network_config
+    };
+
+    visited.pop();
+    Ok(network_config)
+}
+
+/// Assign the `default_args` to the `node` if it doesn't have its own args, or apply
+/// the node args on top of them if it has remove directives (`!<arg>`).
+fn apply_default_args(node: &mut NodeConfig, default_args: &[Arg]) {
+    let args: Vec<Arg> = node.args().into_iter().cloned().collect();
+
+    if args.iter().any(|arg| matches!(arg, Arg::Remove(_))) {
+        let (args, unmatched) = override_args(default_args.to_vec(), args);
+        for removal in unmatched {
+            warn!(
+                "node '{}': '{removal}' is not present in the default args, nothing to remove",
+                node.name()
+            );
+        }
+        node.set_args(args);
+    } else if !default_args.is_empty() && args.is_empty() && !node.no_default_args() {
+        node.set_args(default_args.to_vec());
+    }
+}
+
+/// The checks run by the builder, the `toml` load and [`NetworkConfig::validate`], alongside
+/// the label of each one.
+fn common_checks(network_config: &NetworkConfig) -> [(&'static str, Vec<anyhow::Error>); 4] {
+    [
+        ("images", ensure_image_policy(network_config)),
+        (
+            "hrmp channels",
+            ensure_hrmp_channels_endpoints(network_config),
+        ),
+        ("relay rpc nodes", ensure_relay_rpc_nodes(network_config)),
+        ("node references", ensure_token_references(network_config)),
+    ]
+}
+
+/// Fail with the (joined) errors of the first check with errors, prefixed with its label.
+fn ensure_checks(
+    checks: impl IntoIterator<Item = (&'static str, Vec<anyhow::Error>)>,
+) -> Result<(), anyhow::Error> {
+    for (label, errors) in checks {
+        if !errors.is_empty() {
+            let errors = errors
+                .iter()
+                .map(|error| error.to_string())
+                .collect::<Vec<_>>()
+                .join(", ");
+            return Err(anyhow!("Invalid {label}: {errors}"));
+        }
+    }
+
+    Ok(())
+}
+
+/// The name of each node in the network alongside the chain it belongs to.
+fn nodes_locations(network_config: &NetworkConfig) -> Vec<(&str, String)> {
+    let relaychain_nodes = network_config
+        .relaychain
+        .iter()
+        .flat_map(|relaychain| relaychain.nodes())
+        .map(|node| (node.name(), "relaychain".to_string()));
+
+    let collators = network_config.parachains.iter().flat_map(|parachain| {
+        parachain
+            .collators()
+            .into_iter()
+            .map(|collator| (collator.name(), format!("parachain[{}]", parachain.id())))
+    });
+
+    relaychain_nodes.chain(collators).collect()
+}
+
+/// Each node in the network alongside the id of the parachain it belongs to (`None` for the
+/// relaychain nodes).
+fn nodes_with_para_id(network_config: &NetworkConfig) -> Vec<(&NodeConfig, Option<ParaId>)> {
+    let relaychain_nodes = network_config
+        .relaychain
+        .iter()
+        .flat_map(|relaychain| relaychain.nodes())
+        .map(|node| (node, None));
+
+    let collators = network_config.parachains.iter().flat_map(|parachain| {
+        parachain
+            .collators()
+            .into_iter()
+            .map(|collator| (collator, Some(parachain.id())))
+    });
+
+    relaychain_nodes.chain(collators).collect()
+}
+
+/// Wrap a node field error with the path of the node (relaychain node or parachain collator).
+fn node_error(name: &str, para_id: Option<ParaId>, error: FieldError) -> anyhow::Error {
+    match para_id {
+        None => ConfigError::Relaychain(ConfigError::Node(name.to_string(), error.into()).into()),
+        Some(id) => ConfigError::Parachain(
+            id,
+            ConfigError::Collator(name.to_string(), error.into()).into(),
+        ),
+    }
+    .into()
+}
+
+/// The errors of the image and command values, wrapped with the given fields.
+fn image_and_command_errors(
+    image: Option<&Image>,
+    command: Option<&Command>,
+    image_field: fn(anyhow::Error) -> FieldError,
+    command_field: fn(anyhow::Error) -> FieldError,
+) -> Vec<FieldError> {
+    let image = image
+        .and_then(|image| Image::try_from(image.as_str()).err())
+        .map(|error| image_field(error.into()));
+    let command = command
+        .and_then(|command| Command::try_from(command.as_str()).err())
+        .map(|error| command_field(error.into()));
+
+    image.into_iter().chain(command).collect()
+}
+
+/// Ensure the chains, images and commands values are valid. The builders validate them
+/// on creation but they are only deserialized while loading a `toml` file.
+fn ensure_values(network_config: &NetworkConfig) -> Vec<anyhow::Error> {
+    let mut errors = vec![];
+
+    if let Some(relaychain) = network_config.relaychain.as_ref() {
+        let chain = Chain::try_from(relaychain.chain().as_str())
+            .err()
+            .map(|error| FieldError::Chain(error.into()));
+        let defaults = image_and_command_errors(
+            relaychain.default_image(),
+            relaychain.default_command(),
+            FieldError::DefaultImage,
+            FieldError::DefaultCommand,
+        );
+        errors.extend(
+            chain
+                .into_iter()
+                .chain(defaults)
+                .map(|error| ConfigError::Relaychain(error.into()).into()),
+        );
+    }
+
+    for parachain in network_config.parachains.iter() {
+        let chain = parachain
+            .chain()
+            .and_then(|chain| Chain::try_from(chain.as_str()).err())
+            .map(|error| FieldError::Chain(error.into()));
+        let defaults = image_and_command_errors(
+            parachain.default_image(),
+            parachain.default_command(),
+            FieldError::DefaultImage,
+            FieldError::DefaultCommand,
+        );
+        errors.extend(
+            chain
+                .into_iter()
+                .chain(defaults)
+                .map(|error| ConfigError::Parachain(parachain.id(), error.into()).into()),
+        );
+    }
+
+    for (node, para_id) in nodes_with_para_id(network_config) {
+        errors.extend(
+            image_and_command_errors(
+                node.image(),
+                node.command(),
+                FieldError::Image,
+                FieldError::Command,
+            )
+            .into_iter()
+            .map(|error| node_error(node.name(), para_id, error)),
+        );
+    }
+
+    errors
+}
+
+/// Ensure the fields validated by the builders on creation (genesis raw entries, relay args,
+/// namespace prefix and the nodes fields) are valid, since they are only deserialized while
+/// loading a `toml` file.
+fn ensure_fields(network_config: &NetworkConfig) -> Vec<anyhow::Error> {
+    let mut errors = vec![];
+
+    if let Some(namespace_prefix) = network_config.global_settings.namespace_prefix() {
+        if let Err(error) = ensure_namespace_prefix(namespace_prefix) {
+            errors.push(
+                ConfigError::GlobalSettings(FieldError::NamespacePrefix(error).into()).into(),
+            );
+        }
+    }
+
+    if let Some(relaychain) = network_config.relaychain.as_ref() {
+        errors.extend(
+            ensure_genesis_raw_entries(relaychain.genesis_raw_entries())
+                .into_iter()
+                .chain(relaychain::ensure_no_relay_args(relaychain))
+                .map(|error| ConfigError::Relaychain(error).into()),
+        );
+    }
+
+    for parachain in network_config.parachains.iter() {
+        errors.extend(
+            ensure_genesis_raw_entries(parachain.genesis_raw_entries())
+                .into_iter()
+                .chain(parachain::ensure_cumulus_only_fields(parachain))
+                .chain(parachain::ensure_no_relay_node_args(parachain))
+                .map(|error| ConfigError::Parachain(parachain.id(), error).into()),
+        );
+    }
+
+    // the nodes of each chain, alongside the default args they can inherit
+    let relaychain_nodes = network_config
+        .relaychain
+        .iter()
+        .map(|relaychain| (relaychain.nodes(), relaychain.default_args(), None));
+    let collators = network_config.parachains.iter().map(|parachain| {
+        (
+            parachain.collators(),
+            parachain.default_args(),
+            Some(parachain.id()),
+        )
+    });
+    for (nodes, default_args, para_id) in relaychain_nodes.chain(collators) {
+        let default_args: Vec<Arg> = default_args.into_iter().cloned().collect();
+        for node in nodes {
+            errors.extend(
+                ensure_node_fields(node, &default_args)
+                    .into_iter()
+                    .map(|error| node_error(node.name(), para_id, error)),
+            );
+        }
+    }
+
+    errors
+}
+
+/// Ensure the nodes names and ports are unique across the config.
+fn ensure_unique_nodes(network_config: &NetworkConfig) -> Vec<anyhow::Error> {
+    let validation_context = Rc::new(RefCell::new(ValidationContext::default()));
+    let mut errors = vec![];
+
+    for (node, para_id) in nodes_with_para_id(network_config) {
+        if let Err(error) = ensure_node_name_unique(node.name(), validation_context.clone()) {
+            errors.push(node_error(node.name(), para_id, FieldError::Name(error)));
+        }
+
+        let ports = [
+            (
+                node.ws_port(),
+                FieldError::WsPort as fn(anyhow::Error) -> FieldError,
+            ),
+            (node.rpc_port(), FieldError::RpcPort),
+            (node.prometheus_port(), FieldError::PrometheusPort),
+            (node.p2p_port(), FieldError::P2pPort),
+        ];
+        for (port, field) in ports {
+            let Some(port) = port else {
+                continue;
+            };
+            if let Err(error) = ensure_port_unique(port, validation_context.clone()) {
+                errors.push(node_error(node.name(), para_id, field(error)));
+            }
+        }
+    }
+
+    errors
+}
+
+/// Ensure the parachains ids are unique across the config.
+fn ensure_unique_para_ids(network_config: &NetworkConfig) -> Vec<anyhow::Error> {
+    let mut ids = vec![];
+    let mut errors = vec![];
+
+    for parachain in network_config.parachains.iter() {
+        if ids.contains(&parachain.id()) {
+            errors.push(
+                ConfigError::Parachain(
+                    parachain.id(),
+                    FieldError::Id(ValidationError::ParaIdAlreadyUsed(parachain.id()).into())
+                        .into(),
+                )
+                .into(),
+            );
+        } else {
+            ids.push(parachain.id());
+        }
+    }
+
+    errors
+}
+
+/// Ensure the `{{ZOMBIE:<node>:<field>}}` tokens in the nodes args/env reference nodes
+/// declared in the config, otherwise they can't be replaced at spawn time.
+fn ensure_token_references(network_config: &NetworkConfig) -> Vec<anyhow::Error> {
+    let names = nodes_locations(network_config)
+        .into_iter()
+        .map(|(name, _)| name)
+        .collect::<Vec<_>>();
+    let unknown_references = |text: &str| -> Vec<String> {
+        zombie_token_references(text)
+            .into_iter()
+            .filter(|(name, _)| !names.contains(name))
+            .map(|(_, token)| token.to_string())
+            .collect()
+    };
+    let mut errors = vec![];
+
+    for (node, para_id) in nodes_with_para_id(network_config) {
+        let args = node.args().into_iter().flat_map(|arg| match arg {
+            Arg::Flag(flag) => unknown_references(flag),
+            Arg::Option(option, value) => {
+                [unknown_references(option), unknown_references(value)].concat()
+            },
+            Arg::Remove(_) => vec![],
+        });
+        let env = node
+            .env()
+            .into_iter()
+            .flat_map(|var| unknown_references(&var.value));
+
+        errors.extend(
+            args.map(|token| FieldError::Args(ValidationError::UnknownNodeReference(token).into()))
+                .chain(env.map(|token| {
+                    FieldError::Env(ValidationError::UnknownNodeReference(token).into())
+                }))
+                .map(|error| node_error(node.name(), para_id, error))
+                .collect::<Vec<_>>(),
+        );
+    }
+
+    errors
+}
+
+/// Ensure the images (chains `default_image` and nodes `image`) follow the [`ImagePolicy`]
+/// from the global settings. Nodes using the chain default image are not reported twice.
+fn ensure_image_policy(network_config: &NetworkConfig) -> Vec<anyhow::Error> {
+    if network_config.global_settings.image_policy() != ImagePolicy::RequireDigest {
+        return vec![];
+    }
+
+    let not_pinned = |image: Option<&Image>| -> Option<anyhow::Error> {
+        image
+            .filter(|image| !image.is_pinned())
+            .map(|image| ValidationError::ImageNotPinned(image.as_str().to_string()).into())
+    };
+    let mut errors = vec![];
+
+    if let Some(error) = not_pinned(network_config.global_settings.spec_builder_image()) {
+        errors.push(ConfigError::GlobalSettings(FieldError::SpecBuilderImage(error).into()).into());
+    }
+
+    if let Some(relaychain) = network_config.relaychain.as_ref() {
+        if let Some(error) = not_pinned(relaychain.default_image()) {
+            errors.push(ConfigError::Relaychain(FieldError::DefaultImage(error).into()).into());
+        }
+
+        for node in relaychain.nodes() {
+            if node.image() == relaychain.default_image() {
+                continue;
+            }
+            if let Some(error) = not_pinned(node.image()) {
+                errors.push(
+                    ConfigError::Relaychain(
+                        ConfigError::Node(node.name().to_string(), FieldError::Image(error).into())
+                            .into(),
+                    )
+                    .into(),
+                );
+            }
+        }
+    }
+
+    for parachain in network_config.parachains.iter() {
+        if let Some(error) = not_pinned(parachain.default_image()) {
+            errors.push(
+                ConfigError::Parachain(parachain.id(), FieldError::DefaultImage(error).into())
+                    .into(),
+            );
+        }
+
+        for collator in parachain.collators() {
+            if collator.image() == parachain.default_image() {
+                continue;
+            }
+            if let Some(error) = not_pinned(collator.image()) {
+                errors.push(
+                    ConfigError::Parachain(
+                        parachain.id(),
+                        ConfigError::Collator(
+                            collator.name().to_string(),
+                            FieldError::Image(error).into(),
+                        )
+                        .into(),
+                    )
+                    .into(),
+                );
+            }
+        }
+    }
+
+    errors
+}
+
+/// Ensure the HRMP channels `sender` and `recipient` are parachains declared in the config
+/// (with any registration strategy), otherwise the channels would be silently ignored.
+fn ensure_hrmp_channels_endpoints(network_config: &NetworkConfig) -> Vec<anyhow::Error> {
+    let para_ids = network_config
+        .parachains
+        .iter()
+        .map(|parachain| parachain.id())
+        .collect::<Vec<_>>();
+    let mut errors = vec![];
+
+    for (index, channel) in network_config.hrmp_channels.iter().enumerate() {
+        if !para_ids.contains(&channel.sender()) {
+            errors.push(
+                ConfigError::HrmpChannel(
+                    index,
+                    FieldError::Sender(ValidationError::UnknownParaId(channel.sender()).into())
+                        .into(),
+                )
+                .into(),
+            );
+        }
+
+        if !para_ids.contains(&channel.recipient()) {
+            errors.push(
+                ConfigError::HrmpChannel(
+                    index,
+                    FieldError::Recipient(
+                        ValidationError::UnknownParaId(channel.recipient()).into(),
+                    )
+                    .into(),
+                )
+                .into(),
+            );
+        }
+    }
+
+    errors
+}
+
+/// Ensure the relaychain nodes referenced by the parachains (to connect the collators
+/// to their rpc) are declared in the config.
+fn ensure_relay_rpc_nodes(network_config: &NetworkConfig) -> Vec<anyhow::Error> {
+    let relay_nodes = network_config
+        .relaychain()
+        .nodes()
+        .into_iter()
+        .map(|node| node.name())
+        .collect::<Vec<_>>();
+
+    network_config
+        .parachains()
+        .into_iter()
+        .filter_map(|parachain| {
+            let node_name = parachain.relay_rpc_node()?;
+            let error = if parachain.relay_rpc_url().is_some() {
+                ValidationError::RelayRpcAlreadySet()
+            } else if !relay_nodes.contains(&node_name) {
+                ValidationError::UnknownRelayNode(node_name.to_string())
+            } else {
+                return None;
+            };
+
+            Some(
+                ConfigError::Parachain(
+                    parachain.id(),
+                    FieldError::RelayRpcNode(error.into()).into(),
+                )
+                .into(),
+            )
+        })
+        .collect()
+}
+
+/// Ensure the nodes names and parachains ids are unique, since are used to match them while merging.
+fn ensure_mergeable(
+    network_config: &NetworkConfig,
+    config_name: &'static str,
+) -> Vec<anyhow::Error> {
+    let mut names = vec![];
+    let mut ids = vec![];
+    let mut errors = vec![];
+
+    for (name, _) in nodes_locations(network_config) {
+        if names.contains(&name) {
+            errors.push(MergeError::DuplicatedNodeName(name.to_string(), config_name).into());
+        } else {
+            names.push(name);
+        }
+    }
+
+    for parachain in network_config.parachains.iter() {
+        if ids.contains(&parachain.id()) {
+            errors.push(MergeError::DuplicatedParaId(parachain.id(), config_name).into());
+        } else {
+            ids.push(parachain.id());
+        }
+    }
+
+    errors
 }
 
 states! {
@@ -260,15 +1049,15 @@ states! {
 ///     })
 ///     .with_hrmp_channel(|hrmp_channel1| {
 ///         hrmp_channel1
-///             .with_sender(1)
-///             .with_recipient(2)
+///             .with_sender(1000)
+///             .with_recipient(2000)
 ///             .with_max_capacity(200)
 ///             .with_max_message_size(500)
 ///     })
 ///     .with_hrmp_channel(|hrmp_channel2| {
 ///         hrmp_channel2
-///             .with_sender(2)
-///             .with_recipient(1)
+///             .with_sender(2000)
+///             .with_recipient(1000)
 ///             .with_max_capacity(100)
 ///             .with_max_message_size(250)
 ///     })
@@ -489,6 +1278,14 @@ impl NetworkConfigBuilder<WithRelaychain> {
             return Err(self.errors);
         }
 
+        let errors = common_checks(&self.config)
+            .into_iter()
+            .flat_map(|(_, errors)| errors)
+            .collect::<Vec<_>>();
+        if !errors.is_empty() {
+            return Err(errors);
+        }
+
         Ok(self.config)
     }
 }
@@ -757,6 +1554,196 @@ mod tests {
         );
     }
 
+    #[test]
+    fn network_config_builder_should_fails_if_images_are_not_pinned_with_require_digest_policy() {
+        let pinned = "docker.io/parity/polkadot-parachain@sha256:3b8dd8dbc1f4f2d4c2f2b8e5b2f1f6a4e5d6c7b8a9f0e1d2c3b4a5f6e7d8c9b0";
+        let errors = NetworkConfigBuilder::new()
+            .with_relaychain(|relaychain| {
+                relaychain
+                    .with_chain("rococo-local")
+                    .with_default_image("docker.io/parity/polkadot:latest")
+                    .with_node(|node| node.with_name("alice"))
+                    .with_node(|node| {
+                        node.with_name("bob")
+                            .with_image("docker.io/parity/polkadot:v1.0.0")
+                    })
+            })
+            .with_parachain(|parachain| {
+                parachain
+                    .with_id(1000)
+                    .with_default_image(pinned)
+                    .with_collator(|collator| collator.with_name("collator1"))
+                    .with_collator(|collator| {
+                        collator
+                            .with_name("collator2")
+                            .with_image("docker.io/parity/polkadot-parachain")
+                    })
+            })
+            .with_global_settings(|global_settings| {
+                global_settings.with_image_policy(ImagePolicy::RequireDigest)
+            })
+            .build()
+            .unwrap_err();
+
+        let errors: Vec<String> = errors.iter().map(|error| error.to_string()).collect();
+        assert_eq!(
+            errors,
+            vec![
+                "relaychain.default_image: 'docker.io/parity/polkadot:latest' should be pinned by digest (e.g. 'name@sha256:<digest>')",
+                "relaychain.nodes['bob'].image: 'docker.io/parity/polkadot:v1.0.0' should be pinned by digest (e.g. 'name@sha256:<digest>')",
+                "parachain[1000].collators['collator2'].image: 'docker.io/parity/polkadot-parachain' should be pinned by digest (e.g. 'name@sha256:<digest>')",
+            ]
+        );
+    }
+
+    #[test]
+    fn network_config_builder_should_fails_if_hrmp_channels_reference_unknown_parachains() {
+        let errors = NetworkConfigBuilder::new()
+            .with_relaychain(|relaychain| {
+                relaychain
+                    .with_chain("rococo-local")
+                    .with_default_command("polkadot")
+                    .with_node(|node| node.with_name("alice"))
+            })
+            .with_parachain(|parachain| {
+                parachain
+                    .with_id(1000)
+                    .with_collator(|collator| collator.with_name("collator1"))
+            })
+            .with_hrmp_channel(|hrmp_channel| hrmp_channel.with_sender(1000).with_recipient(2000))
+            .with_hrmp_channel(|hrmp_channel| hrmp_channel.with_sender(3000).with_recipient(4000))
+            .build()
+            .unwrap_err();
+
+        let errors: Vec<String> = errors.iter().map(|error| error.to_string()).collect();
+        assert_eq!(
+            errors,
+            vec![
+                "hrmp_channels[0].recipient: '2000' is not the id of a parachain in the config",
+                "hrmp_channels[1].sender: '3000' is not the id of a parachain in the config",
+                "hrmp_channels[1].recipient: '4000' is not the id of a parachain in the config",
+            ]
+        );
+    }
+
+    #[test]
+    fn network_config_builder_should_fails_if_relay_rpc_node_is_unknown() {
+        let errors = NetworkConfigBuilder::new()
+            .with_relaychain(|relaychain| {
+                relaychain
+                    .with_chain("rococo-local")
+                    .with_default_command("polkadot")
+                    .with_node(|node| node.with_name("alice"))
+            })
+            .with_parachain(|parachain| {
+                parachain
+                    .with_id(1000)
+                    .with_relay_rpc_node("alice")
+                    .with_collator(|collator| collator.with_name("collator1"))
+            })
+            .with_parachain(|parachain| {
+                parachain
+                    .with_id(2000)
+                    .with_relay_rpc_node("bob")
+                    .with_collator(|collator| collator.with_name("collator2"))
+            })
+            .build()
+            .unwrap_err();
+
+        let errors: Vec<String> = errors.iter().map(|error| error.to_string()).collect();
+        assert_eq!(
+            errors,
+            vec![
+                "parachain[2000].relay_rpc_node: 'bob' is not the name of a relaychain node in the config",
+            ]
+        );
+    }
+
+    #[test]
+    fn network_config_builder_should_fails_if_tokens_reference_unknown_nodes() {
+        let errors = NetworkConfigBuilder::new()
+            .with_relaychain(|relaychain| {
+                relaychain
+                    .with_chain("rococo-local")
+                    .with_default_command("polkadot")
+                    .with_node(|node| node.with_name("alice"))
+                    .with_node(|node| {
+                        node.with_name("bob").with_args(vec![(
+                            "--bootnodes",
+                            "{{ZOMBIE:alice:multiaddr}}",
+                        )
+                            .into()])
+                    })
+            })
+            .with_parachain(|parachain| {
+                parachain
+                    .with_id(100)
+                    .with_default_command("adder-collator")
+                    .with_collator(|collator| {
+                        collator.with_name("collator1").with_args(vec![(
+                            "--bootnodes",
+                            "{{ZOMBIE:bobb:multiaddr}}",
+                        )
+                            .into()])
+                    })
+            })
+            .build()
+            .unwrap_err();
+
+        let errors: Vec<String> = errors.iter().map(|error| error.to_string()).collect();
+        assert_eq!(
+            errors,
+            vec![
+                "parachain[100].collators['collator1'].args: '{{ZOMBIE:bobb:multiaddr}}' references a node that is not in the config",
+            ]
+        );
+    }
+
+    #[test]
+    fn network_config_builder_should_allow_hrmp_channels_between_manually_registered_parachains() {
+        let network_config = NetworkConfigBuilder::new()
+            .with_relaychain(|relaychain| {
+                relaychain
+                    .with_chain("rococo-local")
+                    .with_default_command("polkadot")
+                    .with_node(|node| node.with_name("alice"))
+            })
+            .with_parachain(|parachain| {
+                parachain
+                    .with_id(1000)
+                    .with_collator(|collator| collator.with_name("collator1"))
+            })
+            .with_parachain(|parachain| {
+                parachain
+                    .with_id(2000)
+                    .with_registration_strategy(RegistrationStrategy::Manual)
+                    .with_collator(|collator| collator.with_name("collator2"))
+            })
+            .with_hrmp_channel(|hrmp_channel| hrmp_channel.with_sender(1000).with_recipient(2000))
+            .build()
+            .unwrap();
+
+        assert_eq!(network_config.hrmp_channels().len(), 1);
+    }
+
+    #[test]
+    fn network_config_builder_should_allow_tags_by_default() {
+        let network_config = NetworkConfigBuilder::new()
+            .with_relaychain(|relaychain| {
+                relaychain
+                    .with_chain("rococo-local")
+                    .with_default_image("docker.io/parity/polkadot:latest")
+                    .with_node(|node| node.with_name("alice"))
+            })
+            .build()
+            .unwrap();
+
+        assert_eq!(
+            network_config.global_settings().image_policy(),
+            ImagePolicy::AllowTags
+        );
+    }
+
     #[test]
     fn network_config_builder_should_fails_and_returns_multiple_errors_if_global_settings_is_invalid(
     ) {
@@ -1527,6 +2514,312 @@ mod tests {
             });
     }
 
+    #[test]
+    fn the_toml_config_with_remove_args_should_be_imported_and_apply_them_to_the_default_args() {
+        let network_config =
+            NetworkConfig::load_from_toml("./testing/snapshots/0005-args-override.toml").unwrap();
+
+        let nodes = network_config.relaychain().nodes();
+        assert_eq!(
+            nodes[0].args(),
+            vec![&"-lparachain=debug".into(), &"--flag".into()]
+        );
+        assert_eq!(
+            nodes[1].args(),
+            vec![&"--flag".into(), &"-lruntime=trace".into()]
+        );
+
+        let collators = network_config.parachains()[0].collators();
+        assert_eq!(
+            collators[0].args(),
+            vec![&"-lparachain=debug".into(), &"--flag".into()]
+        );
+        assert_eq!(
+            collators[1].args(),
+            vec![&"-lparachain=debug".into(), &"-lruntime=trace".into()]
+        );
+    }
+
+    #[test]
+    fn the_toml_config_with_extends_should_be_layered_on_top_of_the_base() {
+        let network_config =
+            NetworkConfig::load_from_toml("./testing/snapshots/0006-extends.toml").unwrap();
+
+        assert_eq!(
+            network_config.global_settings().network_spawn_timeout(),
+            3600
+        );
+        let relaychain = network_config.relaychain();
+        assert_eq!(relaychain.default_command().unwrap().as_str(), "polkadot");
+        assert_eq!(
+            relaychain.default_image().unwrap().as_str(),
+            "docker.io/parity/polkadot:latest"
+        );
+
+        let nodes = relaychain.nodes();
+        assert_eq!(nodes.len(), 2);
+        assert_eq!(nodes[0].name(), "alice");
+        assert_eq!(nodes[0].args(), vec![&"-lparachain=debug".into()]);
+        assert_eq!(nodes[1].name(), "bob");
+        assert_eq!(nodes[1].args(), vec![&"-lruntime=trace".into()]);
+        assert_eq!(
+            nodes[1].image().unwrap().as_str(),
+            "docker.io/parity/polkadot:latest"
+        );
+
+        let parachains = network_config.parachains();
+        assert_eq!(parachains.len(), 1);
+        assert_eq!(parachains[0].collators()[0].name(), "john");
+    }
+
+    #[test]
+    fn the_toml_config_with_extends_cycle_should_fail() {
+        let err = NetworkConfig::load_from_toml("./testing/snapshots/0007-extends-cycle-a.toml")
+            .unwrap_err();
+
+        assert!(err.to_string().contains("extends itself"));
+        assert!(err.to_string().contains("0007-extends-cycle-b.toml"));
+    }
+
+    #[test]
+    fn the_toml_config_with_an_unknown_node_reference_should_fail() {
+        let err =
+            NetworkConfig::load_from_toml("./testing/snapshots/0010-unknown-node-reference.toml")
+                .unwrap_err();
+
+        assert_eq!(
+            err.to_string(),
+            "Invalid node references: relaychain.nodes['bob'].args: '{{ZOMBIE:bbo:multiaddr}}' references a node that is not in the config"
+        );
+    }
+
+    #[test]
+    fn the_toml_config_with_no_default_args_should_skip_the_defaults_only_for_flagged_nodes() {
+        let network_config =
+            NetworkConfig::load_from_toml("./testing/snapshots/0008-no-default-args.toml").unwrap();
+
+        let nodes = network_config.relaychain().nodes();
+        assert_eq!(nodes[0].args(), vec![&"-lparachain=debug".into()]);
+        assert!(!nodes[0].no_default_args());
+        assert!(nodes[1].args().is_empty());
+        assert!(nodes[1].no_default_args());
+    }
+
+    #[test]
+    fn the_zombie_json_should_load_the_linked_config() {
+        let network_config =
+            NetworkConfig::load_from_zombie_json("./testing/snapshots/0009-zombie.json").unwrap();
+
+        let relaychain = network_config.relaychain();
+        assert_eq!(relaychain.chain().as_str(), "rococo-local");
+        assert!(relaychain.chain_spec_path().is_some());
+        let nodes = relaychain.nodes();
+        assert_eq!(nodes[0].name(), "alice");
+        assert_eq!(nodes[0].command().unwrap().as_str(), "polkadot");
+        assert_eq!(nodes[0].rpc_port(), Some(9944));
+    }
+
+    #[test]
+    fn validate_should_returns_all_the_errors_of_a_loaded_config() {
+        let network_config: NetworkConfig = toml::from_str(
+            r#"
+            [relaychain]
+            chain = "rococo-local"
+            default_command = "polkadot"
+
+            [[relaychain.nodes]]
+            name = "alice"
+            rpc_port = 9944
+
+            [[relaychain.nodes]]
+            name = "bob"
+            command = "invalid command"
+            rpc_port = 9944
+            args = ["--reserved-nodes={{ZOMBIE:charlie:multiaddr}}"]
+
+            [[parachains]]
+            id = 1000
+
+            [[parachains]]
+            id = 1000
+            "#,
+        )
+        .unwrap();
+
+        let errors = network_config.validate().unwrap_err();
+
+        assert_eq!(errors.len(), 4);
+        assert_eq!(
+            errors[0].to_string(),
+            "relaychain.nodes['bob'].command: 'invalid command' shouldn't contains whitespace"
+        );
+        assert_eq!(
+            errors[1].to_string(),
+            "relaychain.nodes['bob'].rpc_port: '9944' is already used across config"
+        );
+        assert_eq!(
+            errors[2].to_string(),
+            "parachain[1000].id: '1000' is already used across config"
+        );
+        assert_eq!(
+            errors[3].to_string(),
+            "relaychain.nodes['bob'].args: '{{ZOMBIE:charlie:multiaddr}}' references a node that is not in the config"
+        );
+    }
+
+    #[test]
+    fn validate_should_run_the_checks_of_the_builders_on_a_loaded_config() {
+        let network_config: NetworkConfig = toml::from_str(
+            r#"
+            [settings]
+            namespace_prefix = "Invalid_Prefix"
+
+            [relaychain]
+            chain = "rococo-local"
+            default_command = "polkadot"
+            genesis_raw_entries = [["0x00", "invalid"]]
+
+            [[relaychain.nodes]]
+            name = "alice"
+            relay_args = ["--sync=warp"]
+            listen_addr = "/dns/localhost/tcp/30333"
+            rpc_methods = "unsafe"
+            args = ["--rpc-external", "--sync=warp"]
+            sync_mode = "warp"
+            keystore_keys = [{ key_type = "aur", seed = "//Alice", public = "0xzz" }]
+            injected_files = [{ local_path = "/not/a/file", remote_path = "cfg/node.key" }]
+
+            [[parachains]]
+            id = 1000
+            cumulus_based = false
+
+            [[parachains.collators]]
+            name = "collator"
+            relay_args = ["--sync=warp"]
+            "#,
+        )
+        .unwrap();
+
+        let errors = network_config
+            .validate()
+            .unwrap_err()
+            .iter()
+            .map(|error| error.to_string())
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            errors,
+            vec![
+                "global_settings.namespace_prefix: 'Invalid_Prefix' should be a DNS label of at most 26 chars (lowercase alphanumerics or '-', starting and ending with an alphanumeric)",
+                "relaychain.genesis_raw_entries[0]: 'invalid' should be a `0x` prefixed hex string",
+                "relaychain.nodes['alice'].relay_args: only allowed on the collators of a cumulus based parachain",
+                "parachain[1000].collators['collator'].relay_args: only allowed on the collators of a cumulus based parachain",
+                "relaychain.nodes['alice'].keystore_keys[0]: 'aur' should be a 4 chars crypto id",
+                "relaychain.nodes['alice'].keystore_keys[0]: '0xzz' should be a `0x` prefixed hex string",
+                "relaychain.nodes['alice'].injected_files[0]: '/not/a/file' doesn't exist",
+                "relaychain.nodes['alice'].injected_files[0]: 'cfg/node.key' should be an absolute path",
+                "relaychain.nodes['alice'].listen_addr: '/dns/localhost/tcp/30333' should be an `/ip4/<ip>/tcp/<port>` (or `/ip6`) multiaddr",
+                "relaychain.nodes['alice'].rpc_methods: `--rpc-external` can only be used with the `safe` methods, use `--unsafe-rpc-external` to expose the unsafe ones",
+                "relaychain.nodes['alice'].sync_mode: can't be used with a `--sync` arg",
+            ]
+        );
+    }
+
+    #[test]
+    fn validate_should_fails_if_the_sync_mode_is_also_set_in_the_inherited_default_args() {
+        let network_config: NetworkConfig = toml::from_str(
+            r#"
+            [relaychain]
+            chain = "rococo-local"
+            default_command = "polkadot"
+            default_args = ["--sync=fast"]
+
+            [[relaychain.nodes]]
+            name = "alice"
+            sync_mode = "warp"
+            "#,
+        )
+        .unwrap();
+
+        let errors = network_config.validate().unwrap_err();
+
+        assert_eq!(errors.len(), 1);
+        assert_eq!(
+            errors[0].to_string(),
+            "relaychain.nodes['alice'].sync_mode: can't be used with a `--sync` arg"
+        );
+    }
+
+    #[test]
+    fn validate_should_fails_if_both_relay_rpc_url_and_node_are_loaded() {
+        let network_config: NetworkConfig = toml::from_str(
+            r#"
+            [relaychain]
+            chain = "rococo-local"
+            default_command = "polkadot"
+
+            [[relaychain.nodes]]
+            name = "alice"
+
+            [[parachains]]
+            id = 2000
+            relay_rpc_url = "wss://rpc.example.com"
+            relay_rpc_node = "alice"
+
+            [[parachains.collators]]
+            name = "collator"
+            command = "polkadot-parachain"
+            "#,
+        )
+        .unwrap();
+
+        let errors = network_config.validate().unwrap_err();
+
+        assert_eq!(errors.len(), 1);
+        assert_eq!(
+            errors[0].to_string(),
+            "parachain[2000].relay_rpc_node: can't be used with `relay_rpc_url`, only one relay rpc can be set"
+        );
+    }
+
+    #[test]
+    fn the_json_schema_should_describe_the_network_config() {
+        let schema = NetworkConfig::json_schema();
+
+        let properties = schema["properties"].as_object().unwrap();
+        assert!(properties.contains_key("settings"));
+        assert!(properties.contains_key("relaychain"));
+        assert!(properties.contains_key("parachains"));
+        assert!(properties.contains_key("hrmp_channels"));
+
+        let node = &schema["definitions"]["NodeConfig"]["properties"];
+        assert!(node.get("chain_context").is_none());
+        assert_eq!(node["bootnodes_addresses"]["items"]["type"], "string");
+        assert_eq!(node["initial_balance"]["default"], 2_000_000_000_000_u64);
+        let parachain = &schema["definitions"]["ParachainConfig"]["properties"];
+        assert!(parachain.get("add_to_genesis").is_some());
+        assert!(parachain.get("registration_strategy").is_none());
+
+        // the values are constrained as in the builders
+        let definitions = &schema["definitions"];
+        assert_eq!(definitions["Chain"]["pattern"], r"^\S+$");
+        assert_eq!(definitions["Command"]["pattern"], r"^\S*$");
+        let image_pattern = Regex::new(definitions["Image"]["pattern"].as_str().unwrap()).unwrap();
+        assert!(image_pattern.is_match("docker.io/parity/polkadot:latest"));
+        assert!(!image_pattern.is_match("invalid image"));
+        assert_eq!(
+            definitions["LogTarget"]["pattern"],
+            "^[a-zA-Z0-9_:-]+=(error|warn|info|debug|trace)$"
+        );
+        assert_eq!(node["rpc_port"]["minimum"], 1.0);
+        assert_eq!(node["rpc_port"]["maximum"], 65535.0);
+        assert_eq!(parachain["id"]["minimum"], 1.0);
+        assert_eq!(
+            definitions["HrmpChannelConfig"]["properties"]["sender"]["minimum"],
+            1.0
+        );
+    }
+
     #[test]
     fn with_chain_and_nodes_works() {
         let network_config = NetworkConfigBuilder::with_chain_and_nodes(
@@ -1651,4 +2944,152 @@ mod tests {
             "parachain[1].collators[''].name: can't be empty"
         );
     }
+
+    #[test]
+    fn merge_should_layer_the_overlay_on_top_of_the_base_config() {
+        let base = NetworkConfigBuilder::new()
+            .with_relaychain(|relaychain| {
+                relaychain
+                    .with_chain("rococo-local")
+                    .with_default_command("polkadot")
+                    .with_default_args(vec![("-l", "babe=debug").into()])
+                    .with_node(|node| node.with_name("alice").with_rpc_external(false))
+                    .with_node(|node| node.with_name("bob").with_image("bob-image:v1"))
+            })
+            .with_parachain(|parachain| {
+                parachain
+                    .with_id(1000)
+                    .with_chain("asset-hub-rococo-local")
+                    .with_default_command("polkadot-parachain")
+                    .with_collator(|collator| collator.with_name("collator1"))
+            })
+            .with_global_settings(|settings| settings.with_network_spawn_timeout(1200))
+            .build()
+            .unwrap();
+
+        let overlay = NetworkConfigBuilder::new()
+            .with_relaychain(|relaychain| {
+                relaychain
+                    .with_chain("rococo-local")
+                    .with_default_image("polkadot:v2")
+                    .with_node(|node| {
+                        node.with_name("alice").with_args(vec![
+                            ("-l", "parachain=trace").into(),
+                            "--pruning=archive".into(),
+                        ])
+                    })
+                    .with_node(|node| node.with_name("charlie"))
+            })
+            .with_parachain(|parachain| {
+                parachain
+                    .with_id(1000)
+                    .with_default_image("polkadot-parachain:v2")
+                    .with_collator(|collator| {
+                        collator
+                            .with_name("collator1")
+                            .with_rpc_port(9944)
+                            .with_prometheus_external(false)
+                    })
+            })
+            .with_parachain(|parachain| {
+                parachain
+                    .with_id(2000)
+                    .with_collator(|collator| collator.with_name("collator2"))
+            })
+            .with_global_settings(|settings| settings.with_node_spawn_timeout(600))
+            .build()
+            .unwrap();
+
+        let network_config = base.merge(overlay).unwrap();
+
+        let nodes = network_config.relaychain().nodes();
+        assert_eq!(
+            nodes.iter().map(|node| node.name()).collect::<Vec<_>>(),
+            vec!["alice", "bob", "charlie"]
+        );
+        assert_eq!(nodes[0].command().unwrap().as_str(), "polkadot");
+        assert_eq!(nodes[0].image().unwrap().as_str(), "polkadot:v2");
+        assert_eq!(
+            nodes[0].args(),
+            vec![
+                &("-l", "parachain=trace").into(),
+                &"--pruning=archive".into()
+            ]
+        );
+        // flags not set in the overlay are kept from the base
+        assert!(!nodes[0].rpc_external());
+        assert!(nodes[0].prometheus_external());
+        assert_eq!(nodes[1].image().unwrap().as_str(), "bob-image:v1");
+        assert_eq!(nodes[1].args(), vec![&("-l", "babe=debug").into()]);
+        assert_eq!(nodes[2].command().unwrap().as_str(), "polkadot");
+        assert_eq!(nodes[2].args(), vec![&("-l", "babe=debug").into()]);
+
+        let parachains = network_config.parachains();
+        assert_eq!(parachains.len(), 2);
+        assert_eq!(
+            parachains[0].chain().unwrap().as_str(),
+            "asset-hub-rococo-local"
+        );
+        let collator = parachains[0].collators()[0];
+        assert_eq!(collator.command().unwrap().as_str(), "polkadot-parachain");
+        assert_eq!(collator.image().unwrap().as_str(), "polkadot-parachain:v2");
+        assert_eq!(collator.rpc_port(), Some(9944));
+        assert!(collator.rpc_external());
+        assert!(!collator.prometheus_external());
+        assert_eq!(parachains[1].id(), 2000);
+
+        assert_eq!(
+            network_config.global_settings().network_spawn_timeout(),
+            1200
+        );
+        assert_eq!(network_config.global_settings().node_spawn_timeout(), 600);
+    }
+
+    #[test]
+    fn merge_should_fail_if_a_node_belongs_to_different_chains() {
+        let base =
+            NetworkConfigBuilder::with_chain_and_nodes("rococo-local", vec!["alice".to_string()])
+                .build()
+                .unwrap();
+        let overlay =
+            NetworkConfigBuilder::with_chain_and_nodes("rococo-local", vec!["bob".to_string()])
+                .with_parachain_id_and_collators(1000, vec!["alice".to_string()])
+                .build()
+                .unwrap();
+
+        let errors = base.merge(overlay).unwrap_err();
+
+        assert_eq!(
+            errors.first().unwrap().to_string(),
+            "node 'alice' belongs to relaychain in the base config but to parachain[1000] in the overlay, nodes are only matched by name within the same chain"
+        );
+    }
+
+    #[test]
+    fn merge_should_fail_if_a_node_name_is_duplicated() {
+        let base =
+            NetworkConfigBuilder::with_chain_and_nodes("rococo-local", vec!["alice".to_string()])
+                .build()
+                .unwrap();
+        let overlay: NetworkConfig = toml::from_str(
+            r#"
+            [relaychain]
+            chain = "rococo-local"
+
+            [[relaychain.nodes]]
+            name = "alice"
+
+            [[relaychain.nodes]]
+            name = "alice"
+            "#,
+        )
+        .unwrap();
+
+        let errors = base.merge(overlay).unwrap_err();
+
+        assert_eq!(
+            errors.first().unwrap().to_string(),
+            "node 'alice' is defined more than once in the overlay config, nodes are matched by name so the merge is ambiguous"
+        );
+    }
 }
@@ -2,20 +2,28 @@ use std::{cell::RefCell, error::Error, fmt::Display, marker::PhantomData, rc::Rc
 
 use anyhow::anyhow;
 use multiaddr::Multiaddr;
+use schemars::{gen::SchemaGenerator, schema::Schema, JsonSchema};
 use serde::{
     de::{self, Visitor},
     ser::SerializeStruct,
     Deserialize, Serialize,
 };
+use url::Url;
 
 use crate::{
     shared::{
-        errors::{ConfigError, FieldError},
-        helpers::{merge_errors, merge_errors_vecs},
-        node::{self, NodeConfig, NodeConfigBuilder},
+        chain_properties::{ChainProperties, ChainPropertiesBuilder},
+        errors::{ConfigError, FieldError, ValidationError},
+        helpers::{
+            ensure_genesis_raw_entries, ensure_hex_prefixed, ensure_value_is_not_empty,
+            explicit_value, merge_args, merge_by_key, merge_errors, merge_errors_vecs, merge_json,
+            merge_log_targets, merge_unique, parse_log_targets, resolve_command_env,
+        },
+        node::{self, merge_nodes, NodeConfig, NodeConfigBuilder},
         resources::{Resources, ResourcesBuilder},
         types::{
-            Arg, AssetLocation, Chain, ChainDefaultContext, Command, Image, ValidationContext, U128,
+            Arg, AssetLocation, BootnodeStrategy, Chain, ChainDefaultContext, ChainSpecModifier,
+            Command, Image, LogTarget, ValidationContext, U128,
         },
     },
     types::CommandWithCustomArgs,
@@ -30,7 +38,11 @@ pub enum RegistrationStrategy {
     /// The parachain will be registered using an extrinsic after spawning.
     UsingExtrinsic,
     /// The parachaing will not be registered and the user can doit after spawning manually.
+    /// The genesis artifacts (state/wasm) are still generated, to be used for the registration.
     Manual,
+    /// The parachain will never be registered, only the collators are spawned (e.g. to test the
+    /// pre-onboarding behavior). Unlike `Manual`, the genesis artifacts are not generated.
+    None,
 }
 
 impl Serialize for RegistrationStrategy {
@@ -47,6 +59,7 @@ impl Serialize for RegistrationStrategy {
                 state.serialize_field("add_to_genesis", &false)?;
                 state.serialize_field("register_para", &false)?;
             },
+            Self::None => state.serialize_field("skip_registration", &true)?,
         }
 
         state.end()
@@ -68,20 +81,32 @@ impl<'de> Visitor<'de> for RegistrationStrategyVisitor {
     {
         let mut add_to_genesis = false;
         let mut register_para = false;
+        let mut skip_registration = false;
 
         while let Some(key) = map.next_key::<String>()? {
             match key.as_str() {
                 "addToGenesis" | "add_to_genesis" => add_to_genesis = map.next_value()?,
                 "registerPara" | "register_para" => register_para = map.next_value()?,
+                "skipRegistration" | "skip_registration" => skip_registration = map.next_value()?,
                 _ => {
                     return Err(de::Error::unknown_field(
                         &key,
-                        &["add_to_genesis", "register_para"],
+                        &["add_to_genesis", "register_para", "skip_registration"],
                     ))
                 },
             }
         }
 
+        if skip_registration {
+            return if add_to_genesis || register_para {
+                Err(de::Error::custom(
+                    "skip_registration can't be used with add_to_genesis or register_para",
+                ))
+            } else {
+                Ok(RegistrationStrategy::None)
+            };
+        }
+
         match (add_to_genesis, register_para) {
             (true, false) => Ok(RegistrationStrategy::InGenesis),
             (false, true) => Ok(RegistrationStrategy::UsingExtrinsic),
@@ -97,15 +122,35 @@ impl<'de> Deserialize<'de> for RegistrationStrategy {
     {
         deserializer.deserialize_struct(
             "RegistrationStrategy",
-            &["add_to_genesis", "register_para"],
+            &["add_to_genesis", "register_para", "skip_registration"],
             RegistrationStrategyVisitor,
         )
     }
 }
 
+// Shape of the (de)serialized registration strategy, only used to generate the schema.
+#[allow(dead_code)]
+#[derive(JsonSchema)]
+struct RegistrationStrategySchema {
+    add_to_genesis: Option<bool>,
+    register_para: Option<bool>,
+    skip_registration: Option<bool>,
+}
+
+impl JsonSchema for RegistrationStrategy {
+    fn schema_name() -> String {
+        "RegistrationStrategy".to_string()
+    }
+
+    fn json_schema(gen: &mut SchemaGenerator) -> Schema {
+        RegistrationStrategySchema::json_schema(gen)
+    }
+}
+
 /// A parachain configuration, composed of collators and fine-grained configuration options.
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
 pub struct ParachainConfig {
+    #[schemars(range(min = 1))]
     id: u32,
     chain: Option<Chain>,
     #[serde(flatten)]
@@ -115,19 +160,46 @@ pub struct ParachainConfig {
         default = "default_as_true"
     )]
     onboard_as_parachain: bool,
+    // Seed (`0x` prefixed 32 bytes hex) of the account used to register the parachain,
+    // never serialized to keep it out of the dumped configs
+    #[serde(skip_serializing, default)]
+    registration_seed: Option<String>,
+    // Wait until the registration extrinsic is finalized (instead of only included)
+    #[serde(skip_serializing_if = "is_false", default)]
+    wait_for_finalization: bool,
     #[serde(rename = "balance", default = "default_initial_balance")]
     initial_balance: U128,
     default_command: Option<Command>,
+    // Env var to read the default command from at spawn time, `default_command` is the fallback
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    default_command_env: Option<String>,
     default_image: Option<Image>,
     default_resources: Option<Resources>,
     default_db_snapshot: Option<AssetLocation>,
     #[serde(skip_serializing_if = "std::vec::Vec::is_empty", default)]
     default_args: Vec<Arg>,
+    // Log targets compiled into the nodes `-l` flag, merged with the node ones
+    #[serde(skip_serializing_if = "std::vec::Vec::is_empty", default)]
+    default_log_targets: Vec<LogTarget>,
     genesis_wasm_path: Option<AssetLocation>,
     genesis_wasm_generator: Option<Command>,
     genesis_state_path: Option<AssetLocation>,
     genesis_state_generator: Option<CommandWithCustomArgs>,
     chain_spec_path: Option<AssetLocation>,
+    // Runtime (wasm) to use instead of the one in the chain-spec
+    #[serde(skip_serializing_if = "Option::is_none")]
+    wasm_override: Option<AssetLocation>,
+    // Raw storage entries (key/value) to inject in the raw chain-spec
+    #[serde(skip_serializing_if = "std::vec::Vec::is_empty", default)]
+    genesis_raw_entries: Vec<(String, String)>,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    bootnode_strategy: Option<BootnodeStrategy>,
+    // Properties (e.g. `tokenDecimals`) to set in the chain-spec
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    chain_properties: Option<ChainProperties>,
+    // Closure to post-process the generated chain-spec (only available through the builder)
+    #[serde(skip)]
+    chain_spec_modifier: Option<ChainSpecModifier>,
     // Full _template_ command, will be rendered using [tera]
     // and executed for generate the chain-spec.
     // available tokens {{chainName}} / {{disableBootnodes}}
@@ -140,7 +212,16 @@ pub struct ParachainConfig {
     #[serde(rename = "evm_based", default = "default_as_false")]
     is_evm_based: bool,
     #[serde(skip_serializing_if = "std::vec::Vec::is_empty", default)]
+    #[schemars(with = "Vec<String>")]
     bootnodes_addresses: Vec<Multiaddr>,
+    // External relaychain rpc the (cumulus) collators connect to, instead of running
+    // an embedded relaychain node
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    #[schemars(with = "Option<String>")]
+    relay_rpc_url: Option<Url>,
+    // Relaychain node whose rpc the (cumulus) collators connect to
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    relay_rpc_node: Option<String>,
     #[serde(rename = "genesis", skip_serializing_if = "Option::is_none")]
     genesis_overrides: Option<serde_json::Value>,
     #[serde(skip_serializing_if = "std::vec::Vec::is_empty", default)]
@@ -175,6 +256,17 @@ impl ParachainConfig {
         self.onboard_as_parachain
     }
 
+    /// The seed (`0x` prefixed 32 bytes hex) of the account used to register the parachain
+    /// with an extrinsic (default to `//Alice`).
+    pub fn registration_seed(&self) -> Option<&str> {
+        self.registration_seed.as_deref()
+    }
+
+    /// Whether the registration (with an extrinsic) should wait until the block is finalized.
+    pub fn wait_for_finalization(&self) -> bool {
+        self.wait_for_finalization
+    }
+
     /// The initial balance of the parachain account.
     pub fn initial_balance(&self) -> u128 {
         self.initial_balance.0
@@ -185,6 +277,11 @@ impl ParachainConfig {
         self.default_command.as_ref()
     }
 
+    /// The env var to read the default command from at spawn time.
+    pub fn default_command_env(&self) -> Option<&str> {
+        self.default_command_env.as_deref()
+    }
+
     /// The default container image used for collators.
     pub fn default_image(&self) -> Option<&Image> {
         self.default_image.as_ref()
@@ -205,6 +302,11 @@ impl ParachainConfig {
         self.default_args.iter().collect::<Vec<&Arg>>()
     }
 
+    /// The default log targets (with their level) of the nodes.
+    pub fn default_log_targets(&self) -> Vec<&LogTarget> {
+        self.default_log_targets.iter().collect()
+    }
+
     /// The location of a pre-existing genesis WASM runtime blob of the parachain.
     pub fn genesis_wasm_path(&self) -> Option<&AssetLocation> {
         self.genesis_wasm_path.as_ref()
@@ -235,6 +337,34 @@ impl ParachainConfig {
         self.chain_spec_path.as_ref()
     }
 
+    /// The location of the runtime (wasm) to use instead of the one in the chain-spec.
+    pub fn wasm_override(&self) -> Option<&AssetLocation> {
+        self.wasm_override.as_ref()
+    }
+
+    /// The raw storage entries (key/value) to inject in the raw chain-spec.
+    pub fn genesis_raw_entries(&self) -> Vec<(&str, &str)> {
+        self.genesis_raw_entries
+            .iter()
+            .map(|(key, value)| (key.as_str(), value.as_str()))
+            .collect()
+    }
+
+    /// The strategy used to select the bootnodes between the collators (default to [`BootnodeStrategy::FirstValidator`]).
+    pub fn bootnode_strategy(&self) -> BootnodeStrategy {
+        self.bootnode_strategy.unwrap_or_default()
+    }
+
+    /// The properties (e.g. `tokenDecimals`) to set in the chain-spec.
+    pub fn chain_properties(&self) -> Option<&ChainProperties> {
+        self.chain_properties.as_ref()
+    }
+
+    /// The closure used to post-process the generated chain-spec.
+    pub fn chain_spec_modifier(&self) -> Option<&ChainSpecModifier> {
+        self.chain_spec_modifier.as_ref()
+    }
+
     /// The full _template_ command to genera the chain-spec
     pub fn chain_spec_command(&self) -> Option<&str> {
         self.chain_spec_command.as_deref()
@@ -260,6 +390,16 @@ impl ParachainConfig {
         self.bootnodes_addresses.iter().collect::<Vec<_>>()
     }
 
+    /// External relaychain rpc url the collators connect to (`--relay-chain-rpc-url`).
+    pub fn relay_rpc_url(&self) -> Option<&Url> {
+        self.relay_rpc_url.as_ref()
+    }
+
+    /// Name of the relaychain node whose rpc the collators connect to (`--relay-chain-rpc-url`).
+    pub fn relay_rpc_node(&self) -> Option<&str> {
+        self.relay_rpc_node.as_deref()
+    }
+
     /// The collators of the parachain.
     pub fn collators(&self) -> Vec<&NodeConfig> {
         let mut cols = self.collators.iter().collect::<Vec<_>>();
@@ -268,6 +408,145 @@ impl ParachainConfig {
         }
         cols
     }
+
+    // The given collators include the single (v1) collator, if any.
+    pub(crate) fn set_collators(&mut self, collators: Vec<NodeConfig>) {
+        self.collators = collators;
+        self.collator = None;
+    }
+
+    fn chain_context(&self) -> ChainDefaultContext {
+        ChainDefaultContext {
+            default_command: self.default_command.clone(),
+            default_image: self.default_image.clone(),
+            default_resources: self.default_resources.clone(),
+            default_db_snapshot: self.default_db_snapshot.clone(),
+            default_args: self.default_args.clone(),
+        }
+    }
+
+    /// Resolve the commands set from env vars (`default_command_env` and the collators
+    /// `command_env`), returning the config with the effective commands. Collators using the
+    /// default command follow the resolved one.
+    pub fn with_resolved_commands(&self) -> Result<Self, anyhow::Error> {
+        let current_context = self.chain_context();
+        let mut resolved = self.clone();
+        resolved.default_command = resolve_command_env(
+            self.default_command_env.as_deref(),
+            self.default_command.as_ref(),
+        )
+        .map_err(|error| {
+            ConfigError::Parachain(self.id, FieldError::DefaultCommand(error).into())
+        })?;
+
+        let resolved_context = resolved.chain_context();
+        let resolve = |collator: NodeConfig| {
+            let name = collator.name().to_string();
+            collator
+                .rebase(&current_context, &resolved_context)
+                .resolve_command_env()
+                .map_err(|error| {
+                    ConfigError::Parachain(self.id, ConfigError::Collator(name, error).into())
+                })
+        };
+        resolved.collators = self
+            .collators
+            .iter()
+            .cloned()
+            .map(resolve)
+            .collect::<Result<_, _>>()?;
+        resolved.collator = self.collator.clone().map(resolve).transpose()?;
+
+        Ok(resolved)
+    }
+
+    /// Merge the `overlay` parachain into this one, the `overlay` values win
+    /// unless they are unset (or hold the default value) and collators are matched by name.
+    pub(crate) fn merge(self, overlay: ParachainConfig) -> Self {
+        let base_context = self.chain_context();
+        let overlay_context = overlay.chain_context();
+        // the single (v1) collator is merged as any other one
+        let base_collators = self.collators.into_iter().chain(self.collator).collect();
+        let overlay_collators = overlay
+            .collators
+            .into_iter()
+            .chain(overlay.collator)
+            .collect();
+
+        let mut merged = Self {
+            id: self.id,
+            chain: overlay.chain.or(self.chain),
+            registration_strategy: explicit_value(
+                overlay.registration_strategy,
+                &Some(RegistrationStrategy::InGenesis),
+            )
+            .or(self.registration_strategy),
+            onboard_as_parachain: self.onboard_as_parachain && overlay.onboard_as_parachain,
+            registration_seed: overlay.registration_seed.or(self.registration_seed),
+            wait_for_finalization: self.wait_for_finalization || overlay.wait_for_finalization,
+            initial_balance: if overlay.initial_balance == default_initial_balance() {
+                self.initial_balance
+            } else {
+                overlay.initial_balance
+            },
+            default_command: overlay.default_command.or(self.default_command),
+            default_command_env: overlay.default_command_env.or(self.default_command_env),
+            default_image: overlay.default_image.or(self.default_image),
+            default_resources: overlay.default_resources.or(self.default_resources),
+            default_db_snapshot: overlay.default_db_snapshot.or(self.default_db_snapshot),
+            default_args: merge_args(self.default_args, overlay.default_args),
+            default_log_targets: merge_log_targets(
+                self.default_log_targets,
+                overlay.default_log_targets,
+            ),
+            genesis_wasm_path: overlay.genesis_wasm_path.or(self.genesis_wasm_path),
+            genesis_wasm_generator: overlay
+                .genesis_wasm_generator
+                .or(self.genesis_wasm_generator),
+            genesis_state_path: overlay.genesis_state_path.or(self.genesis_state_path),
+            genesis_state_generator: overlay
+                .genesis_state_generator
+                .or(self.genesis_state_generator),
+            chain_spec_path: overlay.chain_spec_path.or(self.chain_spec_path),
+            wasm_override: overlay.wasm_override.or(self.wasm_override),
+            genesis_raw_entries: merge_by_key(
+                self.genesis_raw_entries,
+                overlay.genesis_raw_entries,
+                |(key, _)| key.clone(),
+                |_, entry| entry,
+            ),
+            bootnode_strategy: overlay.bootnode_strategy.or(self.bootnode_strategy),
+            chain_properties: overlay.chain_properties.or(self.chain_properties),
+            chain_spec_modifier: overlay.chain_spec_modifier.or(self.chain_spec_modifier),
+            chain_spec_command: overlay.chain_spec_command.or(self.chain_spec_command),
+            chain_spec_command_is_local: self.chain_spec_command_is_local
+                || overlay.chain_spec_command_is_local,
+            is_cumulus_based: self.is_cumulus_based && overlay.is_cumulus_based,
+            is_evm_based: self.is_evm_based || overlay.is_evm_based,
+            bootnodes_addresses: merge_unique(
+                self.bootnodes_addresses,
+                overlay.bootnodes_addresses,
+            ),
+            relay_rpc_url: overlay.relay_rpc_url.or(self.relay_rpc_url),
+            relay_rpc_node: overlay.relay_rpc_node.or(self.relay_rpc_node),
+            genesis_overrides: match (self.genesis_overrides, overlay.genesis_overrides) {
+                (Some(base), Some(overlay)) => Some(merge_json(base, overlay)),
+                (base, overlay) => overlay.or(base),
+            },
+            collators: vec![],
+            collator: None,
+        };
+
+        merged.collators = merge_nodes(
+            base_collators,
+            overlay_collators,
+            &base_context,
+            &overlay_context,
+            &merged.chain_context(),
+        );
+
+        merged
+    }
 }
 
 pub mod states {
@@ -290,6 +569,70 @@ pub mod states {
 }
 
 use states::{Bootstrap, Context, Initial, Running, WithAtLeastOneCollator, WithId};
+// The embedded relaychain node (args after `--` or replaced by an external rpc)
+// only exists in cumulus collators.
+pub(crate) fn ensure_cumulus_only_fields(config: &ParachainConfig) -> Vec<anyhow::Error> {
+    let mut errors = vec![];
+    if config.is_cumulus_based {
+        return errors;
+    }
+
+    if config.relay_rpc_url.is_some() {
+        errors.push(FieldError::RelayRpcUrl(ValidationError::RelayRpcNotAllowed().into()).into());
+    }
+
+    if config.relay_rpc_node.is_some() {
+        errors.push(FieldError::RelayRpcNode(ValidationError::RelayRpcNotAllowed().into()).into());
+    }
+
+    for collator in &config.collators {
+        if !collator.relay_args().is_empty() {
+            errors.push(
+                ConfigError::Collator(
+                    collator.name().to_string(),
+                    FieldError::RelayArgs(ValidationError::RelayArgsNotAllowed().into()).into(),
+                )
+                .into(),
+            );
+        }
+    }
+
+    errors
+}
+
+// Collators using an external relay rpc don't start the embedded relaychain node, so the
+// args for it (after `--` or in `relay_args`) would be silently dropped.
+pub(crate) fn ensure_no_relay_node_args(config: &ParachainConfig) -> Vec<anyhow::Error> {
+    if config.relay_rpc_url.is_none() && config.relay_rpc_node.is_none() {
+        return vec![];
+    }
+
+    let separator = Arg::Flag("--".into());
+    let mut errors = vec![];
+    for collator in &config.collators {
+        let mut collator_errors = vec![];
+        if collator.args().contains(&&separator) {
+            collator_errors.push(FieldError::Args(
+                ValidationError::RelayNodeArgsWithRelayRpc("the args after `--`").into(),
+            ));
+        }
+
+        if !collator.relay_args().is_empty() {
+            collator_errors.push(FieldError::RelayArgs(
+                ValidationError::RelayNodeArgsWithRelayRpc("the relay args").into(),
+            ));
+        }
+
+        errors.extend(
+            collator_errors.into_iter().map(|error| {
+                ConfigError::Collator(collator.name().to_string(), error.into()).into()
+            }),
+        );
+    }
+
+    errors
+}
+
 /// A parachain configuration builder, used to build a [`ParachainConfig`] declaratively with fields validation.
 pub struct ParachainConfigBuilder<S, C> {
     config: ParachainConfig,
@@ -307,23 +650,34 @@ impl<C: Context> Default for ParachainConfigBuilder<Initial, C> {
                 chain: None,
                 registration_strategy: Some(RegistrationStrategy::InGenesis),
                 onboard_as_parachain: true,
+                registration_seed: None,
+                wait_for_finalization: false,
                 initial_balance: 2_000_000_000_000.into(),
                 default_command: None,
+                default_command_env: None,
                 default_image: None,
                 default_resources: None,
                 default_db_snapshot: None,
                 default_args: vec![],
+                default_log_targets: vec![],
                 genesis_wasm_path: None,
                 genesis_wasm_generator: None,
                 genesis_state_path: None,
                 genesis_state_generator: None,
                 genesis_overrides: None,
                 chain_spec_path: None,
+                wasm_override: None,
+                genesis_raw_entries: vec![],
+                bootnode_strategy: None,
+                chain_properties: None,
+                chain_spec_modifier: None,
                 chain_spec_command: None,
                 chain_spec_command_is_local: false, // remote by default
                 is_cumulus_based: true,
                 is_evm_based: false,
                 bootnodes_addresses: vec![],
+                relay_rpc_url: None,
+                relay_rpc_node: None,
                 collators: vec![],
                 collator: None,
             },
@@ -404,16 +758,16 @@ impl ParachainConfigBuilder<WithId, Running> {
                     .into(),
                 ),
             ),
-            RegistrationStrategy::Manual | RegistrationStrategy::UsingExtrinsic => {
-                Self::transition(
-                    ParachainConfig {
-                        registration_strategy: Some(strategy),
-                        ..self.config
-                    },
-                    self.validation_context,
-                    self.errors,
-                )
-            },
+            RegistrationStrategy::Manual
+            | RegistrationStrategy::UsingExtrinsic
+            | RegistrationStrategy::None => Self::transition(
+                ParachainConfig {
+                    registration_strategy: Some(strategy),
+                    ..self.config
+                },
+                self.validation_context,
+                self.errors,
+            ),
         }
     }
 }
@@ -483,6 +837,45 @@ impl<C: Context> ParachainConfigBuilder<WithId, C> {
         )
     }
 
+    /// Set the seed (`0x` prefixed 32 bytes hex) of the account used to register the parachain
+    /// with an extrinsic.
+    pub fn with_registration_seed(self, seed: impl Into<String>) -> Self {
+        let seed: String = seed.into();
+        let is_valid = ensure_hex_prefixed(&seed).is_ok() && seed.len() == 66;
+
+        if is_valid {
+            Self::transition(
+                ParachainConfig {
+                    registration_seed: Some(seed),
+                    ..self.config
+                },
+                self.validation_context,
+                self.errors,
+            )
+        } else {
+            Self::transition(
+                self.config,
+                self.validation_context,
+                merge_errors(
+                    self.errors,
+                    FieldError::RegistrationSeed(ValidationError::InvalidSeed(seed).into()).into(),
+                ),
+            )
+        }
+    }
+
+    /// Set whether the registration (with an extrinsic) should wait until the block is finalized.
+    pub fn wait_for_finalization(self, choice: bool) -> Self {
+        Self::transition(
+            ParachainConfig {
+                wait_for_finalization: choice,
+                ..self.config
+            },
+            self.validation_context,
+            self.errors,
+        )
+    }
+
     /// Set the initial balance of the parachain account.
     pub fn with_initial_balance(self, initial_balance: u128) -> Self {
         Self::transition(
@@ -518,6 +911,31 @@ impl<C: Context> ParachainConfigBuilder<WithId, C> {
         }
     }
 
+    /// Set the env var to read the default command from at spawn time, the default command is
+    /// used as fallback if the env var is unset.
+    pub fn with_default_command_env<T>(self, env_var: T) -> Self
+    where
+        T: Into<String>,
+    {
+        let env_var: String = env_var.into();
+
+        match ensure_value_is_not_empty(&env_var) {
+            Ok(_) => Self::transition(
+                ParachainConfig {
+                    default_command_env: Some(env_var),
+                    ..self.config
+                },
+                self.validation_context,
+                self.errors,
+            ),
+            Err(error) => Self::transition(
+                self.config,
+                self.validation_context,
+                merge_errors(self.errors, FieldError::DefaultCommandEnv(error).into()),
+            ),
+        }
+    }
+
     /// Set the default container image used for collators. Can be overridden.
     pub fn with_default_image<T>(self, image: T) -> Self
     where
@@ -593,7 +1011,24 @@ impl<C: Context> ParachainConfigBuilder<WithId, C> {
         )
     }
 
+    /// Set the default log targets (e.g. `("parachain", "debug")`) compiled into the nodes
+    /// `-l` flag, merged with the node ones (the node level wins for the same target).
+    pub fn with_default_log_targets(self, log_targets: Vec<(&str, &str)>) -> Self {
+        let (default_log_targets, errors) =
+            parse_log_targets(log_targets, FieldError::DefaultLogTarget);
+
+        Self::transition(
+            ParachainConfig {
+                default_log_targets,
+                ..self.config
+            },
+            self.validation_context,
+            merge_errors_vecs(self.errors, errors),
+        )
+    }
+
     /// Set the location of a pre-existing genesis WASM runtime blob of the parachain.
+    /// The file (hex encoded) is used as is for the registration, skipping the generation.
     pub fn with_genesis_wasm_path(self, location: impl Into<AssetLocation>) -> Self {
         Self::transition(
             ParachainConfig {
@@ -632,6 +1067,7 @@ impl<C: Context> ParachainConfigBuilder<WithId, C> {
     }
 
     /// Set the location of a pre-existing genesis state of the parachain.
+    /// The file (hex encoded) is used as is for the registration, skipping the generation.
     pub fn with_genesis_state_path(self, location: impl Into<AssetLocation>) -> Self {
         Self::transition(
             ParachainConfig {
@@ -693,6 +1129,129 @@ impl<C: Context> ParachainConfigBuilder<WithId, C> {
         )
     }
 
+    /// Set the content of a pre-existing (json) chain specification for the parachain,
+    /// e.g. when the chain-spec is only available in an env var.
+    pub fn with_chain_spec_inline(self, chain_spec: impl Into<String>) -> Self {
+        let chain_spec = chain_spec.into();
+
+        match serde_json::from_str::<serde_json::Value>(&chain_spec) {
+            Ok(_) => Self::transition(
+                ParachainConfig {
+                    chain_spec_path: Some(AssetLocation::InlineJson(chain_spec)),
+                    ..self.config
+                },
+                self.validation_context,
+                self.errors,
+            ),
+            Err(error) => Self::transition(
+                self.config,
+                self.validation_context,
+                merge_errors(self.errors, FieldError::ChainSpecPath(error.into()).into()),
+            ),
+        }
+    }
+
+    /// Set the location of the runtime (wasm) to override the one in the chain-spec.
+    /// Remote (url) locations are cached in the base dir.
+    pub fn with_wasm_override(self, location: impl Into<AssetLocation>) -> Self {
+        Self::transition(
+            ParachainConfig {
+                wasm_override: Some(location.into()),
+                ..self.config
+            },
+            self.validation_context,
+            self.errors,
+        )
+    }
+
+    /// Set raw storage entries (`0x` prefixed hex key/value) to inject in the raw chain-spec
+    /// (`/genesis/raw/top`), replacing the existing values for the same keys.
+    pub fn with_genesis_raw_entries<K, V>(self, entries: Vec<(K, V)>) -> Self
+    where
+        K: Into<String>,
+        V: Into<String>,
+    {
+        let raw_entries: Vec<(String, String)> = entries
+            .into_iter()
+            .map(|(key, value)| (key.into(), value.into()))
+            .collect();
+        let errors = ensure_genesis_raw_entries(
+            raw_entries
+                .iter()
+                .map(|(key, value)| (key.as_str(), value.as_str())),
+        );
+
+        Self::transition(
+            ParachainConfig {
+                genesis_raw_entries: raw_entries,
+                ..self.config
+            },
+            self.validation_context,
+            merge_errors_vecs(self.errors, errors),
+        )
+    }
+
+    /// Set the strategy used to select the bootnodes between the collators.
+    pub fn with_bootnode_strategy(self, strategy: BootnodeStrategy) -> Self {
+        Self::transition(
+            ParachainConfig {
+                bootnode_strategy: Some(strategy),
+                ..self.config
+            },
+            self.validation_context,
+            self.errors,
+        )
+    }
+
+    /// Set the properties of the chain-spec (e.g. `tokenDecimals`, `tokenSymbol`, `ss58Format`),
+    /// written in `/properties` while customizing the plain chain-spec.
+    pub fn with_chain_properties(
+        self,
+        f: impl FnOnce(ChainPropertiesBuilder) -> ChainPropertiesBuilder,
+    ) -> Self {
+        match f(ChainPropertiesBuilder::new()).build() {
+            Ok(chain_properties) => Self::transition(
+                ParachainConfig {
+                    chain_properties: Some(chain_properties),
+                    ..self.config
+                },
+                self.validation_context,
+                self.errors,
+            ),
+            Err(errors) => Self::transition(
+                self.config,
+                self.validation_context,
+                merge_errors_vecs(
+                    self.errors,
+                    errors
+                        .into_iter()
+                        .map(|error| FieldError::ChainProperties(error).into())
+                        .collect::<Vec<_>>(),
+                ),
+            ),
+        }
+    }
+
+    /// Set a closure to post-process the generated chain-spec (e.g. to set a custom top-level
+    /// property), as an escape hatch for the customizations not covered by the other options.
+    ///
+    /// The closure runs on the plain chain-spec json after the built-in customization
+    /// (e.g. genesis overrides, authorities) and before the raw conversion. It's not
+    /// applied to chain-specs provided in raw format.
+    pub fn with_chain_spec_modifier(
+        self,
+        modifier: impl Fn(&mut serde_json::Value) + Send + Sync + 'static,
+    ) -> Self {
+        Self::transition(
+            ParachainConfig {
+                chain_spec_modifier: Some(ChainSpecModifier::new(modifier)),
+                ..self.config
+            },
+            self.validation_context,
+            self.errors,
+        )
+    }
+
     /// Set the chain-spec command _template_ for the relay chain.
     pub fn with_chain_spec_command(self, cmd_template: impl Into<String>) -> Self {
         Self::transition(
@@ -769,6 +1328,51 @@ impl<C: Context> ParachainConfigBuilder<WithId, C> {
         )
     }
 
+    /// Set an external relaychain rpc (`ws://` or `wss://`) the collators connect to
+    /// (`--relay-chain-rpc-url`), instead of running an embedded relaychain node.
+    /// Only allowed on cumulus based parachains.
+    pub fn with_relay_rpc_url(self, relay_rpc_url: &str) -> Self {
+        match Url::parse(relay_rpc_url) {
+            Ok(url) if matches!(url.scheme(), "ws" | "wss") => Self::transition(
+                ParachainConfig {
+                    relay_rpc_url: Some(url),
+                    ..self.config
+                },
+                self.validation_context,
+                self.errors,
+            ),
+            Ok(url) => Self::transition(
+                self.config,
+                self.validation_context,
+                merge_errors(
+                    self.errors,
+                    FieldError::RelayRpcUrl(anyhow!("'{url}' should use the `ws` or `wss` scheme"))
+                        .into(),
+                ),
+            ),
+            Err(error) => Self::transition(
+                self.config,
+                self.validation_context,
+                merge_errors(self.errors, FieldError::RelayRpcUrl(error.into()).into()),
+            ),
+        }
+    }
+
+    /// Connect the collators to the rpc of the relaychain node `node_name`
+    /// (`--relay-chain-rpc-url`), instead of running an embedded relaychain node.
+    /// The collators are spawned once the relaychain nodes are running.
+    /// Only allowed on cumulus based parachains.
+    pub fn with_relay_rpc_node(self, node_name: impl Into<String>) -> Self {
+        Self::transition(
+            ParachainConfig {
+                relay_rpc_node: Some(node_name.into()),
+                ..self.config
+            },
+            self.validation_context,
+            self.errors,
+        )
+    }
+
     /// Add a new collator using a nested [`NodeConfigBuilder`].
     pub fn with_collator(
         self,
@@ -810,7 +1414,7 @@ impl<C: Context> ParachainConfigBuilder<WithAtLeastOneCollator, C> {
         f: impl FnOnce(NodeConfigBuilder<node::Initial>) -> NodeConfigBuilder<node::Buildable>,
     ) -> Self {
         match f(NodeConfigBuilder::new(
-            ChainDefaultContext::default(),
+            self.default_chain_context(),
             self.validation_context.clone(),
         ))
         .build()
@@ -839,9 +1443,19 @@ impl<C: Context> ParachainConfigBuilder<WithAtLeastOneCollator, C> {
 
     /// Seals the builder and returns a [`ParachainConfig`] if there are no validation errors, else returns errors.
     pub fn build(self) -> Result<ParachainConfig, Vec<anyhow::Error>> {
-        if !self.errors.is_empty() {
-            return Err(self
-                .errors
+        let mut errors = self.errors;
+
+        if self.config.relay_rpc_url.is_some() && self.config.relay_rpc_node.is_some() {
+            errors.push(
+                FieldError::RelayRpcNode(ValidationError::RelayRpcAlreadySet().into()).into(),
+            );
+        }
+
+        errors.extend(ensure_cumulus_only_fields(&self.config));
+        errors.extend(ensure_no_relay_node_args(&self.config));
+
+        if !errors.is_empty() {
+            return Err(errors
                 .into_iter()
                 .map(|error| ConfigError::Parachain(self.config.id, error).into())
                 .collect::<Vec<_>>());
@@ -879,6 +1493,8 @@ mod tests {
             .with_genesis_state_path("./path/to/genesis/state")
             .with_genesis_state_generator("generator_state")
             .with_chain_spec_path("./path/to/chain/spec.json")
+            .with_wasm_override("https://www.urltomywasm.com/runtime.wasm")
+            .with_genesis_raw_entries(vec![("0x3a686561707061676573", "0x08")])
             .cumulus_based(false)
             .evm_based(false)
             .with_bootnodes_addresses(vec![
@@ -938,6 +1554,14 @@ mod tests {
             parachain_config.chain_spec_path().unwrap(),
             AssetLocation::FilePath(value) if value.to_str().unwrap() == "./path/to/chain/spec.json"
         ));
+        assert!(matches!(
+            parachain_config.wasm_override().unwrap(),
+            AssetLocation::Url(value) if value.as_str() == "https://www.urltomywasm.com/runtime.wasm"
+        ));
+        assert_eq!(
+            parachain_config.genesis_raw_entries(),
+            vec![("0x3a686561707061676573", "0x08")]
+        );
         let args: Vec<Arg> = vec![("--arg1", "value1").into(), "--option2".into()];
         assert_eq!(
             parachain_config.default_args(),
@@ -1044,6 +1668,157 @@ mod tests {
         );
     }
 
+    #[test]
+    fn parachain_config_builder_should_succeeds_with_collator_relay_args() {
+        let parachain_config = ParachainConfigBuilder::new(Default::default())
+            .with_id(1000)
+            .with_chain("chain")
+            .with_collator(|collator| {
+                collator
+                    .with_name("collator")
+                    .with_command("command")
+                    .with_args(vec![("-l", "parachain=debug").into()])
+                    .with_relay_args(vec![("--relay-chain-rpc-url", "ws://127.0.0.1:9944").into()])
+            })
+            .build()
+            .unwrap();
+
+        let collator = parachain_config.collators().into_iter().next().unwrap();
+        assert_eq!(
+            collator.relay_args(),
+            vec![&("--relay-chain-rpc-url", "ws://127.0.0.1:9944").into()]
+        );
+        assert_eq!(collator.args(), vec![&("-l", "parachain=debug").into()]);
+    }
+
+    #[test]
+    fn parachain_config_builder_should_fails_and_returns_an_error_if_relay_args_are_set_on_non_cumulus_collators(
+    ) {
+        let errors = ParachainConfigBuilder::new(Default::default())
+            .with_id(1000)
+            .with_chain("chain")
+            .cumulus_based(false)
+            .with_collator(|collator| {
+                collator
+                    .with_name("collator")
+                    .with_command("adder-collator")
+                    .with_relay_args(vec!["--alice".into()])
+            })
+            .build()
+            .unwrap_err();
+
+        assert_eq!(errors.len(), 1);
+        assert_eq!(
+            errors.first().unwrap().to_string(),
+            "parachain[1000].collators['collator'].relay_args: only allowed on the collators of a cumulus based parachain"
+        );
+    }
+
+    #[test]
+    fn parachain_config_builder_should_succeeds_with_relay_rpc() {
+        let parachain_config = ParachainConfigBuilder::new(Default::default())
+            .with_id(1000)
+            .with_chain("chain")
+            .with_relay_rpc_url("wss://rpc.example.com")
+            .with_collator(|collator| collator.with_name("collator").with_command("command"))
+            .build()
+            .unwrap();
+
+        assert_eq!(
+            parachain_config.relay_rpc_url().unwrap().as_str(),
+            "wss://rpc.example.com/"
+        );
+        assert!(parachain_config.relay_rpc_node().is_none());
+
+        let parachain_config = ParachainConfigBuilder::new(Default::default())
+            .with_id(1000)
+            .with_chain("chain")
+            .with_relay_rpc_node("alice")
+            .with_collator(|collator| collator.with_name("collator").with_command("command"))
+            .build()
+            .unwrap();
+
+        assert!(parachain_config.relay_rpc_url().is_none());
+        assert_eq!(parachain_config.relay_rpc_node(), Some("alice"));
+    }
+
+    #[test]
+    fn parachain_config_builder_should_fails_if_both_relay_rpc_url_and_node_are_set() {
+        let errors = ParachainConfigBuilder::new(Default::default())
+            .with_id(1000)
+            .with_chain("chain")
+            .with_relay_rpc_url("wss://rpc.example.com")
+            .with_relay_rpc_node("alice")
+            .with_collator(|collator| collator.with_name("collator").with_command("command"))
+            .build()
+            .unwrap_err();
+
+        assert_eq!(errors.len(), 1);
+        assert_eq!(
+            errors.first().unwrap().to_string(),
+            "parachain[1000].relay_rpc_node: can't be used with `relay_rpc_url`, only one relay rpc can be set"
+        );
+    }
+
+    #[test]
+    fn parachain_config_builder_should_fails_if_relay_node_args_are_set_with_a_relay_rpc() {
+        let errors = ParachainConfigBuilder::new(Default::default())
+            .with_id(1000)
+            .with_chain("chain")
+            .with_relay_rpc_node("alice")
+            .with_collator(|collator| {
+                collator
+                    .with_name("collator")
+                    .with_command("command")
+                    .with_args(vec!["--".into(), "--in-peers=5".into()])
+                    .with_relay_args(vec!["--sync=warp".into()])
+            })
+            .build()
+            .unwrap_err();
+
+        assert_eq!(errors.len(), 2);
+        assert_eq!(
+            errors[0].to_string(),
+            "parachain[1000].collators['collator'].args: the args after `--` can't be used with an external relay rpc (`relay_rpc_url` or `relay_rpc_node`), the embedded relaychain node is not started"
+        );
+        assert_eq!(
+            errors[1].to_string(),
+            "parachain[1000].collators['collator'].relay_args: the relay args can't be used with an external relay rpc (`relay_rpc_url` or `relay_rpc_node`), the embedded relaychain node is not started"
+        );
+    }
+
+    #[test]
+    fn parachain_config_builder_should_fails_and_returns_multiple_errors_if_relay_rpc_is_invalid() {
+        let errors = ParachainConfigBuilder::new(Default::default())
+            .with_id(1000)
+            .with_chain("chain")
+            .with_relay_rpc_url("http://rpc.example.com")
+            .with_collator(|collator| collator.with_name("collator").with_command("command"))
+            .build()
+            .unwrap_err();
+
+        assert_eq!(errors.len(), 1);
+        assert_eq!(
+            errors.first().unwrap().to_string(),
+            "parachain[1000].relay_rpc_url: 'http://rpc.example.com/' should use the `ws` or `wss` scheme"
+        );
+
+        let errors = ParachainConfigBuilder::new(Default::default())
+            .with_id(1000)
+            .with_chain("chain")
+            .cumulus_based(false)
+            .with_relay_rpc_node("alice")
+            .with_collator(|collator| collator.with_name("collator").with_command("command"))
+            .build()
+            .unwrap_err();
+
+        assert_eq!(errors.len(), 1);
+        assert_eq!(
+            errors.first().unwrap().to_string(),
+            "parachain[1000].relay_rpc_node: only allowed on a cumulus based parachain"
+        );
+    }
+
     #[test]
     fn parachain_config_builder_should_fails_and_returns_an_error_if_default_command_is_invalid() {
         let errors = ParachainConfigBuilder::new(Default::default())
@@ -1138,6 +1913,33 @@ mod tests {
         );
     }
 
+    #[test]
+    fn parachain_config_builder_should_fails_and_returns_an_error_if_genesis_raw_entries_are_invalid(
+    ) {
+        let errors = ParachainConfigBuilder::new(Default::default())
+            .with_id(2000)
+            .with_chain("myparachain")
+            .with_genesis_raw_entries(vec![("0x3a636f6465", "0x0"), ("3a636f6465", "0x00")])
+            .with_collator(|collator| {
+                collator
+                    .with_name("collator")
+                    .with_command("command")
+                    .validator(true)
+            })
+            .build()
+            .unwrap_err();
+
+        assert_eq!(errors.len(), 2);
+        assert_eq!(
+            errors.first().unwrap().to_string(),
+            "parachain[2000].genesis_raw_entries[0]: '0x0' should be a `0x` prefixed hex string"
+        );
+        assert_eq!(
+            errors.get(1).unwrap().to_string(),
+            "parachain[2000].genesis_raw_entries[1]: '3a636f6465' should be a `0x` prefixed hex string"
+        );
+    }
+
     #[test]
     fn parachain_config_builder_should_fails_and_returns_an_error_if_bootnodes_addresses_are_invalid(
     ) {
@@ -1300,6 +2102,26 @@ mod tests {
         assert!(parachain_evm.is_evm_based());
     }
 
+    #[test]
+    fn registration_strategy_none_should_serialize_and_deserialize() {
+        let config = ParachainConfigBuilder::new(Default::default())
+            .with_id(2000)
+            .with_registration_strategy(RegistrationStrategy::None)
+            .with_collator(|collator| collator.with_name("collator"))
+            .build()
+            .unwrap();
+
+        let serialized = toml::to_string(config.registration_strategy().unwrap()).unwrap();
+        assert_eq!(serialized, "skip_registration = true\n");
+
+        let deserialized: RegistrationStrategy = toml::from_str(&serialized).unwrap();
+        assert_eq!(deserialized, RegistrationStrategy::None);
+        assert!(toml::from_str::<RegistrationStrategy>(
+            "skip_registration = true\nregister_para = true"
+        )
+        .is_err());
+    }
+
     #[test]
     fn onboard_as_parachain_should_default_to_true() {
         let config = ParachainConfigBuilder::new(Default::default())
@@ -1312,6 +2134,42 @@ mod tests {
         assert!(config.onboard_as_parachain());
     }
 
+    #[test]
+    fn parachain_config_builder_should_works_with_registration_seed_and_finalization() {
+        let seed = "0xe5be9a5092b81bca64be81d212e7f2f9eba183bb7a90954f7b76361f6edb5c0a";
+        let config = ParachainConfigBuilder::new(Default::default())
+            .with_id(2000)
+            .with_chain("myparachain")
+            .with_registration_strategy(RegistrationStrategy::UsingExtrinsic)
+            .with_registration_seed(seed)
+            .wait_for_finalization(true)
+            .with_collator(|collator| collator.with_name("collator"))
+            .build()
+            .unwrap();
+
+        assert_eq!(config.registration_seed(), Some(seed));
+        assert!(config.wait_for_finalization());
+        assert!(!toml::to_string(&config).unwrap().contains(seed));
+    }
+
+    #[test]
+    fn parachain_config_builder_should_fails_and_returns_an_error_if_registration_seed_is_invalid()
+    {
+        let errors = ParachainConfigBuilder::new(Default::default())
+            .with_id(2000)
+            .with_chain("myparachain")
+            .with_registration_seed("//Bob")
+            .with_collator(|collator| collator.with_name("collator"))
+            .build()
+            .unwrap_err();
+
+        assert_eq!(errors.len(), 1);
+        assert_eq!(
+            errors.first().unwrap().to_string(),
+            "parachain[2000].registration_seed: '//Bob' should be a `0x` prefixed 32 bytes hex seed"
+        );
+    }
+
     #[test]
     fn evm_based_default_to_false() {
         let config = ParachainConfigBuilder::new(Default::default())
@@ -1352,6 +2210,44 @@ mod tests {
         );
     }
 
+    #[test]
+    fn parachain_config_builder_should_works_with_chain_spec_inline() {
+        let chain_spec = r#"{"name": "Para Local", "id": "para_local"}"#;
+        let config = ParachainConfigBuilder::new(Default::default())
+            .with_id(2000)
+            .with_chain("some-chain")
+            .with_default_command("default_command")
+            .with_chain_spec_inline(chain_spec)
+            .with_collator(|collator| collator.with_name("collator"))
+            .build()
+            .unwrap();
+
+        assert!(matches!(
+            config.chain_spec_path().unwrap(),
+            AssetLocation::InlineJson(value) if value == chain_spec
+        ));
+    }
+
+    #[test]
+    fn parachain_config_builder_should_fails_and_returns_an_error_if_chain_spec_inline_is_invalid()
+    {
+        let errors = ParachainConfigBuilder::new(Default::default())
+            .with_id(2000)
+            .with_chain("some-chain")
+            .with_default_command("default_command")
+            .with_chain_spec_inline("{ not json")
+            .with_collator(|collator| collator.with_name("collator"))
+            .build()
+            .unwrap_err();
+
+        assert_eq!(errors.len(), 1);
+        assert!(errors
+            .first()
+            .unwrap()
+            .to_string()
+            .starts_with("parachain[2000].chain_spec_path: "));
+    }
+
     #[test]
     fn parachain_config_builder_should_works_with_chain_spec_command() {
         const CMD_TPL: &str = "./bin/chain-spec-generator {% raw %} {{chainName}} {% endraw %}";
@@ -1386,4 +2282,31 @@ mod tests {
         assert_eq!(config.chain_spec_command(), Some(CMD_TPL));
         assert!(config.chain_spec_command_is_local());
     }
+
+    #[test]
+    fn parachain_config_with_resolved_commands_should_fallback_to_the_default_command() {
+        let config = ParachainConfigBuilder::new(Default::default())
+            .with_id(2000)
+            .with_chain("myparachain")
+            .with_default_command("polkadot-parachain")
+            .with_default_command_env("ZOMBIE_TEST_UNSET_PARA_BINARY")
+            .with_collator(|collator| collator.with_name("collator"))
+            .build()
+            .unwrap();
+
+        let resolved = config.with_resolved_commands().unwrap();
+
+        assert_eq!(
+            resolved.default_command().unwrap().as_str(),
+            "polkadot-parachain"
+        );
+        assert_eq!(
+            resolved.collators()[0].command().unwrap().as_str(),
+            "polkadot-parachain"
+        );
+        assert_eq!(
+            resolved.default_command_env(),
+            Some("ZOMBIE_TEST_UNSET_PARA_BINARY")
+        );
+    }
 }
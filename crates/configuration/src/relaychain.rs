@@ -1,34 +1,61 @@
 use std::{cell::RefCell, error::Error, fmt::Debug, marker::PhantomData, rc::Rc};
 
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use support::constants::{DEFAULT_TYPESTATE, THIS_IS_A_BUG};
 
 use crate::{
     shared::{
-        errors::{ConfigError, FieldError},
-        helpers::{merge_errors, merge_errors_vecs},
+        chain_properties::{ChainProperties, ChainPropertiesBuilder},
+        errors::{ConfigError, FieldError, ValidationError},
+        helpers::{
+            ensure_genesis_raw_entries, ensure_value_is_not_empty, explicit_value, merge_args,
+            merge_by_key, merge_errors, merge_errors_vecs, merge_json, merge_log_targets,
+            parse_log_targets, resolve_command_env,
+        },
         macros::states,
-        node::{self, NodeConfig, NodeConfigBuilder},
+        node::{self, merge_nodes, GroupNodeConfigBuilder, NodeConfig, NodeConfigBuilder},
         resources::{Resources, ResourcesBuilder},
         types::{
-            Arg, AssetLocation, Chain, ChainDefaultContext, Command, Image, ValidationContext,
+            Arg, AssetLocation, BootnodeStrategy, Chain, ChainDefaultContext, ChainSpecModifier,
+            Command, Image, LogTarget, ValidationContext,
         },
     },
     utils::{default_command_polkadot, is_false},
 };
 
 /// A relay chain configuration, composed of nodes and fine-grained configuration options.
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
 pub struct RelaychainConfig {
     chain: Chain,
     #[serde(default = "default_command_polkadot")]
     default_command: Option<Command>,
+    // Env var to read the default command from at spawn time, `default_command` is the fallback
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    default_command_env: Option<String>,
     default_image: Option<Image>,
     default_resources: Option<Resources>,
     default_db_snapshot: Option<AssetLocation>,
     #[serde(skip_serializing_if = "std::vec::Vec::is_empty", default)]
     default_args: Vec<Arg>,
+    // Log targets compiled into the nodes `-l` flag, merged with the node ones
+    #[serde(skip_serializing_if = "std::vec::Vec::is_empty", default)]
+    default_log_targets: Vec<LogTarget>,
     chain_spec_path: Option<AssetLocation>,
+    // Runtime (wasm) to use instead of the one in the chain-spec
+    #[serde(skip_serializing_if = "Option::is_none")]
+    wasm_override: Option<AssetLocation>,
+    // Raw storage entries (key/value) to inject in the raw chain-spec
+    #[serde(skip_serializing_if = "std::vec::Vec::is_empty", default)]
+    genesis_raw_entries: Vec<(String, String)>,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    bootnode_strategy: Option<BootnodeStrategy>,
+    // Properties (e.g. `tokenDecimals`) to set in the chain-spec
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    chain_properties: Option<ChainProperties>,
+    // Closure to post-process the generated chain-spec (only available through the builder)
+    #[serde(skip)]
+    chain_spec_modifier: Option<ChainSpecModifier>,
     // Full _template_ command, will be rendered (using custom token replacements)
     // and executed for generate the chain-spec.
     // available tokens {{chainName}} / {{disableBootnodes}}
@@ -55,6 +82,11 @@ impl RelaychainConfig {
         self.default_command.as_ref()
     }
 
+    /// The env var to read the default command from at spawn time.
+    pub fn default_command_env(&self) -> Option<&str> {
+        self.default_command_env.as_deref()
+    }
+
     /// The default container image used for nodes.
     pub fn default_image(&self) -> Option<&Image> {
         self.default_image.as_ref()
@@ -75,11 +107,44 @@ impl RelaychainConfig {
         self.default_args.iter().collect::<Vec<&Arg>>()
     }
 
+    /// The default log targets (with their level) of the nodes.
+    pub fn default_log_targets(&self) -> Vec<&LogTarget> {
+        self.default_log_targets.iter().collect()
+    }
+
     /// The location of an pre-existing chain specification for the relay chain.
     pub fn chain_spec_path(&self) -> Option<&AssetLocation> {
         self.chain_spec_path.as_ref()
     }
 
+    /// The location of the runtime (wasm) to use instead of the one in the chain-spec.
+    pub fn wasm_override(&self) -> Option<&AssetLocation> {
+        self.wasm_override.as_ref()
+    }
+
+    /// The raw storage entries (key/value) to inject in the raw chain-spec.
+    pub fn genesis_raw_entries(&self) -> Vec<(&str, &str)> {
+        self.genesis_raw_entries
+            .iter()
+            .map(|(key, value)| (key.as_str(), value.as_str()))
+            .collect()
+    }
+
+    /// The strategy used to select the bootnodes between the nodes (default to [`BootnodeStrategy::FirstValidator`]).
+    pub fn bootnode_strategy(&self) -> BootnodeStrategy {
+        self.bootnode_strategy.unwrap_or_default()
+    }
+
+    /// The properties (e.g. `tokenDecimals`) to set in the chain-spec.
+    pub fn chain_properties(&self) -> Option<&ChainProperties> {
+        self.chain_properties.as_ref()
+    }
+
+    /// The closure used to post-process the generated chain-spec.
+    pub fn chain_spec_modifier(&self) -> Option<&ChainSpecModifier> {
+        self.chain_spec_modifier.as_ref()
+    }
+
     /// The full _template_ command to genera the chain-spec
     pub fn chain_spec_command(&self) -> Option<&str> {
         self.chain_spec_command.as_deref()
@@ -118,6 +183,102 @@ impl RelaychainConfig {
     pub(crate) fn set_nodes(&mut self, nodes: Vec<NodeConfig>) {
         self.nodes = nodes;
     }
+
+    fn chain_context(&self) -> ChainDefaultContext {
+        ChainDefaultContext {
+            default_command: self.default_command.clone(),
+            default_image: self.default_image.clone(),
+            default_resources: self.default_resources.clone(),
+            default_db_snapshot: self.default_db_snapshot.clone(),
+            default_args: self.default_args.clone(),
+        }
+    }
+
+    /// Resolve the commands set from env vars (`default_command_env` and the nodes
+    /// `command_env`), returning the config with the effective commands. Nodes using the
+    /// default command follow the resolved one.
+    pub fn with_resolved_commands(&self) -> Result<Self, anyhow::Error> {
+        let current_context = self.chain_context();
+        let mut resolved = self.clone();
+        resolved.default_command = resolve_command_env(
+            self.default_command_env.as_deref(),
+            self.default_command.as_ref(),
+        )
+        .map_err(|error| ConfigError::Relaychain(FieldError::DefaultCommand(error).into()))?;
+
+        let resolved_context = resolved.chain_context();
+        resolved.nodes = self
+            .nodes
+            .iter()
+            .cloned()
+            .map(|node| {
+                let name = node.name().to_string();
+                node.rebase(&current_context, &resolved_context)
+                    .resolve_command_env()
+                    .map_err(|error| ConfigError::Relaychain(ConfigError::Node(name, error).into()))
+            })
+            .collect::<Result<_, _>>()?;
+
+        Ok(resolved)
+    }
+
+    /// Merge the `overlay` relay chain into this one, the `overlay` values win
+    /// unless they are unset and nodes are matched by name.
+    pub(crate) fn merge(self, overlay: RelaychainConfig) -> Self {
+        let base_context = self.chain_context();
+        let overlay_context = overlay.chain_context();
+
+        let mut merged = Self {
+            chain: overlay.chain,
+            // `polkadot` is the (deserialization) default, so we don't override an explicit one
+            default_command: explicit_value(overlay.default_command, &default_command_polkadot())
+                .or(self.default_command),
+            default_command_env: overlay.default_command_env.or(self.default_command_env),
+            default_image: overlay.default_image.or(self.default_image),
+            default_resources: overlay.default_resources.or(self.default_resources),
+            default_db_snapshot: overlay.default_db_snapshot.or(self.default_db_snapshot),
+            default_args: merge_args(self.default_args, overlay.default_args),
+            default_log_targets: merge_log_targets(
+                self.default_log_targets,
+                overlay.default_log_targets,
+            ),
+            chain_spec_path: overlay.chain_spec_path.or(self.chain_spec_path),
+            wasm_override: overlay.wasm_override.or(self.wasm_override),
+            genesis_raw_entries: merge_by_key(
+                self.genesis_raw_entries,
+                overlay.genesis_raw_entries,
+                |(key, _)| key.clone(),
+                |_, entry| entry,
+            ),
+            bootnode_strategy: overlay.bootnode_strategy.or(self.bootnode_strategy),
+            chain_properties: overlay.chain_properties.or(self.chain_properties),
+            chain_spec_modifier: overlay.chain_spec_modifier.or(self.chain_spec_modifier),
+            chain_spec_command: overlay.chain_spec_command.or(self.chain_spec_command),
+            chain_spec_command_is_local: self.chain_spec_command_is_local
+                || overlay.chain_spec_command_is_local,
+            random_nominators_count: overlay
+                .random_nominators_count
+                .or(self.random_nominators_count),
+            max_nominations: overlay.max_nominations.or(self.max_nominations),
+            nodes: vec![],
+            runtime_genesis_patch: match (self.runtime_genesis_patch, overlay.runtime_genesis_patch)
+            {
+                (Some(base), Some(overlay)) => Some(merge_json(base, overlay)),
+                (base, overlay) => overlay.or(base),
+            },
+            command: overlay.command.or(self.command),
+        };
+
+        merged.nodes = merge_nodes(
+            self.nodes,
+            overlay.nodes,
+            &base_context,
+            &overlay_context,
+            &merged.chain_context(),
+        );
+
+        merged
+    }
 }
 
 states! {
@@ -126,6 +287,22 @@ states! {
     WithAtLeastOneNode
 }
 
+// The relay args (after `--`) are only used by the collators of cumulus based parachains.
+pub(crate) fn ensure_no_relay_args(config: &RelaychainConfig) -> Vec<anyhow::Error> {
+    config
+        .nodes
+        .iter()
+        .filter(|node| !node.relay_args().is_empty())
+        .map(|node| {
+            ConfigError::Node(
+                node.name().to_string(),
+                FieldError::RelayArgs(ValidationError::RelayArgsNotAllowed().into()).into(),
+            )
+            .into()
+        })
+        .collect()
+}
+
 /// A relay chain configuration builder, used to build a [`RelaychainConfig`] declaratively with fields validation.
 pub struct RelaychainConfigBuilder<State> {
     config: RelaychainConfig,
@@ -142,11 +319,18 @@ impl Default for RelaychainConfigBuilder<Initial> {
                     .try_into()
                     .expect(&format!("{} {}", DEFAULT_TYPESTATE, THIS_IS_A_BUG)),
                 default_command: None,
+                default_command_env: None,
                 default_image: None,
                 default_resources: None,
                 default_db_snapshot: None,
                 default_args: vec![],
+                default_log_targets: vec![],
                 chain_spec_path: None,
+                wasm_override: None,
+                genesis_raw_entries: vec![],
+                bootnode_strategy: None,
+                chain_properties: None,
+                chain_spec_modifier: None,
                 chain_spec_command: None,
                 chain_spec_command_is_local: false, // remote cmd by default
                 command: None,
@@ -245,6 +429,31 @@ impl RelaychainConfigBuilder<WithChain> {
         }
     }
 
+    /// Set the env var (e.g. `POLKADOT_BINARY`) to read the default command from at spawn time,
+    /// the default command is used as fallback if the env var is unset.
+    pub fn with_default_command_env<T>(self, env_var: T) -> Self
+    where
+        T: Into<String>,
+    {
+        let env_var: String = env_var.into();
+
+        match ensure_value_is_not_empty(&env_var) {
+            Ok(_) => Self::transition(
+                RelaychainConfig {
+                    default_command_env: Some(env_var),
+                    ..self.config
+                },
+                self.validation_context,
+                self.errors,
+            ),
+            Err(error) => Self::transition(
+                self.config,
+                self.validation_context,
+                merge_errors(self.errors, FieldError::DefaultCommandEnv(error).into()),
+            ),
+        }
+    }
+
     /// Set the default container image used for nodes. Can be overridden.
     pub fn with_default_image<T>(self, image: T) -> Self
     where
@@ -320,6 +529,22 @@ impl RelaychainConfigBuilder<WithChain> {
         )
     }
 
+    /// Set the default log targets (e.g. `("parachain", "debug")`) compiled into the nodes
+    /// `-l` flag, merged with the node ones (the node level wins for the same target).
+    pub fn with_default_log_targets(self, log_targets: Vec<(&str, &str)>) -> Self {
+        let (default_log_targets, errors) =
+            parse_log_targets(log_targets, FieldError::DefaultLogTarget);
+
+        Self::transition(
+            RelaychainConfig {
+                default_log_targets,
+                ..self.config
+            },
+            self.validation_context,
+            merge_errors_vecs(self.errors, errors),
+        )
+    }
+
     /// Set the location of a pre-existing chain specification for the relay chain.
     pub fn with_chain_spec_path(self, location: impl Into<AssetLocation>) -> Self {
         Self::transition(
@@ -332,6 +557,129 @@ impl RelaychainConfigBuilder<WithChain> {
         )
     }
 
+    /// Set the content of a pre-existing (json) chain specification for the relay chain,
+    /// e.g. when the chain-spec is only available in an env var.
+    pub fn with_chain_spec_inline(self, chain_spec: impl Into<String>) -> Self {
+        let chain_spec = chain_spec.into();
+
+        match serde_json::from_str::<serde_json::Value>(&chain_spec) {
+            Ok(_) => Self::transition(
+                RelaychainConfig {
+                    chain_spec_path: Some(AssetLocation::InlineJson(chain_spec)),
+                    ..self.config
+                },
+                self.validation_context,
+                self.errors,
+            ),
+            Err(error) => Self::transition(
+                self.config,
+                self.validation_context,
+                merge_errors(self.errors, FieldError::ChainSpecPath(error.into()).into()),
+            ),
+        }
+    }
+
+    /// Set the location of the runtime (wasm) to override the one in the chain-spec.
+    /// Remote (url) locations are cached in the base dir.
+    pub fn with_wasm_override(self, location: impl Into<AssetLocation>) -> Self {
+        Self::transition(
+            RelaychainConfig {
+                wasm_override: Some(location.into()),
+                ..self.config
+            },
+            self.validation_context,
+            self.errors,
+        )
+    }
+
+    /// Set raw storage entries (`0x` prefixed hex key/value) to inject in the raw chain-spec
+    /// (`/genesis/raw/top`), replacing the existing values for the same keys.
+    pub fn with_genesis_raw_entries<K, V>(self, entries: Vec<(K, V)>) -> Self
+    where
+        K: Into<String>,
+        V: Into<String>,
+    {
+        let raw_entries: Vec<(String, String)> = entries
+            .into_iter()
+            .map(|(key, value)| (key.into(), value.into()))
+            .collect();
+        let errors = ensure_genesis_raw_entries(
+            raw_entries
+                .iter()
+                .map(|(key, value)| (key.as_str(), value.as_str())),
+        );
+
+        Self::transition(
+            RelaychainConfig {
+                genesis_raw_entries: raw_entries,
+                ..self.config
+            },
+            self.validation_context,
+            merge_errors_vecs(self.errors, errors),
+        )
+    }
+
+    /// Set the strategy used to select the bootnodes between the nodes.
+    pub fn with_bootnode_strategy(self, strategy: BootnodeStrategy) -> Self {
+        Self::transition(
+            RelaychainConfig {
+                bootnode_strategy: Some(strategy),
+                ..self.config
+            },
+            self.validation_context,
+            self.errors,
+        )
+    }
+
+    /// Set the properties of the chain-spec (e.g. `tokenDecimals`, `tokenSymbol`, `ss58Format`),
+    /// written in `/properties` while customizing the plain chain-spec.
+    pub fn with_chain_properties(
+        self,
+        f: impl FnOnce(ChainPropertiesBuilder) -> ChainPropertiesBuilder,
+    ) -> Self {
+        match f(ChainPropertiesBuilder::new()).build() {
+            Ok(chain_properties) => Self::transition(
+                RelaychainConfig {
+                    chain_properties: Some(chain_properties),
+                    ..self.config
+                },
+                self.validation_context,
+                self.errors,
+            ),
+            Err(errors) => Self::transition(
+                self.config,
+                self.validation_context,
+                merge_errors_vecs(
+                    self.errors,
+                    errors
+                        .into_iter()
+                        .map(|error| FieldError::ChainProperties(error).into())
+                        .collect::<Vec<_>>(),
+                ),
+            ),
+        }
+    }
+
+    /// Set a closure to post-process the generated chain-spec (e.g. to set a custom top-level
+    /// property), as an escape hatch for the customizations not covered by the other options.
+    ///
+    /// The closure runs on the plain chain-spec json after the built-in customization
+    /// (e.g. genesis overrides, authorities) and before the raw conversion. It's not
+    /// applied to chain-specs provided in raw format.
+    pub fn with_chain_spec_modifier(
+        self,
+        modifier: impl Fn(&mut serde_json::Value) + Send + Sync + 'static,
+    ) -> Self {
+        Self::transition(
+            RelaychainConfig {
+                chain_spec_modifier: Some(ChainSpecModifier::new(modifier)),
+                ..self.config
+            },
+            self.validation_context,
+            self.errors,
+        )
+    }
+
     /// Set the chain-spec command _template_ for the relay chain.
     pub fn with_chain_spec_command(self, cmd_template: impl Into<String>) -> Self {
         Self::transition(
@@ -424,6 +772,41 @@ impl RelaychainConfigBuilder<WithChain> {
             ),
         }
     }
+
+    /// Add a group of validators using a nested [`GroupNodeConfigBuilder`], the validators
+    /// are named `<name_prefix>-<index>` (e.g. `validator-0`, `validator-1`).
+    pub fn with_validator_group(
+        self,
+        f: impl FnOnce(GroupNodeConfigBuilder<node::Initial>) -> GroupNodeConfigBuilder<node::Buildable>,
+    ) -> RelaychainConfigBuilder<WithAtLeastOneNode> {
+        match f(GroupNodeConfigBuilder::new(
+            self.default_chain_context(),
+            self.validation_context.clone(),
+        ))
+        .validator(true)
+        .build()
+        {
+            Ok(nodes) => Self::transition(
+                RelaychainConfig {
+                    nodes,
+                    ..self.config
+                },
+                self.validation_context,
+                self.errors,
+            ),
+            Err((name, errors)) => Self::transition(
+                self.config,
+                self.validation_context,
+                merge_errors_vecs(
+                    self.errors,
+                    errors
+                        .into_iter()
+                        .map(|error| ConfigError::Node(name.clone(), error).into())
+                        .collect::<Vec<_>>(),
+                ),
+            ),
+        }
+    }
 }
 
 impl RelaychainConfigBuilder<WithAtLeastOneNode> {
@@ -460,11 +843,47 @@ impl RelaychainConfigBuilder<WithAtLeastOneNode> {
         }
     }
 
+    /// Add a group of validators using a nested [`GroupNodeConfigBuilder`], the validators
+    /// are named `<name_prefix>-<index>` (e.g. `validator-0`, `validator-1`).
+    pub fn with_validator_group(
+        self,
+        f: impl FnOnce(GroupNodeConfigBuilder<node::Initial>) -> GroupNodeConfigBuilder<node::Buildable>,
+    ) -> Self {
+        match f(GroupNodeConfigBuilder::new(
+            self.default_chain_context(),
+            self.validation_context.clone(),
+        ))
+        .validator(true)
+        .build()
+        {
+            Ok(nodes) => Self::transition(
+                RelaychainConfig {
+                    nodes: [self.config.nodes, nodes].concat(),
+                    ..self.config
+                },
+                self.validation_context,
+                self.errors,
+            ),
+            Err((name, errors)) => Self::transition(
+                self.config,
+                self.validation_context,
+                merge_errors_vecs(
+                    self.errors,
+                    errors
+                        .into_iter()
+                        .map(|error| ConfigError::Node(name.clone(), error).into())
+                        .collect::<Vec<_>>(),
+                ),
+            ),
+        }
+    }
+
     /// Seals the builder and returns a [`RelaychainConfig`] if there are no validation errors, else returns errors.
     pub fn build(self) -> Result<RelaychainConfig, Vec<anyhow::Error>> {
-        if !self.errors.is_empty() {
-            return Err(self
-                .errors
+        let errors = merge_errors_vecs(self.errors, ensure_no_relay_args(&self.config));
+
+        if !errors.is_empty() {
+            return Err(errors
                 .into_iter()
                 .map(|error| ConfigError::Relaychain(error).into())
                 .collect::<Vec<_>>());
@@ -492,6 +911,8 @@ mod tests {
             })
             .with_default_db_snapshot("https://www.urltomysnapshot.com/file.tgz")
             .with_chain_spec_path("./path/to/chain/spec.json")
+            .with_wasm_override("https://www.urltomywasm.com/runtime.wasm")
+            .with_genesis_raw_entries(vec![("0x3a686561707061676573", "0x08")])
             .with_default_args(vec![("--arg1", "value1").into(), "--option2".into()])
             .with_random_nominators_count(42)
             .with_max_nominations(5)
@@ -534,6 +955,14 @@ mod tests {
             relaychain_config.chain_spec_path().unwrap(),
             AssetLocation::FilePath(value) if value.to_str().unwrap() == "./path/to/chain/spec.json"
         ));
+        assert!(matches!(
+            relaychain_config.wasm_override().unwrap(),
+            AssetLocation::Url(value) if value.as_str() == "https://www.urltomywasm.com/runtime.wasm"
+        ));
+        assert_eq!(
+            relaychain_config.genesis_raw_entries(),
+            vec![("0x3a686561707061676573", "0x08")]
+        );
         let args: Vec<Arg> = vec![("--arg1", "value1").into(), "--option2".into()];
         assert_eq!(
             relaychain_config.default_args(),
@@ -562,6 +991,71 @@ mod tests {
         );
     }
 
+    #[test]
+    fn relaychain_config_builder_should_fails_and_returns_an_error_if_genesis_raw_entries_are_invalid(
+    ) {
+        let errors = RelaychainConfigBuilder::new(Default::default())
+            .with_chain("chain")
+            .with_genesis_raw_entries(vec![("0x3a636f6465", "0x0"), ("3a636f6465", "0x00")])
+            .with_node(|node| {
+                node.with_name("node")
+                    .with_command("command")
+                    .validator(true)
+            })
+            .build()
+            .unwrap_err();
+
+        assert_eq!(errors.len(), 2);
+        assert_eq!(
+            errors.first().unwrap().to_string(),
+            "relaychain.genesis_raw_entries[0]: '0x0' should be a `0x` prefixed hex string"
+        );
+        assert_eq!(
+            errors.get(1).unwrap().to_string(),
+            "relaychain.genesis_raw_entries[1]: '3a636f6465' should be a `0x` prefixed hex string"
+        );
+    }
+
+    #[test]
+    fn relaychain_config_builder_should_works_with_chain_spec_inline() {
+        let chain_spec = r#"{"name": "Local Testnet", "id": "local_testnet"}"#;
+        let relaychain_config = RelaychainConfigBuilder::new(Default::default())
+            .with_chain("chain")
+            .with_chain_spec_inline(chain_spec)
+            .with_node(|node| node.with_name("node").with_command("command"))
+            .build()
+            .unwrap();
+
+        assert!(matches!(
+            relaychain_config.chain_spec_path().unwrap(),
+            AssetLocation::InlineJson(value) if value == chain_spec
+        ));
+
+        // the inline content is kept when the location is serialized
+        let location = relaychain_config.chain_spec_path().unwrap();
+        let serialized = serde_json::to_string(location).unwrap();
+        let deserialized: AssetLocation = serde_json::from_str(&serialized).unwrap();
+        assert_eq!(&deserialized, location);
+    }
+
+    #[test]
+    fn relaychain_config_builder_should_fails_and_returns_an_error_if_chain_spec_inline_is_invalid()
+    {
+        let errors = RelaychainConfigBuilder::new(Default::default())
+            .with_chain("chain")
+            .with_chain_spec_inline("{ not json")
+            .with_node(|node| node.with_name("node").with_command("command"))
+            .build()
+            .unwrap_err();
+
+        assert_eq!(errors.len(), 1);
+        assert!(errors
+            .first()
+            .unwrap()
+            .to_string()
+            .starts_with("relaychain.chain_spec_path: "));
+    }
+
     #[test]
     fn relaychain_config_builder_should_fails_and_returns_an_error_if_default_command_is_invalid() {
         let errors = RelaychainConfigBuilder::new(Default::default())
@@ -582,6 +1076,27 @@ mod tests {
         );
     }
 
+    #[test]
+    fn relaychain_config_builder_should_fails_and_returns_an_error_if_default_log_targets_are_invalid(
+    ) {
+        let errors = RelaychainConfigBuilder::new(Default::default())
+            .with_chain("chain")
+            .with_default_log_targets(vec![("parachain", "debug"), ("sync", "verbose")])
+            .with_node(|node| {
+                node.with_name("node")
+                    .with_command("command")
+                    .validator(true)
+            })
+            .build()
+            .unwrap_err();
+
+        assert_eq!(errors.len(), 1);
+        assert_eq!(
+            errors.first().unwrap().to_string(),
+            "relaychain.default_log_targets[1]: 'sync=verbose' 'verbose' doesn't match regex '^(error|warn|info|debug|trace)$'"
+        );
+    }
+
     #[test]
     fn relaychain_config_builder_should_fails_and_returns_an_error_if_default_image_is_invalid() {
         let errors = RelaychainConfigBuilder::new(Default::default())
@@ -733,4 +1248,177 @@ mod tests {
         assert_eq!(config.chain_spec_command(), Some(CMD_TPL));
         assert!(config.chain_spec_command_is_local());
     }
+
+    #[test]
+    fn relaychain_config_with_resolved_commands_should_read_the_commands_from_env() {
+        std::env::set_var("ZOMBIE_TEST_RELAY_BINARY", "/opt/bin/polkadot");
+        let config = RelaychainConfigBuilder::new(Default::default())
+            .with_chain("polkadot")
+            .with_default_command("polkadot")
+            .with_default_command_env("ZOMBIE_TEST_RELAY_BINARY")
+            .with_node(|node| node.with_name("alice"))
+            .with_node(|node| node.with_name("bob").with_command("polkadot-custom"))
+            .with_node(|node| {
+                node.with_name("charlie")
+                    .with_command("polkadot-fallback")
+                    .with_command_env("ZOMBIE_TEST_UNSET_RELAY_BINARY")
+            })
+            .build()
+            .unwrap();
+
+        let resolved = config.with_resolved_commands().unwrap();
+
+        assert_eq!(
+            resolved.default_command().unwrap().as_str(),
+            "/opt/bin/polkadot"
+        );
+        let commands: Vec<&str> = resolved
+            .nodes()
+            .iter()
+            .map(|node| node.command().unwrap().as_str())
+            .collect();
+        assert_eq!(
+            commands,
+            vec!["/opt/bin/polkadot", "polkadot-custom", "polkadot-fallback"]
+        );
+    }
+
+    #[test]
+    fn relaychain_config_with_resolved_commands_should_fails_if_the_env_is_unset_without_fallback()
+    {
+        let config = RelaychainConfigBuilder::new(Default::default())
+            .with_chain("polkadot")
+            .with_node(|node| {
+                node.with_name("alice")
+                    .with_command_env("ZOMBIE_TEST_UNSET_BINARY")
+            })
+            .build()
+            .unwrap();
+
+        let error = config.with_resolved_commands().unwrap_err();
+
+        assert_eq!(
+            error.to_string(),
+            "relaychain.nodes['alice'].command: env var 'ZOMBIE_TEST_UNSET_BINARY' is not set and there is no fallback command"
+        );
+    }
+
+    #[test]
+    fn relaychain_config_builder_should_works_with_validator_group() {
+        let config = RelaychainConfigBuilder::new(Default::default())
+            .with_chain("polkadot")
+            .with_default_command("polkadot")
+            .with_node(|node| node.with_name("alice").bootnode(true))
+            .with_validator_group(|group| group.with_name_prefix("validator").with_count(3))
+            .build()
+            .unwrap();
+
+        let names: Vec<&str> = config.nodes().iter().map(|node| node.name()).collect();
+        assert_eq!(
+            names,
+            vec!["alice", "validator-0", "validator-1", "validator-2"]
+        );
+        assert!(config.nodes().iter().all(|node| node.is_validator()));
+    }
+
+    #[test]
+    fn relaychain_config_builder_should_fails_if_validator_group_count_is_zero() {
+        let errors = RelaychainConfigBuilder::new(Default::default())
+            .with_chain("polkadot")
+            .with_default_command("polkadot")
+            .with_validator_group(|group| group.with_name_prefix("validator").with_count(0))
+            .build()
+            .unwrap_err();
+
+        assert_eq!(errors.len(), 1);
+        assert_eq!(
+            errors.first().unwrap().to_string(),
+            "relaychain.nodes['validator'].count: can't be zero"
+        );
+    }
+
+    #[test]
+    fn relaychain_config_builder_should_works_with_bootnode_strategy() {
+        let config = RelaychainConfigBuilder::new(Default::default())
+            .with_chain("polkadot")
+            .with_default_command("polkadot")
+            .with_bootnode_strategy(BootnodeStrategy::AllValidators)
+            .with_node(|node| node.with_name("alice"))
+            .build()
+            .unwrap();
+
+        assert_eq!(config.bootnode_strategy(), BootnodeStrategy::AllValidators);
+        assert_eq!(
+            serde_json::to_value(config.bootnode_strategy()).unwrap(),
+            serde_json::json!("all_validators")
+        );
+    }
+
+    #[test]
+    fn relaychain_config_bootnode_strategy_should_default_to_first_validator() {
+        let config = RelaychainConfigBuilder::new(Default::default())
+            .with_chain("polkadot")
+            .with_node(|node| node.with_name("alice"))
+            .build()
+            .unwrap();
+
+        assert_eq!(config.bootnode_strategy(), BootnodeStrategy::FirstValidator);
+    }
+
+    #[test]
+    fn relaychain_config_builder_should_works_with_chain_spec_modifier() {
+        let config = RelaychainConfigBuilder::new(Default::default())
+            .with_chain("polkadot")
+            .with_default_command("polkadot")
+            .with_chain_spec_modifier(|spec| {
+                spec["properties"]["ss58Format"] = serde_json::json!(42);
+            })
+            .with_node(|node| node.with_name("alice"))
+            .build()
+            .unwrap();
+
+        let mut spec = serde_json::json!({ "name": "polkadot" });
+        config.chain_spec_modifier().unwrap().apply(&mut spec);
+        assert_eq!(spec["properties"]["ss58Format"], serde_json::json!(42));
+        // the closure is never serialized
+        assert!(!toml::to_string(&config).unwrap().contains("modifier"));
+    }
+
+    #[test]
+    fn relaychain_config_builder_should_works_with_chain_properties() {
+        let config = RelaychainConfigBuilder::new(Default::default())
+            .with_chain("polkadot")
+            .with_default_command("polkadot")
+            .with_chain_properties(|p| {
+                p.with_token_decimals(10)
+                    .with_token_symbol("UNIT")
+                    .with_ss58_prefix(42)
+            })
+            .with_node(|node| node.with_name("alice"))
+            .build()
+            .unwrap();
+
+        let properties = config.chain_properties().unwrap();
+        assert_eq!(properties.token_decimals(), Some(10));
+        assert_eq!(properties.token_symbol(), Some("UNIT"));
+        assert_eq!(properties.ss58_prefix(), Some(42));
+    }
+
+    #[test]
+    fn relaychain_config_builder_should_fails_and_returns_an_error_if_chain_properties_are_invalid()
+    {
+        let errors = RelaychainConfigBuilder::new(Default::default())
+            .with_chain("polkadot")
+            .with_default_command("polkadot")
+            .with_chain_properties(|p| p.with_token_decimals(300))
+            .with_node(|node| node.with_name("alice"))
+            .build()
+            .unwrap_err();
+
+        assert_eq!(errors.len(), 1);
+        assert_eq!(
+            errors.first().unwrap().to_string(),
+            "relaychain.chain_properties.token_decimals: '300' doesn't fit in a u8"
+        );
+    }
 }
@@ -1,3 +1,4 @@
+pub mod chain_properties;
 pub mod errors;
 pub mod helpers;
 pub mod macros;
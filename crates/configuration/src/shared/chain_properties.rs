@@ -0,0 +1,171 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use super::{
+    errors::{ConversionError, FieldError},
+    helpers::merge_errors,
+};
+
+/// Chain properties (`/properties` of the chain-spec) to set while customizing the chain-spec.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct ChainProperties {
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    token_decimals: Option<u8>,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    token_symbol: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    ss58_prefix: Option<u16>,
+}
+
+impl ChainProperties {
+    /// Amount of decimals of the native token (`tokenDecimals`).
+    pub fn token_decimals(&self) -> Option<u8> {
+        self.token_decimals
+    }
+
+    /// Symbol of the native token (`tokenSymbol`).
+    pub fn token_symbol(&self) -> Option<&str> {
+        self.token_symbol.as_deref()
+    }
+
+    /// SS58 address format (`ss58Format`).
+    pub fn ss58_prefix(&self) -> Option<u16> {
+        self.ss58_prefix
+    }
+}
+
+/// A chain properties builder, used to build [`ChainProperties`] declaratively with fields validation.
+#[derive(Debug, Default)]
+pub struct ChainPropertiesBuilder {
+    config: ChainProperties,
+    errors: Vec<anyhow::Error>,
+}
+
+impl ChainPropertiesBuilder {
+    pub fn new() -> ChainPropertiesBuilder {
+        Self::default()
+    }
+
+    fn transition(config: ChainProperties, errors: Vec<anyhow::Error>) -> Self {
+        Self { config, errors }
+    }
+
+    /// Set the amount of decimals of the native token (should fit in a `u8`).
+    pub fn with_token_decimals(self, token_decimals: u32) -> Self {
+        match u8::try_from(token_decimals) {
+            Ok(token_decimals) => Self::transition(
+                ChainProperties {
+                    token_decimals: Some(token_decimals),
+                    ..self.config
+                },
+                self.errors,
+            ),
+            Err(_) => Self::transition(
+                self.config,
+                merge_errors(
+                    self.errors,
+                    FieldError::TokenDecimals(anyhow::anyhow!(
+                        "'{token_decimals}' doesn't fit in a u8"
+                    ))
+                    .into(),
+                ),
+            ),
+        }
+    }
+
+    /// Set the symbol of the native token (e.g. `UNIT`).
+    pub fn with_token_symbol(self, token_symbol: impl Into<String>) -> Self {
+        let token_symbol: String = token_symbol.into();
+        if token_symbol.trim().is_empty() {
+            return Self::transition(
+                self.config,
+                merge_errors(
+                    self.errors,
+                    FieldError::TokenSymbol(ConversionError::CantBeEmpty.into()).into(),
+                ),
+            );
+        }
+
+        Self::transition(
+            ChainProperties {
+                token_symbol: Some(token_symbol),
+                ..self.config
+            },
+            self.errors,
+        )
+    }
+
+    /// Set the SS58 address format (should fit in a `u16`).
+    pub fn with_ss58_prefix(self, ss58_prefix: u32) -> Self {
+        match u16::try_from(ss58_prefix) {
+            Ok(ss58_prefix) => Self::transition(
+                ChainProperties {
+                    ss58_prefix: Some(ss58_prefix),
+                    ..self.config
+                },
+                self.errors,
+            ),
+            Err(_) => Self::transition(
+                self.config,
+                merge_errors(
+                    self.errors,
+                    FieldError::Ss58Prefix(anyhow::anyhow!("'{ss58_prefix}' doesn't fit in a u16"))
+                        .into(),
+                ),
+            ),
+        }
+    }
+
+    /// Seals the builder and returns a [`ChainProperties`] if there are no validation errors, else returns errors.
+    pub fn build(self) -> Result<ChainProperties, Vec<anyhow::Error>> {
+        if !self.errors.is_empty() {
+            return Err(self.errors);
+        }
+
+        Ok(self.config)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn chain_properties_builder_should_succeeds_and_returns_chain_properties() {
+        let properties = ChainPropertiesBuilder::new()
+            .with_token_decimals(10)
+            .with_token_symbol("UNIT")
+            .with_ss58_prefix(42)
+            .build()
+            .unwrap();
+
+        assert_eq!(properties.token_decimals(), Some(10));
+        assert_eq!(properties.token_symbol(), Some("UNIT"));
+        assert_eq!(properties.ss58_prefix(), Some(42));
+    }
+
+    #[test]
+    fn chain_properties_builder_should_fails_and_returns_multiple_errors_if_values_are_out_of_range(
+    ) {
+        let errors = ChainPropertiesBuilder::new()
+            .with_token_decimals(256)
+            .with_token_symbol("")
+            .with_ss58_prefix(70_000)
+            .build()
+            .unwrap_err();
+
+        assert_eq!(errors.len(), 3);
+        assert_eq!(
+            errors.first().unwrap().to_string(),
+            "token_decimals: '256' doesn't fit in a u8"
+        );
+        assert_eq!(
+            errors.get(1).unwrap().to_string(),
+            "token_symbol: can't be empty"
+        );
+        assert_eq!(
+            errors.get(2).unwrap().to_string(),
+            "ss58_prefix: '70000' doesn't fit in a u16"
+        );
+    }
+}
@@ -17,6 +17,25 @@ pub enum ConfigError {
 
     #[error("collators['{0}'].{1}")]
     Collator(String, anyhow::Error),
+
+    #[error("hrmp_channels[{0}].{1}")]
+    HrmpChannel(usize, anyhow::Error),
+}
+
+/// An error while merging an overlay into a base network configuration.
+#[derive(thiserror::Error, Debug)]
+pub enum MergeError {
+    #[error("node '{0}' is defined more than once in the {1} config, nodes are matched by name so the merge is ambiguous")]
+    DuplicatedNodeName(String, &'static str),
+
+    #[error("parachain '{0}' is defined more than once in the {1} config, parachains are matched by id so the merge is ambiguous")]
+    DuplicatedParaId(ParaId, &'static str),
+
+    #[error("node '{0}' belongs to {1} in the base config but to {2} in the overlay, nodes are only matched by name within the same chain")]
+    NodeChainMismatch(String, String, String),
+
+    #[error("config '{0}' extends itself ({1}), `extends` cycles are not allowed")]
+    ExtendsCycle(String, String),
 }
 
 /// An error at the field level.
@@ -25,6 +44,9 @@ pub enum FieldError {
     #[error("name: {0}")]
     Name(anyhow::Error),
 
+    #[error("id: {0}")]
+    Id(anyhow::Error),
+
     #[error("chain: {0}")]
     Chain(anyhow::Error),
 
@@ -34,15 +56,30 @@ pub enum FieldError {
     #[error("default_image: {0}")]
     DefaultImage(anyhow::Error),
 
+    #[error("spec_builder_image: {0}")]
+    SpecBuilderImage(anyhow::Error),
+
     #[error("command: {0}")]
     Command(anyhow::Error),
 
     #[error("default_command: {0}")]
     DefaultCommand(anyhow::Error),
 
+    #[error("command_env: {0}")]
+    CommandEnv(anyhow::Error),
+
+    #[error("default_command_env: {0}")]
+    DefaultCommandEnv(anyhow::Error),
+
     #[error("bootnodes_addresses[{0}]: '{1}' {2}")]
     BootnodesAddress(usize, String, anyhow::Error),
 
+    #[error("log_targets[{0}]: '{1}' {2}")]
+    LogTarget(usize, String, anyhow::Error),
+
+    #[error("default_log_targets[{0}]: '{1}' {2}")]
+    DefaultLogTarget(usize, String, anyhow::Error),
+
     #[error("genesis_wasm_generator: {0}")]
     GenesisWasmGenerator(anyhow::Error),
 
@@ -58,6 +95,18 @@ pub enum FieldError {
     #[error("resources.{0}")]
     Resources(anyhow::Error),
 
+    #[error("chain_properties.{0}")]
+    ChainProperties(anyhow::Error),
+
+    #[error("token_decimals: {0}")]
+    TokenDecimals(anyhow::Error),
+
+    #[error("token_symbol: {0}")]
+    TokenSymbol(anyhow::Error),
+
+    #[error("ss58_prefix: {0}")]
+    Ss58Prefix(anyhow::Error),
+
     #[error("request_memory: {0}")]
     RequestMemory(anyhow::Error),
 
@@ -70,6 +119,9 @@ pub enum FieldError {
     #[error("limit_cpu: {0}")]
     LimitCpu(anyhow::Error),
 
+    #[error("limit_address_space: {0}")]
+    LimitAddressSpace(anyhow::Error),
+
     #[error("ws_port: {0}")]
     WsPort(anyhow::Error),
 
@@ -82,8 +134,71 @@ pub enum FieldError {
     #[error("p2p_port: {0}")]
     P2pPort(anyhow::Error),
 
+    #[error("listen_addr: '{0}' {1}")]
+    ListenAddr(String, anyhow::Error),
+
+    #[error("rpc_methods: {0}")]
+    RpcMethods(anyhow::Error),
+
+    #[error("sync_mode: {0}")]
+    SyncMode(anyhow::Error),
+
     #[error("registration_strategy: {0}")]
     RegistrationStrategy(anyhow::Error),
+
+    #[error("count: {0}")]
+    Count(anyhow::Error),
+
+    #[error("port_range: {0}")]
+    PortRange(anyhow::Error),
+
+    #[error("spawn_concurrency: {0}")]
+    SpawnConcurrency(anyhow::Error),
+
+    #[error("log_max_bytes: {0}")]
+    LogMaxBytes(anyhow::Error),
+
+    #[error("namespace_prefix: {0}")]
+    NamespacePrefix(anyhow::Error),
+
+    #[error("stop_grace: {0}")]
+    StopGrace(anyhow::Error),
+
+    #[error("args: {0}")]
+    Args(anyhow::Error),
+
+    #[error("env: {0}")]
+    Env(anyhow::Error),
+
+    #[error("relay_args: {0}")]
+    RelayArgs(anyhow::Error),
+
+    #[error("relay_rpc_url: {0}")]
+    RelayRpcUrl(anyhow::Error),
+
+    #[error("relay_rpc_node: {0}")]
+    RelayRpcNode(anyhow::Error),
+
+    #[error("genesis_raw_entries[{0}]: '{1}' {2}")]
+    GenesisRawEntry(usize, String, anyhow::Error),
+
+    #[error("chain_spec_path: {0}")]
+    ChainSpecPath(anyhow::Error),
+
+    #[error("keystore_keys[{0}]: '{1}' {2}")]
+    KeystoreKey(usize, String, anyhow::Error),
+
+    #[error("injected_files[{0}]: '{1}' {2}")]
+    InjectedFile(usize, String, anyhow::Error),
+
+    #[error("registration_seed: {0}")]
+    RegistrationSeed(anyhow::Error),
+
+    #[error("sender: {0}")]
+    Sender(anyhow::Error),
+
+    #[error("recipient: {0}")]
+    Recipient(anyhow::Error),
 }
 
 /// A conversion error for shared types across fields.
@@ -111,6 +226,74 @@ pub enum ValidationError {
     #[error("'{0}' is already used across config")]
     NodeNameAlreadyUsed(String),
 
+    #[error("'{0}' is already used across config")]
+    ParaIdAlreadyUsed(ParaId),
+
     #[error("can't be empty")]
     CantBeEmpty(),
+
+    #[error("can't be zero")]
+    CantBeZero(),
+
+    #[error("[{0}, {0} + {1}) exceeds the max port")]
+    PortRangeOverflow(Port, u16),
+
+    #[error("should be a `0x` prefixed hex string")]
+    InvalidHex(),
+
+    #[error("should be a 4 chars crypto id")]
+    InvalidKeyType(),
+
+    #[error("'{0}' should be pinned by digest (e.g. 'name@sha256:<digest>')")]
+    ImageNotPinned(String),
+
+    #[error("'{0}' should be a `0x` prefixed 32 bytes hex seed")]
+    InvalidSeed(String),
+
+    #[error("'{0}' is not the id of a parachain in the config")]
+    UnknownParaId(ParaId),
+
+    #[error("'{0}' is not the name of a relaychain node in the config")]
+    UnknownRelayNode(String),
+
+    #[error("'{0}' references a node that is not in the config")]
+    UnknownNodeReference(String),
+
+    #[error("doesn't exist")]
+    PathNotFound(),
+
+    #[error("should be an absolute path")]
+    PathNotAbsolute(),
+
+    #[error("should be an `/ip4/<ip>/tcp/<port>` (or `/ip6`) multiaddr")]
+    InvalidListenAddr(),
+
+    #[error("`--rpc-external` can only be used with the `safe` methods, use `--unsafe-rpc-external` to expose the unsafe ones")]
+    RpcExternalRequiresSafeMethods(),
+
+    #[error("can't be used with a `--sync` arg")]
+    SyncModeAlreadySet(),
+
+    #[error("only allowed on the collators of a cumulus based parachain")]
+    RelayArgsNotAllowed(),
+
+    #[error("only allowed on a cumulus based parachain")]
+    RelayRpcNotAllowed(),
+
+    #[error("can't be used with `relay_rpc_url`, only one relay rpc can be set")]
+    RelayRpcAlreadySet(),
+
+    #[error("{0} can't be used with an external relay rpc (`relay_rpc_url` or `relay_rpc_node`), the embedded relaychain node is not started")]
+    RelayNodeArgsWithRelayRpc(&'static str),
+
+    #[error("'{0}' should be a DNS label of at most {1} chars (lowercase alphanumerics or '-', starting and ending with an alphanumeric)")]
+    InvalidDnsLabel(String, usize),
+
+    #[error("env var '{0}' is not set and there is no fallback command")]
+    CommandEnvNotSet(String),
+
+    #[error(
+        "'{0}' conflicts with the command parts (ending with '{1}'), only one of them can be set"
+    )]
+    CommandConflictsWithCommandParts(String, String),
 }
@@ -1,10 +1,14 @@
 use std::{cell::RefCell, rc::Rc};
 
-use support::constants::{BORROWABLE, THIS_IS_A_BUG};
+use multiaddr::{Multiaddr, Protocol};
+use support::{
+    constants::{BORROWABLE, THIS_IS_A_BUG},
+    replacer::ZOMBIE_TOKEN_RE,
+};
 
 use super::{
-    errors::ValidationError,
-    types::{Port, ValidationContext},
+    errors::{FieldError, ValidationError},
+    types::{Arg, Command, LogTarget, Port, ValidationContext},
 };
 
 pub fn merge_errors(errors: Vec<anyhow::Error>, new_error: anyhow::Error) -> Vec<anyhow::Error> {
@@ -52,6 +56,40 @@ pub fn ensure_value_is_not_empty(value: &str) -> Result<(), anyhow::Error> {
     }
 }
 
+pub fn ensure_hex_prefixed(value: &str) -> Result<(), anyhow::Error> {
+    match value.strip_prefix("0x") {
+        Some(hex) if hex.len() % 2 == 0 && hex.chars().all(|c| c.is_ascii_hexdigit()) => Ok(()),
+        _ => Err(ValidationError::InvalidHex().into()),
+    }
+}
+
+/// Ensure the keys and values of the raw storage entries are `0x` prefixed hex strings.
+pub(crate) fn ensure_genesis_raw_entries<'a>(
+    entries: impl IntoIterator<Item = (&'a str, &'a str)>,
+) -> Vec<anyhow::Error> {
+    let mut errors = vec![];
+
+    for (index, (key, value)) in entries.into_iter().enumerate() {
+        for item in [key, value] {
+            if let Err(error) = ensure_hex_prefixed(item) {
+                errors.push(FieldError::GenesisRawEntry(index, item.to_string(), error).into());
+            }
+        }
+    }
+
+    errors
+}
+
+/// Ensure the listen address starts with `/ip4/<ip>/tcp/<port>` (or `/ip6`), so the port
+/// can be replaced by the p2p one of the node.
+pub(crate) fn ensure_listen_addr(addr: &Multiaddr) -> Result<(), anyhow::Error> {
+    let mut protocols = addr.iter();
+    match (protocols.next(), protocols.next()) {
+        (Some(Protocol::Ip4(_) | Protocol::Ip6(_)), Some(Protocol::Tcp(_))) => Ok(()),
+        _ => Err(ValidationError::InvalidListenAddr().into()),
+    }
+}
+
 pub fn ensure_port_unique(
     port: Port,
     validation_context: Rc<RefCell<ValidationContext>>,
@@ -67,3 +105,277 @@ pub fn ensure_port_unique(
 
     Err(ValidationError::PortAlreadyUsed(port).into())
 }
+
+/// Resolve the command from the `env_var` (if any) at spawn time, the `fallback` one is used
+/// if the env var is unset (or empty).
+pub(crate) fn resolve_command_env(
+    env_var: Option<&str>,
+    fallback: Option<&Command>,
+) -> Result<Option<Command>, anyhow::Error> {
+    let Some(env_var) = env_var else {
+        return Ok(fallback.cloned());
+    };
+
+    match std::env::var(env_var) {
+        Ok(value) if !value.is_empty() => Ok(Some(value.as_str().try_into()?)),
+        _ => match fallback {
+            Some(command) => Ok(Some(command.clone())),
+            None => Err(ValidationError::CommandEnvNotSet(env_var.to_string()).into()),
+        },
+    }
+}
+
+/// Returns `value` only if it differs from the `default` one (e.g. it was explicitly set).
+pub(crate) fn explicit_value<T: PartialEq>(value: Option<T>, default: &Option<T>) -> Option<T> {
+    if value == *default {
+        None
+    } else {
+        value
+    }
+}
+
+/// Whether the `arg` is dropped by a remove directive of `target`
+/// (matching the flag, the option name or `name=value`).
+fn is_removed_by(arg: &Arg, target: &str) -> bool {
+    match arg {
+        Arg::Flag(flag) => flag == target,
+        Arg::Option(name, value) => name == target || format!("{name}={value}") == target,
+        Arg::Remove(_) => false,
+    }
+}
+
+/// Append the `overlay` arguments to the `base` ones, an option already
+/// present in `base` (matched by name) gets its value replaced and
+/// remove directives drop the matching `base` arguments.
+pub(crate) fn merge_args(base: Vec<Arg>, overlay: Vec<Arg>) -> Vec<Arg> {
+    let mut args = base;
+
+    for arg in overlay {
+        if let Arg::Remove(target) = &arg {
+            args.retain(|current| !is_removed_by(current, target));
+            continue;
+        }
+
+        let existing = args.iter().position(|current| match (current, &arg) {
+            (Arg::Option(current_name, _), Arg::Option(name, _)) => current_name == name,
+            (current, arg) => current == arg,
+        });
+
+        match existing {
+            Some(index) => args[index] = arg,
+            None => args.push(arg),
+        }
+    }
+
+    args
+}
+
+/// Apply the `overrides` to the `defaults` args (see [`merge_args`]), returning the effective args
+/// and the remove directives that don't match any of the `defaults`.
+pub(crate) fn override_args(defaults: Vec<Arg>, overrides: Vec<Arg>) -> (Vec<Arg>, Vec<String>) {
+    let unmatched = overrides
+        .iter()
+        .filter_map(|arg| match arg {
+            Arg::Remove(target) if !defaults.iter().any(|arg| is_removed_by(arg, target)) => {
+                Some(target.clone())
+            },
+            _ => None,
+        })
+        .collect();
+
+    (merge_args(defaults, overrides), unmatched)
+}
+
+/// Convert the `(target, level)` pairs into [`LogTarget`]s, invalid pairs are reported
+/// with the `field_error` built from their index.
+pub(crate) fn parse_log_targets(
+    log_targets: Vec<(&str, &str)>,
+    field_error: impl Fn(usize, String, anyhow::Error) -> FieldError,
+) -> (Vec<LogTarget>, Vec<anyhow::Error>) {
+    let mut targets = vec![];
+    let mut errors = vec![];
+
+    for (index, (target, level)) in log_targets.into_iter().enumerate() {
+        match LogTarget::try_from((target, level)) {
+            Ok(log_target) => targets.push(log_target),
+            Err(error) => {
+                errors.push(field_error(index, format!("{target}={level}"), error.into()).into())
+            },
+        }
+    }
+
+    (targets, errors)
+}
+
+/// Append the `overlay` log targets to the `base` ones, a target already
+/// present in `base` gets its level replaced.
+pub fn merge_log_targets(base: Vec<LogTarget>, overlay: Vec<LogTarget>) -> Vec<LogTarget> {
+    merge_by_key(
+        base,
+        overlay,
+        |log_target| log_target.target().to_string(),
+        |_, log_target| log_target,
+    )
+}
+
+/// Append the `overlay` items not already present in `base`.
+pub(crate) fn merge_unique<T: PartialEq>(base: Vec<T>, overlay: Vec<T>) -> Vec<T> {
+    let mut items = base;
+
+    for item in overlay {
+        if !items.contains(&item) {
+            items.push(item);
+        }
+    }
+
+    items
+}
+
+/// Merge the `overlay` items into the `base` ones matching them by `key`,
+/// `merge` receives the matching `base` item (if any) and the `overlay` one.
+///
+/// The `base` order is preserved and unmatched `overlay` items are appended.
+pub(crate) fn merge_by_key<T, K: PartialEq>(
+    base: Vec<T>,
+    overlay: Vec<T>,
+    key: impl Fn(&T) -> K,
+    merge: impl Fn(Option<T>, T) -> T,
+) -> Vec<T> {
+    let mut base = base.into_iter().map(Some).collect::<Vec<_>>();
+    let mut appended = vec![];
+
+    for item in overlay {
+        let item_key = key(&item);
+        match base.iter().position(|current| {
+            current
+                .as_ref()
+                .is_some_and(|current| key(current) == item_key)
+        }) {
+            Some(index) => base[index] = Some(merge(base[index].take(), item)),
+            None => appended.push(merge(None, item)),
+        }
+    }
+
+    base.into_iter().flatten().chain(appended).collect()
+}
+
+/// Deep merge the `overlay` json value into the `base` one, objects are merged
+/// recursively and any other value is replaced.
+pub(crate) fn merge_json(base: serde_json::Value, overlay: serde_json::Value) -> serde_json::Value {
+    match (base, overlay) {
+        (serde_json::Value::Object(mut base), serde_json::Value::Object(overlay)) => {
+            for (key, value) in overlay {
+                let merged = match base.remove(&key) {
+                    Some(current) => merge_json(current, value),
+                    None => value,
+                };
+                base.insert(key, merged);
+            }
+
+            serde_json::Value::Object(base)
+        },
+        (_, overlay) => overlay,
+    }
+}
+
+/// Get the `(node name, token)` references of the `{{ZOMBIE:<node>:<field>}}` tokens in `text`.
+pub fn zombie_token_references(text: &str) -> Vec<(&str, &str)> {
+    ZOMBIE_TOKEN_RE
+        .captures_iter(text)
+        .filter_map(|caps| Some((caps.get(1)?.as_str(), caps.get(0)?.as_str())))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn zombie_token_references_should_return_the_referenced_nodes() {
+        let references = zombie_token_references(
+            "--bootnodes={{ZOMBIE:alice:multiaddr}},{{ZOMBIE:bob-1:ws_uri}}",
+        );
+
+        assert_eq!(
+            references,
+            vec![
+                ("alice", "{{ZOMBIE:alice:multiaddr}}"),
+                ("bob-1", "{{ZOMBIE:bob-1:ws_uri}}")
+            ]
+        );
+        assert!(zombie_token_references("{{ZOMBIE:alice}}").is_empty());
+    }
+
+    #[test]
+    fn merge_args_should_replace_options_and_append_new_args() {
+        let args = merge_args(
+            vec![("--pruning", "archive").into(), "--alice".into()],
+            vec![
+                ("--pruning", "1000").into(),
+                "--alice".into(),
+                ("-l", "parachain=debug").into(),
+            ],
+        );
+
+        assert_eq!(
+            args,
+            vec![
+                ("--pruning", "1000").into(),
+                "--alice".into(),
+                ("-l", "parachain=debug").into()
+            ]
+        );
+    }
+
+    #[test]
+    fn override_args_should_remove_default_args_and_report_unmatched_removals() {
+        let (args, unmatched) = override_args(
+            vec![
+                "-lparachain=debug".into(),
+                ("--pruning", "archive").into(),
+                "--alice".into(),
+            ],
+            vec![
+                Arg::Remove("-lparachain=debug".into()),
+                Arg::Remove("--pruning".into()),
+                Arg::Remove("--bob".into()),
+                "--force-authoring".into(),
+            ],
+        );
+
+        assert_eq!(args, vec!["--alice".into(), "--force-authoring".into()]);
+        assert_eq!(unmatched, vec!["--bob".to_string()]);
+    }
+
+    #[test]
+    fn merge_by_key_should_preserve_base_order_and_append_unmatched_items() {
+        let merged = merge_by_key(
+            vec![(1, "a"), (2, "b")],
+            vec![(3, "c"), (1, "d")],
+            |(key, _)| *key,
+            |current, item| match current {
+                Some((key, value)) => (key, if value == "a" { item.1 } else { value }),
+                None => item,
+            },
+        );
+
+        assert_eq!(merged, vec![(1, "d"), (2, "b"), (3, "c")]);
+    }
+
+    #[test]
+    fn merge_json_should_merge_objects_recursively() {
+        let merged = merge_json(
+            serde_json::json!({"balances": {"devAccounts": [1, 2]}, "sudo": {"key": "alice"}}),
+            serde_json::json!({"balances": {"devAccounts": [3]}, "staking": {"validatorCount": 4}}),
+        );
+
+        assert_eq!(
+            merged,
+            serde_json::json!({
+                "balances": {"devAccounts": [3]},
+                "sudo": {"key": "alice"},
+                "staking": {"validatorCount": 4}
+            })
+        );
+    }
+}
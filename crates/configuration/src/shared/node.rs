@@ -1,24 +1,38 @@
-use std::{cell::RefCell, error::Error, fmt::Display, marker::PhantomData, rc::Rc};
+use std::{
+    cell::RefCell,
+    error::Error,
+    fmt::Display,
+    marker::PhantomData,
+    path::{Path, PathBuf},
+    rc::Rc,
+};
 
 use multiaddr::Multiaddr;
+use schemars::JsonSchema;
 use serde::{ser::SerializeStruct, Deserialize, Serialize};
+use tracing::warn;
 
 use super::{
-    errors::FieldError,
+    errors::{ConversionError, FieldError, ValidationError},
     helpers::{
-        ensure_node_name_unique, ensure_port_unique, ensure_value_is_not_empty, merge_errors,
-        merge_errors_vecs,
+        ensure_hex_prefixed, ensure_listen_addr, ensure_node_name_unique, ensure_port_unique,
+        ensure_value_is_not_empty, explicit_value, merge_args, merge_by_key, merge_errors,
+        merge_errors_vecs, merge_log_targets, merge_unique, override_args, parse_log_targets,
+        resolve_command_env,
     },
     macros::states,
     resources::ResourcesBuilder,
-    types::{AssetLocation, ChainDefaultContext, Command, Image, ValidationContext, U128},
+    types::{
+        AssetLocation, ChainDefaultContext, Command, Image, LogTarget, RpcMethods, SessionKeyType,
+        SyncMode, ValidationContext, U128,
+    },
 };
 use crate::{
     shared::{
         resources::Resources,
         types::{Arg, Port},
     },
-    utils::{default_as_true, default_initial_balance},
+    utils::{default_as_true, default_initial_balance, is_false},
 };
 
 states! {
@@ -44,7 +58,7 @@ states! {
 ///     }
 /// )
 /// ```
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
 pub struct EnvVar {
     /// The name of the environment variable.
     pub name: String,
@@ -62,36 +76,112 @@ impl From<(&str, &str)> for EnvVar {
     }
 }
 
+/// A key to pre-populate the node keystore with, in addition to the derived ones.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct KeystoreKey {
+    /// The key type, a 4 chars crypto id (e.g. `aura`, `gran`).
+    pub key_type: String,
+
+    /// The secret seed (or suri) of the key.
+    pub seed: String,
+
+    /// The `0x` prefixed hex encoded public key.
+    pub public: String,
+
+    /// Whether the key is injected in the chain-spec authorities, replacing the derived
+    /// key of the same type.
+    #[serde(skip_serializing_if = "is_false", default)]
+    pub authority: bool,
+}
+
+/// A file to inject in the node (e.g. a `--node-key-file`) before starting it.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct InjectedFile {
+    /// The path of the local file.
+    pub local_path: PathBuf,
+
+    /// The (absolute) path of the file inside the node, e.g. `/cfg/node.key`.
+    /// Use the `{{nodeBaseDir}}` token in the node args to reference it (e.g.
+    /// `--node-key-file={{nodeBaseDir}}/cfg/node.key`).
+    pub remote_path: PathBuf,
+}
+
 /// A node configuration, with fine-grained configuration options.
-#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Deserialize, JsonSchema)]
 pub struct NodeConfig {
     name: String,
     pub(crate) image: Option<Image>,
     pub(crate) command: Option<Command>,
+    // Env var to read the command from at spawn time, `command` is used as fallback
+    command_env: Option<String>,
     pub(crate) subcommand: Option<Command>,
+    // Wrapper (with its own args) followed by the node binary, e.g. `taskset -c 0-3 polkadot`
+    #[serde(default)]
+    command_parts: Vec<String>,
     #[serde(default)]
     args: Vec<Arg>,
+    // Args passed to the embedded relaychain node (after `--`), only for cumulus collators
+    #[serde(default)]
+    relay_args: Vec<Arg>,
+    // Log targets compiled into the `-l` flag, merged with the chain `default_log_targets`
+    #[serde(default)]
+    log_targets: Vec<LogTarget>,
+    // Flags assumed as supported by the node, even if missing in the binary help output
+    #[serde(default)]
+    force_args: Vec<String>,
+    // Don't inherit the chain `default_args` (keep the node args empty)
+    #[serde(default)]
+    no_default_args: bool,
     #[serde(alias = "validator", default = "default_as_true")]
     pub(crate) is_validator: bool,
     #[serde(alias = "invulnerable", default = "default_as_true")]
     pub(crate) is_invulnerable: bool,
     #[serde(alias = "bootnode", default)]
     pub(crate) is_bootnode: bool,
+    #[serde(alias = "dedicated_bootnode", default)]
+    pub(crate) is_dedicated_bootnode: bool,
     #[serde(alias = "balance")]
     #[serde(default = "default_initial_balance")]
     initial_balance: U128,
     #[serde(default)]
     env: Vec<EnvVar>,
     #[serde(default)]
+    #[schemars(with = "Vec<String>")]
     bootnodes_addresses: Vec<Multiaddr>,
     pub(crate) resources: Option<Resources>,
+    #[schemars(range(min = 1, max = 65535))]
     ws_port: Option<Port>,
+    #[schemars(range(min = 1, max = 65535))]
     rpc_port: Option<Port>,
+    #[schemars(range(min = 1, max = 65535))]
     prometheus_port: Option<Port>,
+    #[schemars(range(min = 1, max = 65535))]
     p2p_port: Option<Port>,
     p2p_cert_hash: Option<String>,
+    // Bind the rpc on all the interfaces (`--unsafe-rpc-external` or `--rpc-external`)
+    #[serde(default)]
+    rpc_external: Option<bool>,
+    // Bind prometheus on all the interfaces (`--prometheus-external`)
+    #[serde(default)]
+    prometheus_external: Option<bool>,
+    #[serde(default)]
+    rpc_methods: RpcMethods,
+    // P2P listen address (`--listen-addr`), the port is replaced by the p2p one
+    #[schemars(with = "Option<String>")]
+    listen_addr: Option<Multiaddr>,
+    // Account used in the genesis session keys, derived from the chain if not set
+    session_key_type: Option<SessionKeyType>,
+    // Syncing mode (`--sync`), the node default if not set
+    sync_mode: Option<SyncMode>,
     pub(crate) db_snapshot: Option<AssetLocation>,
     #[serde(default)]
+    keystore_keys: Vec<KeystoreKey>,
+    #[serde(default)]
+    injected_files: Vec<InjectedFile>,
+    // Data dir (`--base-path`) to use instead of the one derived from the node base dir
+    data_dir: Option<PathBuf>,
+    #[serde(default)]
+    #[schemars(skip)]
     // used to skip serialization of fields with defaults to avoid duplication
     pub(crate) chain_context: ChainDefaultContext,
 }
@@ -101,7 +191,7 @@ impl Serialize for NodeConfig {
     where
         S: serde::Serializer,
     {
-        let mut state = serializer.serialize_struct("NodeConfig", 18)?;
+        let mut state = serializer.serialize_struct("NodeConfig", 20)?;
         state.serialize_field("name", &self.name)?;
 
         if self.image == self.chain_context.default_image {
@@ -116,6 +206,18 @@ impl Serialize for NodeConfig {
             state.serialize_field("command", &self.command)?;
         }
 
+        if self.command_env.is_none() {
+            state.skip_field("command_env")?;
+        } else {
+            state.serialize_field("command_env", &self.command_env)?;
+        }
+
+        if self.command_parts.is_empty() {
+            state.skip_field("command_parts")?;
+        } else {
+            state.serialize_field("command_parts", &self.command_parts)?;
+        }
+
         if self.subcommand.is_none() {
             state.skip_field("subcommand")?;
         } else {
@@ -128,9 +230,40 @@ impl Serialize for NodeConfig {
             state.serialize_field("args", &self.args)?;
         }
 
+        if self.relay_args.is_empty() {
+            state.skip_field("relay_args")?;
+        } else {
+            state.serialize_field("relay_args", &self.relay_args)?;
+        }
+
+        if self.log_targets.is_empty() {
+            state.skip_field("log_targets")?;
+        } else {
+            state.serialize_field("log_targets", &self.log_targets)?;
+        }
+
+        if self.force_args.is_empty() {
+            state.skip_field("force_args")?;
+        } else {
+            state.serialize_field("force_args", &self.force_args)?;
+        }
+
+        if self.no_default_args {
+            state.serialize_field("no_default_args", &self.no_default_args)?;
+        } else {
+            state.skip_field("no_default_args")?;
+        }
+
         state.serialize_field("validator", &self.is_validator)?;
         state.serialize_field("invulnerable", &self.is_invulnerable)?;
         state.serialize_field("bootnode", &self.is_bootnode)?;
+
+        if self.is_dedicated_bootnode {
+            state.serialize_field("dedicated_bootnode", &self.is_dedicated_bootnode)?;
+        } else {
+            state.skip_field("dedicated_bootnode")?;
+        }
+
         state.serialize_field("balance", &self.initial_balance)?;
 
         if self.env.is_empty() {
@@ -157,12 +290,66 @@ impl Serialize for NodeConfig {
         state.serialize_field("p2p_port", &self.p2p_port)?;
         state.serialize_field("p2p_cert_hash", &self.p2p_cert_hash)?;
 
+        if let Some(rpc_external) = self.rpc_external {
+            state.serialize_field("rpc_external", &rpc_external)?;
+        } else {
+            state.skip_field("rpc_external")?;
+        }
+
+        if let Some(prometheus_external) = self.prometheus_external {
+            state.serialize_field("prometheus_external", &prometheus_external)?;
+        } else {
+            state.skip_field("prometheus_external")?;
+        }
+
+        if self.rpc_methods == RpcMethods::default() {
+            state.skip_field("rpc_methods")?;
+        } else {
+            state.serialize_field("rpc_methods", &self.rpc_methods)?;
+        }
+
+        if self.listen_addr.is_none() {
+            state.skip_field("listen_addr")?;
+        } else {
+            state.serialize_field("listen_addr", &self.listen_addr)?;
+        }
+
+        if self.session_key_type.is_none() {
+            state.skip_field("session_key_type")?;
+        } else {
+            state.serialize_field("session_key_type", &self.session_key_type)?;
+        }
+
+        if self.sync_mode.is_none() {
+            state.skip_field("sync_mode")?;
+        } else {
+            state.serialize_field("sync_mode", &self.sync_mode)?;
+        }
+
         if self.db_snapshot == self.chain_context.default_db_snapshot {
             state.skip_field("db_snapshot")?;
         } else {
             state.serialize_field("db_snapshot", &self.db_snapshot)?;
         }
 
+        if self.keystore_keys.is_empty() {
+            state.skip_field("keystore_keys")?;
+        } else {
+            state.serialize_field("keystore_keys", &self.keystore_keys)?;
+        }
+
+        if self.injected_files.is_empty() {
+            state.skip_field("injected_files")?;
+        } else {
+            state.serialize_field("injected_files", &self.injected_files)?;
+        }
+
+        if self.data_dir.is_none() {
+            state.skip_field("data_dir")?;
+        } else {
+            state.serialize_field("data_dir", &self.data_dir)?;
+        }
+
         state.skip_field("chain_context")?;
         state.end()
     }
@@ -184,16 +371,41 @@ impl NodeConfig {
         self.command.as_ref()
     }
 
+    /// Env var to read the command from at spawn time (the command is used as fallback).
+    pub fn command_env(&self) -> Option<&str> {
+        self.command_env.as_deref()
+    }
+
     /// Subcommand to run the node.
     pub fn subcommand(&self) -> Option<&Command> {
         self.subcommand.as_ref()
     }
 
+    /// Ordered parts of the command (wrapper and its arguments followed by the node binary).
+    pub fn command_parts(&self) -> Vec<&str> {
+        self.command_parts.iter().map(String::as_str).collect()
+    }
+
     /// Arguments to use for node.
     pub fn args(&self) -> Vec<&Arg> {
         self.args.iter().collect()
     }
 
+    /// Arguments passed to the embedded relaychain node of a cumulus collator (after `--`).
+    pub fn relay_args(&self) -> Vec<&Arg> {
+        self.relay_args.iter().collect()
+    }
+
+    /// Log targets (with their level) of the node.
+    pub fn log_targets(&self) -> Vec<&LogTarget> {
+        self.log_targets.iter().collect()
+    }
+
+    /// Flags assumed as supported by the node, without checking the binary help output.
+    pub fn force_args(&self) -> Vec<&str> {
+        self.force_args.iter().map(String::as_str).collect()
+    }
+
     /// Arguments to use for node.
     pub(crate) fn set_args(&mut self, args: Vec<Arg>) {
         self.args = args;
@@ -209,11 +421,22 @@ impl NodeConfig {
         self.is_invulnerable
     }
 
+    /// Whether the node skips the chain default arguments.
+    pub fn no_default_args(&self) -> bool {
+        self.no_default_args
+    }
+
     /// Whether the node is a bootnode.
     pub fn is_bootnode(&self) -> bool {
         self.is_bootnode
     }
 
+    /// Whether the node is a dedicated bootnode (a bootnode that is never part of the
+    /// validator/authority sets).
+    pub fn is_dedicated_bootnode(&self) -> bool {
+        self.is_dedicated_bootnode
+    }
+
     /// Node initial balance present in genesis.
     pub fn initial_balance(&self) -> u128 {
         self.initial_balance.0
@@ -259,10 +482,312 @@ impl NodeConfig {
         self.p2p_cert_hash.as_deref()
     }
 
+    /// Whether the rpc is bound on all the interfaces.
+    pub fn rpc_external(&self) -> bool {
+        self.rpc_external.unwrap_or(true)
+    }
+
+    /// Whether prometheus is bound on all the interfaces.
+    pub fn prometheus_external(&self) -> bool {
+        self.prometheus_external.unwrap_or(true)
+    }
+
+    /// RPC methods exposed by the node.
+    pub fn rpc_methods(&self) -> RpcMethods {
+        self.rpc_methods
+    }
+
+    /// P2P listen address (the port is replaced by the p2p one).
+    pub fn listen_addr(&self) -> Option<&Multiaddr> {
+        self.listen_addr.as_ref()
+    }
+
+    /// Account used in the genesis session keys (derived from the chain if not set).
+    pub fn session_key_type(&self) -> Option<SessionKeyType> {
+        self.session_key_type
+    }
+
+    /// Syncing mode of the node (the node default if not set).
+    pub fn sync_mode(&self) -> Option<SyncMode> {
+        self.sync_mode
+    }
+
     /// Database snapshot.
     pub fn db_snapshot(&self) -> Option<&AssetLocation> {
         self.db_snapshot.as_ref()
     }
+
+    /// Keys to pre-populate the node keystore with.
+    pub fn keystore_keys(&self) -> Vec<&KeystoreKey> {
+        self.keystore_keys.iter().collect()
+    }
+
+    /// Files to inject in the node before starting it.
+    pub fn injected_files(&self) -> Vec<&InjectedFile> {
+        self.injected_files.iter().collect()
+    }
+
+    /// Data dir (`--base-path`) to use instead of the derived one.
+    pub fn data_dir(&self) -> Option<&Path> {
+        self.data_dir.as_deref()
+    }
+
+    /// Replace the values inherited from the `current` chain defaults
+    /// with the ones from the `new` chain defaults.
+    pub(crate) fn rebase(
+        mut self,
+        current: &ChainDefaultContext,
+        new: &ChainDefaultContext,
+    ) -> Self {
+        if self.command == current.default_command {
+            self.command.clone_from(&new.default_command);
+        }
+
+        if self.image == current.default_image {
+            self.image.clone_from(&new.default_image);
+        }
+
+        if self.resources == current.default_resources {
+            self.resources.clone_from(&new.default_resources);
+        }
+
+        if self.db_snapshot == current.default_db_snapshot {
+            self.db_snapshot.clone_from(&new.default_db_snapshot);
+        }
+
+        if self.args == current.default_args && !self.no_default_args {
+            self.args.clone_from(&new.default_args);
+        }
+
+        self.chain_context = new.clone();
+        self
+    }
+
+    /// Resolve the command from the `command_env` (if set), falling back to the current one.
+    pub(crate) fn resolve_command_env(mut self) -> Result<Self, anyhow::Error> {
+        self.command = resolve_command_env(self.command_env.as_deref(), self.command.as_ref())
+            .map_err(FieldError::Command)?;
+
+        Ok(self)
+    }
+
+    /// Merge the `overlay` node into this one, values inherited by the `overlay`
+    /// from its chain defaults (`overlay_context`) are ignored.
+    pub(crate) fn merge(self, overlay: NodeConfig, overlay_context: &ChainDefaultContext) -> Self {
+        Self {
+            image: explicit_value(overlay.image, &overlay_context.default_image).or(self.image),
+            command: explicit_value(overlay.command, &overlay_context.default_command)
+                .or(self.command),
+            command_env: overlay.command_env.or(self.command_env),
+            subcommand: overlay.subcommand.or(self.subcommand),
+            command_parts: if overlay.command_parts.is_empty() {
+                self.command_parts
+            } else {
+                overlay.command_parts
+            },
+            args: if overlay.args == overlay_context.default_args {
+                self.args
+            } else {
+                merge_args(self.args, overlay.args)
+            },
+            relay_args: merge_args(self.relay_args, overlay.relay_args),
+            log_targets: merge_log_targets(self.log_targets, overlay.log_targets),
+            force_args: merge_by_key(
+                self.force_args,
+                overlay.force_args,
+                |arg| arg.clone(),
+                |_, arg| arg,
+            ),
+            no_default_args: self.no_default_args || overlay.no_default_args,
+            is_validator: self.is_validator && overlay.is_validator,
+            is_invulnerable: self.is_invulnerable && overlay.is_invulnerable,
+            is_bootnode: self.is_bootnode || overlay.is_bootnode,
+            is_dedicated_bootnode: self.is_dedicated_bootnode || overlay.is_dedicated_bootnode,
+            initial_balance: if overlay.initial_balance == default_initial_balance() {
+                self.initial_balance
+            } else {
+                overlay.initial_balance
+            },
+            env: merge_by_key(self.env, overlay.env, |var| var.name.clone(), |_, var| var),
+            bootnodes_addresses: merge_unique(
+                self.bootnodes_addresses,
+                overlay.bootnodes_addresses,
+            ),
+            resources: explicit_value(overlay.resources, &overlay_context.default_resources)
+                .or(self.resources),
+            ws_port: overlay.ws_port.or(self.ws_port),
+            rpc_port: overlay.rpc_port.or(self.rpc_port),
+            prometheus_port: overlay.prometheus_port.or(self.prometheus_port),
+            p2p_port: overlay.p2p_port.or(self.p2p_port),
+            p2p_cert_hash: overlay.p2p_cert_hash.or(self.p2p_cert_hash),
+            rpc_external: overlay.rpc_external.or(self.rpc_external),
+            prometheus_external: overlay.prometheus_external.or(self.prometheus_external),
+            rpc_methods: if overlay.rpc_methods == RpcMethods::default() {
+                self.rpc_methods
+            } else {
+                overlay.rpc_methods
+            },
+            listen_addr: overlay.listen_addr.or(self.listen_addr),
+            session_key_type: overlay.session_key_type.or(self.session_key_type),
+            sync_mode: overlay.sync_mode.or(self.sync_mode),
+            db_snapshot: explicit_value(overlay.db_snapshot, &overlay_context.default_db_snapshot)
+                .or(self.db_snapshot),
+            keystore_keys: merge_by_key(
+                self.keystore_keys,
+                overlay.keystore_keys,
+                |key| (key.key_type.clone(), key.public.clone()),
+                |_, key| key,
+            ),
+            injected_files: merge_by_key(
+                self.injected_files,
+                overlay.injected_files,
+                |file| file.remote_path.clone(),
+                |_, file| file,
+            ),
+            data_dir: overlay.data_dir.or(self.data_dir),
+            ..self
+        }
+    }
+}
+
+/// Merge the `overlay` nodes into the `base` ones, matching them by name.
+///
+/// Values inherited from the chain defaults are replaced by the `merged` chain defaults
+/// and nodes only present in the `overlay` are appended.
+pub(crate) fn merge_nodes(
+    base: Vec<NodeConfig>,
+    overlay: Vec<NodeConfig>,
+    base_context: &ChainDefaultContext,
+    overlay_context: &ChainDefaultContext,
+    merged_context: &ChainDefaultContext,
+) -> Vec<NodeConfig> {
+    merge_by_key(
+        base.into_iter()
+            .map(|node| node.rebase(base_context, merged_context))
+            .collect(),
+        overlay,
+        |node| node.name.clone(),
+        |node, overlay_node| match node {
+            Some(node) => node.merge(overlay_node, overlay_context),
+            None => overlay_node.rebase(overlay_context, merged_context),
+        },
+    )
+}
+
+// The key type should be a 4 chars crypto id and the public key a `0x` prefixed hex string.
+fn ensure_keystore_key(index: usize, key: &KeystoreKey) -> Vec<FieldError> {
+    let mut errors = vec![];
+
+    if key.key_type.len() != 4 || !key.key_type.chars().all(|c| c.is_ascii_alphanumeric()) {
+        errors.push(FieldError::KeystoreKey(
+            index,
+            key.key_type.clone(),
+            ValidationError::InvalidKeyType().into(),
+        ));
+    }
+
+    if let Err(error) = ensure_hex_prefixed(&key.public) {
+        errors.push(FieldError::KeystoreKey(index, key.public.clone(), error));
+    }
+
+    errors
+}
+
+// The local file should exist and the remote path should be absolute.
+fn ensure_injected_file(index: usize, file: &InjectedFile) -> Vec<FieldError> {
+    let mut errors = vec![];
+
+    if !file.local_path.is_file() {
+        errors.push(FieldError::InjectedFile(
+            index,
+            file.local_path.to_string_lossy().to_string(),
+            ValidationError::PathNotFound().into(),
+        ));
+    }
+
+    if !file.remote_path.is_absolute() {
+        errors.push(FieldError::InjectedFile(
+            index,
+            file.remote_path.to_string_lossy().to_string(),
+            ValidationError::PathNotAbsolute().into(),
+        ));
+    }
+
+    errors
+}
+
+/// Run the checks of the node builder on the node fields (keystore keys, injected files,
+/// listen address, rpc methods and syncing mode), e.g. for a node loaded from a `toml` file.
+/// The `default_args` are the ones of the chain the node belongs to.
+pub(crate) fn ensure_node_fields(config: &NodeConfig, default_args: &[Arg]) -> Vec<FieldError> {
+    let mut errors = vec![];
+
+    for (index, key) in config.keystore_keys.iter().enumerate() {
+        errors.extend(ensure_keystore_key(index, key));
+    }
+
+    for (index, file) in config.injected_files.iter().enumerate() {
+        errors.extend(ensure_injected_file(index, file));
+    }
+
+    if let Some(addr) = &config.listen_addr {
+        if let Err(error) = ensure_listen_addr(addr) {
+            errors.push(FieldError::ListenAddr(addr.to_string(), error));
+        }
+    }
+
+    if let Err(error) = ensure_rpc_external_methods(config) {
+        errors.push(FieldError::RpcMethods(error));
+    }
+
+    if let Err(error) = ensure_sync_mode(config, default_args) {
+        errors.push(FieldError::SyncMode(error));
+    }
+
+    errors
+}
+
+// `--rpc-external` is rejected by the node if the unsafe methods are exposed.
+fn ensure_rpc_external_methods(config: &NodeConfig) -> Result<(), anyhow::Error> {
+    let rpc_external_flag = Arg::Flag("--rpc-external".into());
+    if config.rpc_methods == RpcMethods::Unsafe && config.args.contains(&rpc_external_flag) {
+        return Err(ValidationError::RpcExternalRequiresSafeMethods().into());
+    }
+
+    Ok(())
+}
+
+// Whether the arg sets the syncing mode, as an option (`--sync <mode>`) or a flag (`--sync=<mode>`).
+fn is_sync_arg(arg: &Arg) -> bool {
+    match arg {
+        Arg::Option(key, _) => key == "--sync",
+        Arg::Flag(flag) => {
+            flag == "--sync" || flag.starts_with("--sync=") || flag.starts_with("--sync ")
+        },
+        Arg::Remove(_) => false,
+    }
+}
+
+// The syncing mode is set through `--sync`, it can't be also set in the args (including the
+// `default_args` of the chain inherited by the node).
+fn ensure_sync_mode(config: &NodeConfig, default_args: &[Arg]) -> Result<(), anyhow::Error> {
+    if config.sync_mode.is_none() {
+        return Ok(());
+    }
+
+    let args = if config.args.iter().any(|arg| matches!(arg, Arg::Remove(_))) {
+        override_args(default_args.to_vec(), config.args.clone()).0
+    } else if config.args.is_empty() && !config.no_default_args {
+        default_args.to_vec()
+    } else {
+        config.args.clone()
+    };
+
+    if args.iter().any(is_sync_arg) {
+        return Err(ValidationError::SyncModeAlreadySet().into());
+    }
+
+    Ok(())
 }
 
 /// A node configuration builder, used to build a [`NodeConfig`] declaratively with fields validation.
@@ -280,11 +805,18 @@ impl Default for NodeConfigBuilder<Initial> {
                 name: "".into(),
                 image: None,
                 command: None,
+                command_env: None,
                 subcommand: None,
+                command_parts: vec![],
                 args: vec![],
+                relay_args: vec![],
+                log_targets: vec![],
+                force_args: vec![],
                 is_validator: true,
                 is_invulnerable: true,
                 is_bootnode: false,
+                is_dedicated_bootnode: false,
+                no_default_args: false,
                 initial_balance: 2_000_000_000_000.into(),
                 env: vec![],
                 bootnodes_addresses: vec![],
@@ -294,7 +826,16 @@ impl Default for NodeConfigBuilder<Initial> {
                 prometheus_port: None,
                 p2p_port: None,
                 p2p_cert_hash: None,
+                rpc_external: None,
+                prometheus_external: None,
+                rpc_methods: RpcMethods::default(),
+                listen_addr: None,
+                session_key_type: None,
+                sync_mode: None,
                 db_snapshot: None,
+                keystore_keys: vec![],
+                injected_files: vec![],
+                data_dir: None,
                 chain_context: Default::default(),
             },
             validation_context: Default::default(),
@@ -385,9 +926,94 @@ impl NodeConfigBuilder<Buildable> {
         T::Error: Error + Send + Sync + 'static,
     {
         match command.try_into() {
+            Ok(command) => match self.config.command_parts.last() {
+                // the command is the last of the command parts
+                Some(binary) if binary != command.as_str() => {
+                    let error = ValidationError::CommandConflictsWithCommandParts(
+                        command.as_str().to_string(),
+                        binary.clone(),
+                    );
+                    Self::transition(
+                        self.config,
+                        self.validation_context,
+                        merge_errors(self.errors, FieldError::Command(error.into()).into()),
+                    )
+                },
+                _ => Self::transition(
+                    NodeConfig {
+                        command: Some(command),
+                        ..self.config
+                    },
+                    self.validation_context,
+                    self.errors,
+                ),
+            },
+            Err(error) => Self::transition(
+                self.config,
+                self.validation_context,
+                merge_errors(self.errors, FieldError::Command(error.into()).into()),
+            ),
+        }
+    }
+
+    /// Set the env var (e.g. `POLKADOT_BINARY`) to read the command from at spawn time. The
+    /// command (set with [`Self::with_command`] or the chain default) is used as fallback if
+    /// the env var is unset.
+    pub fn with_command_env<T>(self, env_var: T) -> Self
+    where
+        T: Into<String>,
+    {
+        let env_var: String = env_var.into();
+
+        match ensure_value_is_not_empty(&env_var) {
+            Ok(_) => Self::transition(
+                NodeConfig {
+                    command_env: Some(env_var),
+                    ..self.config
+                },
+                self.validation_context,
+                self.errors,
+            ),
+            Err(error) => Self::transition(
+                self.config,
+                self.validation_context,
+                merge_errors(self.errors, FieldError::CommandEnv(error).into()),
+            ),
+        }
+    }
+
+    /// Set the command as ordered parts, a wrapper with its own arguments followed by the node
+    /// binary (e.g. `["taskset", "-c", "0-3", "polkadot"]`). The first part is the program
+    /// launched and the last one is used as the node command. Override the default.
+    pub fn with_command_parts<T>(self, parts: Vec<T>) -> Self
+    where
+        T: Into<String>,
+    {
+        let parts: Vec<String> = parts.into_iter().map(Into::into).collect();
+        let command = match parts.last() {
+            Some(binary) => Command::try_from(binary.as_str()),
+            None => Err(ConversionError::CantBeEmpty),
+        };
+
+        if let (Some(current), Some(binary)) = (&self.config.command, parts.last()) {
+            if current.as_str() != binary {
+                let error = ValidationError::CommandConflictsWithCommandParts(
+                    current.as_str().to_string(),
+                    binary.clone(),
+                );
+                return Self::transition(
+                    self.config,
+                    self.validation_context,
+                    merge_errors(self.errors, FieldError::Command(error.into()).into()),
+                );
+            }
+        }
+
+        match command {
             Ok(command) => Self::transition(
                 NodeConfig {
                     command: Some(command),
+                    command_parts: parts,
                     ..self.config
                 },
                 self.validation_context,
@@ -459,11 +1085,17 @@ impl NodeConfigBuilder<Buildable> {
         )
     }
 
-    /// Set whether the node is a validator.
-    pub fn validator(self, choice: bool) -> Self {
+    /// Set the flags assumed as supported by the node (e.g. `--insecure-validator-i-know-what-i-do`)
+    /// even if they are missing in the binary help output, useful for new flags not listed
+    /// yet in `--help`. These flags are not validated against the binary.
+    ///
+    /// NOTE: only the flags added by zombienet depending on the binary support (currently
+    /// `--insecure-validator-i-know-what-i-do`) are affected, the others are ignored with
+    /// a warning (use [`Self::with_args`] to pass them).
+    pub fn with_force_args(self, force_args: Vec<impl Into<String>>) -> Self {
         Self::transition(
             NodeConfig {
-                is_validator: choice,
+                force_args: force_args.into_iter().map(Into::into).collect(),
                 ..self.config
             },
             self.validation_context,
@@ -471,11 +1103,13 @@ impl NodeConfigBuilder<Buildable> {
         )
     }
 
-    /// Set whether the node is invulnerable.
-    pub fn invulnerable(self, choice: bool) -> Self {
+    /// Set the arguments passed to the embedded relaychain node of a cumulus collator,
+    /// appended after the `--` separator (e.g. `--relay-chain-rpc-url`).
+    /// Only allowed on the collators of a cumulus based parachain.
+    pub fn with_relay_args(self, relay_args: Vec<Arg>) -> Self {
         Self::transition(
             NodeConfig {
-                is_invulnerable: choice,
+                relay_args,
                 ..self.config
             },
             self.validation_context,
@@ -483,23 +1117,35 @@ impl NodeConfigBuilder<Buildable> {
         )
     }
 
-    /// Set whether the node is a bootnode.
-    pub fn bootnode(self, choice: bool) -> Self {
+    /// Set the log targets (e.g. `("parachain", "debug")`) compiled into the node `-l` flag,
+    /// merged with the chain default ones (the node level wins for the same target).
+    pub fn with_log_targets(self, log_targets: Vec<(&str, &str)>) -> Self {
+        let (log_targets, errors) = parse_log_targets(log_targets, FieldError::LogTarget);
+
         Self::transition(
             NodeConfig {
-                is_bootnode: choice,
+                log_targets,
                 ..self.config
             },
             self.validation_context,
-            self.errors,
+            merge_errors_vecs(self.errors, errors),
         )
     }
 
-    /// Set the node initial balance.
-    pub fn with_initial_balance(self, initial_balance: u128) -> Self {
+    /// Set the arguments that will be used when launching the node on top of the default ones,
+    /// [`Arg::Remove`] directives drop the matching default arguments.
+    pub fn with_args_override(self, args: Vec<Arg>) -> Self {
+        let (args, unmatched) = override_args(self.config.chain_context.default_args.clone(), args);
+        for removal in unmatched {
+            warn!(
+                "node '{}': '{removal}' is not present in the default args, nothing to remove",
+                self.config.name
+            );
+        }
+
         Self::transition(
             NodeConfig {
-                initial_balance: initial_balance.into(),
+                args,
                 ..self.config
             },
             self.validation_context,
@@ -507,35 +1153,119 @@ impl NodeConfigBuilder<Buildable> {
         )
     }
 
-    /// Set the node environment variables that will be used when launched. Override the default.
-    pub fn with_env(self, env: Vec<impl Into<EnvVar>>) -> Self {
-        let env = env.into_iter().map(|var| var.into()).collect::<Vec<_>>();
+    /// Don't inherit the chain default arguments, the node is launched without extra
+    /// arguments unless they are set explicitly with [`NodeConfigBuilder::with_args`]
+    /// (before or after this call).
+    pub fn with_no_default_args(self) -> Self {
+        // only drop the inherited defaults, keeping the args set explicitly
+        let args = if self.config.args == self.config.chain_context.default_args {
+            vec![]
+        } else {
+            self.config.args
+        };
 
         Self::transition(
-            NodeConfig { env, ..self.config },
+            NodeConfig {
+                args,
+                no_default_args: true,
+                ..self.config
+            },
             self.validation_context,
             self.errors,
         )
     }
 
-    /// Set the bootnodes addresses that the node will try to connect to. Override the default.
-    pub fn with_bootnodes_addresses<T>(self, bootnodes_addresses: Vec<T>) -> Self
-    where
-        T: TryInto<Multiaddr> + Display + Copy,
-        T::Error: Error + Send + Sync + 'static,
-    {
-        let mut addrs = vec![];
-        let mut errors = vec![];
-
-        for (index, addr) in bootnodes_addresses.into_iter().enumerate() {
-            match addr.try_into() {
-                Ok(addr) => addrs.push(addr),
-                Err(error) => errors.push(
-                    FieldError::BootnodesAddress(index, addr.to_string(), error.into()).into(),
-                ),
-            }
-        }
-
+    /// Set whether the node is a validator.
+    pub fn validator(self, choice: bool) -> Self {
+        Self::transition(
+            NodeConfig {
+                is_validator: choice,
+                ..self.config
+            },
+            self.validation_context,
+            self.errors,
+        )
+    }
+
+    /// Set whether the node is invulnerable.
+    pub fn invulnerable(self, choice: bool) -> Self {
+        Self::transition(
+            NodeConfig {
+                is_invulnerable: choice,
+                ..self.config
+            },
+            self.validation_context,
+            self.errors,
+        )
+    }
+
+    /// Set whether the node is a bootnode.
+    pub fn bootnode(self, choice: bool) -> Self {
+        Self::transition(
+            NodeConfig {
+                is_bootnode: choice,
+                ..self.config
+            },
+            self.validation_context,
+            self.errors,
+        )
+    }
+
+    /// Set whether the node is a dedicated bootnode, spawned only to help with the peer
+    /// discovery. A dedicated bootnode is always a bootnode and is never a validator.
+    pub fn dedicated_bootnode(self, choice: bool) -> Self {
+        Self::transition(
+            NodeConfig {
+                is_dedicated_bootnode: choice,
+                is_bootnode: choice || self.config.is_bootnode,
+                ..self.config
+            },
+            self.validation_context,
+            self.errors,
+        )
+    }
+
+    /// Set the node initial balance.
+    pub fn with_initial_balance(self, initial_balance: u128) -> Self {
+        Self::transition(
+            NodeConfig {
+                initial_balance: initial_balance.into(),
+                ..self.config
+            },
+            self.validation_context,
+            self.errors,
+        )
+    }
+
+    /// Set the node environment variables that will be used when launched. Override the default.
+    pub fn with_env(self, env: Vec<impl Into<EnvVar>>) -> Self {
+        let env = env.into_iter().map(|var| var.into()).collect::<Vec<_>>();
+
+        Self::transition(
+            NodeConfig { env, ..self.config },
+            self.validation_context,
+            self.errors,
+        )
+    }
+
+    /// Set the bootnodes addresses that the node will try to connect to. Override the default.
+    pub fn with_bootnodes_addresses<T>(self, bootnodes_addresses: Vec<T>) -> Self
+    where
+        T: TryInto<Multiaddr> + Display + Copy,
+        T::Error: Error + Send + Sync + 'static,
+    {
+        let mut addrs = vec![];
+        let mut errors = vec![];
+
+        for (index, addr) in bootnodes_addresses.into_iter().enumerate() {
+            match addr.try_into() {
+                Ok(addr) => addrs.push(addr),
+                Err(error) => errors.push(
+                    FieldError::BootnodesAddress(index, addr.to_string(), error.into()).into(),
+                ),
+            }
+        }
+
         Self::transition(
             NodeConfig {
                 bootnodes_addresses: addrs,
@@ -647,38 +1377,504 @@ impl NodeConfigBuilder<Buildable> {
         }
     }
 
-    /// Set the P2P cert hash that will be used as part of the multiaddress
-    /// if and only if the multiaddress is set to use `webrtc`.
-    pub fn with_p2p_cert_hash(self, p2p_cert_hash: impl Into<String>) -> Self {
-        Self::transition(
-            NodeConfig {
-                p2p_cert_hash: Some(p2p_cert_hash.into()),
-                ..self.config
-            },
-            self.validation_context,
-            self.errors,
-        )
+    /// Set the P2P cert hash that will be used as part of the multiaddress
+    /// if and only if the multiaddress is set to use `webrtc`.
+    pub fn with_p2p_cert_hash(self, p2p_cert_hash: impl Into<String>) -> Self {
+        Self::transition(
+            NodeConfig {
+                p2p_cert_hash: Some(p2p_cert_hash.into()),
+                ..self.config
+            },
+            self.validation_context,
+            self.errors,
+        )
+    }
+
+    /// Set whether the rpc should be bound on all the interfaces (default to true), with
+    /// `--unsafe-rpc-external` or `--rpc-external` (for the `safe` methods).
+    pub fn with_rpc_external(self, choice: bool) -> Self {
+        Self::transition(
+            NodeConfig {
+                rpc_external: Some(choice),
+                ..self.config
+            },
+            self.validation_context,
+            self.errors,
+        )
+    }
+
+    /// Set whether prometheus should be bound on all the interfaces (default to true), if not
+    /// the metrics are only reachable from the node host (e.g. through a port-forward).
+    pub fn with_prometheus_external(self, choice: bool) -> Self {
+        Self::transition(
+            NodeConfig {
+                prometheus_external: Some(choice),
+                ..self.config
+            },
+            self.validation_context,
+            self.errors,
+        )
+    }
+
+    /// Set the RPC methods exposed by the node (default to `unsafe`).
+    pub fn with_rpc_methods(self, rpc_methods: RpcMethods) -> Self {
+        Self::transition(
+            NodeConfig {
+                rpc_methods,
+                ..self.config
+            },
+            self.validation_context,
+            self.errors,
+        )
+    }
+
+    /// Set the account used in the genesis session keys. If not set, the `evm` account is used
+    /// for evm based parachains, the stash one for the relaychain (with `session`) and the
+    /// default one otherwise.
+    pub fn with_session_key_type(self, session_key_type: SessionKeyType) -> Self {
+        Self::transition(
+            NodeConfig {
+                session_key_type: Some(session_key_type),
+                ..self.config
+            },
+            self.validation_context,
+            self.errors,
+        )
+    }
+
+    /// Set the syncing mode of the node (`--sync`), e.g. `warp` to check that a node
+    /// bootstraps from the latest finalized state instead of the genesis.
+    pub fn with_sync_mode(self, sync_mode: SyncMode) -> Self {
+        Self::transition(
+            NodeConfig {
+                sync_mode: Some(sync_mode),
+                ..self.config
+            },
+            self.validation_context,
+            self.errors,
+        )
+    }
+
+    /// Set the P2P listen address (e.g. `/ip4/127.0.0.1/tcp/30333/ws`), the port is replaced
+    /// by the p2p one of the node.
+    pub fn with_listen_addr<T>(self, listen_addr: T) -> Self
+    where
+        T: TryInto<Multiaddr> + Display + Copy,
+        T::Error: Error + Send + Sync + 'static,
+    {
+        let value = listen_addr.to_string();
+        let listen_addr = match listen_addr.try_into() {
+            Ok(addr) => ensure_listen_addr(&addr).map(|_| addr),
+            Err(error) => Err(error.into()),
+        };
+
+        match listen_addr {
+            Ok(addr) => Self::transition(
+                NodeConfig {
+                    listen_addr: Some(addr),
+                    ..self.config
+                },
+                self.validation_context,
+                self.errors,
+            ),
+            Err(error) => Self::transition(
+                self.config,
+                self.validation_context,
+                merge_errors(self.errors, FieldError::ListenAddr(value, error).into()),
+            ),
+        }
+    }
+
+    /// Set the database snapshot that will be used to launch the node. Override the default.
+    pub fn with_db_snapshot(self, location: impl Into<AssetLocation>) -> Self {
+        Self::transition(
+            NodeConfig {
+                db_snapshot: Some(location.into()),
+                ..self.config
+            },
+            self.validation_context,
+            self.errors,
+        )
+    }
+
+    /// Set the data dir (`--base-path`) to use instead of the one derived from the node
+    /// base dir (e.g. a pre-seeded/restored database). The path should be reachable by the
+    /// node (e.g. mounted in the container for podman/k8s).
+    pub fn with_data_dir(self, data_dir: impl Into<PathBuf>) -> Self {
+        Self::transition(
+            NodeConfig {
+                data_dir: Some(data_dir.into()),
+                ..self.config
+            },
+            self.validation_context,
+            self.errors,
+        )
+    }
+
+    /// Add a key to pre-populate the node keystore with (e.g. to simulate a key rotation).
+    /// The `key_type` should be a 4 chars crypto id and the `public` key a `0x` prefixed hex string.
+    pub fn with_keystore_key(self, key_type: &str, seed: &str, public: &str) -> Self {
+        self.push_keystore_key(key_type, seed, public, false)
+    }
+
+    /// Add a key to pre-populate the node keystore with, also injecting it in the chain-spec
+    /// authorities instead of the derived key of the same type (e.g. `aura`, `gran`).
+    pub fn with_keystore_authority_key(self, key_type: &str, seed: &str, public: &str) -> Self {
+        self.push_keystore_key(key_type, seed, public, true)
+    }
+
+    fn push_keystore_key(self, key_type: &str, seed: &str, public: &str, authority: bool) -> Self {
+        let key = KeystoreKey {
+            key_type: key_type.to_string(),
+            seed: seed.to_string(),
+            public: public.to_string(),
+            authority,
+        };
+        let errors = ensure_keystore_key(self.config.keystore_keys.len(), &key)
+            .into_iter()
+            .map(Into::into)
+            .collect();
+
+        let mut keystore_keys = self.config.keystore_keys;
+        keystore_keys.push(key);
+
+        Self::transition(
+            NodeConfig {
+                keystore_keys,
+                ..self.config
+            },
+            self.validation_context,
+            merge_errors_vecs(self.errors, errors),
+        )
+    }
+
+    /// Add a local file to inject in the node before starting it (e.g. a node key or a rpc
+    /// allowlist). The `local` file should exist and the `remote` path should be absolute
+    /// (e.g. `/cfg/node.key`), the node args can reference it with the `{{nodeBaseDir}}` token
+    /// (e.g. `--node-key-file={{nodeBaseDir}}/cfg/node.key`).
+    pub fn with_injected_file(self, local: impl Into<PathBuf>, remote: impl Into<PathBuf>) -> Self {
+        let file = InjectedFile {
+            local_path: local.into(),
+            remote_path: remote.into(),
+        };
+        let errors = ensure_injected_file(self.config.injected_files.len(), &file)
+            .into_iter()
+            .map(Into::into)
+            .collect();
+
+        let mut injected_files = self.config.injected_files;
+        injected_files.push(file);
+
+        Self::transition(
+            NodeConfig {
+                injected_files,
+                ..self.config
+            },
+            self.validation_context,
+            merge_errors_vecs(self.errors, errors),
+        )
+    }
+
+    /// Seals the builder and returns a [`NodeConfig`] if there are no validation errors, else returns errors.
+    pub fn build(self) -> Result<NodeConfig, (String, Vec<anyhow::Error>)> {
+        let errors = match ensure_rpc_external_methods(&self.config) {
+            Ok(_) => self.errors,
+            Err(error) => merge_errors(self.errors, FieldError::RpcMethods(error).into()),
+        };
+        let errors = match ensure_sync_mode(&self.config, &self.config.chain_context.default_args) {
+            Ok(_) => errors,
+            Err(error) => merge_errors(errors, FieldError::SyncMode(error).into()),
+        };
+
+        if !errors.is_empty() {
+            return Err((self.config.name.clone(), errors));
+        }
+
+        Ok(self.config)
+    }
+}
+
+/// A group of nodes configuration builder, used to declare `count` nodes sharing the same
+/// configuration with deterministic names (`<name_prefix>-<index>`).
+pub struct GroupNodeConfigBuilder<S> {
+    node: NodeConfigBuilder<S>,
+    count: usize,
+}
+
+impl GroupNodeConfigBuilder<Initial> {
+    pub fn new(
+        chain_context: ChainDefaultContext,
+        validation_context: Rc<RefCell<ValidationContext>>,
+    ) -> Self {
+        Self {
+            node: NodeConfigBuilder::new(chain_context, validation_context),
+            count: 1,
+        }
+    }
+
+    /// Set the name prefix of the nodes, they will be named `<name_prefix>-<index>`.
+    pub fn with_name_prefix(
+        self,
+        name_prefix: impl Into<String>,
+    ) -> GroupNodeConfigBuilder<Buildable> {
+        let name_prefix: String = name_prefix.into();
+        let errors = match ensure_value_is_not_empty(&name_prefix) {
+            Ok(_) => self.node.errors,
+            Err(e) => merge_errors(self.node.errors, FieldError::Name(e).into()),
+        };
+
+        GroupNodeConfigBuilder {
+            node: NodeConfigBuilder::<Initial>::transition(
+                NodeConfig {
+                    name: name_prefix,
+                    ..self.node.config
+                },
+                self.node.validation_context,
+                errors,
+            ),
+            count: self.count,
+        }
+    }
+}
+
+impl GroupNodeConfigBuilder<Buildable> {
+    /// Set the number of nodes in the group (should be greater than 0).
+    pub fn with_count(self, count: usize) -> Self {
+        Self { count, ..self }
+    }
+
+    /// Set the command that will be executed to launch the nodes. Override the default.
+    pub fn with_command<T>(self, command: T) -> Self
+    where
+        T: TryInto<Command>,
+        T::Error: Error + Send + Sync + 'static,
+    {
+        Self {
+            node: self.node.with_command(command),
+            ..self
+        }
+    }
+
+    /// Set the env var to read the command of the nodes from at spawn time, the command is
+    /// used as fallback.
+    pub fn with_command_env<T>(self, env_var: T) -> Self
+    where
+        T: Into<String>,
+    {
+        Self {
+            node: self.node.with_command_env(env_var),
+            ..self
+        }
+    }
+
+    /// Set the command as ordered parts, a wrapper with its own arguments followed by the node
+    /// binary. Override the default.
+    pub fn with_command_parts<T>(self, parts: Vec<T>) -> Self
+    where
+        T: Into<String>,
+    {
+        Self {
+            node: self.node.with_command_parts(parts),
+            ..self
+        }
+    }
+
+    /// Set the subcommand that will be executed to launch the nodes.
+    pub fn with_subcommand<T>(self, subcommand: T) -> Self
+    where
+        T: TryInto<Command>,
+        T::Error: Error + Send + Sync + 'static,
+    {
+        Self {
+            node: self.node.with_subcommand(subcommand),
+            ..self
+        }
+    }
+
+    /// Set the image that will be used for the nodes (only podman/k8s). Override the default.
+    pub fn with_image<T>(self, image: T) -> Self
+    where
+        T: TryInto<Image>,
+        T::Error: Error + Send + Sync + 'static,
+    {
+        Self {
+            node: self.node.with_image(image),
+            ..self
+        }
+    }
+
+    /// Set the arguments that will be used when launching the nodes. Override the default.
+    pub fn with_args(self, args: Vec<Arg>) -> Self {
+        Self {
+            node: self.node.with_args(args),
+            ..self
+        }
+    }
+
+    /// Set the arguments passed to the embedded relaychain node of the cumulus collators,
+    /// appended after the `--` separator.
+    pub fn with_relay_args(self, relay_args: Vec<Arg>) -> Self {
+        Self {
+            node: self.node.with_relay_args(relay_args),
+            ..self
+        }
+    }
+
+    /// Set the log targets compiled into the `-l` flag of the nodes, merged with the chain
+    /// default ones.
+    pub fn with_log_targets(self, log_targets: Vec<(&str, &str)>) -> Self {
+        Self {
+            node: self.node.with_log_targets(log_targets),
+            ..self
+        }
+    }
+
+    /// Set the arguments that will be used when launching the nodes on top of the default ones,
+    /// [`Arg::Remove`] directives drop the matching default arguments.
+    pub fn with_args_override(self, args: Vec<Arg>) -> Self {
+        Self {
+            node: self.node.with_args_override(args),
+            ..self
+        }
+    }
+
+    /// Set the flags assumed as supported by the nodes, even if missing in the binary help output.
+    pub fn with_force_args(self, force_args: Vec<impl Into<String>>) -> Self {
+        Self {
+            node: self.node.with_force_args(force_args),
+            ..self
+        }
+    }
+
+    /// Set whether the nodes are validators.
+    pub fn validator(self, choice: bool) -> Self {
+        Self {
+            node: self.node.validator(choice),
+            ..self
+        }
+    }
+
+    /// Set whether the nodes are invulnerables.
+    pub fn invulnerable(self, choice: bool) -> Self {
+        Self {
+            node: self.node.invulnerable(choice),
+            ..self
+        }
+    }
+
+    /// Set the nodes initial balance.
+    pub fn with_initial_balance(self, initial_balance: u128) -> Self {
+        Self {
+            node: self.node.with_initial_balance(initial_balance),
+            ..self
+        }
+    }
+
+    /// Set the nodes environment variables that will be used when launched. Override the default.
+    pub fn with_env(self, env: Vec<impl Into<EnvVar>>) -> Self {
+        Self {
+            node: self.node.with_env(env),
+            ..self
+        }
+    }
+
+    /// Set the resources limits what will be used for the nodes (only podman/k8s). Override the default.
+    pub fn with_resources(self, f: impl FnOnce(ResourcesBuilder) -> ResourcesBuilder) -> Self {
+        Self {
+            node: self.node.with_resources(f),
+            ..self
+        }
+    }
+
+    /// Set the database snapshot that will be used to launch the nodes. Override the default.
+    pub fn with_db_snapshot(self, location: impl Into<AssetLocation>) -> Self {
+        Self {
+            node: self.node.with_db_snapshot(location),
+            ..self
+        }
+    }
+
+    /// Set whether the rpc of the nodes should be bound on all the interfaces (default to true).
+    pub fn with_rpc_external(self, choice: bool) -> Self {
+        Self {
+            node: self.node.with_rpc_external(choice),
+            ..self
+        }
+    }
+
+    /// Set whether prometheus of the nodes should be bound on all the interfaces (default to true).
+    pub fn with_prometheus_external(self, choice: bool) -> Self {
+        Self {
+            node: self.node.with_prometheus_external(choice),
+            ..self
+        }
+    }
+
+    /// Set the RPC methods exposed by the nodes (default to `unsafe`).
+    pub fn with_rpc_methods(self, rpc_methods: RpcMethods) -> Self {
+        Self {
+            node: self.node.with_rpc_methods(rpc_methods),
+            ..self
+        }
     }
 
-    /// Set the database snapshot that will be used to launch the node. Override the default.
-    pub fn with_db_snapshot(self, location: impl Into<AssetLocation>) -> Self {
-        Self::transition(
-            NodeConfig {
-                db_snapshot: Some(location.into()),
-                ..self.config
-            },
-            self.validation_context,
-            self.errors,
-        )
+    /// Set the account used in the genesis session keys of the nodes.
+    pub fn with_session_key_type(self, session_key_type: SessionKeyType) -> Self {
+        Self {
+            node: self.node.with_session_key_type(session_key_type),
+            ..self
+        }
     }
 
-    /// Seals the builder and returns a [`NodeConfig`] if there are no validation errors, else returns errors.
-    pub fn build(self) -> Result<NodeConfig, (String, Vec<anyhow::Error>)> {
-        if !self.errors.is_empty() {
-            return Err((self.config.name.clone(), self.errors));
+    /// Set the syncing mode of the nodes (`--sync`).
+    pub fn with_sync_mode(self, sync_mode: SyncMode) -> Self {
+        Self {
+            node: self.node.with_sync_mode(sync_mode),
+            ..self
         }
+    }
 
-        Ok(self.config)
+    /// Seals the builder and returns the expanded [`NodeConfig`]s (named `<name_prefix>-<index>`)
+    /// if there are no validation errors, else returns errors.
+    pub fn build(self) -> Result<Vec<NodeConfig>, (String, Vec<anyhow::Error>)> {
+        let name_prefix = self.node.config.name.clone();
+        let validation_context = self.node.validation_context.clone();
+        let mut errors = self.node.errors;
+
+        if self.count == 0 {
+            errors.push(FieldError::Count(ValidationError::CantBeZero().into()).into());
+        }
+
+        if let Err(error) = ensure_rpc_external_methods(&self.node.config) {
+            errors.push(FieldError::RpcMethods(error).into());
+        }
+
+        if let Err(error) = ensure_sync_mode(
+            &self.node.config,
+            &self.node.config.chain_context.default_args,
+        ) {
+            errors.push(FieldError::SyncMode(error).into());
+        }
+
+        let mut nodes = vec![];
+        for index in 0..self.count {
+            let name = format!("{name_prefix}-{index}");
+            if let Err(e) = ensure_node_name_unique(&name, validation_context.clone()) {
+                errors.push(FieldError::Name(e).into());
+                continue;
+            }
+
+            nodes.push(NodeConfig {
+                name,
+                ..self.node.config.clone()
+            });
+        }
+
+        if !errors.is_empty() {
+            return Err((name_prefix, errors));
+        }
+
+        Ok(nodes)
     }
 }
 
@@ -718,6 +1914,7 @@ mod tests {
                     "ec8d6467180a4b72a52b24c53aa1e53b76c05602fa96f5d0961bf720edda267f",
                 )
                 .with_db_snapshot("/tmp/mysnapshot")
+                .with_force_args(vec!["--new-flag"])
                 .build()
                 .unwrap();
 
@@ -726,6 +1923,7 @@ mod tests {
         assert_eq!(node_config.image().unwrap().as_str(), "myrepo:myimage");
         let args: Vec<Arg> = vec![("--arg1", "value1").into(), "--option2".into()];
         assert_eq!(node_config.args(), args.iter().collect::<Vec<_>>());
+        assert_eq!(node_config.force_args(), vec!["--new-flag"]);
         assert!(node_config.is_validator());
         assert!(node_config.is_invulnerable());
         assert!(node_config.is_bootnode());
@@ -775,6 +1973,66 @@ mod tests {
         );
     }
 
+    #[test]
+    fn node_config_builder_with_command_parts_should_use_the_last_part_as_command() {
+        let node_config =
+            NodeConfigBuilder::new(ChainDefaultContext::default(), Default::default())
+                .with_name("node")
+                .with_command_parts(vec!["taskset", "-c", "0-3", "polkadot"])
+                .build()
+                .unwrap();
+
+        assert_eq!(node_config.command().unwrap().as_str(), "polkadot");
+        assert_eq!(
+            node_config.command_parts(),
+            vec!["taskset", "-c", "0-3", "polkadot"]
+        );
+    }
+
+    #[test]
+    fn node_config_builder_should_fails_if_command_conflicts_with_command_parts() {
+        let (_, errors) =
+            NodeConfigBuilder::new(ChainDefaultContext::default(), Default::default())
+                .with_name("node")
+                .with_command_parts(vec!["taskset", "-c", "0-3", "polkadot"])
+                .with_command("polkadot-parachain")
+                .build()
+                .unwrap_err();
+
+        assert_eq!(
+            errors.first().unwrap().to_string(),
+            "command: 'polkadot-parachain' conflicts with the command parts (ending with 'polkadot'), only one of them can be set"
+        );
+
+        let (_, errors) =
+            NodeConfigBuilder::new(ChainDefaultContext::default(), Default::default())
+                .with_name("node")
+                .with_command("polkadot-parachain")
+                .with_command_parts(vec!["taskset", "-c", "0-3", "polkadot"])
+                .build()
+                .unwrap_err();
+
+        assert_eq!(errors.len(), 1);
+    }
+
+    #[test]
+    fn node_config_builder_should_fails_and_returns_an_error_and_node_name_if_command_parts_are_empty(
+    ) {
+        let (node_name, errors) =
+            NodeConfigBuilder::new(ChainDefaultContext::default(), Default::default())
+                .with_name("node")
+                .with_command_parts(Vec::<String>::new())
+                .build()
+                .unwrap_err();
+
+        assert_eq!(node_name, "node");
+        assert_eq!(errors.len(), 1);
+        assert_eq!(
+            errors.first().unwrap().to_string(),
+            "command: can't be empty"
+        );
+    }
+
     #[test]
     fn node_config_builder_should_fails_and_returns_an_error_and_node_name_if_image_is_invalid() {
         let (node_name, errors) =
@@ -1036,4 +2294,341 @@ mod tests {
         assert_eq!(errors.len(), 1);
         assert_eq!(errors.first().unwrap().to_string(), "name: can't be empty");
     }
+
+    #[test]
+    fn node_config_builder_with_data_dir_should_succeeds() {
+        let node_config =
+            NodeConfigBuilder::new(ChainDefaultContext::default(), Default::default())
+                .with_name("alice")
+                .with_data_dir("/restored/alice")
+                .build()
+                .unwrap();
+
+        assert_eq!(node_config.data_dir(), Some(Path::new("/restored/alice")));
+        assert!(toml::to_string(&node_config)
+            .unwrap()
+            .contains("data_dir = \"/restored/alice\""));
+    }
+
+    #[test]
+    fn node_config_builder_with_dedicated_bootnode_should_succeeds() {
+        let node_config =
+            NodeConfigBuilder::new(ChainDefaultContext::default(), Default::default())
+                .with_name("boot")
+                .dedicated_bootnode(true)
+                .build()
+                .unwrap();
+
+        assert!(node_config.is_dedicated_bootnode());
+        assert!(node_config.is_bootnode());
+        assert!(toml::to_string(&node_config)
+            .unwrap()
+            .contains("dedicated_bootnode = true"));
+    }
+
+    #[test]
+    fn node_config_builder_with_no_default_args_should_skip_the_chain_default_args() {
+        let chain_context = ChainDefaultContext {
+            default_args: vec!["-lparachain=debug".into()],
+            ..Default::default()
+        };
+        let node_config = NodeConfigBuilder::new(chain_context, Default::default())
+            .with_name("alice")
+            .with_no_default_args()
+            .build()
+            .unwrap();
+
+        assert!(node_config.args().is_empty());
+        assert!(node_config.no_default_args());
+        assert!(toml::to_string(&node_config)
+            .unwrap()
+            .contains("no_default_args = true"));
+    }
+
+    #[test]
+    fn node_config_builder_with_no_default_args_should_keep_the_args_set_before() {
+        let chain_context = ChainDefaultContext {
+            default_args: vec!["-lparachain=debug".into()],
+            ..Default::default()
+        };
+        let node_config = NodeConfigBuilder::new(chain_context, Default::default())
+            .with_name("alice")
+            .with_args(vec!["--pruning=archive".into()])
+            .with_no_default_args()
+            .build()
+            .unwrap();
+
+        assert_eq!(node_config.args(), vec![&Arg::from("--pruning=archive")]);
+        assert!(node_config.no_default_args());
+    }
+
+    #[test]
+    fn node_config_builder_with_keystore_keys_should_succeeds() {
+        let node_config =
+            NodeConfigBuilder::new(ChainDefaultContext::default(), Default::default())
+                .with_name("alice")
+                .with_keystore_key("aura", "//Rotated", "0xd43593c715fdd31c")
+                .build()
+                .unwrap();
+
+        assert_eq!(
+            node_config.keystore_keys(),
+            vec![&KeystoreKey {
+                key_type: "aura".into(),
+                seed: "//Rotated".into(),
+                public: "0xd43593c715fdd31c".into(),
+                authority: false,
+            }]
+        );
+    }
+
+    #[test]
+    fn node_config_builder_with_keystore_authority_key_should_mark_the_key() {
+        let node_config =
+            NodeConfigBuilder::new(ChainDefaultContext::default(), Default::default())
+                .with_name("alice")
+                .with_keystore_key("babe", "//Rotated", "0xd43593c715fdd31c")
+                .with_keystore_authority_key("aura", "//Rotated", "0xd43593c715fdd31c")
+                .build()
+                .unwrap();
+
+        let authorities: Vec<&str> = node_config
+            .keystore_keys()
+            .into_iter()
+            .filter(|key| key.authority)
+            .map(|key| key.key_type.as_str())
+            .collect();
+        assert_eq!(authorities, vec!["aura"]);
+    }
+
+    #[test]
+    fn node_config_builder_should_fails_if_keystore_key_is_invalid() {
+        let (_, errors) =
+            NodeConfigBuilder::new(ChainDefaultContext::default(), Default::default())
+                .with_name("alice")
+                .with_keystore_key("aur", "//Rotated", "0xd43593c715fdd31c")
+                .with_keystore_key("gran", "//Rotated", "d43593")
+                .build()
+                .unwrap_err();
+
+        assert_eq!(errors.len(), 2);
+        assert_eq!(
+            errors.first().unwrap().to_string(),
+            "keystore_keys[0]: 'aur' should be a 4 chars crypto id"
+        );
+        assert_eq!(
+            errors.get(1).unwrap().to_string(),
+            "keystore_keys[1]: 'd43593' should be a `0x` prefixed hex string"
+        );
+    }
+
+    #[test]
+    fn node_config_builder_with_injected_file_should_succeeds() {
+        let local = tempfile::NamedTempFile::new().unwrap();
+        std::fs::write(local.path(), "0x01").unwrap();
+
+        let node_config =
+            NodeConfigBuilder::new(ChainDefaultContext::default(), Default::default())
+                .with_name("alice")
+                .with_injected_file(local.path(), "/cfg/node.key")
+                .build()
+                .unwrap();
+
+        assert_eq!(
+            node_config.injected_files(),
+            vec![&InjectedFile {
+                local_path: local.path().to_path_buf(),
+                remote_path: "/cfg/node.key".into(),
+            }]
+        );
+    }
+
+    #[test]
+    fn node_config_builder_should_fails_if_injected_file_is_invalid() {
+        let (_, errors) =
+            NodeConfigBuilder::new(ChainDefaultContext::default(), Default::default())
+                .with_name("alice")
+                .with_injected_file("/tmp/zombienet-missing-file.key", "cfg/node.key")
+                .build()
+                .unwrap_err();
+
+        assert_eq!(errors.len(), 2);
+        assert_eq!(
+            errors.first().unwrap().to_string(),
+            "injected_files[0]: '/tmp/zombienet-missing-file.key' doesn't exist"
+        );
+        assert_eq!(
+            errors.get(1).unwrap().to_string(),
+            "injected_files[0]: 'cfg/node.key' should be an absolute path"
+        );
+    }
+
+    #[test]
+    fn node_config_builder_with_binding_options_should_succeeds() {
+        let node_config =
+            NodeConfigBuilder::new(ChainDefaultContext::default(), Default::default())
+                .with_name("alice")
+                .with_rpc_external(false)
+                .with_prometheus_external(false)
+                .with_rpc_methods(RpcMethods::Safe)
+                .with_listen_addr("/ip4/127.0.0.1/tcp/30333/ws")
+                .with_session_key_type(SessionKeyType::Stash)
+                .with_sync_mode(SyncMode::Warp)
+                .build()
+                .unwrap();
+
+        assert!(!node_config.rpc_external());
+        assert!(!node_config.prometheus_external());
+        assert_eq!(node_config.rpc_methods(), RpcMethods::Safe);
+        assert_eq!(
+            node_config.listen_addr().unwrap().to_string(),
+            "/ip4/127.0.0.1/tcp/30333/ws"
+        );
+        assert_eq!(node_config.session_key_type(), Some(SessionKeyType::Stash));
+        assert_eq!(node_config.sync_mode(), Some(SyncMode::Warp));
+    }
+
+    #[test]
+    fn node_config_builder_should_fails_if_binding_options_are_invalid() {
+        let (_, errors) =
+            NodeConfigBuilder::new(ChainDefaultContext::default(), Default::default())
+                .with_name("alice")
+                .with_args(vec!["--rpc-external".into(), ("--sync", "fast").into()])
+                .with_listen_addr("/dns/localhost/tcp/30333")
+                .with_sync_mode(SyncMode::Warp)
+                .build()
+                .unwrap_err();
+
+        assert_eq!(errors.len(), 3);
+        assert_eq!(
+            errors.first().unwrap().to_string(),
+            "listen_addr: '/dns/localhost/tcp/30333' should be an `/ip4/<ip>/tcp/<port>` (or `/ip6`) multiaddr"
+        );
+        assert_eq!(
+            errors.get(1).unwrap().to_string(),
+            "rpc_methods: `--rpc-external` can only be used with the `safe` methods, use `--unsafe-rpc-external` to expose the unsafe ones"
+        );
+        assert_eq!(
+            errors.get(2).unwrap().to_string(),
+            "sync_mode: can't be used with a `--sync` arg"
+        );
+    }
+
+    #[test]
+    fn node_config_builder_should_fails_if_the_sync_mode_is_also_set_in_a_flag_or_the_default_args()
+    {
+        let (_, errors) =
+            NodeConfigBuilder::new(ChainDefaultContext::default(), Default::default())
+                .with_name("alice")
+                .with_args(vec!["--sync=fast".into()])
+                .with_sync_mode(SyncMode::Warp)
+                .build()
+                .unwrap_err();
+
+        assert_eq!(
+            errors.first().unwrap().to_string(),
+            "sync_mode: can't be used with a `--sync` arg"
+        );
+
+        let chain_context = ChainDefaultContext {
+            default_args: vec![("--sync", "fast").into(), "--force-authoring".into()],
+            ..Default::default()
+        };
+        let (_, errors) = NodeConfigBuilder::new(chain_context.clone(), Default::default())
+            .with_name("alice")
+            .with_sync_mode(SyncMode::Warp)
+            .build()
+            .unwrap_err();
+
+        assert_eq!(
+            errors.first().unwrap().to_string(),
+            "sync_mode: can't be used with a `--sync` arg"
+        );
+
+        // removing the inherited `--sync` is fine
+        assert!(NodeConfigBuilder::new(chain_context, Default::default())
+            .with_name("alice")
+            .with_args_override(vec![Arg::Remove("--sync".into())])
+            .with_sync_mode(SyncMode::Warp)
+            .build()
+            .is_ok());
+    }
+
+    #[test]
+    fn node_config_builder_with_args_override_should_remove_default_args() {
+        let chain_context = ChainDefaultContext {
+            default_args: vec!["-lparachain=debug".into(), "--force-authoring".into()],
+            ..Default::default()
+        };
+
+        let node = NodeConfigBuilder::new(chain_context, Default::default())
+            .with_name("alice")
+            .with_args_override(vec![
+                Arg::Remove("-lparachain=debug".into()),
+                ("--pruning", "archive").into(),
+            ])
+            .build()
+            .unwrap();
+
+        assert_eq!(
+            node.args(),
+            vec![
+                &Arg::Flag("--force-authoring".into()),
+                &Arg::Option("--pruning".into(), "archive".into())
+            ]
+        );
+    }
+
+    #[test]
+    fn group_node_config_builder_should_expand_nodes_with_index_suffixed_names() {
+        let nodes = GroupNodeConfigBuilder::new(ChainDefaultContext::default(), Default::default())
+            .with_name_prefix("validator")
+            .with_count(3)
+            .with_command("polkadot")
+            .with_args(vec!["--option".into()])
+            .build()
+            .unwrap();
+
+        assert_eq!(nodes.len(), 3);
+        for (index, node) in nodes.iter().enumerate() {
+            assert_eq!(node.name(), format!("validator-{index}"));
+            assert_eq!(node.command().unwrap().as_str(), "polkadot");
+            assert_eq!(node.args(), vec![&Arg::Flag("--option".into())]);
+        }
+    }
+
+    #[test]
+    fn group_node_config_builder_should_fails_if_count_is_zero() {
+        let (name_prefix, errors) =
+            GroupNodeConfigBuilder::new(ChainDefaultContext::default(), Default::default())
+                .with_name_prefix("validator")
+                .with_count(0)
+                .build()
+                .unwrap_err();
+
+        assert_eq!(name_prefix, "validator");
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors.first().unwrap().to_string(), "count: can't be zero");
+    }
+
+    #[test]
+    fn group_node_config_builder_should_fails_if_a_node_name_is_already_used() {
+        let validation_context = Rc::new(RefCell::new(ValidationContext {
+            used_nodes_names: vec!["validator-1".into()],
+            ..Default::default()
+        }));
+
+        let (_, errors) =
+            GroupNodeConfigBuilder::new(ChainDefaultContext::default(), validation_context)
+                .with_name_prefix("validator")
+                .with_count(2)
+                .build()
+                .unwrap_err();
+
+        assert_eq!(errors.len(), 1);
+        assert_eq!(
+            errors.first().unwrap().to_string(),
+            "name: 'validator-1' is already used across config"
+        );
+    }
 }
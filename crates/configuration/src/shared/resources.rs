@@ -2,6 +2,7 @@ use std::error::Error;
 
 use lazy_static::lazy_static;
 use regex::Regex;
+use schemars::{gen::SchemaGenerator, schema::Schema, JsonSchema};
 use serde::{
     de::{self},
     ser::SerializeStruct,
@@ -33,7 +34,7 @@ use super::{
 /// assert_eq!(quantity3.as_str(), "1Gi");
 /// assert_eq!(quantity4.as_str(), "10000");
 /// ```
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
 pub struct ResourceQuantity(String);
 
 impl ResourceQuantity {
@@ -75,12 +76,18 @@ pub struct Resources {
     request_cpu: Option<ResourceQuantity>,
     limit_memory: Option<ResourceQuantity>,
     limit_cpu: Option<ResourceQuantity>,
+    limit_nofile: Option<u64>,
+    limit_address_space: Option<ResourceQuantity>,
 }
 
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, JsonSchema)]
 struct ResourcesField {
     memory: Option<ResourceQuantity>,
     cpu: Option<ResourceQuantity>,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    nofile: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    address_space: Option<ResourceQuantity>,
 }
 
 impl Serialize for Resources {
@@ -90,24 +97,32 @@ impl Serialize for Resources {
     {
         let mut state = serializer.serialize_struct("Resources", 2)?;
 
-        if self.request_memory.is_some() || self.request_memory.is_some() {
+        if self.request_memory.is_some() || self.request_cpu.is_some() {
             state.serialize_field(
                 "requests",
                 &ResourcesField {
                     memory: self.request_memory.clone(),
                     cpu: self.request_cpu.clone(),
+                    nofile: None,
+                    address_space: None,
                 },
             )?;
         } else {
             state.skip_field("requests")?;
         }
 
-        if self.limit_memory.is_some() || self.limit_memory.is_some() {
+        if self.limit_memory.is_some()
+            || self.limit_cpu.is_some()
+            || self.limit_nofile.is_some()
+            || self.limit_address_space.is_some()
+        {
             state.serialize_field(
                 "limits",
                 &ResourcesField {
                     memory: self.limit_memory.clone(),
                     cpu: self.limit_cpu.clone(),
+                    nofile: self.limit_nofile,
+                    address_space: self.limit_address_space.clone(),
                 },
             )?;
         } else {
@@ -142,11 +157,20 @@ impl<'de> de::Visitor<'de> for ResourcesVisitor {
                 "limits" => {
                     resources.limit_memory = value.memory;
                     resources.limit_cpu = value.cpu;
+                    resources.limit_nofile = value.nofile;
+                    resources.limit_address_space = value.address_space;
                 },
                 _ => {
                     return Err(de::Error::unknown_field(
                         &key,
-                        &["requests", "limits", "cpu", "memory"],
+                        &[
+                            "requests",
+                            "limits",
+                            "cpu",
+                            "memory",
+                            "nofile",
+                            "address_space",
+                        ],
                     ))
                 },
             }
@@ -164,6 +188,24 @@ impl<'de> Deserialize<'de> for Resources {
     }
 }
 
+// Shape of the (de)serialized resources, only used to generate the schema.
+#[allow(dead_code)]
+#[derive(JsonSchema)]
+struct ResourcesSchema {
+    requests: Option<ResourcesField>,
+    limits: Option<ResourcesField>,
+}
+
+impl JsonSchema for Resources {
+    fn schema_name() -> String {
+        "Resources".to_string()
+    }
+
+    fn json_schema(gen: &mut SchemaGenerator) -> Schema {
+        ResourcesSchema::json_schema(gen)
+    }
+}
+
 impl Resources {
     /// Memory limit applied to requests.
     pub fn request_memory(&self) -> Option<&ResourceQuantity> {
@@ -175,7 +217,7 @@ impl Resources {
         self.request_cpu.as_ref()
     }
 
-    /// Overall memory limit applied.
+    /// Overall memory limit applied (ignored by the native provider).
     pub fn limit_memory(&self) -> Option<&ResourceQuantity> {
         self.limit_memory.as_ref()
     }
@@ -184,6 +226,16 @@ impl Resources {
     pub fn limit_cpu(&self) -> Option<&ResourceQuantity> {
         self.limit_cpu.as_ref()
     }
+
+    /// Maximum number of open file descriptors (native provider only).
+    pub fn limit_nofile(&self) -> Option<u64> {
+        self.limit_nofile
+    }
+
+    /// Maximum size of the virtual address space of the process (native provider only).
+    pub fn limit_address_space(&self) -> Option<&ResourceQuantity> {
+        self.limit_address_space.as_ref()
+    }
 }
 
 /// A resources builder, used to build a [`Resources`] declaratively with fields validation.
@@ -286,6 +338,46 @@ impl ResourcesBuilder {
         }
     }
 
+    /// Set the maximum number of open file descriptors for a node process.
+    /// Only used by the native provider, and only on Linux.
+    pub fn with_limit_nofile(self, nofile: u64) -> Self {
+        Self::transition(
+            Resources {
+                limit_nofile: Some(nofile),
+                ..self.config
+            },
+            self.errors,
+        )
+    }
+
+    /// Set the maximum size of the virtual address space (`RLIMIT_AS`) of a node process.
+    /// Only used by the native provider, and only on Linux.
+    ///
+    /// NOTE: this bounds the reserved address space (not the resident memory), the nodes
+    /// reserve many GiB upfront (e.g. for the wasm executor), so it needs to account for it.
+    pub fn with_limit_address_space<T>(self, quantity: T) -> Self
+    where
+        T: TryInto<ResourceQuantity>,
+        T::Error: Error + Send + Sync + 'static,
+    {
+        match quantity.try_into() {
+            Ok(quantity) => Self::transition(
+                Resources {
+                    limit_address_space: Some(quantity),
+                    ..self.config
+                },
+                self.errors,
+            ),
+            Err(error) => Self::transition(
+                self.config,
+                merge_errors(
+                    self.errors,
+                    FieldError::LimitAddressSpace(error.into()).into(),
+                ),
+            ),
+        }
+    }
+
     /// Seals the builder and returns a [`Resources`] if there are no validation errors, else returns errors.
     pub fn build(self) -> Result<Resources, Vec<anyhow::Error>> {
         if !self.errors.is_empty() {
@@ -402,6 +494,35 @@ mod tests {
         assert_eq!(resources.limit_memory().unwrap().as_str(), "2G");
     }
 
+    #[test]
+    fn resources_with_limit_nofile_roundtrip_through_toml() {
+        let resources = ResourcesBuilder::new()
+            .with_limit_memory("2G")
+            .with_limit_nofile(4096)
+            .with_limit_address_space("64Gi")
+            .build()
+            .unwrap();
+        assert_eq!(resources.limit_nofile(), Some(4096));
+        assert_eq!(resources.limit_address_space().unwrap().as_str(), "64Gi");
+
+        let serialized = toml::to_string(&resources).unwrap();
+        let deserialized: Resources = toml::from_str(&serialized).unwrap();
+        assert_eq!(deserialized, resources);
+    }
+
+    #[test]
+    fn resources_with_only_cpu_roundtrip_through_toml() {
+        let resources = ResourcesBuilder::new()
+            .with_request_cpu("100m")
+            .with_limit_cpu("500m")
+            .build()
+            .unwrap();
+
+        let serialized = toml::to_string(&resources).unwrap();
+        let deserialized: Resources = toml::from_str(&serialized).unwrap();
+        assert_eq!(deserialized, resources);
+    }
+
     #[test]
     fn resources_config_toml_import_should_succeeds_and_returns_a_resources_config() {
         let load_from_toml =
@@ -1,18 +1,30 @@
 use std::{
     error::Error,
     fmt::{self, Display},
-    path::PathBuf,
+    path::{Path, PathBuf},
     str::FromStr,
+    sync::Arc,
 };
 
 use anyhow::anyhow;
 use lazy_static::lazy_static;
 use regex::Regex;
+use reqwest::header::{ETAG, LAST_MODIFIED};
+use schemars::{
+    gen::SchemaGenerator,
+    schema::{InstanceType, Schema, SchemaObject, StringValidation},
+    JsonSchema,
+};
 use serde::{
     de::{self, IntoDeserializer},
     Deserialize, Deserializer, Serialize,
 };
-use support::constants::{INFAILABLE, PREFIX_CANT_BE_NONE, SHOULD_COMPILE, THIS_IS_A_BUG};
+use sha2::{Digest, Sha256};
+use support::{
+    constants::{INFAILABLE, PREFIX_CANT_BE_NONE, SHOULD_COMPILE, THIS_IS_A_BUG},
+    fs::FileSystem,
+};
+use tracing::{debug, info, warn};
 use url::Url;
 
 use super::{errors::ConversionError, resources::Resources};
@@ -82,6 +94,30 @@ impl<'de> Deserialize<'de> for U128 {
     }
 }
 
+impl JsonSchema for U128 {
+    fn schema_name() -> String {
+        "U128".to_string()
+    }
+
+    fn json_schema(gen: &mut SchemaGenerator) -> Schema {
+        // written as a plain integer in the `toml` definitions
+        u128::json_schema(gen)
+    }
+}
+
+/// A string schema only allowing the values matching the `pattern`.
+fn pattern_schema(pattern: impl Into<String>) -> Schema {
+    SchemaObject {
+        instance_type: Some(InstanceType::String.into()),
+        string: Some(Box::new(StringValidation {
+            pattern: Some(pattern.into()),
+            ..Default::default()
+        })),
+        ..Default::default()
+    }
+    .into()
+}
+
 /// A chain name.
 /// It can be constructed for an `&str`, if it fails, it will returns a [`ConversionError`].
 ///
@@ -122,6 +158,17 @@ impl Chain {
     }
 }
 
+impl JsonSchema for Chain {
+    fn schema_name() -> String {
+        "Chain".to_string()
+    }
+
+    fn json_schema(_gen: &mut SchemaGenerator) -> Schema {
+        // not empty and without whitespaces
+        pattern_schema(r"^\S+$")
+    }
+}
+
 /// A container image.
 /// It can be constructed from an `&str` including a combination of name, version, IPv4 or/and hostname, if it fails, it will returns a [`ConversionError`].
 ///
@@ -142,19 +189,28 @@ impl Chain {
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Image(String);
 
-impl TryFrom<&str> for Image {
-    type Error = ConversionError;
-
-    fn try_from(value: &str) -> Result<Self, Self::Error> {
+impl Image {
+    /// The pattern of a valid image (`[ip|hostname/]name[:version][@digest]`).
+    fn pattern() -> String {
         static IP_PART: &str = "((([0-9]|[1-9][0-9]|1[0-9]{2}|2[0-4][0-9]|25[0-5]).){3}([0-9]|[1-9][0-9]|1[0-9]{2}|2[0-4][0-9]|25[0-5]))";
         static HOSTNAME_PART: &str = "((([a-zA-Z0-9]|[a-zA-Z0-9][a-zA-Z0-9-]*[a-zA-Z0-9]).)*([A-Za-z0-9]|[A-Za-z0-9][A-Za-z0-9-]*[A-Za-z0-9]))";
         static TAG_NAME_PART: &str = "([a-z0-9](-*[a-z0-9])*)";
         static TAG_VERSION_PART: &str = "([a-z0-9_]([-._a-z0-9])*)";
+        static DIGEST_PART: &str = "([a-z0-9]+:[a-f0-9]{32,})";
+
+        format!(
+            "^({IP_PART}|{HOSTNAME_PART}/)?{TAG_NAME_PART}(:{TAG_VERSION_PART})?(@{DIGEST_PART})?$",
+        )
+    }
+}
+
+impl TryFrom<&str> for Image {
+    type Error = ConversionError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
         lazy_static! {
-            static ref RE: Regex = Regex::new(&format!(
-                "^({IP_PART}|{HOSTNAME_PART}/)?{TAG_NAME_PART}(:{TAG_VERSION_PART})?$",
-            ))
-            .expect(&format!("{}, {}", SHOULD_COMPILE, THIS_IS_A_BUG));
+            static ref RE: Regex = Regex::new(&Image::pattern())
+                .expect(&format!("{}, {}", SHOULD_COMPILE, THIS_IS_A_BUG));
         };
 
         if !RE.is_match(value) {
@@ -172,6 +228,21 @@ impl Image {
     pub fn as_str(&self) -> &str {
         &self.0
     }
+
+    /// Whether the image is pinned by digest (e.g. `name@sha256:<digest>`).
+    pub fn is_pinned(&self) -> bool {
+        self.0.contains('@')
+    }
+}
+
+impl JsonSchema for Image {
+    fn schema_name() -> String {
+        "Image".to_string()
+    }
+
+    fn json_schema(_gen: &mut SchemaGenerator) -> Schema {
+        pattern_schema(Image::pattern())
+    }
 }
 
 /// A command that will be executed natively (native provider) or in a container (podman/k8s).
@@ -213,6 +284,17 @@ impl Command {
     }
 }
 
+impl JsonSchema for Command {
+    fn schema_name() -> String {
+        "Command".to_string()
+    }
+
+    fn json_schema(_gen: &mut SchemaGenerator) -> Schema {
+        // without whitespaces
+        pattern_schema(r"^\S*$")
+    }
+}
+
 /// A command with optional custom arguments, the command will be executed natively (native provider) or in a container (podman/k8s).
 /// It can be constructed from an `&str`, if it fails, it will returns a [`ConversionError`].
 ///
@@ -226,7 +308,7 @@ impl Command {
 /// assert_eq!(command1.cmd().as_str(), "mycommand");
 /// assert_eq!(command2.cmd().as_str(), "my_other_cmd_without_args");
 /// ```
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
 pub struct CommandWithCustomArgs(Command, Vec<Arg>);
 
 impl TryFrom<&str> for CommandWithCustomArgs {
@@ -267,8 +349,9 @@ impl CommandWithCustomArgs {
     }
 }
 
-/// A location for a locally or remotely stored asset.
-/// It can be constructed from an [`url::Url`], a [`std::path::PathBuf`] or an `&str`.
+/// A location for a locally or remotely stored asset, or the inline content of a json asset.
+/// It can be constructed from an [`url::Url`], a [`std::path::PathBuf`] or an `&str`
+/// (a `&str` starting with `{` is handled as inline json).
 ///
 /// # Examples:
 /// ```
@@ -285,11 +368,15 @@ impl CommandWithCustomArgs {
 /// assert!(matches!(url_location2, AssetLocation::Url(value) if value.as_str() == "https://mycloudstorage.com/path/to/my/file.tgz"));
 /// assert!(matches!(path_location, AssetLocation::FilePath(value) if value.to_str().unwrap() == "/tmp/path/to/my/file"));
 /// assert!(matches!(path_location2, AssetLocation::FilePath(value) if value.to_str().unwrap() == "/tmp/path/to/my/file"));
+///
+/// let inline_location: AssetLocation = r#"{"name": "Local Testnet"}"#.into();
+/// assert!(matches!(inline_location, AssetLocation::InlineJson(value) if value == r#"{"name": "Local Testnet"}"#));
 /// ```
 #[derive(Debug, Clone, PartialEq)]
 pub enum AssetLocation {
     Url(Url),
     FilePath(PathBuf),
+    InlineJson(String),
 }
 
 impl From<Url> for AssetLocation {
@@ -306,6 +393,10 @@ impl From<PathBuf> for AssetLocation {
 
 impl From<&str> for AssetLocation {
     fn from(value: &str) -> Self {
+        if value.trim_start().starts_with('{') {
+            return Self::InlineJson(value.to_string());
+        }
+
         if let Ok(parsed_url) = Url::parse(value) {
             return Self::Url(parsed_url);
         }
@@ -321,6 +412,7 @@ impl Display for AssetLocation {
         match self {
             AssetLocation::Url(value) => write!(f, "{}", value.as_str()),
             AssetLocation::FilePath(value) => write!(f, "{}", value.display()),
+            AssetLocation::InlineJson(value) => write!(f, "{}", value),
         }
     }
 }
@@ -329,13 +421,16 @@ impl AssetLocation {
     pub async fn get_asset(&self) -> Result<Vec<u8>, anyhow::Error> {
         let contents = match self {
             AssetLocation::Url(location) => {
-                let res = reqwest::get(location.as_ref()).await.map_err(|err| {
-                    anyhow!(
-                        "Error dowinloding asset from url {} - {}",
-                        location,
-                        err.to_string()
-                    )
-                })?;
+                let res = reqwest::get(location.as_ref())
+                    .await
+                    .and_then(|res| res.error_for_status())
+                    .map_err(|err| {
+                        anyhow!(
+                            "Error dowinloding asset from url {} - {}",
+                            location,
+                            err.to_string()
+                        )
+                    })?;
 
                 res.bytes().await.unwrap().into()
             },
@@ -348,10 +443,92 @@ impl AssetLocation {
                     )
                 })?
             },
+            AssetLocation::InlineJson(content) => content.as_bytes().to_vec(),
         };
 
         Ok(contents)
     }
+
+    /// Get the asset, caching the remote (url) ones in `cache_dir`.
+    /// The cache is keyed by the url, the `ETag`/`Last-Modified` header (when provided by
+    /// the server) is stored next to the cached asset, so a remote asset reporting a
+    /// different one is downloaded again. The cached asset is used if the server can't be
+    /// reached or doesn't provide these headers.
+    pub async fn get_asset_cached(
+        &self,
+        filesystem: &impl FileSystem,
+        cache_dir: impl AsRef<Path>,
+    ) -> Result<Vec<u8>, anyhow::Error> {
+        let AssetLocation::Url(location) = self else {
+            return self.get_asset().await;
+        };
+
+        let validator = match reqwest::Client::new()
+            .head(location.as_ref())
+            .send()
+            .await
+            .and_then(|res| res.error_for_status())
+        {
+            Ok(res) => res
+                .headers()
+                .get(ETAG)
+                .or(res.headers().get(LAST_MODIFIED))
+                .and_then(|value| value.to_str().ok())
+                .map(str::to_string),
+            Err(err) => {
+                warn!("Error getting the headers of asset {location} - {err}");
+                None
+            },
+        };
+
+        let cache_dir = cache_dir.as_ref();
+        let cache_key = asset_cache_key(location);
+        let cached_path = cache_dir.join(&cache_key);
+        let validator_path = cache_dir.join(format!("{cache_key}.validator"));
+        if let Ok(contents) = filesystem.read(&cached_path).await {
+            let cached_validator = filesystem.read_to_string(&validator_path).await.ok();
+            if !is_cached_asset_stale(cached_validator.as_deref(), validator.as_deref()) {
+                info!("💾 asset {location} found in cache (cache hit)");
+                return Ok(contents);
+            }
+
+            debug!("asset {location} changed since it was cached, downloading");
+        } else {
+            debug!("asset {location} not found in cache (cache miss), downloading");
+        }
+
+        let contents = self.get_asset().await?;
+
+        // failing to write the cache is not fatal
+        if let Err(err) = filesystem.create_dir_all(cache_dir).await {
+            warn!("Error creating cache dir {} - {err}", cache_dir.display());
+        } else if let Err(err) = filesystem.write(&cached_path, contents.as_slice()).await {
+            warn!("Error caching asset {location} - {err}");
+        } else {
+            let stored = match validator {
+                Some(validator) => filesystem.write(&validator_path, validator).await,
+                None if filesystem.exists(&validator_path).await => {
+                    filesystem.remove_file(&validator_path).await
+                },
+                None => Ok(()),
+            };
+            if let Err(err) = stored {
+                warn!("Error caching the headers of asset {location} - {err}");
+            }
+        }
+
+        Ok(contents)
+    }
+}
+
+fn asset_cache_key(location: &Url) -> String {
+    hex::encode(Sha256::digest(location.as_str().as_bytes()))
+}
+
+/// A cached asset is only stale if the server reports a validator (`ETag`/`Last-Modified`)
+/// different from the one stored with it.
+fn is_cached_asset_stale(cached_validator: Option<&str>, validator: Option<&str>) -> bool {
+    validator.is_some() && validator != cached_validator
 }
 
 impl Serialize for AssetLocation {
@@ -389,9 +566,23 @@ impl<'de> Deserialize<'de> for AssetLocation {
     }
 }
 
+impl JsonSchema for AssetLocation {
+    fn schema_name() -> String {
+        "AssetLocation".to_string()
+    }
+
+    fn json_schema(gen: &mut SchemaGenerator) -> Schema {
+        // an url or a local path
+        String::json_schema(gen)
+    }
+}
+
 /// A CLI argument passed to an executed command, can be an option with an assigned value or a simple flag to enable/disable a feature.
 /// A flag arg can be constructed from a `&str` and a option arg can be constructed from a `(&str, &str)`.
 ///
+/// A remove directive (`!<arg>` in `toml`) drops an inherited default arg, matching it
+/// by flag, option name or `name=value`, see `with_args_override`.
+///
 /// # Examples:
 /// ```
 /// use zombienet_configuration::shared::types::Arg;
@@ -406,6 +597,7 @@ impl<'de> Deserialize<'de> for AssetLocation {
 pub enum Arg {
     Flag(String),
     Option(String, String),
+    Remove(String),
 }
 
 impl From<&str> for Arg {
@@ -430,6 +622,7 @@ impl Serialize for Arg {
             Arg::Option(option, value) => {
                 serializer.serialize_str(&format!("{}={}", option, value))
             },
+            Arg::Remove(value) => serializer.serialize_str(&format!("!{}", value)),
         }
     }
 }
@@ -447,6 +640,10 @@ impl de::Visitor<'_> for ArgVisitor {
     where
         E: de::Error,
     {
+        if let Some(value) = v.strip_prefix('!') {
+            return Ok(Arg::Remove(value.to_string()));
+        }
+
         // covers the "-lruntime=debug,parachain=trace" case
         // TODO: Make this more generic by adding the scenario in the regex below
         if v.starts_with("-l") || v.starts_with("-log") {
@@ -485,6 +682,224 @@ impl<'de> Deserialize<'de> for Arg {
     }
 }
 
+impl JsonSchema for Arg {
+    fn schema_name() -> String {
+        "Arg".to_string()
+    }
+
+    fn json_schema(gen: &mut SchemaGenerator) -> Schema {
+        // `<flag>`, `<option>=<value>` or `!<arg>`
+        String::json_schema(gen)
+    }
+}
+
+/// A log target with its level (e.g. `parachain=debug`), the targets of a node are compiled
+/// into a single `-l<target>=<level>,...` flag at spawn time.
+/// It can be constructed from a `(&str, &str)`, if it fails, it will returns a [`ConversionError`].
+///
+/// # Examples:
+/// ```
+/// use zombienet_configuration::shared::types::LogTarget;
+///
+/// let log_target: LogTarget = ("parachain", "debug").try_into().unwrap();
+///
+/// assert_eq!(log_target.target(), "parachain");
+/// assert_eq!(log_target.level(), "debug");
+/// assert_eq!(log_target.to_string(), "parachain=debug");
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct LogTarget {
+    target: String,
+    level: String,
+}
+
+const LOG_TARGET_PART: &str = "[a-zA-Z0-9_:-]+";
+const LOG_LEVEL_PART: &str = "(error|warn|info|debug|trace)";
+
+impl TryFrom<(&str, &str)> for LogTarget {
+    type Error = ConversionError;
+
+    fn try_from((target, level): (&str, &str)) -> Result<Self, Self::Error> {
+        lazy_static! {
+            static ref TARGET_RE: Regex = Regex::new(&format!("^{LOG_TARGET_PART}$"))
+                .expect(&format!("{}, {}", SHOULD_COMPILE, THIS_IS_A_BUG));
+            static ref LEVEL_RE: Regex = Regex::new(&format!("^{LOG_LEVEL_PART}$"))
+                .expect(&format!("{}, {}", SHOULD_COMPILE, THIS_IS_A_BUG));
+        };
+
+        if !TARGET_RE.is_match(target) {
+            return Err(ConversionError::DoesntMatchRegex {
+                value: target.to_string(),
+                regex: TARGET_RE.to_string(),
+            });
+        }
+
+        if !LEVEL_RE.is_match(level) {
+            return Err(ConversionError::DoesntMatchRegex {
+                value: level.to_string(),
+                regex: LEVEL_RE.to_string(),
+            });
+        }
+
+        Ok(Self {
+            target: target.to_string(),
+            level: level.to_string(),
+        })
+    }
+}
+
+impl LogTarget {
+    pub fn target(&self) -> &str {
+        &self.target
+    }
+
+    pub fn level(&self) -> &str {
+        &self.level
+    }
+}
+
+impl Display for LogTarget {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}={}", self.target, self.level)
+    }
+}
+
+impl Serialize for LogTarget {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+impl<'de> Deserialize<'de> for LogTarget {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let value = String::deserialize(deserializer)?;
+        let (target, level) = value
+            .split_once('=')
+            .ok_or_else(|| de::Error::custom(format!("'{value}' should be <target>=<level>")))?;
+
+        (target, level).try_into().map_err(de::Error::custom)
+    }
+}
+
+impl JsonSchema for LogTarget {
+    fn schema_name() -> String {
+        "LogTarget".to_string()
+    }
+
+    fn json_schema(_gen: &mut SchemaGenerator) -> Schema {
+        // `<target>=<level>`
+        pattern_schema(format!("^{LOG_TARGET_PART}={LOG_LEVEL_PART}$"))
+    }
+}
+
+/// The strategy used to select the bootnodes of a chain (spawned before the rest of the nodes).
+#[derive(Debug, Default, Clone, Copy, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum BootnodeStrategy {
+    /// Use the nodes flagged as bootnodes, or the first validator if none is flagged.
+    #[default]
+    FirstValidator,
+    /// Use the nodes flagged as bootnodes and all the validators.
+    AllValidators,
+    /// Use only the nodes flagged as bootnodes, failing if none is flagged.
+    ExplicitOnly,
+    /// Don't use any bootnode, all the nodes are spawned at the same time.
+    None,
+}
+
+/// The RPC methods exposed by a node (`--rpc-methods`).
+#[derive(Debug, Default, Clone, Copy, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum RpcMethods {
+    /// Only the safe methods, required to bind the RPC externally with `--rpc-external`.
+    Safe,
+    /// All the methods, the RPC is bound externally with `--unsafe-rpc-external`.
+    #[default]
+    Unsafe,
+}
+
+impl RpcMethods {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RpcMethods::Safe => "safe",
+            RpcMethods::Unsafe => "unsafe",
+        }
+    }
+}
+
+/// The blockchain syncing mode of a node (`--sync`).
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SyncMode {
+    /// Download and verify the full blockchain history.
+    Full,
+    /// Download the blocks and the latest state only.
+    Fast,
+    /// Like `fast`, but without downloading the blocks data.
+    FastUnsafe,
+    /// Download the latest state and the proof (needs a `GRANDPA` finalized chain).
+    Warp,
+}
+
+impl SyncMode {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SyncMode::Full => "full",
+            SyncMode::Fast => "fast",
+            SyncMode::FastUnsafe => "fast-unsafe",
+            SyncMode::Warp => "warp",
+        }
+    }
+}
+
+/// The account used as the node (validator/collator) id in the genesis session keys.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SessionKeyType {
+    /// The `sr` account of the node.
+    #[default]
+    Default,
+    /// The `sr` stash account of the node (`//<Name>//stash`).
+    Stash,
+    /// The `eth` account of the node (evm based chains).
+    Evm,
+}
+
+/// A closure to post-process the generated (plain) chain-spec, see
+/// `with_chain_spec_modifier` in the relaychain/parachain builders.
+#[derive(Clone)]
+pub struct ChainSpecModifier(Arc<dyn Fn(&mut serde_json::Value) + Send + Sync>);
+
+impl ChainSpecModifier {
+    pub fn new(modifier: impl Fn(&mut serde_json::Value) + Send + Sync + 'static) -> Self {
+        Self(Arc::new(modifier))
+    }
+
+    /// Apply the modifier to the `chain_spec` json.
+    pub fn apply(&self, chain_spec: &mut serde_json::Value) {
+        (self.0)(chain_spec)
+    }
+}
+
+impl fmt::Debug for ChainSpecModifier {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("ChainSpecModifier")
+    }
+}
+
+// Closures can't be compared, two modifiers are equal only if they are the same closure.
+impl PartialEq for ChainSpecModifier {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
 #[derive(Debug, Default, Clone)]
 pub struct ValidationContext {
     pub used_ports: Vec<Port>,
@@ -526,6 +941,24 @@ mod tests {
         assert_eq!(got.unwrap().as_str(), "myimage:version");
     }
 
+    #[test]
+    fn converting_a_str_containing_a_digest_into_an_image_should_succeeds() {
+        let digest = "sha256:3b8dd8dbc1f4f2d4c2f2b8e5b2f1f6a4e5d6c7b8a9f0e1d2c3b4a5f6e7d8c9b0";
+        let got: Result<Image, ConversionError> = format!("docker.io/parity/polkadot@{digest}")
+            .as_str()
+            .try_into();
+
+        let image = got.unwrap();
+        assert_eq!(
+            image.as_str(),
+            format!("docker.io/parity/polkadot@{digest}")
+        );
+        assert!(image.is_pinned());
+        assert!(!Image::try_from("docker.io/parity/polkadot:latest")
+            .unwrap()
+            .is_pinned());
+    }
+
     #[test]
     fn converting_a_str_containing_hostname_and_tag_name_into_an_image_should_succeeds() {
         let got: Result<Image, ConversionError> = "myrepository.com/myimage".try_into();
@@ -597,6 +1030,14 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn converting_a_str_containing_json_into_an_inline_json_asset_location_should_succeeds() {
+        let content = r#"  {"name": "Local Testnet", "id": "local_testnet"}"#;
+        let got: AssetLocation = content.into();
+
+        assert!(matches!(got, AssetLocation::InlineJson(value) if value == content));
+    }
+
     #[test]
     fn converting_a_str_into_an_flag_arg_should_succeeds() {
         let got: Arg = "myflag".into();
@@ -611,6 +1052,16 @@ mod tests {
         assert!(matches!(got, Arg::Option(name, value) if name == "name" && value == "value"));
     }
 
+    #[test]
+    fn converting_a_str_tuple_with_an_unknown_level_into_a_log_target_should_fails() {
+        let got: Result<LogTarget, ConversionError> = ("parachain", "verbose").try_into();
+
+        assert!(matches!(
+            got.unwrap_err(),
+            ConversionError::DoesntMatchRegex { value, .. } if value == "verbose"
+        ));
+    }
+
     #[test]
     fn converting_a_str_with_whitespaces_into_a_chain_should_fails() {
         let got: Result<Chain, ConversionError> = "my chain".try_into();
@@ -696,4 +1147,16 @@ mod tests {
             "'my command' shouldn't contains whitespace"
         );
     }
+
+    #[test]
+    fn cached_asset_should_only_be_stale_if_the_remote_validator_changed() {
+        // the server reports a different validator
+        assert!(is_cached_asset_stale(Some("\"abc\""), Some("\"def\"")));
+        assert!(is_cached_asset_stale(None, Some("\"abc\"")));
+        // the same validator
+        assert!(!is_cached_asset_stale(Some("\"abc\""), Some("\"abc\"")));
+        // the server can't be reached or doesn't report validators
+        assert!(!is_cached_asset_stale(Some("\"abc\""), None));
+        assert!(!is_cached_asset_stale(None, None));
+    }
 }
@@ -1,21 +1,36 @@
 #![allow(clippy::expect_fun_call)]
-use std::io;
+use std::{
+    collections::HashMap,
+    io,
+    sync::Arc,
+    time::{SystemTime, UNIX_EPOCH},
+};
 
 use axum::{
     extract::{Path, Request, State},
-    http::StatusCode,
+    http::{HeaderValue, Method, StatusCode},
+    middleware::{self, Next},
+    response::Response,
     routing::{get, post},
-    Router,
+    Json, Router,
 };
 use futures::TryStreamExt;
-use tokio::{fs::File, io::BufWriter, net::TcpListener};
+use nix::sys::statvfs::statvfs;
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+use tokio::{fs::File, io::BufWriter, net::TcpListener, sync::RwLock};
 use tokio_util::io::StreamReader;
 use tower_http::services::ServeDir;
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 
+// Header used to check the integrity of the uploaded/served files
+const CONTENT_SHA256_HEADER: &str = "x-content-sha256";
+
 #[derive(Clone)]
 struct AppState {
     uploads_directory: String,
+    // sha256 of the uploaded files (by path), computed while uploading them
+    hashes: Arc<RwLock<HashMap<String, String>>>,
 }
 
 #[tokio::main]
@@ -33,19 +48,33 @@ async fn main() {
         .await
         .expect(&format!("failed to create '{uploads_directory}' directory"));
 
-    let app = Router::new()
-        .route("/", get(|| async { "Ok" }))
-        .route(
-            "/*file_path",
-            post(upload).get_service(ServeDir::new(&uploads_directory)),
-        )
-        .with_state(AppState { uploads_directory });
+    let state = AppState {
+        uploads_directory,
+        hashes: Default::default(),
+    };
 
     let listener = TcpListener::bind(&address)
         .await
         .expect(&format!("failed to listen on {address}"));
     tracing::info!("file server started on {}", listener.local_addr().unwrap());
-    axum::serve(listener, app).await.unwrap()
+    axum::serve(listener, app(state)).await.unwrap()
+}
+
+fn app(state: AppState) -> Router {
+    Router::new()
+        .route("/", get(|| async { "Ok" }))
+        .route("/healthz", get(healthz))
+        .route("/readyz", get(readyz))
+        .route(
+            "/*file_path",
+            post(upload)
+                .get_service(ServeDir::new(&state.uploads_directory))
+                .layer(middleware::from_fn_with_state(
+                    state.clone(),
+                    add_content_sha256,
+                )),
+        )
+        .with_state(state)
 }
 
 async fn upload(
@@ -57,15 +86,35 @@ async fn upload(
         return Err((StatusCode::BAD_REQUEST, "Invalid path".to_owned()));
     }
 
-    async {
-        let path = std::path::Path::new(&state.uploads_directory).join(file_path);
+    let expected_hash = request
+        .headers()
+        .get(CONTENT_SHA256_HEADER)
+        .map(|value| value.to_str().map(|hash| hash.to_lowercase()))
+        .transpose()
+        .map_err(|_| {
+            (
+                StatusCode::BAD_REQUEST,
+                format!("Invalid {CONTENT_SHA256_HEADER} header"),
+            )
+        })?;
+
+    let path = std::path::Path::new(&state.uploads_directory).join(&file_path);
+    // the previous content (if any) is overwritten
+    state.hashes.write().await.remove(&file_path);
 
+    let hash = async {
         if let Some(parent_dir) = path.parent() {
             tokio::fs::create_dir_all(parent_dir).await?;
         }
 
+        let mut hasher = Sha256::new();
         let stream = request.into_body().into_data_stream();
-        let body_with_io_error = stream.map_err(|err| io::Error::new(io::ErrorKind::Other, err));
+        let body_with_io_error = stream
+            .map_ok(|chunk| {
+                hasher.update(&chunk);
+                chunk
+            })
+            .map_err(|err| io::Error::new(io::ErrorKind::Other, err));
         let body_reader = StreamReader::new(body_with_io_error);
         futures::pin_mut!(body_reader);
 
@@ -74,10 +123,95 @@ async fn upload(
 
         tracing::info!("created file '{}'", path.to_string_lossy());
 
-        Ok::<_, io::Error>(())
+        Ok::<_, io::Error>(hex::encode(hasher.finalize()))
     }
     .await
-    .map_err(|err| (StatusCode::INTERNAL_SERVER_ERROR, err.to_string()))
+    .map_err(|err| (StatusCode::INTERNAL_SERVER_ERROR, err.to_string()))?;
+
+    if let Some(expected_hash) = expected_hash {
+        if hash != expected_hash {
+            if let Err(err) = tokio::fs::remove_file(&path).await {
+                tracing::warn!(
+                    "failed to remove corrupted file '{}': {err}",
+                    path.to_string_lossy()
+                );
+            }
+
+            return Err((
+                StatusCode::BAD_REQUEST,
+                format!("Checksum mismatch, expected {expected_hash} got {hash}"),
+            ));
+        }
+    }
+
+    state.hashes.write().await.insert(file_path, hash);
+
+    Ok(())
+}
+
+// Add the sha256 of the served file (computed on upload) as response header
+async fn add_content_sha256(
+    State(state): State<AppState>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let is_download = matches!(*request.method(), Method::GET | Method::HEAD);
+    let file_path = request.uri().path().trim_start_matches('/').to_string();
+
+    let mut response = next.run(request).await;
+
+    if is_download && response.status() == StatusCode::OK {
+        if let Some(hash) = state.hashes.read().await.get(&file_path) {
+            if let Ok(value) = HeaderValue::from_str(hash) {
+                response.headers_mut().insert(CONTENT_SHA256_HEADER, value);
+            }
+        }
+    }
+
+    response
+}
+
+// Healthy only if the uploads directory is writable
+async fn healthz(State(state): State<AppState>) -> (StatusCode, Json<Value>) {
+    match check_writable(&state.uploads_directory).await {
+        Ok(()) => (StatusCode::OK, Json(json!({ "status": "ok" }))),
+        Err(err) => (
+            StatusCode::SERVICE_UNAVAILABLE,
+            Json(json!({ "status": "error", "error": err.to_string() })),
+        ),
+    }
+}
+
+// Report the free space (in bytes) available under the uploads directory
+// NOTE: the statvfs field types differ across platforms, hence the casts
+#[allow(clippy::unnecessary_cast)]
+async fn readyz(State(state): State<AppState>) -> (StatusCode, Json<Value>) {
+    match statvfs(state.uploads_directory.as_str()) {
+        Ok(stats) => (
+            StatusCode::OK,
+            Json(json!({
+                "status": "ok",
+                "uploads_directory": state.uploads_directory,
+                "free_bytes": stats.blocks_available() as u64 * stats.fragment_size() as u64,
+            })),
+        ),
+        Err(err) => (
+            StatusCode::SERVICE_UNAVAILABLE,
+            Json(json!({ "status": "error", "error": err.to_string() })),
+        ),
+    }
+}
+
+async fn check_writable(uploads_directory: &str) -> io::Result<()> {
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_nanos())
+        .unwrap_or_default();
+    let path = std::path::Path::new(uploads_directory)
+        .join(format!(".healthz-{}-{nanos}", std::process::id()));
+
+    tokio::fs::write(&path, b"ok").await?;
+    tokio::fs::remove_file(&path).await
 }
 
 fn path_is_valid(path: &str) -> bool {
@@ -86,3 +220,115 @@ fn path_is_valid(path: &str) -> bool {
 
     components.all(|component| matches!(component, std::path::Component::Normal(_)))
 }
+
+#[cfg(test)]
+mod tests {
+    use axum::body::{to_bytes, Body};
+    use tower::ServiceExt;
+
+    use super::*;
+
+    fn state(uploads_directory: &tempfile::TempDir) -> AppState {
+        AppState {
+            uploads_directory: uploads_directory.path().to_string_lossy().to_string(),
+            hashes: Default::default(),
+        }
+    }
+
+    fn upload_request(path: &str, contents: &'static str, hash: &str) -> Request {
+        Request::post(path)
+            .header(CONTENT_SHA256_HEADER, hash)
+            .body(Body::from(contents))
+            .unwrap()
+    }
+
+    async fn body_json(response: Response) -> Value {
+        serde_json::from_slice(&to_bytes(response.into_body(), usize::MAX).await.unwrap()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn upload_should_fail_and_remove_the_file_if_the_checksum_mismatch() {
+        let uploads_directory = tempfile::tempdir().unwrap();
+        let app = app(state(&uploads_directory));
+
+        let response = app
+            .oneshot(upload_request("/dir/file", "content", &"0".repeat(64)))
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        assert!(!uploads_directory.path().join("dir/file").exists());
+    }
+
+    #[tokio::test]
+    async fn download_should_return_the_checksum_computed_on_upload() {
+        let uploads_directory = tempfile::tempdir().unwrap();
+        let app = app(state(&uploads_directory));
+        let hash = hex::encode(Sha256::digest(b"content"));
+
+        let response = app
+            .clone()
+            .oneshot(upload_request("/dir/file", "content", &hash))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        for method in [Method::GET, Method::HEAD] {
+            let response = app
+                .clone()
+                .oneshot(
+                    Request::builder()
+                        .method(method)
+                        .uri("/dir/file")
+                        .body(Body::empty())
+                        .unwrap(),
+                )
+                .await
+                .unwrap();
+
+            assert_eq!(response.status(), StatusCode::OK);
+            assert_eq!(response.headers()[CONTENT_SHA256_HEADER], hash.as_str());
+        }
+    }
+
+    #[tokio::test]
+    async fn healthz_should_report_if_the_uploads_directory_is_writable() {
+        let uploads_directory = tempfile::tempdir().unwrap();
+        let app = app(state(&uploads_directory));
+
+        let response = app
+            .oneshot(Request::get("/healthz").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(body_json(response).await, json!({ "status": "ok" }));
+
+        let missing_directory = uploads_directory.path().join("missing");
+        let response = super::app(AppState {
+            uploads_directory: missing_directory.to_string_lossy().to_string(),
+            hashes: Default::default(),
+        })
+        .oneshot(Request::get("/healthz").body(Body::empty()).unwrap())
+        .await
+        .unwrap();
+        assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+    }
+
+    #[tokio::test]
+    async fn readyz_should_report_the_free_space() {
+        let uploads_directory = tempfile::tempdir().unwrap();
+        let state = state(&uploads_directory);
+        let app = app(state.clone());
+
+        let response = app
+            .oneshot(Request::get("/readyz").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = body_json(response).await;
+        assert_eq!(body["status"], json!("ok"));
+        assert_eq!(body["uploads_directory"], json!(state.uploads_directory));
+        assert!(body["free_bytes"].as_u64().is_some());
+    }
+}
@@ -18,6 +18,10 @@ pub enum OrchestratorError {
     InvariantError(&'static str),
     #[error("Global network spawn timeout: {0} secs")]
     GlobalTimeOut(u32),
+    #[error("Ordered teardown failed for: {0}")]
+    TeardownFailed(String),
+    #[error("Not supported by the provider: {0}")]
+    UnsupportedByProvider(String),
     #[error("Generator error: {0}")]
     GeneratorError(#[from] generators::errors::GeneratorError),
     #[error("Provider error")]
@@ -15,6 +15,8 @@ pub use command::{
     generate_for_node as generate_node_command, GenCmdOptions,
 };
 pub use identity::generate as generate_node_identity;
-pub use key::generate as generate_node_keys;
-pub use keystore::generate as generate_node_keystore;
-pub use port::generate as generate_node_port;
+pub use key::{address_from_public, generate as generate_node_keys};
+pub use keystore::{
+    generate as generate_node_keystore, generate_custom as generate_custom_keystore,
+};
+pub use port::{generate as generate_node_port, PortRangeGenerator};
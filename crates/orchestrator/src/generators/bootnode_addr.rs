@@ -21,11 +21,13 @@ pub fn generate<T: AsRef<str> + Display>(
         let ip_str = ip.to_string();
         let port_str = port.to_string();
         let mut parts = listen_value.split('/').collect::<Vec<&str>>();
+        // the protocol should match the family of the node ip (e.g. `/ip6` in IPv6-only hosts)
+        parts[1] = ip_protocol(ip);
         parts[2] = &ip_str;
         parts[4] = port_str.as_str();
         parts.join("/")
     } else {
-        format!("/ip4/{ip}/tcp/{port}/ws")
+        format!("/{}/{ip}/tcp/{port}/ws", ip_protocol(ip))
     };
 
     let mut addr_with_peer = format!("{addr}/p2p/{peer_id}");
@@ -36,9 +38,19 @@ pub fn generate<T: AsRef<str> + Display>(
     Ok(addr_with_peer)
 }
 
+/// Multiaddr protocol of the `ip` (`ip4` or `ip6`).
+pub(crate) fn ip_protocol(ip: &IpAddr) -> &'static str {
+    match ip {
+        IpAddr::V4(_) => "ip4",
+        IpAddr::V6(_) => "ip6",
+    }
+}
+
 #[cfg(test)]
 mod tests {
 
+    use std::net::Ipv6Addr;
+
     use provider::constants::LOCALHOST;
 
     use super::*;
@@ -74,6 +86,42 @@ mod tests {
         );
     }
 
+    #[test]
+    fn generate_for_alice_with_ipv6_without_args() {
+        let peer_id = "12D3KooWQCkBm1BYtkHpocxCwMgR8yjitEeHGx8spzcDLGt2gkBm"; // from alice as seed
+        let args: Vec<&str> = vec![];
+        let ip = IpAddr::V6(Ipv6Addr::LOCALHOST);
+        let bootnode_addr = generate(peer_id, &ip, 5678, &args, &None).unwrap();
+        assert_eq!(
+            &bootnode_addr,
+            "/ip6/::1/tcp/5678/ws/p2p/12D3KooWQCkBm1BYtkHpocxCwMgR8yjitEeHGx8spzcDLGt2gkBm"
+        );
+    }
+
+    #[test]
+    fn generate_for_alice_with_ipv6_and_ipv4_listen_addr() {
+        // Should override the protocol, ip and port
+        let peer_id = "12D3KooWQCkBm1BYtkHpocxCwMgR8yjitEeHGx8spzcDLGt2gkBm"; // from alice as seed
+        let args: Vec<&str> = vec!["--listen-addr", "/ip4/0.0.0.0/tcp/30333/ws"];
+        let ip: IpAddr = "fd00::10".parse().unwrap();
+        let bootnode_addr = generate(peer_id, &ip, 5678, &args, &None).unwrap();
+        assert_eq!(
+            &bootnode_addr,
+            "/ip6/fd00::10/tcp/5678/ws/p2p/12D3KooWQCkBm1BYtkHpocxCwMgR8yjitEeHGx8spzcDLGt2gkBm"
+        );
+    }
+
+    #[test]
+    fn generate_for_alice_with_ipv4_and_ipv6_listen_addr() {
+        let peer_id = "12D3KooWQCkBm1BYtkHpocxCwMgR8yjitEeHGx8spzcDLGt2gkBm"; // from alice as seed
+        let args: Vec<&str> = vec!["--listen-addr", "/ip6/::/tcp/30333/ws"];
+        let bootnode_addr = generate(peer_id, &LOCALHOST, 5678, &args, &None).unwrap();
+        assert_eq!(
+            &bootnode_addr,
+            "/ip4/127.0.0.1/tcp/5678/ws/p2p/12D3KooWQCkBm1BYtkHpocxCwMgR8yjitEeHGx8spzcDLGt2gkBm"
+        );
+    }
+
     #[test]
     fn generate_for_alice_with_listen_addr_without_value_must_fail() {
         // Should override the ip/port
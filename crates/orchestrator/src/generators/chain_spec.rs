@@ -4,21 +4,28 @@ use std::{
 };
 
 use anyhow::anyhow;
-use configuration::{types::AssetLocation, HrmpChannelConfig};
+use configuration::{
+    shared::chain_properties::ChainProperties,
+    types::{AssetLocation, SessionKeyType},
+    GlobalSettings, HrmpChannelConfig,
+};
 use provider::{
     constants::NODE_CONFIG_DIR,
     types::{GenerateFileCommand, GenerateFilesOptions, TransferedFile},
     DynNamespace, ProviderError,
 };
+use rand::seq::SliceRandom;
 use serde::Serialize;
 use serde_json::json;
+use sp_core::{sr25519, Pair};
 use support::{constants::THIS_IS_A_BUG, fs::FileSystem, replacer::apply_replacements};
 use tokio::process::Command;
 use tracing::{debug, trace, warn};
 
-use super::errors::GeneratorError;
+use super::{errors::GeneratorError, key::generate_pair};
 use crate::{
     network_spec::{node::NodeSpec, parachain::ParachainSpec, relaychain::RelaychainSpec},
+    shared::constants::ASSETS_CACHE_DIR,
     ScopedFilesystem,
 };
 
@@ -40,19 +47,6 @@ enum KeyType {
     Grandpa,
 }
 
-#[derive(Debug, Clone, Copy)]
-enum SessionKeyType {
-    Default,
-    Stash,
-    Evm,
-}
-
-impl Default for SessionKeyType {
-    fn default() -> Self {
-        Self::Default
-    }
-}
-
 #[derive(Debug, Clone, Serialize)]
 pub enum CommandInContext {
     Local(String),
@@ -109,6 +103,10 @@ impl ChainSpec {
         self.chain_spec_name.as_ref()
     }
 
+    pub(crate) fn set_chain_spec_name(&mut self, chain_spec_name: impl Into<String>) {
+        self.chain_spec_name = chain_spec_name.into();
+    }
+
     pub(crate) fn chain_name(&self) -> Option<&str> {
         self.chain_name.as_deref()
     }
@@ -138,6 +136,22 @@ impl ChainSpec {
         self
     }
 
+    /// Override the image and the command used to build the chain-spec with the global
+    /// spec builder ones (if set).
+    pub(crate) fn apply_spec_builder_overrides(&mut self, global_settings: &GlobalSettings) {
+        if let Some(image) = global_settings.spec_builder_image() {
+            self.image = Some(image.as_str().to_string());
+        }
+
+        if let Some(command) = global_settings.spec_builder_command() {
+            let replacements = HashMap::from([("disableBootnodes", "--disable-default-bootnode")]);
+            self.command = Some(CommandInContext::Remote(apply_replacements(
+                command,
+                &replacements,
+            )));
+        }
+    }
+
     /// Build the chain-spec
     pub async fn build<'a, T>(
         &mut self,
@@ -173,6 +187,17 @@ impl ChainSpec {
                         })?;
                 },
                 AssetLocation::Url(_url) => todo!(),
+                AssetLocation::InlineJson(content) => {
+                    scoped_fs
+                        .write(&maybe_plain_spec_path, content)
+                        .await
+                        .map_err(|_| {
+                            GeneratorError::ChainSpecGeneration(format!(
+                                "Error writing inline chain-spec to {}",
+                                maybe_plain_spec_path.display()
+                            ))
+                        })?;
+                },
             }
         } else {
             // we should create the chain-spec using command.
@@ -335,10 +360,99 @@ impl ChainSpec {
         Ok(())
     }
 
+    /// Override the runtime (`:code`) in the raw chain-spec with the `wasm_override`.
+    /// Remote (url) runtimes are cached in the base dir, to reuse them in the next spawn.
+    pub async fn override_code<'a, T>(
+        &self,
+        scoped_fs: &ScopedFilesystem<'a, T>,
+        wasm_override: &AssetLocation,
+    ) -> Result<(), GeneratorError>
+    where
+        T: FileSystem,
+    {
+        // first ensure we have the raw version of the chain-spec
+        if self.raw_path.is_none() {
+            return Err(GeneratorError::OverridingWasm(
+                "Raw path should be set at this point.".into(),
+            ));
+        }
+
+        let (content, _) = self.read_spec(scoped_fs).await?;
+        let cache_dir = PathBuf::from(scoped_fs.base_dir).join(ASSETS_CACHE_DIR);
+        let override_wasm_data = wasm_override
+            .get_asset_cached(scoped_fs.fs, cache_dir)
+            .await
+            .map_err(|err| GeneratorError::OverridingWasm(err.to_string()))?;
+
+        let mut chain_spec_json: serde_json::Value = serde_json::from_str(&content)
+            .map_err(|_| GeneratorError::ChainSpecGeneration("Can not parse chain-spec".into()))?;
+
+        let Some(code) = chain_spec_json.pointer_mut("/genesis/raw/top/0x3a636f6465") else {
+            return Err(GeneratorError::OverridingWasm(
+                "Can not find `:code` in the raw chain-spec".into(),
+            ));
+        };
+        *code = json!(format!("0x{}", hex::encode(override_wasm_data)));
+
+        let content = serde_json::to_string_pretty(&chain_spec_json).map_err(|_| {
+            GeneratorError::ChainSpecGeneration("can not parse chain-spec value as json".into())
+        })?;
+        self.write_spec(scoped_fs, content).await?;
+
+        Ok(())
+    }
+
+    /// Inject the raw storage `entries` (key/value) in the raw chain-spec (`/genesis/raw/top`),
+    /// replacing the existing values for the same keys.
+    pub async fn override_raw_entries<'a, T>(
+        &self,
+        scoped_fs: &ScopedFilesystem<'a, T>,
+        entries: &[(String, String)],
+    ) -> Result<(), GeneratorError>
+    where
+        T: FileSystem,
+    {
+        // first ensure we have the raw version of the chain-spec
+        if self.raw_path.is_none() {
+            return Err(GeneratorError::ChainSpecGeneration(
+                "Raw path should be set at this point.".into(),
+            ));
+        }
+
+        let (content, _) = self.read_spec(scoped_fs).await?;
+        let mut chain_spec_json: serde_json::Value = serde_json::from_str(&content)
+            .map_err(|_| GeneratorError::ChainSpecGeneration("Can not parse chain-spec".into()))?;
+
+        let Some(top) = chain_spec_json
+            .pointer_mut("/genesis/raw/top")
+            .and_then(|top| top.as_object_mut())
+        else {
+            return Err(GeneratorError::ChainSpecGeneration(
+                "Can not find `/genesis/raw/top` in the raw chain-spec".into(),
+            ));
+        };
+
+        for (key, value) in entries {
+            trace!("injecting raw entry {key}: {value}");
+            top.insert(key.clone(), json!(value));
+        }
+
+        let content = serde_json::to_string_pretty(&chain_spec_json).map_err(|_| {
+            GeneratorError::ChainSpecGeneration("can not parse chain-spec value as json".into())
+        })?;
+        self.write_spec(scoped_fs, content).await?;
+
+        Ok(())
+    }
+
     pub fn raw_path(&self) -> Option<&Path> {
         self.raw_path.as_deref()
     }
 
+    pub fn get_asset_location(&self) -> Option<&AssetLocation> {
+        self.asset_location.as_ref()
+    }
+
     pub fn set_asset_location(&mut self, location: AssetLocation) {
         self.asset_location = Some(location)
     }
@@ -449,53 +563,32 @@ impl ChainSpec {
                 }
             }
 
-            clear_authorities(&pointer, &mut chain_spec_json);
-
             let key_type_to_use = if para.is_evm_based {
                 SessionKeyType::Evm
             } else {
                 SessionKeyType::Default
             };
 
-            // Get validators to add as authorities
-            let validators: Vec<&NodeSpec> = para
-                .collators
-                .iter()
-                .filter(|node| node.is_validator)
-                .collect();
-
-            // check chain key types
-            if chain_spec_json
-                .pointer(&format!("{}/session", pointer))
-                .is_some()
-            {
-                add_authorities(&pointer, &mut chain_spec_json, &validators, key_type_to_use);
-            } else if chain_spec_json
-                .pointer(&format!("{}/aura", pointer))
-                .is_some()
-            {
-                add_aura_authorities(&pointer, &mut chain_spec_json, &validators, KeyType::Aura);
-            } else {
-                warn!("Can't customize keys, not `session` or `aura` find in the chain-spec file");
-            };
-
-            // Add nodes to collator
-            let invulnerables: Vec<&NodeSpec> = para
-                .collators
-                .iter()
-                .filter(|node| node.is_invulnerable)
-                .collect();
-
-            add_collator_selection(
+            add_para_authorities(
                 &pointer,
                 &mut chain_spec_json,
-                &invulnerables,
+                &para.collators,
                 key_type_to_use,
             );
 
             // override `parachainInfo/parachainId`
             override_parachain_info(&pointer, &mut chain_spec_json, para.id);
 
+            // override the `properties` (e.g. tokenDecimals)
+            if let Some(properties) = &para.chain_properties {
+                override_chain_properties(&mut chain_spec_json, properties);
+            }
+
+            // custom modifier (if any) runs last, before the raw conversion
+            if let Some(modifier) = &para.chain_spec_modifier {
+                modifier.apply(&mut chain_spec_json);
+            }
+
             // write spec
             let content = serde_json::to_string_pretty(&chain_spec_json).map_err(|_| {
                 GeneratorError::ChainSpecGeneration("can not parse chain-spec value as json".into())
@@ -525,6 +618,11 @@ impl ChainSpec {
             })?;
 
         if let ChainSpecFormat::Plain = format {
+            // override the properties first, since `tokenDecimals` is used for the balances
+            if let Some(properties) = &relaychain.chain_properties {
+                override_chain_properties(&mut chain_spec_json, properties);
+            }
+
             // get the tokenDecimals property or set the default (12)
             let token_decimals =
                 if let Some(val) = chain_spec_json.pointer("/properties/tokenDecimals") {
@@ -573,6 +671,15 @@ impl ChainSpec {
                 staking_min,
             );
 
+            // add nominators (if any)
+            add_nominators(
+                &pointer,
+                &mut chain_spec_json,
+                relaychain.random_nominators_count,
+                relaychain.max_nominations,
+                staking_min,
+            )?;
+
             // Get validators to add as authorities
             let validators: Vec<&NodeSpec> = relaychain
                 .nodes
@@ -596,8 +703,6 @@ impl ChainSpec {
                 add_grandpa_authorities(&pointer, &mut chain_spec_json, &validators, KeyType::Aura);
             }
 
-            // staking && nominators
-
             if !hrmp_channels.is_empty() {
                 add_hrmp_channels(&pointer, &mut chain_spec_json, hrmp_channels);
             }
@@ -614,9 +719,10 @@ impl ChainSpec {
                 .map_err(|e| GeneratorError::ChainSpecGeneration(e.to_string()))?;
             }
 
-            // TODO:
-            // - staking
-            // - nominators
+            // custom modifier (if any) runs last, before the raw conversion
+            if let Some(modifier) = &relaychain.chain_spec_modifier {
+                modifier.apply(&mut chain_spec_json);
+            }
 
             // write spec
             let content = serde_json::to_string_pretty(&chain_spec_json).map_err(|_| {
@@ -1022,6 +1128,21 @@ fn add_balances(
     }
 }
 
+// The session keys names and their key type (crypto id)
+const SESSION_KEY_TYPES: [(&str, &str); 11] = [
+    ("babe", "babe"),
+    ("im_online", "imon"),
+    ("parachain_validator", "para"),
+    ("authority_discovery", "audi"),
+    ("para_validator", "para"),
+    ("para_assignment", "asgn"),
+    ("aura", "aura"),
+    ("nimbus", "nmbs"),
+    ("vrf", "rand"),
+    ("grandpa", "gran"),
+    ("beefy", "beef"),
+];
+
 fn get_node_keys(
     node: &NodeSpec,
     session_key: SessionKeyType,
@@ -1055,6 +1176,13 @@ fn get_node_keys(
     keys.insert("beefy".to_string(), ec_account.address.clone());
     keys.insert("eth".to_string(), eth_account.public_key.clone());
 
+    // the custom keys marked as authorities take precedence over the derived ones
+    for (name, key_type) in SESSION_KEY_TYPES {
+        if let Some(address) = node.authority_keys.get(key_type) {
+            keys.insert(name.to_string(), address.clone());
+        }
+    }
+
     let account_to_use = match session_key {
         SessionKeyType::Default => sr_account.address.clone(),
         SessionKeyType::Stash => sr_stash.address.clone(),
@@ -1063,6 +1191,7 @@ fn get_node_keys(
 
     (account_to_use.clone(), account_to_use, keys)
 }
+// The nodes `session_key_type` (if set) takes precedence over the chain `session_key`.
 fn add_authorities(
     runtime_config_ptr: &str,
     chain_spec_json: &mut serde_json::Value,
@@ -1078,7 +1207,10 @@ fn add_authorities(
         if let Some(session_keys) = val.pointer_mut("/session/keys") {
             let keys: Vec<GenesisNodeKey> = nodes
                 .iter()
-                .map(|node| get_node_keys(node, session_key, asset_hub_polkadot))
+                .map(|node| {
+                    let session_key = node.session_key_type.unwrap_or(session_key);
+                    get_node_keys(node, session_key, asset_hub_polkadot)
+                })
                 .collect();
             *session_keys = json!(keys);
         } else {
@@ -1126,14 +1258,16 @@ fn add_aura_authorities(
             let keys: Vec<String> = nodes
                 .iter()
                 .map(|node| {
-                    node.accounts
-                        .accounts
-                        .get("sr")
-                        .expect(&format!(
-                            "'sr' account should be set at spec computation {THIS_IS_A_BUG}"
-                        ))
-                        .address
-                        .clone()
+                    node.authority_keys.get("aura").cloned().unwrap_or_else(|| {
+                        node.accounts
+                            .accounts
+                            .get("sr")
+                            .expect(&format!(
+                                "'sr' account should be set at spec computation {THIS_IS_A_BUG}"
+                            ))
+                            .address
+                            .clone()
+                    })
                 })
                 .collect();
             *aura_authorities = json!(keys);
@@ -1156,7 +1290,7 @@ fn add_grandpa_authorities(
             let keys: Vec<(String, usize)> = nodes
                 .iter()
                 .map(|node| {
-                    (
+                    let key = node.authority_keys.get("gran").cloned().unwrap_or_else(|| {
                         node.accounts
                             .accounts
                             .get("ed")
@@ -1164,9 +1298,9 @@ fn add_grandpa_authorities(
                                 "'ed' account should be set at spec computation {THIS_IS_A_BUG}"
                             ))
                             .address
-                            .clone(),
-                        1,
-                    )
+                            .clone()
+                    });
+                    (key, 1)
                 })
                 .collect();
             *grandpa_authorities = json!(keys);
@@ -1200,6 +1334,16 @@ fn add_staking(
                 .get("sr_stash")
                 .expect("'sr_stash account should be defined for the node. qed")
                 .address;
+            // the session keys of the node are set with the same account
+            if let Some(session_key_type) = node
+                .session_key_type
+                .filter(|session_key_type| *session_key_type != SessionKeyType::Stash)
+            {
+                warn!(
+                    "⚠️  node {} uses the {session_key_type:?} session key type, but it's staked with the sr_stash account, the session keys will not match the stake",
+                    node.name
+                );
+            }
             stakers.push(json!([
                 sr_stash_addr,
                 sr_stash_addr,
@@ -1220,8 +1364,95 @@ fn add_staking(
     }
 }
 
-// TODO: (team)
-// fn add_nominators() {}
+/// Add `count` nominators (`//Nominator//<i>`) to the staking genesis, bonding `staking_min`
+/// and nominating up to `max_nominations` validators picked at random from the stakers. The
+/// nominators are funded in the balances genesis (with twice the bond, as the nodes).
+/// If the runtime generates the stakers itself (`staking.devStakers`, as `(validators,
+/// nominators)`), the nominators count is set there instead.
+fn add_nominators(
+    runtime_config_ptr: &str,
+    chain_spec_json: &mut serde_json::Value,
+    count: u32,
+    max_nominations: u8,
+    staking_min: u128,
+) -> Result<(), GeneratorError> {
+    if count == 0 {
+        return Ok(());
+    }
+
+    let Some(val) = chain_spec_json.pointer_mut(runtime_config_ptr) else {
+        unreachable!("pointer to runtime config should be valid!")
+    };
+
+    if let Some(dev_stakers) = val
+        .pointer_mut("/staking/devStakers")
+        .filter(|dev_stakers| !dev_stakers.is_null())
+    {
+        // `devStakers` is `(validators, nominators)`
+        let Some(nominators) = dev_stakers.as_array_mut().and_then(|pair| pair.get_mut(1)) else {
+            return Err(GeneratorError::ChainSpecGeneration(format!(
+                "invalid 'staking.devStakers' in runtime config, expected [validators, nominators], got {dev_stakers}"
+            )));
+        };
+        *nominators = json!(count);
+        return Ok(());
+    }
+
+    let Some(stakers) = val.pointer("/staking/stakers").and_then(|s| s.as_array()) else {
+        warn!("NO 'staking' key in runtime config, skipping nominators...");
+        return Ok(());
+    };
+
+    let validators: Vec<serde_json::Value> = stakers
+        .iter()
+        .filter(|staker| staker[3] == json!("Validator"))
+        .map(|staker| staker[0].clone())
+        .collect();
+    if validators.is_empty() {
+        warn!("⚠️  no validators in the staking genesis to nominate, skipping nominators...");
+        return Ok(());
+    }
+
+    let mut rng = rand::thread_rng();
+    let mut addresses = vec![];
+    let mut nominators = vec![];
+    for i in 0..count {
+        let seed = format!("//Nominator//{i}");
+        let address = generate_pair::<sr25519::Pair>(&seed)
+            .map_err(|_| GeneratorError::KeyGeneration("sr".into(), seed.clone()))?
+            .public()
+            .to_string();
+        let targets: Vec<&serde_json::Value> = validators
+            .choose_multiple(&mut rng, max_nominations as usize)
+            .collect();
+
+        nominators.push(json!([
+            address,
+            address,
+            staking_min,
+            { "Nominator": targets }
+        ]));
+        addresses.push(address);
+    }
+
+    if let Some(balances) = val.pointer("/balances/balances") {
+        let mut balances_map = generate_balance_map(balances);
+        for address in addresses {
+            balances_map.insert(address, staking_min * 2);
+        }
+        let new_balances: Vec<(&String, &u128)> = balances_map.iter().collect();
+        val["balances"]["balances"] = json!(new_balances);
+    }
+
+    if let Some(stakers) = val
+        .pointer_mut("/staking/stakers")
+        .and_then(|s| s.as_array_mut())
+    {
+        stakers.extend(nominators);
+    }
+
+    Ok(())
+}
 
 // // TODO: (team) we should think a better way to use the decorators from
 // // current version (ts).
@@ -1241,6 +1472,101 @@ fn override_parachain_info(
         unreachable!("pointer to runtime config should be valid!")
     }
 }
+
+fn override_chain_properties(
+    chain_spec_json: &mut serde_json::Value,
+    properties: &ChainProperties,
+) {
+    let Some(chain_spec) = chain_spec_json.as_object_mut() else {
+        warn!("Can't override the chain properties, the chain-spec is not an object");
+        return;
+    };
+    let Some(chain_properties) = chain_spec
+        .entry("properties")
+        .or_insert_with(|| json!({}))
+        .as_object_mut()
+    else {
+        warn!("Can't override the chain properties, `properties` is not an object");
+        return;
+    };
+
+    if let Some(token_decimals) = properties.token_decimals() {
+        chain_properties.insert("tokenDecimals".into(), json!(token_decimals));
+    }
+    if let Some(token_symbol) = properties.token_symbol() {
+        chain_properties.insert("tokenSymbol".into(), json!(token_symbol));
+    }
+    if let Some(ss58_prefix) = properties.ss58_prefix() {
+        chain_properties.insert("ss58Format".into(), json!(ss58_prefix));
+    }
+}
+
+/// Set the collators as the para authorities: the validators get session keys (or are added to
+/// the aura authorities if the runtime doesn't use `session`) and the invulnerables are set in
+/// `collatorSelection`. Runtimes like asset-hub use both `session` and `collatorSelection`, the
+/// aura authorities are then taken from the session keys at genesis.
+fn add_para_authorities(
+    runtime_config_ptr: &str,
+    chain_spec_json: &mut serde_json::Value,
+    collators: &[NodeSpec],
+    session_key: SessionKeyType,
+) {
+    clear_authorities(runtime_config_ptr, chain_spec_json);
+
+    let validators: Vec<&NodeSpec> = collators.iter().filter(|node| node.is_validator).collect();
+    let invulnerables: Vec<&NodeSpec> = collators
+        .iter()
+        .filter(|node| node.is_invulnerable)
+        .collect();
+
+    let uses_session = chain_spec_json
+        .pointer(&format!("{runtime_config_ptr}/session"))
+        .is_some();
+
+    // check chain key types
+    if uses_session {
+        add_authorities(
+            runtime_config_ptr,
+            chain_spec_json,
+            &validators,
+            session_key,
+        );
+    } else if chain_spec_json
+        .pointer(&format!("{runtime_config_ptr}/aura"))
+        .is_some()
+    {
+        add_aura_authorities(
+            runtime_config_ptr,
+            chain_spec_json,
+            &validators,
+            KeyType::Aura,
+        );
+    } else {
+        warn!("Can't customize keys, not `session` or `aura` find in the chain-spec file");
+    };
+
+    if uses_session
+        && chain_spec_json
+            .pointer(&format!("{runtime_config_ptr}/collatorSelection"))
+            .is_some()
+    {
+        // the session validators are the invulnerables with keys, so the others never author
+        for node in invulnerables.iter().filter(|node| !node.is_validator) {
+            warn!(
+                "⚠️  collator {} is invulnerable but not a validator (without session keys), it will not author blocks",
+                node.name
+            );
+        }
+    }
+
+    add_collator_selection(
+        runtime_config_ptr,
+        chain_spec_json,
+        &invulnerables,
+        session_key,
+    );
+}
+
 fn add_collator_selection(
     runtime_config_ptr: &str,
     chain_spec_json: &mut serde_json::Value,
@@ -1248,14 +1574,15 @@ fn add_collator_selection(
     session_key: SessionKeyType,
 ) {
     if let Some(val) = chain_spec_json.pointer_mut(runtime_config_ptr) {
-        let key_type = if let SessionKeyType::Evm = session_key {
-            "eth"
-        } else {
-            "sr"
-        };
         let keys: Vec<String> = nodes
             .iter()
             .map(|node| {
+                let key_type =
+                    if let SessionKeyType::Evm = node.session_key_type.unwrap_or(session_key) {
+                        "eth"
+                    } else {
+                        "sr"
+                    };
                 node.accounts
                     .accounts
                     .get(key_type)
@@ -1297,18 +1624,35 @@ fn generate_balance_map(balances: &serde_json::Value) -> HashMap<String, u128> {
 mod tests {
     use std::fs;
 
-    use configuration::HrmpChannelConfigBuilder;
+    use configuration::{
+        shared::chain_properties::ChainPropertiesBuilder, HrmpChannelConfigBuilder,
+    };
 
     use super::*;
     use crate::{generators, shared::types::NodeAccounts};
 
     const ROCOCO_LOCAL_PLAIN_TESTING: &str = "./testing/rococo-local-plain.json";
+    const ASSET_HUB_WESTEND_LOCAL_PLAIN_TESTING: &str =
+        "./testing/asset-hub-westend-local-plain.json";
 
     fn chain_spec_test(file: &str) -> serde_json::Value {
         let content = fs::read_to_string(file).unwrap();
         serde_json::from_str(&content).unwrap()
     }
 
+    // node with the accounts derived from `//<name>`
+    fn node_spec(name: &str) -> NodeSpec {
+        let seed = format!("//{name}");
+        NodeSpec {
+            name: name.to_lowercase(),
+            accounts: NodeAccounts {
+                accounts: generators::generate_node_keys(&seed).unwrap(),
+                seed,
+            },
+            ..Default::default()
+        }
+    }
+
     fn chain_spec_with_stake() -> serde_json::Value {
         json!({"genesis": {
             "runtimeGenesis" : {
@@ -1514,6 +1858,77 @@ mod tests {
         assert_eq!(new_staking["stakers"].as_array().unwrap().len(), 1);
     }
 
+    #[test]
+    fn add_nominators_works() {
+        let mut chain_spec_json = chain_spec_with_stake();
+        let pointer = get_runtime_config_pointer(&chain_spec_json).unwrap();
+        let validators = [
+            json!("5GNJqTPyNqANBkUVMN1LPPrxXnFouWXoe2wNSmmEoLctxiZY"),
+            json!("5HpG9w8EBLe5XCrbczpwq5TSXvedjrBGCwqxK1iQ7qUsSWFc"),
+        ];
+
+        add_nominators(&pointer, &mut chain_spec_json, 5, 1, 100).unwrap();
+
+        let stakers = chain_spec_json
+            .pointer(&format!("{pointer}/staking/stakers"))
+            .unwrap()
+            .as_array()
+            .unwrap();
+        let nominators: Vec<&serde_json::Value> = stakers
+            .iter()
+            .filter(|staker| staker[3].get("Nominator").is_some())
+            .collect();
+
+        assert_eq!(stakers.len(), 7);
+        assert_eq!(nominators.len(), 5);
+        for nominator in nominators {
+            assert_eq!(nominator[0], nominator[1]);
+            assert_eq!(nominator[2], json!(100));
+            let targets = nominator[3]["Nominator"].as_array().unwrap();
+            assert_eq!(targets.len(), 1);
+            assert!(validators.contains(&targets[0]));
+        }
+        // validators are kept
+        assert_eq!(
+            chain_spec_json.pointer(&format!("{pointer}/staking/validatorCount")),
+            Some(&json!(2))
+        );
+    }
+
+    #[test]
+    fn add_nominators_should_set_the_dev_stakers_nominators() {
+        let mut chain_spec_json = chain_spec_with_stake();
+        let pointer = get_runtime_config_pointer(&chain_spec_json).unwrap();
+        chain_spec_json.pointer_mut(&pointer).unwrap()["staking"]["devStakers"] = json!([2, 10]);
+
+        add_nominators(&pointer, &mut chain_spec_json, 5, 1, 100).unwrap();
+
+        let staking = chain_spec_json
+            .pointer(&format!("{pointer}/staking"))
+            .unwrap();
+        assert_eq!(staking["devStakers"], json!([2, 5]));
+        // the stakers are generated by the runtime
+        assert_eq!(staking["stakers"].as_array().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn add_nominators_should_fail_with_invalid_dev_stakers() {
+        let mut chain_spec_json = chain_spec_with_stake();
+        let pointer = get_runtime_config_pointer(&chain_spec_json).unwrap();
+
+        for dev_stakers in [json!(2), json!([2])] {
+            chain_spec_json.pointer_mut(&pointer).unwrap()["staking"]["devStakers"] =
+                dev_stakers.clone();
+
+            let err = add_nominators(&pointer, &mut chain_spec_json, 5, 1, 100).unwrap_err();
+
+            assert_eq!(
+                err.to_string(),
+                format!("Chain-spec build error: invalid 'staking.devStakers' in runtime config, expected [validators, nominators], got {dev_stakers}")
+            );
+        }
+    }
+
     #[test]
     fn adding_hrmp_channels_works() {
         let mut spec_plain = chain_spec_test(ROCOCO_LOCAL_PLAIN_TESTING);
@@ -1643,4 +2058,138 @@ mod tests {
         let node_key = get_node_keys(&node, SessionKeyType::default(), true);
         assert_eq!(node_key.2["aura"], node.accounts.accounts["ed"].address);
     }
+
+    #[test]
+    fn add_authorities_should_honor_the_nodes_session_key_type() {
+        let mut chain_spec_json = chain_spec_test(ROCOCO_LOCAL_PLAIN_TESTING);
+        let alice = node_spec("Alice");
+        let bob = NodeSpec {
+            session_key_type: Some(SessionKeyType::Default),
+            ..node_spec("Bob")
+        };
+
+        let pointer = get_runtime_config_pointer(&chain_spec_json).unwrap();
+        add_authorities(
+            &pointer,
+            &mut chain_spec_json,
+            &[&alice, &bob],
+            SessionKeyType::Stash,
+        );
+
+        let session_keys = chain_spec_json
+            .pointer(&format!("{pointer}/session/keys"))
+            .unwrap()
+            .as_array()
+            .unwrap();
+        // alice falls back to the relaychain (stash) derivation
+        let alice_stash = &alice.accounts.accounts["sr_stash"].address;
+        assert_eq!(session_keys[0][0], json!(alice_stash));
+        assert_eq!(session_keys[0][1], json!(alice_stash));
+        // while bob uses its own (default) one
+        let bob_sr = &bob.accounts.accounts["sr"].address;
+        assert_eq!(session_keys[1][0], json!(bob_sr));
+        assert_eq!(session_keys[1][1], json!(bob_sr));
+    }
+
+    #[test]
+    fn add_authorities_should_use_the_keys_marked_as_authorities() {
+        let mut chain_spec_json = chain_spec_test(ROCOCO_LOCAL_PLAIN_TESTING);
+        let rotated = generators::generate_node_keys("//Rotated").unwrap();
+        let alice = NodeSpec {
+            authority_keys: HashMap::from([
+                ("babe".to_string(), rotated["sr"].address.clone()),
+                ("gran".to_string(), rotated["ed"].address.clone()),
+            ]),
+            ..node_spec("Alice")
+        };
+
+        let pointer = get_runtime_config_pointer(&chain_spec_json).unwrap();
+        add_authorities(
+            &pointer,
+            &mut chain_spec_json,
+            &[&alice],
+            SessionKeyType::Stash,
+        );
+        add_grandpa_authorities(&pointer, &mut chain_spec_json, &[&alice], KeyType::Grandpa);
+
+        let session_keys = &chain_spec_json
+            .pointer(&format!("{pointer}/session/keys"))
+            .unwrap()[0][2];
+        assert_eq!(session_keys["babe"], json!(rotated["sr"].address));
+        assert_eq!(session_keys["grandpa"], json!(rotated["ed"].address));
+        // not marked, so the derived one is used
+        assert_eq!(
+            session_keys["im_online"],
+            json!(alice.accounts.accounts["sr"].address)
+        );
+        assert_eq!(
+            chain_spec_json.pointer(&format!("{pointer}/grandpa/authorities")),
+            Some(&json!([[rotated["ed"].address, 1]]))
+        );
+    }
+
+    #[test]
+    fn add_para_authorities_works_with_asset_hub() {
+        let mut chain_spec_json = chain_spec_test(ASSET_HUB_WESTEND_LOCAL_PLAIN_TESTING);
+        let collator = |name: &str, is_validator: bool, is_invulnerable: bool| NodeSpec {
+            is_validator,
+            is_invulnerable,
+            ..node_spec(name)
+        };
+        let collators = vec![
+            collator("Charlie", true, true),
+            collator("Dave", true, false),
+            collator("Eve", false, false),
+        ];
+        let address = |node: &NodeSpec| node.accounts.accounts["sr"].address.clone();
+
+        let pointer = get_runtime_config_pointer(&chain_spec_json).unwrap();
+        add_para_authorities(
+            &pointer,
+            &mut chain_spec_json,
+            &collators,
+            SessionKeyType::Default,
+        );
+
+        let runtime = chain_spec_json.pointer(&pointer).unwrap();
+        // the validators get the session (aura) keys
+        let session_keys = runtime["session"]["keys"].as_array().unwrap();
+        assert_eq!(session_keys.len(), 2);
+        for (key, node) in session_keys.iter().zip(&collators) {
+            assert_eq!(key[0], json!(address(node)));
+            assert_eq!(key[1], json!(address(node)));
+            assert_eq!(key[2]["aura"], json!(address(node)));
+        }
+        // and the invulnerables are the collators, replacing the spec ones
+        assert_eq!(
+            runtime["collatorSelection"]["invulnerables"],
+            json!([address(&collators[0])])
+        );
+        // the other fields are kept
+        assert_eq!(
+            runtime["collatorSelection"]["candidacyBond"],
+            json!(1000000000000_u64)
+        );
+        assert!(runtime.get("aura").is_none());
+    }
+
+    #[test]
+    fn override_chain_properties_works() {
+        let mut chain_spec_json = json!({
+            "name": "Rococo Local Testnet",
+            "properties": { "tokenDecimals": 12, "tokenSymbol": "ROC" }
+        });
+        let properties = ChainPropertiesBuilder::new()
+            .with_token_decimals(10)
+            .with_ss58_prefix(42)
+            .build()
+            .unwrap();
+
+        override_chain_properties(&mut chain_spec_json, &properties);
+
+        assert_eq!(
+            chain_spec_json["properties"],
+            json!({ "tokenDecimals": 10, "tokenSymbol": "ROC", "ss58Format": 42 })
+        );
+    }
 }
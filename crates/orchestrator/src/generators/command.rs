@@ -1,6 +1,11 @@
-use configuration::types::Arg;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+
+use configuration::types::{Arg, LogTarget, RpcMethods};
+use multiaddr::{Multiaddr, Protocol};
 use support::constants::THIS_IS_A_BUG;
+use tracing::warn;
 
+use super::bootnode_addr::ip_protocol;
 use crate::{network_spec::node::NodeSpec, shared::constants::*};
 
 pub struct GenCmdOptions<'a> {
@@ -8,9 +13,11 @@ pub struct GenCmdOptions<'a> {
     pub cfg_path: &'a str,
     pub data_path: &'a str,
     pub relay_data_path: &'a str,
+    pub keystore_path: Option<&'a str>,
     pub use_wrapper: bool,
     pub bootnode_addr: Vec<String>,
     pub use_default_ports_in_cmd: bool,
+    pub relay_rpc_url: Option<&'a str>,
 }
 
 impl Default for GenCmdOptions<'_> {
@@ -20,15 +27,18 @@ impl Default for GenCmdOptions<'_> {
             cfg_path: "/cfg",
             data_path: "/data",
             relay_data_path: "/relay-data",
+            keystore_path: None,
             use_wrapper: true,
             bootnode_addr: vec![],
             use_default_ports_in_cmd: false,
+            relay_rpc_url: None,
         }
     }
 }
 
-const FLAGS_ADDED_BY_US: [&str; 5] = [
+const FLAGS_ADDED_BY_US: [&str; 6] = [
     "--unsafe-rpc-external",
+    "--rpc-external",
     "--no-telemetry",
     "--no-mdns",
     "--collator",
@@ -43,6 +53,32 @@ const OPS_ADDED_BY_US: [&str; 6] = [
     "--node-key",
 ];
 
+// Compile the log targets into a single `-l<target>=<level>,...` flag.
+fn log_targets_flag(log_targets: &[LogTarget]) -> String {
+    let targets = log_targets
+        .iter()
+        .map(LogTarget::to_string)
+        .collect::<Vec<String>>()
+        .join(",");
+    format!("-l{targets}")
+}
+
+// Bind the rpc externally (if enabled) and set the exposed methods, `--rpc-external` is
+// only accepted by the node with the `safe` methods.
+fn rpc_args(node: &NodeSpec) -> Vec<String> {
+    let mut args = vec![];
+    if node.rpc_external {
+        args.push(match node.rpc_methods {
+            RpcMethods::Safe => "--rpc-external".into(),
+            RpcMethods::Unsafe => "--unsafe-rpc-external".into(),
+        });
+    }
+
+    args.push("--rpc-methods".into());
+    args.push(node.rpc_methods.as_str().into());
+    args
+}
+
 // TODO: can we abstract this and use only one fn (or at least split and reuse in small fns)
 pub fn generate_for_cumulus_node(
     node: &NodeSpec,
@@ -53,6 +89,7 @@ pub fn generate_for_cumulus_node(
     let NodeSpec {
         key,
         args,
+        relay_args,
         is_validator,
         bootnodes_addresses,
         ..
@@ -60,7 +97,7 @@ pub fn generate_for_cumulus_node(
 
     let mut tmp_args: Vec<String> = vec!["--node-key".into(), key.clone()];
 
-    if !args.contains(&Arg::Flag("--prometheus-external".into())) {
+    if node.prometheus_external && !args.contains(&Arg::Flag("--prometheus-external".into())) {
         tmp_args.push("--prometheus-external".into())
     }
 
@@ -68,6 +105,10 @@ pub fn generate_for_cumulus_node(
         tmp_args.push("--collator".into())
     }
 
+    if node.is_dedicated_bootnode && !args.contains(&Arg::Flag("--no-mdns".into())) {
+        tmp_args.push("--no-mdns".into())
+    }
+
     if !bootnodes_addresses.is_empty() {
         tmp_args.push("--bootnodes".into());
         let bootnodes = bootnodes_addresses
@@ -78,6 +119,15 @@ pub fn generate_for_cumulus_node(
         tmp_args.push(bootnodes)
     }
 
+    if !node.log_targets.is_empty() {
+        tmp_args.push(log_targets_flag(&node.log_targets));
+    }
+
+    if let Some(sync_mode) = node.sync_mode {
+        tmp_args.push("--sync".into());
+        tmp_args.push(sync_mode.as_str().into());
+    }
+
     // ports
     let (prometheus_port, rpc_port, p2p_port) =
         resolve_ports(node, options.use_default_ports_in_cmd);
@@ -88,15 +138,17 @@ pub fn generate_for_cumulus_node(
     tmp_args.push("--rpc-port".into());
     tmp_args.push(rpc_port.to_string());
 
-    tmp_args.push("--listen-addr".into());
-    tmp_args.push(format!("/ip4/0.0.0.0/tcp/{}/ws", p2p_port));
+    for listen_addr in node_listen_addrs(node, p2p_port) {
+        tmp_args.push("--listen-addr".into());
+        tmp_args.push(listen_addr);
+    }
 
     let mut collator_args: &[Arg] = &[];
     let mut full_node_args: &[Arg] = &[];
     if !args.is_empty() {
         if let Some(index) = args.iter().position(|arg| match arg {
             Arg::Flag(flag) => flag.eq("--"),
-            Arg::Option(..) => false,
+            Arg::Option(..) | Arg::Remove(_) => false,
         }) {
             (collator_args, full_node_args) = args.split_at(index);
         } else {
@@ -109,6 +161,11 @@ pub fn generate_for_cumulus_node(
     tmp_args.push("--base-path".into());
     tmp_args.push(options.data_path.into());
 
+    if let Some(keystore_path) = options.keystore_path {
+        tmp_args.push("--keystore-path".into());
+        tmp_args.push(keystore_path.into());
+    }
+
     let node_specific_bootnodes: Vec<String> = node
         .bootnodes_addresses
         .iter()
@@ -120,9 +177,26 @@ pub fn generate_for_cumulus_node(
         tmp_args.push(full_bootnodes.join(" "));
     }
 
+    // the relay args are set explicitly, warn about the ones we set (and so are ignored)
+    for arg in relay_args {
+        let ignored = match arg {
+            Arg::Flag(flag) if FLAGS_ADDED_BY_US.contains(&flag.as_str()) => Some(flag),
+            Arg::Option(k, _) if OPS_ADDED_BY_US.contains(&k.as_str()) => Some(k),
+            _ => None,
+        };
+        if let Some(ignored) = ignored {
+            warn!(
+                "⚠️  relay arg '{ignored}' of node {} is set by zombienet, ignoring it",
+                node.name
+            );
+        }
+    }
+
     let mut full_node_p2p_needs_to_be_injected = false;
+    // args after the `--` separator, followed by the explicit relay args
     let mut full_node_args_filtered = full_node_args
         .iter()
+        .chain(relay_args.iter())
         .filter_map(|arg| match arg {
             Arg::Flag(flag) => {
                 if FLAGS_ADDED_BY_US.contains(&flag.as_str()) {
@@ -141,6 +215,7 @@ pub fn generate_for_cumulus_node(
                     Some(vec![k.to_owned(), v.to_owned()])
                 }
             },
+            Arg::Remove(_) => None,
         })
         .flatten()
         .collect::<Vec<String>>();
@@ -166,6 +241,7 @@ pub fn generate_for_cumulus_node(
                     Some(vec![k.to_owned(), v.to_owned()])
                 }
             },
+            Arg::Remove(_) => None,
         })
         .flatten()
         .collect::<Vec<String>>();
@@ -181,13 +257,24 @@ pub fn generate_for_cumulus_node(
         node.name.clone(),
         "--rpc-cors".into(),
         "all".into(),
-        "--unsafe-rpc-external".into(),
-        "--rpc-methods".into(),
-        "unsafe".into(),
     ];
 
+    final_args.append(&mut rpc_args(node));
     final_args.append(&mut tmp_args);
 
+    // collators using an external relaychain rpc don't run the embedded full node,
+    // so the args after `--` (and the `relay_args`) are rejected by the config validation.
+    if let Some(relay_rpc_url) = options.relay_rpc_url {
+        final_args.push("--relay-chain-rpc-url".into());
+        final_args.push(relay_rpc_url.into());
+
+        return if options.use_wrapper {
+            ("/cfg/zombie-wrapper.sh".to_string(), final_args)
+        } else {
+            (final_args.remove(0), final_args)
+        };
+    }
+
     let relaychain_spec_path = format!("{}/{}.json", options.cfg_path, options.relay_chain_name);
     let mut full_node_injected: Vec<String> = vec![
         "--".into(),
@@ -228,7 +315,7 @@ pub fn generate_for_node(
         "--no-telemetry".into(),
     ];
 
-    if !args.contains(&Arg::Flag("--prometheus-external".into())) {
+    if node.prometheus_external && !args.contains(&Arg::Flag("--prometheus-external".into())) {
         tmp_args.push("--prometheus-external".into())
     }
 
@@ -239,11 +326,17 @@ pub fn generate_for_node(
 
     if *is_validator && !args.contains(&Arg::Flag("--validator".into())) {
         tmp_args.push("--validator".into());
+        // the optional args (see `OPTIONAL_ARGS`) are only added if supported
         if node.supports_arg("--insecure-validator-i-know-what-i-do") {
             tmp_args.push("--insecure-validator-i-know-what-i-do".into());
         }
     }
 
+    // dedicated bootnodes only listen, peers should discover them through `--bootnodes`
+    if node.is_dedicated_bootnode && !args.contains(&Arg::Flag("--no-mdns".into())) {
+        tmp_args.push("--no-mdns".into());
+    }
+
     if !bootnodes_addresses.is_empty() {
         tmp_args.push("--bootnodes".into());
         let bootnodes = bootnodes_addresses
@@ -254,6 +347,15 @@ pub fn generate_for_node(
         tmp_args.push(bootnodes)
     }
 
+    if !node.log_targets.is_empty() {
+        tmp_args.push(log_targets_flag(&node.log_targets));
+    }
+
+    if let Some(sync_mode) = node.sync_mode {
+        tmp_args.push("--sync".into());
+        tmp_args.push(sync_mode.as_str().into());
+    }
+
     // ports
     let (prometheus_port, rpc_port, p2p_port) =
         resolve_ports(node, options.use_default_ports_in_cmd);
@@ -267,8 +369,8 @@ pub fn generate_for_node(
     tmp_args.push("--rpc-port".into());
     tmp_args.push(rpc_port.to_string());
 
-    let listen_value = if let Some(listen_val) = args.iter().find_map(|arg| match arg {
-        Arg::Flag(_) => None,
+    let listen_values = if let Some(listen_val) = args.iter().find_map(|arg| match arg {
+        Arg::Flag(_) | Arg::Remove(_) => None,
         Arg::Option(k, v) => {
             if k.eq("--listen-addr") {
                 Some(v)
@@ -284,18 +386,25 @@ pub fn generate_for_node(
             .expect(&format!("should have at least 5 parts {THIS_IS_A_BUG}"));
         let port_to_use = p2p_port.to_string();
         *port_part = port_to_use.as_str();
-        parts.join("/")
+        vec![parts.join("/")]
     } else {
-        format!("/ip4/0.0.0.0/tcp/{}/ws", p2p_port)
+        node_listen_addrs(node, p2p_port)
     };
 
-    tmp_args.push("--listen-addr".into());
-    tmp_args.push(listen_value);
+    for listen_value in listen_values {
+        tmp_args.push("--listen-addr".into());
+        tmp_args.push(listen_value);
+    }
 
     // set our base path
     tmp_args.push("--base-path".into());
     tmp_args.push(options.data_path.into());
 
+    if let Some(keystore_path) = options.keystore_path {
+        tmp_args.push("--keystore-path".into());
+        tmp_args.push(keystore_path.into());
+    }
+
     let node_specific_bootnodes: Vec<String> = node
         .bootnodes_addresses
         .iter()
@@ -325,6 +434,7 @@ pub fn generate_for_node(
                     Some(vec![k.to_owned(), v.to_owned()])
                 }
             },
+            Arg::Remove(_) => None,
         })
         .flatten()
         .collect::<Vec<String>>();
@@ -340,11 +450,9 @@ pub fn generate_for_node(
         node.name.clone(),
         "--rpc-cors".into(),
         "all".into(),
-        "--unsafe-rpc-external".into(),
-        "--rpc-methods".into(),
-        "unsafe".into(),
     ];
 
+    final_args.append(&mut rpc_args(node));
     final_args.append(&mut tmp_args);
 
     if let Some(ref subcommand) = node.subcommand {
@@ -366,3 +474,257 @@ fn resolve_ports(node: &NodeSpec, use_default_ports_in_cmd: bool) -> (u16, u16,
         (node.prometheus_port.0, node.rpc_port.0, node.p2p_port.0)
     }
 }
+
+/// Value of `--listen-addr` (ws) for the `ip` family
+fn listen_addr(ip: &IpAddr, p2p_port: u16) -> String {
+    format!("/{}/{ip}/tcp/{p2p_port}/ws", ip_protocol(ip))
+}
+
+/// Values of `--listen-addr`, the node one (using the p2p port) or all the interfaces of both
+/// ip families. The node ip (used in the bootnodes addresses) is only known once spawned, so
+/// listen in both to match it (e.g. `/ip6` in IPv6-only clusters).
+fn node_listen_addrs(node: &NodeSpec, p2p_port: u16) -> Vec<String> {
+    match &node.listen_addr {
+        Some(addr) => vec![addr
+            .iter()
+            .map(|protocol| match protocol {
+                Protocol::Tcp(_) => Protocol::Tcp(p2p_port),
+                protocol => protocol,
+            })
+            .collect::<Multiaddr>()
+            .to_string()],
+        None => [
+            IpAddr::V4(Ipv4Addr::UNSPECIFIED),
+            IpAddr::V6(Ipv6Addr::UNSPECIFIED),
+        ]
+        .iter()
+        .map(|ip| listen_addr(ip, p2p_port))
+        .collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use configuration::{types::SyncMode, NetworkConfigBuilder};
+
+    use super::*;
+    use crate::shared::types::ChainDefaultContext;
+
+    #[test]
+    fn generate_for_node_should_compile_the_merged_log_targets_into_a_single_flag() {
+        let config = NetworkConfigBuilder::new()
+            .with_relaychain(|r| {
+                r.with_chain("rococo-local")
+                    .with_default_command("polkadot")
+                    .with_default_log_targets(vec![("parachain", "info"), ("sync", "trace")])
+                    .with_node(|node| {
+                        node.with_name("alice")
+                            .with_log_targets(vec![("parachain", "debug")])
+                    })
+            })
+            .build()
+            .unwrap();
+        let relaychain = config.relaychain();
+        let chain_context = ChainDefaultContext {
+            default_command: relaychain.default_command(),
+            default_log_targets: relaychain.default_log_targets(),
+            ..Default::default()
+        };
+
+        let mut node = NodeSpec::from_config(relaychain.nodes()[0], &chain_context).unwrap();
+        node.available_args_output = Some("".into());
+        let (_, args) = generate_for_node(&node, GenCmdOptions::default(), None);
+
+        assert!(args.contains(&"-lparachain=debug,sync=trace".to_string()));
+    }
+
+    #[test]
+    fn generate_for_node_should_listen_in_both_ip_families_by_default() {
+        let config = NetworkConfigBuilder::new()
+            .with_relaychain(|r| {
+                r.with_chain("rococo-local")
+                    .with_default_command("polkadot")
+                    .with_node(|node| node.with_name("alice"))
+            })
+            .build()
+            .unwrap();
+        let relaychain = config.relaychain();
+        let chain_context = ChainDefaultContext {
+            default_command: relaychain.default_command(),
+            ..Default::default()
+        };
+
+        let mut node = NodeSpec::from_config(relaychain.nodes()[0], &chain_context).unwrap();
+        node.available_args_output = Some("".into());
+        let (_, args) = generate_for_node(&node, GenCmdOptions::default(), None);
+
+        let listen_addrs: Vec<&String> = args
+            .iter()
+            .enumerate()
+            .filter(|(_, arg)| *arg == "--listen-addr")
+            .map(|(index, _)| &args[index + 1])
+            .collect();
+        let port = node.p2p_port.0;
+        assert_eq!(
+            listen_addrs,
+            vec![
+                &format!("/ip4/0.0.0.0/tcp/{port}/ws"),
+                &format!("/ip6/::/tcp/{port}/ws")
+            ]
+        );
+    }
+
+    #[test]
+    fn generate_for_node_should_follow_the_node_binding_options() {
+        let config = NetworkConfigBuilder::new()
+            .with_relaychain(|r| {
+                r.with_chain("rococo-local")
+                    .with_default_command("polkadot")
+                    .with_node(|node| node.with_name("alice"))
+                    .with_node(|node| {
+                        node.with_name("bob")
+                            .with_rpc_methods(RpcMethods::Safe)
+                            .with_prometheus_external(false)
+                            .with_listen_addr("/ip4/127.0.0.1/tcp/30333/ws")
+                            .with_sync_mode(SyncMode::Warp)
+                    })
+            })
+            .build()
+            .unwrap();
+        let relaychain = config.relaychain();
+        let chain_context = ChainDefaultContext {
+            default_command: relaychain.default_command(),
+            ..Default::default()
+        };
+
+        let mut alice = NodeSpec::from_config(relaychain.nodes()[0], &chain_context).unwrap();
+        alice.available_args_output = Some("".into());
+        let (_, args) = generate_for_node(&alice, GenCmdOptions::default(), None);
+        assert!(args.contains(&"--unsafe-rpc-external".to_string()));
+        assert!(args.contains(&"--prometheus-external".to_string()));
+        assert!(!args.contains(&"--sync".to_string()));
+
+        let mut bob = NodeSpec::from_config(relaychain.nodes()[1], &chain_context).unwrap();
+        bob.available_args_output = Some("".into());
+        let (_, args) = generate_for_node(&bob, GenCmdOptions::default(), None);
+        assert!(args.contains(&"--rpc-external".to_string()));
+        assert!(!args.contains(&"--unsafe-rpc-external".to_string()));
+        assert!(!args.contains(&"--prometheus-external".to_string()));
+        let methods = args.iter().position(|arg| arg == "--rpc-methods").unwrap();
+        assert_eq!(args[methods + 1], "safe");
+        let listen_addr = args.iter().position(|arg| arg == "--listen-addr").unwrap();
+        assert_eq!(
+            args[listen_addr + 1],
+            format!("/ip4/127.0.0.1/tcp/{}/ws", bob.p2p_port.0)
+        );
+        let sync = args.iter().position(|arg| arg == "--sync").unwrap();
+        assert_eq!(args[sync + 1], "warp");
+    }
+
+    #[test]
+    fn generate_for_node_should_add_the_forced_args_missing_in_the_available_args() {
+        let config = NetworkConfigBuilder::new()
+            .with_relaychain(|r| {
+                r.with_chain("rococo-local")
+                    .with_default_command("polkadot")
+                    .with_node(|node| node.with_name("alice"))
+                    .with_node(|node| {
+                        node.with_name("bob")
+                            .with_force_args(vec!["--insecure-validator-i-know-what-i-do"])
+                    })
+            })
+            .build()
+            .unwrap();
+        let relaychain = config.relaychain();
+        let chain_context = ChainDefaultContext {
+            default_command: relaychain.default_command(),
+            ..Default::default()
+        };
+
+        let mut alice = NodeSpec::from_config(relaychain.nodes()[0], &chain_context).unwrap();
+        alice.available_args_output = Some("--validator".into());
+        let (_, args) = generate_for_node(&alice, GenCmdOptions::default(), None);
+        assert!(!args.contains(&"--insecure-validator-i-know-what-i-do".to_string()));
+
+        let mut bob = NodeSpec::from_config(relaychain.nodes()[1], &chain_context).unwrap();
+        bob.available_args_output = Some("--validator".into());
+        let (_, args) = generate_for_node(&bob, GenCmdOptions::default(), None);
+        assert!(args.contains(&"--insecure-validator-i-know-what-i-do".to_string()));
+    }
+
+    fn cumulus_collator(relay_args: Vec<Arg>) -> NodeSpec {
+        let config = NetworkConfigBuilder::new()
+            .with_relaychain(|r| {
+                r.with_chain("rococo-local")
+                    .with_default_command("polkadot")
+                    .with_node(|node| node.with_name("alice"))
+            })
+            .with_parachain(|p| {
+                p.with_id(2000)
+                    .with_default_command("polkadot-parachain")
+                    .with_collator(|collator| {
+                        collator
+                            .with_name("collator")
+                            .with_args(vec!["--".into(), "--in-peers=5".into()])
+                            .with_relay_args(relay_args)
+                    })
+            })
+            .build()
+            .unwrap();
+        let parachain = config.parachains()[0];
+        let chain_context = ChainDefaultContext {
+            default_command: parachain.default_command(),
+            ..Default::default()
+        };
+
+        NodeSpec::from_config(parachain.collators()[0], &chain_context).unwrap()
+    }
+
+    #[test]
+    fn generate_for_cumulus_node_should_append_the_relay_args_to_the_full_node() {
+        let collator = cumulus_collator(vec![
+            "--rpc-external".into(),
+            ("--chain", "other").into(),
+            ("--out-peers", "10").into(),
+        ]);
+
+        let (_, args) = generate_for_cumulus_node(&collator, GenCmdOptions::default(), 2000, 30334);
+
+        let separator = args.iter().position(|arg| arg == "--").unwrap();
+        let full_node_args = &args[separator..];
+        let in_peers = full_node_args
+            .iter()
+            .position(|arg| arg == "--in-peers=5")
+            .unwrap();
+        let out_peers = full_node_args
+            .iter()
+            .position(|arg| arg == "--out-peers")
+            .unwrap();
+        // after the ones the user placed after `--`
+        assert!(in_peers < out_peers);
+        assert_eq!(full_node_args[out_peers + 1], "10");
+        // the ones we set are ignored
+        assert!(!full_node_args.contains(&"--rpc-external".to_string()));
+        assert!(!full_node_args.contains(&"other".to_string()));
+    }
+
+    #[test]
+    fn generate_for_cumulus_node_should_use_the_relay_rpc_url_instead_of_the_full_node() {
+        let collator = cumulus_collator(vec![("--out-peers", "10").into()]);
+        let options = GenCmdOptions {
+            relay_rpc_url: Some("wss://rpc.example.com"),
+            ..Default::default()
+        };
+
+        let (_, args) = generate_for_cumulus_node(&collator, options, 2000, 30334);
+
+        let relay_rpc_url = args
+            .iter()
+            .position(|arg| arg == "--relay-chain-rpc-url")
+            .unwrap();
+        assert_eq!(args[relay_rpc_url + 1], "wss://rpc.example.com");
+        // no embedded full node
+        assert!(!args.contains(&"--".to_string()));
+        assert!(!args.contains(&"--out-peers".to_string()));
+    }
+}
@@ -7,8 +7,14 @@ pub enum GeneratorError {
     KeyGeneration(String, String),
     #[error("Generating port {0}, err {1}")]
     PortGeneration(u16, String),
+    #[error("No free port left in range [{0}, {0} + {1})")]
+    PortRangeExhausted(u16, u16),
     #[error("Chain-spec build error: {0}")]
     ChainSpecGeneration(String),
+    #[error("Overriding wasm on build spec error: {0}")]
+    OverridingWasm(String),
+    #[error("Invalid para artifact {0}: {1}")]
+    InvalidParaArtifact(String, String),
     #[error("Provider error: {0}")]
     ProviderError(#[from] ProviderError),
     #[error("FileSystem error")]
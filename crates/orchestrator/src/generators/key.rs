@@ -53,6 +53,22 @@ pub fn generate(seed: &str) -> Result<Accounts, GeneratorError> {
     Ok(accounts)
 }
 
+/// Compute the address of the `0x` prefixed hex `public` key of the `key_type` (a 4 chars
+/// crypto id), `gran` keys are ed25519, `beef` ecdsa and the others sr25519.
+pub fn address_from_public(key_type: &str, public: &str) -> Result<String, GeneratorError> {
+    let err = || GeneratorError::KeyGeneration(key_type.into(), public.into());
+    let raw = hex::decode(public.strip_prefix("0x").unwrap_or(public)).map_err(|_| err())?;
+
+    let address = match key_type {
+        "gran" => ed25519::Public::try_from(raw.as_slice()).map(|public| public.to_string()),
+        "beef" => ecdsa::Public::try_from(raw.as_slice()).map(|public| public.to_string()),
+        _ => sr25519::Public::try_from(raw.as_slice()).map(|public| public.to_string()),
+    }
+    .map_err(|_| err())?;
+
+    Ok(address)
+}
+
 #[cfg(test)]
 mod tests {
 
@@ -95,6 +111,29 @@ mod tests {
         );
     }
 
+    #[test]
+    fn address_from_public_works() {
+        let alice = generate("//Alice").unwrap();
+
+        for (key_type, account) in [("aura", "sr"), ("gran", "ed"), ("beef", "ec")] {
+            let public = format!("0x{}", alice[account].public_key);
+            assert_eq!(
+                address_from_public(key_type, &public).unwrap(),
+                alice[account].address
+            );
+        }
+    }
+
+    #[test]
+    fn address_from_public_with_invalid_length_should_fail() {
+        let err = address_from_public("aura", "0xd43593c715fdd31c").unwrap_err();
+
+        assert_eq!(
+            err.to_string(),
+            "Generating key aura with input 0xd43593c715fdd31c"
+        );
+    }
+
     #[test]
     fn generate_pair_invalid_should_fail() {
         let s = "Alice";
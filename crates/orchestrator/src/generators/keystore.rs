@@ -3,6 +3,7 @@ use std::{
     vec,
 };
 
+use configuration::shared::node::KeystoreKey;
 use hex::encode;
 use support::{constants::THIS_IS_A_BUG, fs::FileSystem};
 
@@ -90,3 +91,33 @@ where
     futures::future::try_join_all(f).await?;
     Ok(filenames)
 }
+
+/// Write the custom `keys` in the keystore, returning the generated filenames.
+pub async fn generate_custom<'a, T>(
+    keys: &[KeystoreKey],
+    node_files_path: impl AsRef<Path>,
+    scoped_fs: &ScopedFilesystem<'a, T>,
+) -> Result<Vec<PathBuf>, GeneratorError>
+where
+    T: FileSystem,
+{
+    scoped_fs.create_dir_all(node_files_path.as_ref()).await?;
+    let mut filenames = vec![];
+
+    let f = keys.iter().map(|key| {
+        let public = key.public.strip_prefix("0x").unwrap_or(&key.public);
+        let filename = format!("{}{}", encode(&key.key_type), public);
+        let file_path = PathBuf::from(format!(
+            "{}/{}",
+            node_files_path.as_ref().to_string_lossy(),
+            filename
+        ));
+        filenames.push(PathBuf::from(filename));
+        let content = serde_json::to_string(&key.seed)
+            .expect(&format!("Seed should be serializable {THIS_IS_A_BUG}"));
+        scoped_fs.write(file_path, content)
+    });
+
+    futures::future::try_join_all(f).await?;
+    Ok(filenames)
+}
@@ -54,6 +54,10 @@ impl ParaArtifact {
         self
     }
 
+    pub(crate) fn build_option(&self) -> &ParaArtifactBuildOption {
+        &self.build_option
+    }
+
     pub(crate) fn artifact_path(&self) -> Option<&PathBuf> {
         self.artifact_path.as_ref()
     }
@@ -70,8 +74,19 @@ impl ParaArtifact {
     {
         let (cmd, custom_args) = match &self.build_option {
             ParaArtifactBuildOption::Path(path) => {
+                let invalid_artifact =
+                    |err: String| GeneratorError::InvalidParaArtifact(path.to_string(), err);
+
                 let t = TransferedFile::new(PathBuf::from(path), artifact_path.as_ref().into());
-                scoped_fs.copy_files(vec![&t]).await?;
+                scoped_fs
+                    .copy_files(vec![&t])
+                    .await
+                    .map_err(|err| invalid_artifact(err.to_string()))?;
+
+                // the provided artifact is used as is, ensure is valid before registration
+                let content = scoped_fs.read_to_string(artifact_path.as_ref()).await?;
+                decode_hex_artifact(&content).map_err(invalid_artifact)?;
+
                 self.artifact_path = Some(artifact_path.as_ref().into());
                 return Ok(()); // work done!
             },
@@ -132,6 +147,8 @@ impl ParaArtifact {
                         args.push(flag.into());
                         args.push(flag_value.into());
                     },
+                    // remove directives are already applied to the args
+                    configuration::types::Arg::Remove(_) => {},
                 }
             }
 
@@ -157,3 +174,35 @@ impl ParaArtifact {
         Ok(())
     }
 }
+
+/// Decode the content of a para artifact (hex encoded, with an optional `0x` prefix).
+pub(crate) fn decode_hex_artifact(content: &str) -> Result<Vec<u8>, String> {
+    let content = content.trim();
+    let hex_content = content.strip_prefix("0x").unwrap_or(content);
+    if hex_content.is_empty() {
+        return Err("is empty".into());
+    }
+
+    hex::decode(hex_content).map_err(|err| format!("is not valid hex ({err})"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decode_hex_artifact_works() {
+        assert_eq!(decode_hex_artifact("0x0a0b\n").unwrap(), vec![10, 11]);
+        assert_eq!(decode_hex_artifact("0a0b").unwrap(), vec![10, 11]);
+    }
+
+    #[test]
+    fn decode_hex_artifact_fails_if_empty_or_invalid() {
+        assert_eq!(decode_hex_artifact("0x").unwrap_err(), "is empty");
+        assert_eq!(decode_hex_artifact("  ").unwrap_err(), "is empty");
+        assert_eq!(
+            decode_hex_artifact("0xzz").unwrap_err(),
+            "is not valid hex (Invalid character 'z' at position 0)"
+        );
+    }
+}
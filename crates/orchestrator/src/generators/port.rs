@@ -26,6 +26,38 @@ pub fn generate(port: Option<Port>) -> Result<ParkedPort, GeneratorError> {
     Ok(ParkedPort::new(port, listener))
 }
 
+/// Generate ports drawing sequentially from the range `[base, base + stride)`,
+/// skipping the ones already in use.
+pub struct PortRangeGenerator {
+    base: Port,
+    stride: u16,
+    next: u32,
+}
+
+impl PortRangeGenerator {
+    pub fn new(base: Port, stride: u16) -> Self {
+        Self {
+            base,
+            stride,
+            next: base as u32,
+        }
+    }
+
+    pub fn generate(&mut self) -> Result<ParkedPort, GeneratorError> {
+        let end = self.base as u32 + self.stride as u32;
+        while self.next < end {
+            let port = self.next as Port;
+            self.next += 1;
+
+            if let Ok(listener) = TcpListener::bind(format!("0.0.0.0:{port}")) {
+                return Ok(ParkedPort::new(port, listener));
+            }
+        }
+
+        Err(GeneratorError::PortRangeExhausted(self.base, self.stride))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -45,4 +77,33 @@ mod tests {
         assert!(listener.is_some());
         assert_eq!(port.0, 33056);
     }
+
+    #[test]
+    fn generate_from_range() {
+        let mut generator = PortRangeGenerator::new(33100, 3);
+        let ports: Vec<Port> = (0..3).map(|_| generator.generate().unwrap().0).collect();
+
+        assert_eq!(ports, vec![33100, 33101, 33102]);
+    }
+
+    #[test]
+    fn generate_from_range_skip_used_ports() {
+        let used = generate(Some(33201)).unwrap();
+        let mut generator = PortRangeGenerator::new(33200, 3);
+        let ports: Vec<Port> = (0..2).map(|_| generator.generate().unwrap().0).collect();
+
+        assert_eq!(ports, vec![33200, 33202]);
+        drop(used);
+    }
+
+    #[test]
+    fn generate_from_range_exhausted_should_fails() {
+        let mut generator = PortRangeGenerator::new(33300, 1);
+        let _port = generator.generate().unwrap();
+
+        assert!(matches!(
+            generator.generate(),
+            Err(GeneratorError::PortRangeExhausted(33300, 1))
+        ));
+    }
 }
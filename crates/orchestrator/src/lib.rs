@@ -14,13 +14,17 @@ pub mod shared;
 mod spawner;
 
 use std::{
-    collections::HashSet,
+    collections::{HashMap, HashSet},
     net::IpAddr,
     path::{Path, PathBuf},
-    time::Duration,
+    time::{Duration, Instant},
 };
 
-use configuration::{NetworkConfig, RegistrationStrategy};
+use configuration::{
+    shared::helpers::zombie_token_references,
+    types::{Arg, BootnodeStrategy},
+    GlobalSettings, NetworkConfig, RegistrationStrategy,
+};
 use errors::OrchestratorError;
 use generators::errors::GeneratorError;
 use network::{node::NetworkNode, parachain::Parachain, relaychain::Relaychain, Network};
@@ -28,15 +32,21 @@ use network::{node::NetworkNode, parachain::Parachain, relaychain::Relaychain, N
 pub use network_spec::NetworkSpec;
 use network_spec::{node::NodeSpec, parachain::ParachainSpec};
 use provider::{
-    types::{ProviderCapabilities, TransferedFile},
-    DynProvider,
+    types::{CreateNamespaceOptions, ProviderCapabilities, TransferedFile},
+    DynNamespace, DynProvider,
+};
+use support::{
+    fs::{FileSystem, FileSystemError},
+    replacer::apply_running_network_replacements,
 };
-use support::fs::{FileSystem, FileSystemError};
 use tokio::time::timeout;
-use tracing::{debug, info, trace};
+use tracing::{debug, info, trace, warn};
 
 use crate::{
-    shared::{constants::P2P_PORT, types::RegisterParachainOptions},
+    shared::{
+        constants::P2P_PORT,
+        types::{DryRunParaReport, DryRunReport, RegisterParachainOptions, SpawnTimings},
+    },
     spawner::SpawnNodeCtx,
 };
 pub struct Orchestrator<T>
@@ -88,14 +98,15 @@ where
         res?
     }
 
-    async fn spawn_inner(
+    /// Build all the artifacts of the network (chain-specs, genesis state/wasm)
+    /// in the base_dir without spawning any node. Useful to inspect the generated
+    /// genesis without waiting for the nodes.
+    pub async fn spawn_dry_run(
         &self,
-        mut network_spec: NetworkSpec,
-    ) -> Result<Network<T>, OrchestratorError> {
-        // main driver for spawn the network
-        debug!(network_spec = ?network_spec,"Network spec to spawn");
+        network_config: NetworkConfig,
+    ) -> Result<DryRunReport, OrchestratorError> {
+        let mut network_spec = NetworkSpec::from_config(&network_config).await?;
 
-        // TODO: move to Provider trait
         validate_spec_with_provider_capabilities(&network_spec, self.provider.capabilities())
             .map_err(|err| {
                 OrchestratorError::InvalidConfigForProvider(
@@ -104,29 +115,103 @@ where
                 )
             })?;
 
-        // create namespace
-        let ns = if let Some(base_dir) = network_spec.global_settings.base_dir() {
-            self.provider
-                .create_namespace_with_base_dir(base_dir)
-                .await?
-        } else {
-            self.provider.create_namespace().await?
-        };
+        // same checks as `spawn`, the dry run should catch the errors of the real spawn
+        ensure_token_dependencies(&network_spec)?;
 
+        let ns = self.create_namespace(&network_spec).await?;
         info!("🧰 ns: {}", ns.name());
         info!("🧰 base_dir: {:?}", ns.base_dir());
 
+        // the namespace is only used to build the artifacts, destroy it also on errors
+        // (the artifacts are kept in the base_dir)
+        let report = self.dry_run_in_namespace(&mut network_spec, &ns).await;
+        let destroyed = ns.destroy().await;
+
+        let report = report?;
+        destroyed?;
+        Ok(report)
+    }
+
+    async fn dry_run_in_namespace(
+        &self,
+        network_spec: &mut NetworkSpec,
+        ns: &DynNamespace,
+    ) -> Result<DryRunReport, OrchestratorError> {
         network_spec
             .populate_nodes_available_args(ns.clone())
             .await?;
 
         let base_dir = ns.base_dir().to_string_lossy();
         let scoped_fs = ScopedFilesystem::new(&self.filesystem, &base_dir);
+        self.build_artifacts(network_spec, ns, &scoped_fs, &mut SpawnTimings::default())
+            .await?;
+
+        let relay_chain_spec = network_spec
+            .relaychain
+            .chain_spec
+            .raw_path()
+            .ok_or(OrchestratorError::InvariantError(
+                "chain-spec raw path should be set now",
+            ))?
+            .to_path_buf();
+
+        let parachains = network_spec
+            .parachains
+            .iter()
+            .map(|para| DryRunParaReport {
+                id: para.id,
+                chain_spec: para
+                    .chain_spec
+                    .as_ref()
+                    .and_then(|spec| spec.raw_path().map(PathBuf::from)),
+                genesis_state: para.genesis_state.artifact_path().cloned(),
+                genesis_wasm: para.genesis_wasm.artifact_path().cloned(),
+            })
+            .collect();
+
+        Ok(DryRunReport {
+            base_dir: ns.base_dir().clone(),
+            relay_chain_spec,
+            parachains,
+        })
+    }
+
+    async fn create_namespace(
+        &self,
+        network_spec: &NetworkSpec,
+    ) -> Result<DynNamespace, OrchestratorError> {
+        let mut options = CreateNamespaceOptions::new();
+        if let Some(base_dir) = network_spec.global_settings.base_dir() {
+            options = options.base_dir(base_dir);
+        }
+        if let Some(prefix) = network_spec.global_settings.namespace_prefix() {
+            options = options.name_prefix(prefix);
+        }
+
+        let ns = self
+            .provider
+            .create_namespace_with_options(&options)
+            .await?;
+
+        Ok(ns)
+    }
+
+    // Build the relaychain chain-spec and the parachains artifacts (chain-spec, wasm, state),
+    // customize the relaychain spec with the genesis paras and build the raw version.
+    // Returns the relaychain chain id.
+    async fn build_artifacts(
+        &self,
+        network_spec: &mut NetworkSpec,
+        ns: &DynNamespace,
+        scoped_fs: &ScopedFilesystem<'_, T>,
+        timings: &mut SpawnTimings,
+    ) -> Result<String, OrchestratorError> {
+        let start = Instant::now();
         // Create chain-spec for relaychain
         network_spec
             .relaychain
             .chain_spec
-            .build(&ns, &scoped_fs)
+            .build(ns, scoped_fs)
             .await?;
 
         debug!("relaychain spec built!");
@@ -134,29 +219,34 @@ where
         let relay_chain_id = network_spec
             .relaychain
             .chain_spec
-            .read_chain_id(&scoped_fs)
+            .read_chain_id(scoped_fs)
             .await?;
 
-        let relay_chain_name = network_spec.relaychain.chain.as_str().to_owned();
+        timings.relay_chain_spec = start.elapsed();
+
+        let start = Instant::now();
         let base_dir_exists = network_spec.global_settings.base_dir().is_some();
+        let concurrency = calculate_concurrency(&network_spec.global_settings, ns.capabilities());
         network_spec
-            .build_parachain_artifacts(ns.clone(), &scoped_fs, &relay_chain_id, base_dir_exists)
+            .build_parachain_artifacts(
+                ns.clone(),
+                scoped_fs,
+                &relay_chain_id,
+                base_dir_exists,
+                concurrency,
+            )
             .await?;
+        timings.para_artifacts = start.elapsed();
 
-        // Gather the parachains to register in genesis and the ones to register with extrinsic
-        let (para_to_register_in_genesis, para_to_register_with_extrinsic): (
-            Vec<&ParachainSpec>,
-            Vec<&ParachainSpec>,
-        ) = network_spec
-            .parachains
-            .iter()
-            .filter(|para| para.registration_strategy != RegistrationStrategy::Manual)
-            .partition(|para| {
-                matches!(para.registration_strategy, RegistrationStrategy::InGenesis)
-            });
+        let start = Instant::now();
 
+        // Gather the parachains to register in genesis
         let mut para_artifacts = vec![];
-        for para in para_to_register_in_genesis {
+        for para in network_spec
+            .parachains
+            .iter()
+            .filter(|para| matches!(para.registration_strategy, RegistrationStrategy::InGenesis))
+        {
             let genesis_config = para.get_genesis_config()?;
             para_artifacts.push(genesis_config)
         }
@@ -169,7 +259,7 @@ where
                 &network_spec.relaychain,
                 &network_spec.hrmp_channels,
                 para_artifacts,
-                &scoped_fs,
+                scoped_fs,
             )
             .await?;
 
@@ -177,12 +267,88 @@ where
         network_spec
             .relaychain
             .chain_spec
-            .build_raw(&ns, &scoped_fs)
+            .build_raw(ns, scoped_fs)
+            .await?;
+
+        // Override the runtime (wasm) if needed
+        if let Some(wasm_override) = network_spec.relaychain.wasm_override.as_ref() {
+            network_spec
+                .relaychain
+                .chain_spec
+                .override_code(scoped_fs, wasm_override)
+                .await?;
+        }
+
+        // Inject the raw storage entries if needed
+        if !network_spec.relaychain.genesis_raw_entries.is_empty() {
+            network_spec
+                .relaychain
+                .chain_spec
+                .override_raw_entries(scoped_fs, &network_spec.relaychain.genesis_raw_entries)
+                .await?;
+        }
+        timings.relay_chain_spec += start.elapsed();
+
+        Ok(relay_chain_id)
+    }
+
+    async fn spawn_inner(
+        &self,
+        mut network_spec: NetworkSpec,
+    ) -> Result<Network<T>, OrchestratorError> {
+        // main driver for spawn the network
+        debug!(network_spec = ?network_spec,"Network spec to spawn");
+        let spawn_start = Instant::now();
+        let mut timings = SpawnTimings::default();
+
+        // TODO: move to Provider trait
+        validate_spec_with_provider_capabilities(&network_spec, self.provider.capabilities())
+            .map_err(|err| {
+                OrchestratorError::InvalidConfigForProvider(
+                    self.provider.name().into(),
+                    err.to_string(),
+                )
+            })?;
+
+        // fail before creating the namespace if the nodes can't be spawned by levels
+        ensure_token_dependencies(&network_spec)?;
+
+        // create namespace
+        let ns = self.create_namespace(&network_spec).await?;
+
+        info!("🧰 ns: {}", ns.name());
+        info!("🧰 base_dir: {:?}", ns.base_dir());
+
+        network_spec
+            .populate_nodes_available_args(ns.clone())
             .await?;
 
-        let (bootnodes, relaynodes) = split_nodes_by_bootnodes(&network_spec.relaychain.nodes);
+        let base_dir = ns.base_dir().to_string_lossy();
+        let scoped_fs = ScopedFilesystem::new(&self.filesystem, &base_dir);
+
+        // Build the chain-specs and parachain artifacts (customized, raw)
+        let relay_chain_id = self
+            .build_artifacts(&mut network_spec, &ns, &scoped_fs, &mut timings)
+            .await?;
+        let relay_chain_name = network_spec.relaychain.chain.as_str().to_owned();
+
+        // Gather the parachains to register with extrinsic
+        let para_to_register_with_extrinsic: Vec<&ParachainSpec> = network_spec
+            .parachains
+            .iter()
+            .filter(|para| {
+                matches!(
+                    para.registration_strategy,
+                    RegistrationStrategy::UsingExtrinsic
+                )
+            })
+            .collect();
+
+        let (bootnodes, relaynodes) = split_nodes_by_bootnodes(
+            &network_spec.relaychain.nodes,
+            network_spec.relaychain.bootnode_strategy,
+        )?;
 
-        // TODO: we want to still supporting spawn a dedicated bootnode??
         let mut ctx = SpawnNodeCtx {
             chain_id: &relay_chain_id,
             parachain_id: None,
@@ -193,6 +359,8 @@ where
             parachain: None,
             bootnodes_addr: &vec![],
             wait_ready: false,
+            global_settings: &network_spec.global_settings,
+            relay_rpc_url: None,
         };
 
         let global_files_to_inject = vec![TransferedFile::new(
@@ -213,24 +381,24 @@ where
         let mut network =
             Network::new_with_relay(r, ns.clone(), self.filesystem.clone(), network_spec.clone());
 
-        let spawning_tasks = bootnodes
-            .iter()
-            .map(|node| spawner::spawn_node(node, global_files_to_inject.clone(), &ctx));
+        let start = Instant::now();
+        // Values of the running nodes, to replace the `{{ZOMBIE:<node>:<field>}}` tokens
+        let mut running_nodes_values = RunningNodesValues::new();
 
         // Initiate the node_ws_uel which will be later used in the Parachain_with_extrinsic config
         let mut node_ws_url: String = "".to_string();
 
         // Calculate the bootnodes addr from the running nodes
         let mut bootnodes_addr: Vec<String> = vec![];
-        for node in futures::future::try_join_all(spawning_tasks).await? {
-            let ip = node.inner.ip().await?;
-            let port = if ctx.ns.capabilities().use_default_ports_in_cmd {
-                P2P_PORT
-            } else {
-                node.spec.p2p_port.0
-            };
-            let bootnode_multiaddr = generate_bootnode_addr(&node, &ip, port)?;
-            bootnodes_addr.push(bootnode_multiaddr);
+        for node in spawn_nodes_by_levels(
+            &bootnodes,
+            &global_files_to_inject,
+            &ctx,
+            &mut running_nodes_values,
+        )
+        .await?
+        {
+            bootnodes_addr.push(node_multiaddr(&node, &ns).await?);
 
             // Is used in the register_para_options (We need to get this from the relay and not the collators)
             if node_ws_url.is_empty() {
@@ -251,26 +419,42 @@ where
         ctx.bootnodes_addr = &bootnodes_addr;
 
         // spawn the rest of the nodes (TODO: in batches)
-        let spawning_tasks = relaynodes
-            .iter()
-            .map(|node| spawner::spawn_node(node, global_files_to_inject.clone(), &ctx));
+        for node in spawn_nodes_by_levels(
+            &relaynodes,
+            &global_files_to_inject,
+            &ctx,
+            &mut running_nodes_values,
+        )
+        .await?
+        {
+            // without bootnodes (`BootnodeStrategy::None`) use the first relay node
+            if node_ws_url.is_empty() {
+                node_ws_url.clone_from(&node.ws_uri)
+            }
 
-        for node in futures::future::try_join_all(spawning_tasks).await? {
             // Add the node to the `Network` instance
             network.add_running_node(node, None);
         }
 
+        timings.relay_nodes = start.elapsed();
+
         // spawn paras
+        let start = Instant::now();
         for para in network_spec.parachains.iter() {
             // Create parachain (in the context of the running network)
             let parachain = Parachain::from_spec(para, &global_files_to_inject, &scoped_fs).await?;
             let parachain_id = parachain.chain_id.clone();
 
-            let (bootnodes, collators) = split_nodes_by_bootnodes(&para.collators);
+            let (bootnodes, collators) =
+                split_nodes_by_bootnodes(&para.collators, para.bootnode_strategy)?;
+
+            // The relaychain nodes are already running, so a referenced node can be resolved
+            let relay_rpc_url = network.relay_rpc_url(para).await?;
 
             // Create `ctx` for spawn parachain nodes
             let mut ctx_para = SpawnNodeCtx {
                 parachain: Some(para),
+                relay_rpc_url: relay_rpc_url.as_deref(),
                 parachain_id: parachain_id.as_deref(),
                 role: if para.is_cumulus_based {
                     ZombieRole::CumulusCollator
@@ -281,24 +465,17 @@ where
                 ..ctx.clone()
             };
 
-            let spawning_tasks = bootnodes.iter().map(|node| {
-                spawner::spawn_node(node, parachain.files_to_inject.clone(), &ctx_para)
-            });
-
             // Calculate the bootnodes addr from the running nodes
             let mut bootnodes_addr: Vec<String> = vec![];
-            let mut running_nodes: Vec<NetworkNode> = vec![];
-            for node in futures::future::try_join_all(spawning_tasks).await? {
-                let ip = node.inner.ip().await?;
-                let port = if ctx.ns.capabilities().use_default_ports_in_cmd {
-                    P2P_PORT
-                } else {
-                    node.spec.p2p_port.0
-                };
-                let bootnode_multiaddr = generate_bootnode_addr(&node, &ip, port)?;
-                bootnodes_addr.push(bootnode_multiaddr);
-
-                running_nodes.push(node);
+            let mut running_nodes = spawn_nodes_by_levels(
+                &bootnodes,
+                &parachain.files_to_inject,
+                &ctx_para,
+                &mut running_nodes_values,
+            )
+            .await?;
+            for node in running_nodes.iter() {
+                bootnodes_addr.push(node_multiaddr(node, &ns).await?);
             }
 
             if let Some(para_chain_spec) = para.chain_spec.as_ref() {
@@ -310,15 +487,14 @@ where
             ctx_para.bootnodes_addr = &bootnodes_addr;
 
             // Spawn the rest of the nodes
-            let spawning_tasks = collators.iter().map(|node| {
-                spawner::spawn_node(node, parachain.files_to_inject.clone(), &ctx_para)
-            });
-
-            // join all the running nodes
-            running_nodes.extend_from_slice(
-                futures::future::try_join_all(spawning_tasks)
-                    .await?
-                    .as_slice(),
+            running_nodes.extend(
+                spawn_nodes_by_levels(
+                    &collators,
+                    &parachain.files_to_inject,
+                    &ctx_para,
+                    &mut running_nodes_values,
+                )
+                .await?,
             );
 
             let running_para_id = parachain.para_id;
@@ -334,7 +510,10 @@ where
         // verify nodes
         // network_helper::verifier::verify_nodes(&network.nodes()).await?;
 
+        timings.para_nodes = start.elapsed();
+
         // Now we need to register the paras with extrinsic from the Vec collected before;
+        let start = Instant::now();
         for para in para_to_register_with_extrinsic {
             let register_para_options: RegisterParachainOptions = RegisterParachainOptions {
                 id: para.id,
@@ -355,44 +534,399 @@ where
                     .to_path_buf(),
                 node_ws_url: node_ws_url.clone(),
                 onboard_as_para: para.onboard_as_parachain,
-                seed: None, // TODO: Seed is passed by?
-                finalization: false,
+                seed: para.registration_seed,
+                finalization: para.wait_for_finalization,
+                method: Default::default(),
             };
 
             Parachain::register(register_para_options, &scoped_fs).await?;
         }
+        timings.registration = start.elapsed();
 
         // - write zombie.json state file
-        let mut zombie_json = serde_json::to_value(&network)?;
-        zombie_json["local_base_dir"] = serde_json::value::Value::String(base_dir.to_string());
+        network.write_zombie_json().await?;
+
+        timings.total = spawn_start.elapsed();
+        info!("⏱️  network spawned, {timings}");
+        network.set_spawn_timings(timings);
 
-        scoped_fs
-            .write("zombie.json", serde_json::to_string_pretty(&zombie_json)?)
-            .await?;
         Ok(network)
     }
 }
 
 // Helpers
 
-// Split the node list depending if it's bootnode or not
-// NOTE: if there isn't a bootnode declared we use the first one
-fn split_nodes_by_bootnodes(nodes: &[NodeSpec]) -> (Vec<&NodeSpec>, Vec<&NodeSpec>) {
+/// Upper bound of concurrent tasks used when building artifacts and spawning nodes,
+/// unless the user provides a lower `spawn_concurrency` in the global settings.
+const DEFAULT_SPAWN_CONCURRENCY: usize = 100;
+
+/// Compute the number of tasks allowed to run concurrently, bounded by the
+/// provider max (`max_spawn_concurrency`)
+pub(crate) fn calculate_concurrency(
+    global_settings: &GlobalSettings,
+    capabilities: &ProviderCapabilities,
+) -> usize {
+    let max = capabilities.max_spawn_concurrency.max(1);
+    match global_settings.spawn_concurrency() {
+        Some(requested) if requested > max => {
+            warn!("requested spawn concurrency ({requested}) clamped to the provider max ({max})");
+            max
+        },
+        Some(requested) => requested.max(1),
+        None => DEFAULT_SPAWN_CONCURRENCY.min(max),
+    }
+}
+
+/// Split the `nodes` in (bootnodes, other nodes) following the [`BootnodeStrategy`]
+fn split_nodes_by_bootnodes(
+    nodes: &[NodeSpec],
+    strategy: BootnodeStrategy,
+) -> Result<(Vec<&NodeSpec>, Vec<&NodeSpec>), OrchestratorError> {
     // get the bootnodes to spawn first and calculate the bootnode string for use later
-    let mut bootnodes = vec![];
-    let mut other_nodes = vec![];
-    nodes.iter().for_each(|node| {
-        if node.is_bootnode {
-            bootnodes.push(node)
-        } else {
-            other_nodes.push(node)
+    let (mut bootnodes, mut other_nodes): (Vec<&NodeSpec>, Vec<&NodeSpec>) =
+        nodes.iter().partition(|node| {
+            // dedicated bootnodes are always spawned first, regardless of the strategy
+            node.is_dedicated_bootnode
+                || match strategy {
+                    BootnodeStrategy::AllValidators => node.is_bootnode || node.is_validator,
+                    BootnodeStrategy::None => false,
+                    _ => node.is_bootnode,
+                }
+        });
+
+    if bootnodes.is_empty() && !other_nodes.is_empty() {
+        match strategy {
+            BootnodeStrategy::FirstValidator | BootnodeStrategy::AllValidators => {
+                let index = other_nodes
+                    .iter()
+                    .position(|node| node.is_validator)
+                    .unwrap_or_default();
+                bootnodes.push(other_nodes.remove(index))
+            },
+            BootnodeStrategy::ExplicitOnly => {
+                return Err(OrchestratorError::InvalidConfig(format!(
+                    "bootnode strategy is 'explicit_only' but none of the nodes ({}) is flagged as bootnode",
+                    other_nodes
+                        .iter()
+                        .map(|node| node.name.as_str())
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                )))
+            },
+            BootnodeStrategy::None => {},
         }
+    }
+
+    Ok((bootnodes, other_nodes))
+}
+
+/// Values of the running nodes (by node name and field) used to replace the
+/// `{{ZOMBIE:<node>:<field>}}` tokens in the args/env of the nodes spawned after them.
+type RunningNodesValues = HashMap<String, HashMap<&'static str, String>>;
+
+// Spawn the `nodes` by dependency levels (see `dependency_levels_among`), replacing the
+// `{{ZOMBIE:<node>:<field>}}` tokens with the values of the nodes already running.
+// The nodes of each level are spawned concurrently, bounded by `calculate_concurrency`.
+async fn spawn_nodes_by_levels<T: FileSystem>(
+    nodes: &[&NodeSpec],
+    files_to_inject: &[TransferedFile],
+    ctx: &SpawnNodeCtx<'_, T>,
+    running_nodes_values: &mut RunningNodesValues,
+) -> Result<Vec<NetworkNode>, OrchestratorError> {
+    let concurrency = calculate_concurrency(ctx.global_settings, ctx.ns.capabilities());
+    let mut running_nodes = vec![];
+    for level in dependency_levels_among(nodes)? {
+        let level = level
+            .into_iter()
+            .map(|node| replace_node_tokens(node, running_nodes_values))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        // spawn the level in chunks of `concurrency` nodes, keeping the order of the nodes
+        // (e.g. the first node is used for the ws url)
+        for chunk in level.chunks(concurrency) {
+            let spawning_tasks = chunk
+                .iter()
+                .map(|node| spawner::spawn_node(node, files_to_inject.to_vec(), ctx));
+
+            for node in futures::future::try_join_all(spawning_tasks).await? {
+                running_nodes_values
+                    .insert(node.name.clone(), running_node_values(&node, ctx.ns).await?);
+                running_nodes.push(node);
+            }
+        }
+    }
+
+    Ok(running_nodes)
+}
+
+// Replace the `{{ZOMBIE:<node>:<field>}}` tokens in the node args/env with the values of the
+// running nodes, failing if any of the referenced nodes (or fields) can't be resolved.
+fn replace_node_tokens(
+    node: &NodeSpec,
+    running_nodes_values: &RunningNodesValues,
+) -> Result<NodeSpec, OrchestratorError> {
+    let replace = |text: &str| apply_running_network_replacements(text, running_nodes_values);
+    let mut node = node.clone();
+    node.args = node
+        .args
+        .iter()
+        .map(|arg| match arg {
+            Arg::Flag(flag) => Arg::Flag(replace(flag)),
+            Arg::Option(option, value) => Arg::Option(replace(option), replace(value)),
+            Arg::Remove(_) => arg.clone(),
+        })
+        .collect();
+    for var in node.env.iter_mut() {
+        var.value = replace(&var.value);
+    }
+
+    let unresolved: Vec<String> = node_token_references(&node)
+        .into_iter()
+        .map(|(_, token)| token)
+        .collect();
+    if !unresolved.is_empty() {
+        return Err(OrchestratorError::InvalidConfig(format!(
+            "node '{}' references nodes that are not running before it (or unknown fields): {}",
+            node.name,
+            unresolved.join(", ")
+        )));
+    }
+
+    Ok(node)
+}
+
+// Get the values of a running node that can be referenced with `{{ZOMBIE:<node>:<field>}}` tokens
+async fn running_node_values(
+    node: &NetworkNode,
+    ns: &DynNamespace,
+) -> Result<HashMap<&'static str, String>, OrchestratorError> {
+    Ok(HashMap::from([
+        ("multiaddr", node_multiaddr(node, ns).await?),
+        ("ws_uri", node.ws_uri.clone()),
+        ("prometheus_uri", node.prometheus_uri.clone()),
+    ]))
+}
+
+// Ensure the `{{ZOMBIE:<node>:<field>}}` tokens of the network nodes (relaychain and
+// parachains) don't have cyclical dependencies, reporting the nodes involved otherwise,
+// and only reference nodes spawned before or along the node.
+fn ensure_token_dependencies(network_spec: &NetworkSpec) -> Result<(), OrchestratorError> {
+    let nodes: Vec<&NodeSpec> = network_spec
+        .relaychain
+        .nodes
+        .iter()
+        .chain(
+            network_spec
+                .parachains
+                .iter()
+                .flat_map(|para| para.collators.iter()),
+        )
+        .collect();
+
+    dependency_levels_among(&nodes)?;
+
+    // the nodes are spawned in groups (the relaychain bootnodes and other nodes, then the
+    // bootnodes and other collators of each parachain), a node can only reference the nodes
+    // of its group or of the previous ones
+    let mut groups = vec![];
+    let (bootnodes, other_nodes) = split_nodes_by_bootnodes(
+        &network_spec.relaychain.nodes,
+        network_spec.relaychain.bootnode_strategy,
+    )?;
+    groups.extend([bootnodes, other_nodes]);
+    for para in network_spec.parachains.iter() {
+        let (bootnodes, collators) =
+            split_nodes_by_bootnodes(&para.collators, para.bootnode_strategy)?;
+        groups.extend([bootnodes, collators]);
+    }
+
+    let mut spawned_before: HashSet<&str> = HashSet::new();
+    for group in groups {
+        let in_group: HashSet<&str> = group.iter().map(|node| node.name.as_str()).collect();
+        for node in group.iter() {
+            let spawned_after = node_token_references(node)
+                .into_iter()
+                .filter(|(name, _)| {
+                    !spawned_before.contains(name.as_str()) && !in_group.contains(name.as_str())
+                })
+                .map(|(_, token)| token)
+                .collect::<Vec<_>>();
+
+            if !spawned_after.is_empty() {
+                return Err(OrchestratorError::InvalidConfig(format!(
+                    "node '{}' references nodes that are spawned after it (or unknown): {}",
+                    node.name,
+                    spawned_after.join(", ")
+                )));
+            }
+        }
+        spawned_before.extend(in_group);
+    }
+
+    Ok(())
+}
+
+// Get the `(node name, token)` references of the `{{ZOMBIE:<node>:<field>}}` tokens in the
+// node args/env
+fn node_token_references(node: &NodeSpec) -> Vec<(String, String)> {
+    let args = node.args.iter().flat_map(|arg| match arg {
+        Arg::Flag(flag) => vec![flag.as_str()],
+        Arg::Option(option, value) => vec![option.as_str(), value.as_str()],
+        Arg::Remove(_) => vec![],
     });
+    let env = node.env.iter().map(|var| var.value.as_str());
+
+    args.chain(env)
+        .flat_map(|text| {
+            zombie_token_references(text)
+                .into_iter()
+                .map(|(name, token)| (name.to_string(), token.to_string()))
+                .collect::<Vec<_>>()
+        })
+        .collect()
+}
 
-    if bootnodes.is_empty() {
-        bootnodes.push(other_nodes.remove(0))
+// Get the names of the nodes referenced by `{{ZOMBIE:<node>:<field>}}` tokens in the node args/env
+fn node_token_dependencies(node: &NodeSpec) -> HashSet<String> {
+    node_token_references(node)
+        .into_iter()
+        .map(|(name, _)| name)
+        .collect()
+}
+
+/// Compute the levels to spawn the `nodes`, based on the dependencies declared with
+/// `{{ZOMBIE:<node>:<field>}}` tokens in the node args/env. Nodes in the same level
+/// don't depend on each other and the nodes of each level only depends on nodes from
+/// previous levels. Dependencies on nodes that are not part of `nodes` are ignored.
+///
+/// Returns the names of the nodes in each level or an error listing the nodes of a cycle
+/// if the dependencies are cyclic.
+pub fn compute_spawn_levels(nodes: &[NodeSpec]) -> Result<Vec<Vec<String>>, OrchestratorError> {
+    let nodes: Vec<&NodeSpec> = nodes.iter().collect();
+    let levels = dependency_levels_among(&nodes)?
+        .into_iter()
+        .map(|level| level.into_iter().map(|node| node.name.clone()).collect())
+        .collect();
+
+    Ok(levels)
+}
+
+// Split the nodes in levels (using Kahn's algorithm), where each level only depends
+// on the previous ones.
+fn dependency_levels_among<'a>(
+    nodes: &[&'a NodeSpec],
+) -> Result<Vec<Vec<&'a NodeSpec>>, OrchestratorError> {
+    let by_name: HashMap<&str, &'a NodeSpec> = nodes
+        .iter()
+        .map(|node| (node.name.as_str(), *node))
+        .collect();
+    let mut indegree: HashMap<&str, usize> = HashMap::new();
+    let mut dependents: HashMap<&str, Vec<&str>> = HashMap::new();
+    let mut dependencies: HashMap<&str, Vec<&str>> = HashMap::new();
+
+    for node in nodes {
+        let mut deps = vec![];
+        for dep in node_token_dependencies(node) {
+            // only track dependencies among the given nodes
+            if let Some((dep_name, _)) = by_name.get_key_value(dep.as_str()) {
+                dependents.entry(dep_name).or_default().push(&node.name);
+                deps.push(*dep_name);
+            }
+        }
+        // sort to make the cycle reporting deterministic
+        deps.sort();
+        indegree.insert(&node.name, deps.len());
+        dependencies.insert(&node.name, deps);
+    }
+
+    let mut levels = vec![];
+    let mut processed_count = 0;
+    let mut current: Vec<&str> = nodes
+        .iter()
+        .map(|node| node.name.as_str())
+        .filter(|name| indegree.get(name) == Some(&0))
+        .collect();
+
+    while !current.is_empty() {
+        processed_count += current.len();
+        let mut next = vec![];
+        for name in current.iter() {
+            for dependent in dependents.get(name).into_iter().flatten() {
+                if let Some(degree) = indegree.get_mut(dependent) {
+                    *degree -= 1;
+                    if *degree == 0 {
+                        next.push(*dependent);
+                    }
+                }
+            }
+        }
+
+        levels.push(current.iter().map(|name| by_name[name]).collect());
+        current = next;
+    }
+
+    if processed_count != nodes.len() {
+        let cycle = find_dependency_cycle(nodes, &indegree, &dependencies);
+        return Err(OrchestratorError::InvalidConfig(format!(
+            "cyclical dependencies among: {}",
+            cycle.join(" -> ")
+        )));
+    }
+
+    Ok(levels)
+}
+
+// Reconstruct one cycle from the residual graph (nodes with non-zero indegree after
+// the levels computation). Every residual node depends on at least one other residual
+// node, so walking the dependencies from any of them eventually revisits a node.
+fn find_dependency_cycle<'a>(
+    nodes: &[&'a NodeSpec],
+    indegree: &HashMap<&str, usize>,
+    dependencies: &HashMap<&str, Vec<&'a str>>,
+) -> Vec<&'a str> {
+    let is_residual = |name: &str| indegree.get(name).is_some_and(|degree| *degree > 0);
+    let Some(start) = nodes
+        .iter()
+        .map(|node| node.name.as_str())
+        .find(|name| is_residual(name))
+    else {
+        return vec![];
+    };
+
+    let mut path: Vec<&str> = vec![start];
+    let mut current = start;
+    loop {
+        let Some(next) = dependencies
+            .get(current)
+            .and_then(|deps| deps.iter().find(|dep| is_residual(dep)))
+        else {
+            return path;
+        };
+
+        if let Some(pos) = path.iter().position(|name| name == next) {
+            let mut cycle = path.split_off(pos);
+            cycle.push(next);
+            return cycle;
+        }
+
+        path.push(next);
+        current = next;
     }
-    (bootnodes, other_nodes)
+}
+
+// Generate the multiaddress of a running node (e.g. to use as bootnode)
+async fn node_multiaddr(
+    node: &NetworkNode,
+    ns: &DynNamespace,
+) -> Result<String, OrchestratorError> {
+    let ip = node.inner.ip().await?;
+    let port = if ns.capabilities().use_default_ports_in_cmd {
+        P2P_PORT
+    } else {
+        node.spec.p2p_port.0
+    };
+
+    Ok(generate_bootnode_addr(node, &ip, port)?)
 }
 
 // Generate a bootnode multiaddress and return as string
@@ -607,6 +1141,8 @@ pub use network::{AddCollatorOptions, AddNodeOptions};
 pub use network_helper::metrics;
 #[cfg(feature = "pjs")]
 pub use pjs_helper::PjsResult;
+pub use provider::LogStream;
+pub use tokio_util::sync::CancellationToken;
 
 #[cfg(test)]
 mod tests {
@@ -646,16 +1182,362 @@ mod tests {
             .build()
     }
 
+    fn generate_with_tokens(
+        alice_args: Vec<Arg>,
+        bob_args: Vec<Arg>,
+    ) -> Result<NetworkConfig, Vec<anyhow::Error>> {
+        NetworkConfigBuilder::new()
+            .with_relaychain(|r| {
+                r.with_chain("rococo-local")
+                    .with_default_command("polkadot")
+                    .with_node(|node| node.with_name("alice").with_args(alice_args))
+                    .with_node(|node| node.with_name("bob").with_args(bob_args))
+                    .with_node(|node| node.with_name("charlie"))
+            })
+            .build()
+    }
+
+    #[tokio::test]
+    async fn spawn_dry_run_should_build_the_artifacts_without_spawning_nodes() {
+        let base_dir = format!("/tmp/unit_test_{}", uuid::Uuid::new_v4());
+        // already raw, so the chain-spec is used as is (without running the command)
+        let raw_spec = r#"{"name":"Rococo Local","id":"rococo_local_testnet","genesis":{"raw":{"top":{},"childrenDefault":{}}}}"#;
+        let network_config = NetworkConfigBuilder::new()
+            .with_relaychain(|r| {
+                r.with_chain("rococo-local")
+                    .with_default_command("cargo")
+                    .with_chain_spec_inline(raw_spec)
+                    .with_node(|node| node.with_name("alice"))
+            })
+            .with_global_settings(|g| g.with_base_dir(&base_dir))
+            .build()
+            .unwrap();
+        let fs = support::fs::local::LocalFileSystem;
+        let orchestrator = Orchestrator::new(fs.clone(), provider::NativeProvider::new(fs));
+
+        let report = orchestrator.spawn_dry_run(network_config).await.unwrap();
+
+        // the namespace is destroyed, keeping the artifacts
+        assert!(orchestrator.provider.namespaces().await.is_empty());
+        assert_eq!(report.base_dir, PathBuf::from(&base_dir));
+        assert_eq!(report.relay_chain_spec, PathBuf::from("rococo-local.json"));
+        assert!(report.parachains.is_empty());
+        assert!(report.base_dir.join(&report.relay_chain_spec).exists());
+        std::fs::remove_dir_all(&base_dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn spawn_dry_run_should_destroy_the_namespace_on_errors() {
+        let base_dir = format!("/tmp/unit_test_{}", uuid::Uuid::new_v4());
+        let network_config = NetworkConfigBuilder::new()
+            .with_relaychain(|r| {
+                r.with_chain("rococo-local")
+                    .with_default_command("cargo")
+                    .with_chain_spec_path(format!("{base_dir}/missing.json").as_str())
+                    .with_node(|node| node.with_name("alice"))
+            })
+            .with_global_settings(|g| g.with_base_dir(&base_dir))
+            .build()
+            .unwrap();
+        let fs = support::fs::local::LocalFileSystem;
+        let orchestrator = Orchestrator::new(fs.clone(), provider::NativeProvider::new(fs));
+
+        assert!(orchestrator.spawn_dry_run(network_config).await.is_err());
+        assert!(orchestrator.provider.namespaces().await.is_empty());
+        std::fs::remove_dir_all(&base_dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn spawn_dry_run_should_fail_with_invalid_token_dependencies() {
+        let network_config = NetworkConfigBuilder::new()
+            .with_relaychain(|r| {
+                r.with_chain("rococo-local")
+                    .with_default_command("cargo")
+                    .with_node(|node| {
+                        node.with_name("alice").with_args(vec![(
+                            "--reserved-nodes",
+                            "{{ZOMBIE:bob:multiaddr}}",
+                        )
+                            .into()])
+                    })
+                    .with_node(|node| node.with_name("bob"))
+            })
+            .build()
+            .unwrap();
+        let fs = support::fs::local::LocalFileSystem;
+        let orchestrator = Orchestrator::new(fs.clone(), provider::NativeProvider::new(fs));
+
+        let err = orchestrator
+            .spawn_dry_run(network_config)
+            .await
+            .unwrap_err();
+
+        assert!(matches!(err, OrchestratorError::InvalidConfig(_)));
+        // fails before creating the namespace
+        assert!(orchestrator.provider.namespaces().await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn compute_spawn_levels_works() {
+        let network_config = generate_with_tokens(
+            vec![],
+            vec![("--bootnodes", "{{ZOMBIE:alice:multiaddr}}").into()],
+        )
+        .unwrap();
+        let spec = NetworkSpec::from_config(&network_config).await.unwrap();
+
+        let levels = compute_spawn_levels(spec.relaychain().nodes()).unwrap();
+        assert_eq!(
+            levels,
+            vec![
+                vec!["alice".to_string(), "charlie".to_string()],
+                vec!["bob".to_string()]
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn compute_spawn_levels_with_cycle_should_fails() {
+        let network_config = generate_with_tokens(
+            vec![("--bootnodes", "{{ZOMBIE:bob:multiaddr}}").into()],
+            vec![("--bootnodes", "{{ZOMBIE:alice:multiaddr}}").into()],
+        )
+        .unwrap();
+        let spec = NetworkSpec::from_config(&network_config).await.unwrap();
+
+        let err = compute_spawn_levels(spec.relaychain().nodes()).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Invalid network configuration: cyclical dependencies among: alice -> bob -> alice"
+        );
+    }
+
+    #[tokio::test]
+    async fn compute_spawn_levels_should_ignore_the_nodes_not_in_the_given_ones() {
+        let network_config = generate_with_tokens(
+            vec![],
+            vec![("--bootnodes", "{{ZOMBIE:alice:multiaddr}}").into()],
+        )
+        .unwrap();
+        let spec = NetworkSpec::from_config(&network_config).await.unwrap();
+
+        // alice is not part of the nodes (e.g. already running)
+        let levels = compute_spawn_levels(&spec.relaychain().nodes()[1..]).unwrap();
+        assert_eq!(levels, vec![vec!["bob".to_string(), "charlie".to_string()]]);
+    }
+
+    #[tokio::test]
+    async fn ensure_token_dependencies_should_fail_if_a_bootnode_references_a_non_bootnode() {
+        let network_config = generate_with_tokens(
+            vec![("--reserved-nodes", "{{ZOMBIE:bob:multiaddr}}").into()],
+            vec![],
+        )
+        .unwrap();
+        let spec = NetworkSpec::from_config(&network_config).await.unwrap();
+
+        // alice (the first validator) is the bootnode, spawned before bob
+        let err = ensure_token_dependencies(&spec).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Invalid network configuration: node 'alice' references nodes that are spawned after it (or unknown): {{ZOMBIE:bob:multiaddr}}"
+        );
+
+        // bob references the (already running) bootnode
+        let network_config = generate_with_tokens(
+            vec![],
+            vec![("--reserved-nodes", "{{ZOMBIE:alice:multiaddr}}").into()],
+        )
+        .unwrap();
+        let spec = NetworkSpec::from_config(&network_config).await.unwrap();
+        assert!(ensure_token_dependencies(&spec).is_ok());
+    }
+
+    #[tokio::test]
+    async fn replace_node_tokens_should_use_the_running_nodes_values() {
+        let network_config = generate_with_tokens(
+            vec![],
+            vec![("--bootnodes", "{{ZOMBIE:alice:multiaddr}}").into()],
+        )
+        .unwrap();
+        let spec = NetworkSpec::from_config(&network_config).await.unwrap();
+        let bob = &spec.relaychain().nodes()[1];
+        let running_nodes_values = RunningNodesValues::from([(
+            "alice".to_string(),
+            HashMap::from([(
+                "multiaddr",
+                "/ip4/127.0.0.1/tcp/30333/ws/p2p/12D3".to_string(),
+            )]),
+        )]);
+
+        let replaced = replace_node_tokens(bob, &running_nodes_values).unwrap();
+        assert_eq!(
+            replaced.args,
+            vec![Arg::Option(
+                "--bootnodes".into(),
+                "/ip4/127.0.0.1/tcp/30333/ws/p2p/12D3".into()
+            )]
+        );
+
+        // alice is not running yet
+        let err = replace_node_tokens(bob, &RunningNodesValues::new()).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Invalid network configuration: node 'bob' references nodes that are not running before it (or unknown fields): {{ZOMBIE:alice:multiaddr}}"
+        );
+    }
+
+    fn generate_with_bootnode_strategy(
+        strategy: BootnodeStrategy,
+    ) -> Result<NetworkConfig, Vec<anyhow::Error>> {
+        NetworkConfigBuilder::new()
+            .with_relaychain(|r| {
+                r.with_chain("rococo-local")
+                    .with_default_command("polkadot")
+                    .with_bootnode_strategy(strategy)
+                    .with_node(|node| node.with_name("alice").validator(false))
+                    .with_node(|node| node.with_name("bob"))
+                    .with_node(|node| node.with_name("charlie"))
+            })
+            .build()
+    }
+
+    fn split_names(
+        nodes: &[NodeSpec],
+        strategy: BootnodeStrategy,
+    ) -> Result<(Vec<&str>, Vec<&str>), OrchestratorError> {
+        let (bootnodes, others) = split_nodes_by_bootnodes(nodes, strategy)?;
+        fn names(nodes: Vec<&NodeSpec>) -> Vec<&str> {
+            nodes.into_iter().map(|node| node.name.as_str()).collect()
+        }
+        Ok((names(bootnodes), names(others)))
+    }
+
+    #[tokio::test]
+    async fn split_nodes_by_bootnodes_follows_the_strategy() {
+        let cases = [
+            (
+                BootnodeStrategy::FirstValidator,
+                (vec!["bob"], vec!["alice", "charlie"]),
+            ),
+            (
+                BootnodeStrategy::AllValidators,
+                (vec!["bob", "charlie"], vec!["alice"]),
+            ),
+            (
+                BootnodeStrategy::None,
+                (vec![], vec!["alice", "bob", "charlie"]),
+            ),
+        ];
+
+        for (strategy, expected) in cases {
+            let network_config = generate_with_bootnode_strategy(strategy).unwrap();
+            let spec = NetworkSpec::from_config(&network_config).await.unwrap();
+
+            let got = split_names(spec.relaychain().nodes(), strategy).unwrap();
+            assert_eq!(got, expected, "strategy: {strategy:?}");
+        }
+    }
+
+    #[tokio::test]
+    async fn split_nodes_by_bootnodes_with_explicit_only_and_no_bootnode_should_fails() {
+        let network_config =
+            generate_with_bootnode_strategy(BootnodeStrategy::ExplicitOnly).unwrap();
+        let spec = NetworkSpec::from_config(&network_config).await.unwrap();
+
+        let got = split_names(spec.relaychain().nodes(), BootnodeStrategy::ExplicitOnly);
+        assert!(matches!(got, Err(OrchestratorError::InvalidConfig(_))));
+    }
+
+    #[tokio::test]
+    async fn split_nodes_by_bootnodes_with_dedicated_bootnode_should_not_be_a_validator() {
+        let network_config = NetworkConfigBuilder::new()
+            .with_relaychain(|r| {
+                r.with_chain("rococo-local")
+                    .with_default_command("polkadot")
+                    .with_bootnode_strategy(BootnodeStrategy::None)
+                    .with_node(|node| node.with_name("alice"))
+                    .with_node(|node| node.with_name("boot").dedicated_bootnode(true))
+            })
+            .build()
+            .unwrap();
+        let spec = NetworkSpec::from_config(&network_config).await.unwrap();
+
+        let got = split_names(spec.relaychain().nodes(), BootnodeStrategy::None).unwrap();
+        assert_eq!(got, (vec!["boot"], vec!["alice"]));
+
+        let boot = spec.relaychain().nodes().last().unwrap();
+        assert!(boot.is_bootnode);
+        assert!(!boot.is_validator);
+        assert!(!boot.is_invulnerable);
+    }
+
+    #[tokio::test]
+    async fn spawn_with_cyclical_tokens_should_fail_before_creating_the_namespace() {
+        let base_dir = format!("/tmp/unit_test_{}", uuid::Uuid::new_v4());
+        let network_config = NetworkConfigBuilder::new()
+            .with_relaychain(|r| {
+                r.with_chain("rococo-local")
+                    .with_default_command("cargo")
+                    .with_node(|node| {
+                        node.with_name("alice").with_args(vec![(
+                            "--bootnodes",
+                            "{{ZOMBIE:bob:multiaddr}}",
+                        )
+                            .into()])
+                    })
+                    .with_node(|node| {
+                        node.with_name("bob").with_args(vec![(
+                            "--bootnodes",
+                            "{{ZOMBIE:alice:multiaddr}}",
+                        )
+                            .into()])
+                    })
+            })
+            .with_global_settings(|g| g.with_base_dir(&base_dir))
+            .build()
+            .unwrap();
+        let fs = support::fs::local::LocalFileSystem;
+        let orchestrator = Orchestrator::new(fs.clone(), provider::NativeProvider::new(fs));
+
+        let err = orchestrator.spawn(network_config).await.err().unwrap();
+        assert!(
+            matches!(err, OrchestratorError::InvalidConfig(_)),
+            "{err:?}"
+        );
+        assert_eq!(
+            err.to_string(),
+            "Invalid network configuration: cyclical dependencies among: alice -> bob -> alice"
+        );
+        assert!(!PathBuf::from(&base_dir).exists());
+    }
+
+    #[test]
+    fn calculate_concurrency_should_be_bounded_by_the_provider_max() {
+        let caps = ProviderCapabilities::new()
+            .prefix_with_full_path(true)
+            .max_spawn_concurrency(8);
+        let with_concurrency = |concurrency: Option<usize>| {
+            let builder = configuration::GlobalSettingsBuilder::new();
+            match concurrency {
+                Some(concurrency) => builder.with_spawn_concurrency(concurrency),
+                None => builder,
+            }
+            .build()
+            .unwrap()
+        };
+
+        assert_eq!(calculate_concurrency(&with_concurrency(None), &caps), 8);
+        assert_eq!(calculate_concurrency(&with_concurrency(Some(4)), &caps), 4);
+        assert_eq!(calculate_concurrency(&with_concurrency(Some(50)), &caps), 8);
+    }
+
     #[tokio::test]
     async fn valid_config_with_image() {
         let network_config = generate(true, None).unwrap();
         let spec = NetworkSpec::from_config(&network_config).await.unwrap();
-        let caps = ProviderCapabilities {
-            requires_image: true,
-            has_resources: false,
-            prefix_with_full_path: false,
-            use_default_ports_in_cmd: false,
-        };
+        let caps = ProviderCapabilities::new()
+            .requires_image(true)
+            .max_spawn_concurrency(100);
 
         let valid = validate_spec_with_provider_capabilities(&spec, &caps);
         assert!(valid.is_ok())
@@ -665,12 +1547,9 @@ mod tests {
     async fn invalid_config_without_image() {
         let network_config = generate(false, None).unwrap();
         let spec = NetworkSpec::from_config(&network_config).await.unwrap();
-        let caps = ProviderCapabilities {
-            requires_image: true,
-            has_resources: false,
-            prefix_with_full_path: false,
-            use_default_ports_in_cmd: false,
-        };
+        let caps = ProviderCapabilities::new()
+            .requires_image(true)
+            .max_spawn_concurrency(100);
 
         let valid = validate_spec_with_provider_capabilities(&spec, &caps);
         assert!(valid.is_err())
@@ -680,12 +1559,7 @@ mod tests {
     async fn invalid_config_missing_cmd() {
         let network_config = generate(false, Some("other")).unwrap();
         let spec = NetworkSpec::from_config(&network_config).await.unwrap();
-        let caps = ProviderCapabilities {
-            requires_image: false,
-            has_resources: false,
-            prefix_with_full_path: false,
-            use_default_ports_in_cmd: false,
-        };
+        let caps = ProviderCapabilities::new().max_spawn_concurrency(100);
 
         let valid = validate_spec_with_provider_capabilities(&spec, &caps);
         assert!(valid.is_err())
@@ -695,12 +1569,7 @@ mod tests {
     async fn valid_config_present_cmd() {
         let network_config = generate(false, Some("cargo")).unwrap();
         let spec = NetworkSpec::from_config(&network_config).await.unwrap();
-        let caps = ProviderCapabilities {
-            requires_image: false,
-            has_resources: false,
-            prefix_with_full_path: false,
-            use_default_ports_in_cmd: false,
-        };
+        let caps = ProviderCapabilities::new().max_spawn_concurrency(100);
 
         let valid = validate_spec_with_provider_capabilities(&spec, &caps);
         println!("{:?}", valid);
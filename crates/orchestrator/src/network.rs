@@ -3,7 +3,13 @@ pub mod node;
 pub mod parachain;
 pub mod relaychain;
 
-use std::{collections::HashMap, path::PathBuf};
+use std::{
+    collections::{BTreeMap, HashMap},
+    net::SocketAddr,
+    path::{Path, PathBuf},
+    str::FromStr,
+    time::Duration,
+};
 
 use configuration::{
     para_states::{Initial, Running},
@@ -11,22 +17,97 @@ use configuration::{
     types::{Arg, Command, Image, Port},
     ParachainConfig, ParachainConfigBuilder, RegistrationStrategy,
 };
+use futures::StreamExt;
 use provider::{types::TransferedFile, DynNamespace, ProviderError};
+use regex::Regex;
 use serde::Serialize;
-use support::fs::FileSystem;
-
-use self::{node::NetworkNode, parachain::Parachain, relaychain::Relaychain};
+use subxt::{
+    dynamic::Value, ext::scale_value::ValueDef, utils::H256, OnlineClient, PolkadotConfig,
+};
+use subxt_signer::{sr25519::Keypair, SecretUri};
+use support::{fs::FileSystem, net::wait_ws_ready};
+use tokio_util::sync::CancellationToken;
+use tracing::{trace, warn};
+
+use self::{
+    chain_upgrade::ChainUpgrade,
+    node::{sort_nodes, NetworkNode, PeerInfo},
+    parachain::Parachain,
+    relaychain::Relaychain,
+};
 use crate::{
+    errors::OrchestratorError,
     generators::chain_spec::ChainSpec,
     network_spec::{self, NetworkSpec},
     shared::{
+        constants::RPC_PORT,
         macros,
-        types::{ChainDefaultContext, RegisterParachainOptions},
+        types::{
+            ChainDefaultContext, NetworkArtifacts, ParaArtifacts, RegisterParachainOptions,
+            RuntimeUpgradeOptions, SpawnTimings,
+        },
     },
     spawner::{self, SpawnNodeCtx},
-    ScopedFilesystem, ZombieRole,
+    tx_helper, ScopedFilesystem, ZombieRole,
 };
 
+const BEST_BLOCK_METRIC: &str = "block_height{status=\"best\"}";
+
+/// Best block height progress of a parachain, as reported by its first collator.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParachainProgress {
+    pub para_id: u32,
+    /// Name of the collator used to read the block height.
+    pub collator: String,
+    /// First block height read (`None` if the collator wasn't reachable yet).
+    pub initial: Option<u64>,
+    /// Last block height read (`None` if the collator wasn't reachable).
+    pub current: Option<u64>,
+}
+
+impl ParachainProgress {
+    /// Amount of blocks produced between the first and the last read.
+    pub fn produced(&self) -> u64 {
+        match (self.initial, self.current) {
+            (Some(initial), Some(current)) => current.saturating_sub(initial),
+            _ => 0,
+        }
+    }
+}
+
+impl std::fmt::Display for ParachainProgress {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let height = |h: Option<u64>| h.map_or_else(|| "?".to_string(), |h| h.to_string());
+        write!(
+            f,
+            "para {} ({}): {} -> {} ({} blocks)",
+            self.para_id,
+            self.collator,
+            height(self.initial),
+            height(self.current),
+            self.produced()
+        )
+    }
+}
+
+/// Connectivity of the nodes in the network, see [`Network::connectivity_report`].
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ConnectivityReport {
+    /// Peers of each reachable node, by node name.
+    pub peers: HashMap<String, PeerInfo>,
+    /// Nodes without any peer (e.g. a collator that can't find the relaychain).
+    pub isolated: Vec<String>,
+    /// Nodes that couldn't be queried, with the error.
+    pub unreachable: Vec<(String, String)>,
+}
+
+impl ConnectivityReport {
+    /// Whether every node was reachable and connected to at least one peer.
+    pub fn is_healthy(&self) -> bool {
+        self.isolated.is_empty() && self.unreachable.is_empty()
+    }
+}
+
 #[derive(Serialize)]
 pub struct Network<T: FileSystem> {
     #[serde(skip)]
@@ -35,9 +116,86 @@ pub struct Network<T: FileSystem> {
     filesystem: T,
     relay: Relaychain,
     initial_spec: NetworkSpec,
+    #[serde(serialize_with = "serialize_sorted_parachains")]
     parachains: HashMap<u32, Parachain>,
+    // fast lookup by name, the serialized (ordered) nodes live in the relaychain/parachains
+    #[serde(skip)]
+    nodes_by_name: HashMap<String, NodeLocation>,
     #[serde(skip)]
-    nodes_by_name: HashMap<String, NetworkNode>,
+    spawn_timings: Option<SpawnTimings>,
+}
+
+/// Where a node lives in the [`Network`], as index in the relaychain nodes or in the
+/// collators of a parachain.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum NodeLocation {
+    Relaychain(usize),
+    Parachain(u32, usize),
+}
+
+// Serialize the parachains sorted by para id, to keep the state file (`zombie.json`) stable
+// across runs.
+fn serialize_sorted_parachains<S>(
+    parachains: &HashMap<u32, Parachain>,
+    serializer: S,
+) -> Result<S::Ok, S::Error>
+where
+    S: serde::Serializer,
+{
+    serializer.collect_map(parachains.iter().collect::<BTreeMap<_, _>>())
+}
+
+// Collators of the parachain, validators first and then by name.
+fn sorted_collators(para: &Parachain) -> Vec<&NetworkNode> {
+    let mut collators: Vec<&NetworkNode> = para.collators.iter().collect();
+    sort_nodes(&mut collators);
+    collators
+}
+
+// The error of an onboarding wait timeout, with the last lifecycle read (`None` if it was never
+// read, then the `last_error` reading it is reported instead).
+fn onboarding_timeout_error(
+    para_id: u32,
+    timeout_secs: u64,
+    lifecycle: Option<Option<String>>,
+    last_error: Option<anyhow::Error>,
+) -> anyhow::Error {
+    match (lifecycle, last_error) {
+        (None, Some(e)) => anyhow::anyhow!(
+            "Timeout ({timeout_secs}s), waiting for parachain {para_id} to be onboarded, the lifecycle couldn't be read: {e}"
+        ),
+        (lifecycle, _) => anyhow::anyhow!(
+            "Timeout ({timeout_secs}s), waiting for parachain {para_id} to be onboarded, current lifecycle: {}",
+            lifecycle.flatten().as_deref().unwrap_or("not registered")
+        ),
+    }
+}
+
+// Number of the latest relaychain block and the lifecycle (e.g. `Onboarding`, `Parachain`)
+// of the parachain at that block, `None` if the parachain isn't registered.
+async fn para_lifecycle(
+    client: &OnlineClient<PolkadotConfig>,
+    para_id: u32,
+) -> Result<(u32, Option<String>), anyhow::Error> {
+    let block = client.blocks().at_latest().await?;
+    let query = subxt::dynamic::storage(
+        "Paras",
+        "ParaLifecycles",
+        vec![Value::primitive(para_id.into())],
+    );
+
+    let lifecycle = match block.storage().fetch(&query).await? {
+        Some(thunk) => {
+            let value = thunk.to_value()?;
+            Some(match &value.value {
+                ValueDef::Variant(variant) => variant.name.clone(),
+                _ => value.to_string(),
+            })
+        },
+        None => None,
+    };
+
+    Ok((block.number(), lifecycle))
 }
 
 impl<T: FileSystem> std::fmt::Debug for Network<T> {
@@ -48,6 +206,7 @@ impl<T: FileSystem> std::fmt::Debug for Network<T> {
             .field("initial_spec", &self.initial_spec)
             .field("parachains", &self.parachains)
             .field("nodes_by_name", &self.nodes_by_name)
+            .field("spawn_timings", &self.spawn_timings)
             .finish()
     }
 }
@@ -75,6 +234,7 @@ impl<T: FileSystem> Network<T> {
             initial_spec,
             parachains: Default::default(),
             nodes_by_name: Default::default(),
+            spawn_timings: None,
         }
     }
 
@@ -91,11 +251,68 @@ impl<T: FileSystem> Network<T> {
         &self.relay
     }
 
+    /// Whether the provider allows adding nodes to the running network, a pre-flight check
+    /// for [`Network::add_node`], [`Network::add_collator`] and [`Network::add_parachain`].
+    pub fn supports_runtime_node_addition(&self) -> bool {
+        self.ns.capabilities().supports_runtime_node_addition
+    }
+
+    fn ensure_runtime_node_addition(&self) -> Result<(), OrchestratorError> {
+        if self.supports_runtime_node_addition() {
+            Ok(())
+        } else {
+            Err(OrchestratorError::UnsupportedByProvider(
+                "adding nodes to a running network".to_string(),
+            ))
+        }
+    }
+
     // Teardown the network
     pub async fn destroy(self) -> Result<(), ProviderError> {
         self.ns.destroy().await
     }
 
+    /// Teardown the network in the reverse order of the dependencies: the collators are
+    /// stopped first, then the relaychain validators and finally the bootnodes, before
+    /// destroying the namespace.
+    ///
+    /// This is best-effort, a failure to destroy a node doesn't stop the teardown and all
+    /// the failures are reported at the end.
+    pub async fn shutdown_ordered(self) -> Result<(), OrchestratorError> {
+        let mut failures = vec![];
+        for node in self.shutdown_order() {
+            trace!("🧹 destroying node {}", node.name);
+            if let Err(err) = node.inner.destroy().await {
+                failures.push(format!("{}: {err}", node.name));
+            }
+        }
+
+        if let Err(err) = self.ns.destroy().await {
+            failures.push(format!("namespace {}: {err}", self.ns.name()));
+        }
+
+        if failures.is_empty() {
+            Ok(())
+        } else {
+            Err(OrchestratorError::TeardownFailed(failures.join(", ")))
+        }
+    }
+
+    // nodes in the order used by `shutdown_ordered`
+    fn shutdown_order(&self) -> Vec<&NetworkNode> {
+        let collators = self
+            .parachains
+            .values()
+            .flat_map(|para| para.collators.iter());
+        let (bootnodes, validators): (Vec<&NetworkNode>, Vec<&NetworkNode>) = self
+            .relay
+            .nodes
+            .iter()
+            .partition(|node| node.spec.is_bootnode);
+
+        collators.chain(validators).chain(bootnodes).collect()
+    }
+
     /// Add a node to the relaychain
     ///
     /// NOTE: name must be unique in the whole network. The new node is added to the
@@ -129,10 +346,11 @@ impl<T: FileSystem> Network<T> {
         name: impl Into<String>,
         options: AddNodeOptions,
     ) -> Result<(), anyhow::Error> {
+        self.ensure_runtime_node_addition()?;
         let name = name.into();
         let relaychain = self.relaychain();
 
-        if self.nodes_iter().any(|n| n.name == name) {
+        if self.nodes_by_name.contains_key(&name) {
             return Err(anyhow::anyhow!("Name: {} is already used.", name));
         }
 
@@ -152,16 +370,23 @@ impl<T: FileSystem> Network<T> {
             default_resources: self.initial_spec.relaychain.default_resources.as_ref(),
             default_db_snapshot: self.initial_spec.relaychain.default_db_snapshot.as_ref(),
             default_args: self.initial_spec.relaychain.default_args.iter().collect(),
+            default_log_targets: self
+                .initial_spec
+                .relaychain
+                .default_log_targets
+                .iter()
+                .collect(),
         };
 
         let mut node_spec =
             network_spec::node::NodeSpec::from_ad_hoc(&name, options.into(), &chain_context)?;
 
-        node_spec.available_args_output = Some(
-            self.initial_spec
-                .node_available_args_output(&node_spec, self.ns.clone())
-                .await?,
-        );
+        let (available_args_output, version) = self
+            .initial_spec
+            .node_available_args_and_version(&node_spec, self.ns.clone())
+            .await?;
+        node_spec.available_args_output = Some(available_args_output);
+        node_spec.version = Some(version);
 
         let base_dir = self.ns.base_dir().to_string_lossy();
         let scoped_fs = ScopedFilesystem::new(&self.filesystem, &base_dir);
@@ -176,6 +401,8 @@ impl<T: FileSystem> Network<T> {
             parachain: None,
             bootnodes_addr: &vec![],
             wait_ready: true,
+            global_settings: &self.initial_spec.global_settings,
+            relay_rpc_url: None,
         };
 
         let global_files_to_inject = vec![TransferedFile::new(
@@ -232,6 +459,7 @@ impl<T: FileSystem> Network<T> {
         options: AddCollatorOptions,
         para_id: u32,
     ) -> Result<(), anyhow::Error> {
+        self.ensure_runtime_node_addition()?;
         let spec = self
             .initial_spec
             .parachains
@@ -249,6 +477,7 @@ impl<T: FileSystem> Network<T> {
             default_resources: spec.default_resources.as_ref(),
             default_db_snapshot: spec.default_db_snapshot.as_ref(),
             default_args: spec.default_args.iter().collect(),
+            default_log_targets: spec.default_log_targets.iter().collect(),
         };
         let parachain = self
             .parachains
@@ -258,7 +487,8 @@ impl<T: FileSystem> Network<T> {
         let base_dir = self.ns.base_dir().to_string_lossy();
         let scoped_fs = ScopedFilesystem::new(&self.filesystem, &base_dir);
 
-        // TODO: we want to still supporting spawn a dedicated bootnode??
+        let relay_rpc_url = self.relay_rpc_url(spec).await?;
+
         let ctx = SpawnNodeCtx {
             chain_id: &self.relay.chain_id,
             parachain_id: parachain.chain_id.as_deref(),
@@ -269,6 +499,8 @@ impl<T: FileSystem> Network<T> {
             parachain: Some(spec),
             bootnodes_addr: &vec![],
             wait_ready: true,
+            global_settings: &self.initial_spec.global_settings,
+            relay_rpc_url: relay_rpc_url.as_deref(),
         };
 
         let relaychain_spec_path = if let Some(chain_spec_custom_path) = &options.chain_spec_relay {
@@ -308,16 +540,15 @@ impl<T: FileSystem> Network<T> {
         let mut node_spec =
             network_spec::node::NodeSpec::from_ad_hoc(name.into(), options.into(), &chain_context)?;
 
-        node_spec.available_args_output = Some(
-            self.initial_spec
-                .node_available_args_output(&node_spec, self.ns.clone())
-                .await?,
-        );
+        let (available_args_output, version) = self
+            .initial_spec
+            .node_available_args_and_version(&node_spec, self.ns.clone())
+            .await?;
+        node_spec.available_args_output = Some(available_args_output);
+        node_spec.version = Some(version);
 
         let node = spawner::spawn_node(&node_spec, global_files_to_inject, &ctx).await?;
-        let para = self.parachains.get_mut(&para_id).unwrap();
-        para.collators.push(node.clone());
-        self.add_running_node(node, None);
+        self.add_running_node(node, Some(para_id));
 
         Ok(())
     }
@@ -370,8 +601,12 @@ impl<T: FileSystem> Network<T> {
         custom_relaychain_spec: Option<PathBuf>,
         custom_parchain_fs_prefix: Option<String>,
     ) -> Result<(), anyhow::Error> {
+        self.ensure_runtime_node_addition()?;
         // build
         let mut para_spec = network_spec::parachain::ParachainSpec::from_config(para_config)?;
+        if let Some(chain_spec) = para_spec.chain_spec.as_mut() {
+            chain_spec.apply_spec_builder_overrides(&self.initial_spec.global_settings);
+        }
         let base_dir = self.ns.base_dir().to_string_lossy().to_string();
         let scoped_fs = ScopedFilesystem::new(&self.filesystem, &base_dir);
 
@@ -410,31 +645,34 @@ impl<T: FileSystem> Network<T> {
         };
 
         scoped_fs.create_dir(&para_path_prefix).await?;
-        // create wasm/state
-        para_spec
-            .genesis_state
-            .build(
-                chain_spec_raw_path.as_ref(),
-                format!("{}/genesis-state", &para_path_prefix),
-                &self.ns,
-                &scoped_fs,
-            )
-            .await?;
-        para_spec
-            .genesis_wasm
-            .build(
-                chain_spec_raw_path.as_ref(),
-                format!("{}/para_spec-wasm", &para_path_prefix),
-                &self.ns,
-                &scoped_fs,
-            )
-            .await?;
+        // create wasm/state (not needed if the para will never be registered)
+        if para_spec.registration_strategy != RegistrationStrategy::None {
+            para_spec
+                .genesis_state
+                .build(
+                    chain_spec_raw_path.as_ref(),
+                    format!("{}/genesis-state", &para_path_prefix),
+                    &self.ns,
+                    &scoped_fs,
+                )
+                .await?;
+            para_spec
+                .genesis_wasm
+                .build(
+                    chain_spec_raw_path.as_ref(),
+                    format!("{}/para_spec-wasm", &para_path_prefix),
+                    &self.ns,
+                    &scoped_fs,
+                )
+                .await?;
+        }
 
         let parachain =
             Parachain::from_spec(&para_spec, &global_files_to_inject, &scoped_fs).await?;
         let parachain_id = parachain.chain_id.clone();
 
         // Create `ctx` for spawn the nodes
+        let relay_rpc_url = self.relay_rpc_url(&para_spec).await?;
         let ctx_para = SpawnNodeCtx {
             parachain: Some(&para_spec),
             parachain_id: parachain_id.as_deref(),
@@ -449,6 +687,8 @@ impl<T: FileSystem> Network<T> {
             ns: &self.ns,
             scoped_fs: &scoped_fs,
             wait_ready: false,
+            global_settings: &self.initial_spec.global_settings,
+            relay_rpc_url: relay_rpc_url.as_deref(),
         };
 
         // Register the parachain to the running network
@@ -481,8 +721,9 @@ impl<T: FileSystem> Network<T> {
                     .to_path_buf(),
                 node_ws_url: first_node_url.to_string(),
                 onboard_as_para: para_spec.onboard_as_parachain,
-                seed: None, // TODO: Seed is passed by?
-                finalization: false,
+                seed: para_spec.registration_seed,
+                finalization: para_spec.wait_for_finalization,
+                method: Default::default(),
             };
 
             Parachain::register(register_para_options, &scoped_fs).await?;
@@ -507,9 +748,13 @@ impl<T: FileSystem> Network<T> {
     // deregister and stop the collator?
     // remove_parachain()
 
+    /// Get the node (relaychain node or collator) called `name`.
+    ///
+    /// The returned reference borrows the network, so it can't be held across calls taking
+    /// `&mut self` (e.g. [`Network::add_node`]), clone the node if you need to keep it around.
     pub fn get_node(&self, name: impl Into<String>) -> Result<&NetworkNode, anyhow::Error> {
         let name = name.into();
-        if let Some(node) = self.nodes_iter().find(|&n| n.name == name) {
+        if let Some(node) = self.node_by_location(&name) {
             return Ok(node);
         }
 
@@ -530,50 +775,722 @@ impl<T: FileSystem> Network<T> {
         name: impl Into<String>,
     ) -> Result<&mut NetworkNode, anyhow::Error> {
         let name = name.into();
-        self.nodes_iter_mut()
-            .find(|n| n.name == name)
-            .ok_or(anyhow::anyhow!("can't find node with name: {name:?}"))
+        let location = self.nodes_by_name.get(&name).copied();
+        match location {
+            Some(NodeLocation::Relaychain(index)) => self.relay.nodes.get_mut(index),
+            Some(NodeLocation::Parachain(para_id, index)) => self
+                .parachains
+                .get_mut(&para_id)
+                .and_then(|para| para.collators.get_mut(index)),
+            None => None,
+        }
+        .ok_or(anyhow::anyhow!("can't find node with name: {name:?}"))
+    }
+
+    /// Get the (cached) subxt [`OnlineClient`] connected to the node `node_name`.
+    ///
+    /// The client is created on the first call and reused in the next ones. An error is
+    /// returned if the node doesn't exist or the ws endpoint is not reachable yet, so
+    /// callers should wait until the node is up first (e.g. with [`NetworkNode::wait_client`]
+    /// or [`Network::wait_until_metric`]).
+    #[cfg(feature = "client")]
+    pub async fn get_client(
+        &self,
+        node_name: &str,
+    ) -> Result<OnlineClient<PolkadotConfig>, anyhow::Error> {
+        self.get_node(node_name)?.cached_client().await
     }
 
+    /// Query the peers of every node in the network, flagging the nodes without peers
+    /// (isolated) and the ones that couldn't be queried (unreachable).
+    pub async fn connectivity_report(&self) -> ConnectivityReport {
+        let nodes = self.nodes();
+        let results = futures::future::join_all(nodes.iter().map(|node| node.peers())).await;
+
+        let mut report = ConnectivityReport::default();
+        for (node, result) in nodes.into_iter().zip(results) {
+            match result {
+                Ok(info) => {
+                    if info.peers_count == 0 {
+                        report.isolated.push(node.name().to_string());
+                    }
+                    report.peers.insert(node.name().to_string(), info);
+                },
+                Err(err) => report
+                    .unreachable
+                    .push((node.name().to_string(), err.to_string())),
+            }
+        }
+
+        report.isolated.sort();
+        report.unreachable.sort();
+        report
+    }
+
+    /// Wait until every parachain advanced at least `min_blocks` (best block height reported
+    /// by its first collator), with a timeout (secs). Useful as a "network is healthy" gate.
+    ///
+    /// Returns the per-parachain progress, on timeout the error includes the progress of each
+    /// parachain to identify the stalled ones. Fails if a parachain has no collators, since
+    /// its progress can't be read.
+    pub async fn wait_until_parachains_progress(
+        &self,
+        min_blocks: u32,
+        timeout_secs: impl Into<u64>,
+    ) -> Result<Vec<ParachainProgress>, anyhow::Error> {
+        let mut without_collators: Vec<u32> = self
+            .parachains
+            .values()
+            .filter(|para| para.collators.is_empty())
+            .map(|para| para.para_id)
+            .collect();
+        if !without_collators.is_empty() {
+            without_collators.sort();
+            return Err(anyhow::anyhow!(
+                "Can't read the progress of the parachains without collators: {without_collators:?}"
+            ));
+        }
+
+        let deadline = tokio::time::Instant::now() + Duration::from_secs(timeout_secs.into());
+        let mut paras: Vec<(&NetworkNode, ParachainProgress)> = self
+            .parachains
+            .values()
+            .filter_map(|para| {
+                para.collators.first().map(|collator| {
+                    let progress = ParachainProgress {
+                        para_id: para.para_id,
+                        collator: collator.name().to_string(),
+                        initial: None,
+                        current: None,
+                    };
+                    (collator, progress)
+                })
+            })
+            .collect();
+        paras.sort_by_key(|(_, progress)| progress.para_id);
+
+        loop {
+            for (collator, progress) in paras.iter_mut() {
+                // not reachable collators are retried in the next round
+                let height = collator.reports(BEST_BLOCK_METRIC).await.ok();
+                if let Some(height) = height.map(|h| h as u64) {
+                    progress.initial.get_or_insert(height);
+                    progress.current = Some(height);
+                }
+            }
+
+            if paras
+                .iter()
+                .all(|(_, progress)| progress.produced() >= min_blocks as u64)
+            {
+                return Ok(paras.into_iter().map(|(_, progress)| progress).collect());
+            }
+
+            if tokio::time::Instant::now() >= deadline {
+                let report = paras
+                    .iter()
+                    .map(|(_, progress)| progress.to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                return Err(anyhow::anyhow!(
+                    "Timeout waiting for the parachains to produce {min_blocks} blocks: {report}"
+                ));
+            }
+
+            // space out the reads
+            tokio::time::sleep(Duration::from_secs(2)).await;
+        }
+    }
+
+    /// Wait until the parachain `para_id` is onboarded (its lifecycle in the relaychain
+    /// `Paras.ParaLifecycles` storage is `Parachain`), with a timeout (secs). Useful after
+    /// registering a parachain at runtime, since the onboarding takes a couple of sessions.
+    ///
+    /// Returns the number of the relaychain block where the parachain was seen onboarded,
+    /// on timeout the error includes the current lifecycle of the parachain (or the last error
+    /// reading it, if it was never read).
+    ///
+    /// # Example:
+    /// ```rust
+    /// # use provider::NativeProvider;
+    /// # use support::{fs::local::LocalFileSystem};
+    /// # use zombienet_orchestrator::{errors, Orchestrator};
+    /// # use configuration::NetworkConfig;
+    /// # async fn example() -> Result<(), anyhow::Error> {
+    /// #   let provider = NativeProvider::new(LocalFileSystem {});
+    /// #   let orchestrator = Orchestrator::new(LocalFileSystem {}, provider);
+    /// #   let config = NetworkConfig::load_from_toml("config.toml")?;
+    /// let network = orchestrator.spawn(config).await?;
+    ///
+    /// let block_number = network.wait_until_para_onboarded(2000, 300_u64).await?;
+    /// #   Ok(())
+    /// # }
+    /// ```
+    pub async fn wait_until_para_onboarded(
+        &self,
+        para_id: u32,
+        timeout_secs: impl Into<u64>,
+    ) -> Result<u32, anyhow::Error> {
+        let timeout_secs = timeout_secs.into();
+        let deadline = tokio::time::Instant::now() + Duration::from_secs(timeout_secs);
+        let node = self
+            .relay
+            .nodes
+            .first()
+            .ok_or(anyhow::anyhow!("Relaychain doesn't have any node!"))?;
+
+        let client: OnlineClient<PolkadotConfig> =
+            tokio::time::timeout(Duration::from_secs(timeout_secs), node.wait_client())
+                .await
+                .map_err(|_| {
+                    anyhow::anyhow!(
+                        "Timeout ({timeout_secs}s), waiting for the client of node {}",
+                        node.name()
+                    )
+                })??;
+
+        if client.metadata().pallet_by_name("Paras").is_none() {
+            return Err(anyhow::anyhow!(
+                "Relaychain runtime doesn't include the 'Paras' pallet, can't check the onboarding of parachain {para_id}"
+            ));
+        }
+
+        // `None` until the lifecycle is read once
+        let mut lifecycle: Option<Option<String>> = None;
+        let mut last_error = None;
+        loop {
+            match para_lifecycle(&client, para_id).await {
+                Ok((number, Some(state))) if state == "Parachain" => return Ok(number),
+                Ok((_, state)) => lifecycle = Some(state),
+                // transient rpc errors are retried in the next round
+                Err(e) => {
+                    trace!("Error reading the lifecycle of parachain {para_id}: {e}");
+                    last_error = Some(e);
+                },
+            }
+
+            if tokio::time::Instant::now() >= deadline {
+                return Err(onboarding_timeout_error(
+                    para_id,
+                    timeout_secs,
+                    lifecycle,
+                    last_error,
+                ));
+            }
+
+            // space out the reads
+            tokio::time::sleep(Duration::from_secs(2)).await;
+        }
+    }
+
+    /// All the nodes of the network in a deterministic order: the relaychain nodes first and
+    /// then the collators by para id (validators first, then by name within each chain).
     pub fn nodes(&self) -> Vec<&NetworkNode> {
-        self.nodes_by_name.values().collect::<Vec<&NetworkNode>>()
+        let mut nodes = self.relay_nodes();
+        for para in self.parachains() {
+            nodes.extend(sorted_collators(para));
+        }
+        nodes
+    }
+
+    /// The relaychain nodes, validators first and then by name.
+    pub fn relay_nodes(&self) -> Vec<&NetworkNode> {
+        let mut relay_nodes: Vec<&NetworkNode> = self.relay.nodes.iter().collect();
+        sort_nodes(&mut relay_nodes);
+        relay_nodes
+    }
+
+    /// The collators of the parachain `para_id` (validators first and then by name),
+    /// `None` if the parachain doesn't exist.
+    pub fn collators(&self, para_id: u32) -> Option<Vec<&NetworkNode>> {
+        self.parachains.get(&para_id).map(sorted_collators)
+    }
+
+    /// Wait until the metric reported by the node `node_name` pass the `predicate`
+    /// with a timeout (secs). Useful as a readiness gate (e.g. the chain is producing blocks).
+    ///
+    /// # Example:
+    /// ```rust
+    /// # use provider::NativeProvider;
+    /// # use support::{fs::local::LocalFileSystem};
+    /// # use zombienet_orchestrator::{errors, Orchestrator};
+    /// # use configuration::NetworkConfig;
+    /// # async fn example() -> Result<(), anyhow::Error> {
+    /// #   let provider = NativeProvider::new(LocalFileSystem {});
+    /// #   let orchestrator = Orchestrator::new(LocalFileSystem {}, provider);
+    /// #   let config = NetworkConfig::load_from_toml("config.toml")?;
+    /// let network = orchestrator.spawn(config).await?;
+    ///
+    /// network
+    ///     .wait_until_metric(
+    ///         "alice",
+    ///         "block_height{status=\"best\"}",
+    ///         |v| v >= 1_f64,
+    ///         60_u64,
+    ///     )
+    ///     .await?;
+    /// #   Ok(())
+    /// # }
+    /// ```
+    pub async fn wait_until_metric(
+        &self,
+        node_name: impl Into<String>,
+        metric_name: impl Into<String>,
+        predicate: impl Fn(f64) -> bool,
+        timeout_secs: impl Into<u64>,
+    ) -> Result<(), anyhow::Error> {
+        let node = self.get_node(node_name)?;
+        node.wait_metric_with_timeout(metric_name, predicate, timeout_secs)
+            .await
+    }
+
+    /// Same as [`Network::wait_until_metric`] but the wait can be aborted from a
+    /// concurrent task by cancelling the `token` (e.g. on a graceful shutdown).
+    pub async fn wait_until_metric_with_cancel(
+        &self,
+        node_name: impl Into<String>,
+        metric_name: impl Into<String>,
+        predicate: impl Fn(f64) -> bool,
+        timeout_secs: impl Into<u64>,
+        token: CancellationToken,
+    ) -> Result<(), anyhow::Error> {
+        let node = self.get_node(node_name)?;
+        node.wait_metric_with_cancel(metric_name, predicate, timeout_secs, token)
+            .await
+    }
+
+    /// Wait until a line matching the `pattern` (regex) appears in the logs of the node `node_name`,
+    /// returning the matching line or an error if the timeout (secs) is reached.
+    ///
+    /// # Example:
+    /// ```rust
+    /// # use provider::NativeProvider;
+    /// # use support::{fs::local::LocalFileSystem};
+    /// # use zombienet_orchestrator::{errors, Orchestrator};
+    /// # use configuration::NetworkConfig;
+    /// # async fn example() -> Result<(), anyhow::Error> {
+    /// #   let provider = NativeProvider::new(LocalFileSystem {});
+    /// #   let orchestrator = Orchestrator::new(LocalFileSystem {}, provider);
+    /// #   let config = NetworkConfig::load_from_toml("config.toml")?;
+    /// let network = orchestrator.spawn(config).await?;
+    ///
+    /// let line = network
+    ///     .wait_for_log("alice", r"Imported #\d+", 60_u64)
+    ///     .await?;
+    /// #   Ok(())
+    /// # }
+    /// ```
+    pub async fn wait_for_log(
+        &self,
+        node_name: impl Into<String>,
+        pattern: &str,
+        timeout_secs: impl Into<u64>,
+    ) -> Result<String, anyhow::Error> {
+        let node = self.get_node(node_name)?;
+        let re = Regex::new(pattern)?;
+        let secs = timeout_secs.into();
+
+        let wait_for_line = async {
+            loop {
+                let mut log_stream = node.log_stream();
+                while let Some(line) = log_stream.next().await {
+                    let line = line?;
+                    if re.is_match(&line) {
+                        return Ok::<String, anyhow::Error>(line);
+                    }
+                }
+
+                // the stream ends if the node is stopped (e.g. restarted), start again
+                tokio::time::sleep(Duration::from_secs(1)).await;
+            }
+        };
+
+        tokio::time::timeout(Duration::from_secs(secs), wait_for_line)
+            .await
+            .map_err(|_| {
+                anyhow::anyhow!(
+                    "Timeout ({secs}), waiting for pattern '{pattern}' in {} logs",
+                    node.name()
+                )
+            })?
+    }
+
+    /// Perform a runtime upgrade and wait until the new runtime is enacted, returning the new
+    /// `spec_version`. The target chain is the one of `options.node_name` (relaychain if not set).
+    ///
+    /// The `spec_version` is polled (`state_getRuntimeVersion`) until it increases or the
+    /// timeout (secs) is reached. For parachains the new version is only reported a few
+    /// blocks after the upgrade is enacted by the relaychain, so use a generous timeout.
+    ///
+    /// # Example:
+    /// ```rust
+    /// # use provider::NativeProvider;
+    /// # use support::{fs::local::LocalFileSystem};
+    /// # use zombienet_orchestrator::{errors, Orchestrator, shared::types::RuntimeUpgradeOptions};
+    /// # use configuration::{NetworkConfig, types::AssetLocation};
+    /// # async fn example() -> Result<(), anyhow::Error> {
+    /// #   let provider = NativeProvider::new(LocalFileSystem {});
+    /// #   let orchestrator = Orchestrator::new(LocalFileSystem {}, provider);
+    /// #   let config = NetworkConfig::load_from_toml("config.toml")?;
+    /// let network = orchestrator.spawn(config).await?;
+    ///
+    /// let wasm = AssetLocation::from("/tmp/runtime.compact.compressed.wasm");
+    /// let new_version = network
+    ///     .runtime_upgrade_and_wait(RuntimeUpgradeOptions::new(wasm), 300_u64)
+    ///     .await?;
+    /// #   Ok(())
+    /// # }
+    /// ```
+    pub async fn runtime_upgrade_and_wait(
+        &self,
+        options: RuntimeUpgradeOptions,
+        timeout_secs: impl Into<u64>,
+    ) -> Result<u32, anyhow::Error> {
+        let node = if let Some(node_name) = options.node_name.as_ref() {
+            self.get_node(node_name)?
+        } else {
+            self.relay
+                .nodes
+                .first()
+                .ok_or(anyhow::anyhow!("Relaychain doesn't have any node!"))?
+        };
+
+        let current_version = node.spec_version().await?;
+
+        if let Some(para) = self
+            .parachains
+            .values()
+            .find(|para| para.collators.iter().any(|c| c.name == node.name))
+        {
+            para.runtime_upgrade(options).await?;
+        } else {
+            self.relay.runtime_upgrade(options).await?;
+        }
+
+        let secs = timeout_secs.into();
+        let wait_for_version = async {
+            loop {
+                // the node could be restarting/lagging, keep trying until the timeout
+                match node.spec_version().await {
+                    Ok(version) if version > current_version => return version,
+                    Ok(_) => {},
+                    Err(e) => trace!("error getting spec_version from {}: {e}", node.name),
+                }
+
+                tokio::time::sleep(Duration::from_secs(2)).await;
+            }
+        };
+
+        tokio::time::timeout(Duration::from_secs(secs), wait_for_version)
+            .await
+            .map_err(|_| {
+                anyhow::anyhow!(
+                    "Timeout ({secs}), waiting for spec_version > {current_version} in {}",
+                    node.name()
+                )
+            })
+    }
+
+    /// Open an HRMP channel from `sender` to `recipient` on the running network,
+    /// submitting `Hrmp.force_open_hrmp_channel` (with sudo, as `//Alice`) through the first
+    /// relaychain node. Returns the hash of the block where the extrinsic was included.
+    ///
+    /// # Example:
+    /// ```rust
+    /// # use provider::NativeProvider;
+    /// # use support::{fs::local::LocalFileSystem};
+    /// # use zombienet_orchestrator::{errors, Orchestrator};
+    /// # use configuration::NetworkConfig;
+    /// # async fn example() -> Result<(), anyhow::Error> {
+    /// #   let provider = NativeProvider::new(LocalFileSystem {});
+    /// #   let orchestrator = Orchestrator::new(LocalFileSystem {}, provider);
+    /// #   let config = NetworkConfig::load_from_toml("config.toml")?;
+    /// let network = orchestrator.spawn(config).await?;
+    ///
+    /// let block_hash = network.open_hrmp_channel(2000, 2001, 8, 512).await?;
+    /// #   Ok(())
+    /// # }
+    /// ```
+    pub async fn open_hrmp_channel(
+        &self,
+        sender: u32,
+        recipient: u32,
+        max_capacity: u32,
+        max_message_size: u32,
+    ) -> Result<H256, anyhow::Error> {
+        let node = self
+            .relay
+            .nodes
+            .first()
+            .ok_or(anyhow::anyhow!("Relaychain doesn't have any node!"))?;
+
+        let uri = SecretUri::from_str("//Alice")?;
+        let sudo = Keypair::from_uri(&uri)?;
+
+        tx_helper::hrmp::open_channel(
+            node,
+            sender,
+            recipient,
+            max_capacity,
+            max_message_size,
+            &sudo,
+        )
+        .await
     }
 
-    pub async fn detach(&self) {
-        self.ns.detach().await
+    /// Restart the node `name` appending `extra_args` to the current args (e.g. to
+    /// increase the log level). The changed args are persisted to `zombie.json`.
+    ///
+    /// NOTE: only supported by the native provider.
+    ///
+    /// # Example:
+    /// ```rust
+    /// # use provider::NativeProvider;
+    /// # use support::{fs::local::LocalFileSystem};
+    /// # use zombienet_orchestrator::{errors, Orchestrator};
+    /// # use configuration::NetworkConfig;
+    /// # async fn example() -> Result<(), anyhow::Error> {
+    /// #   let provider = NativeProvider::new(LocalFileSystem {});
+    /// #   let orchestrator = Orchestrator::new(LocalFileSystem {}, provider);
+    /// #   let config = NetworkConfig::load_from_toml("config.toml")?;
+    /// let mut network = orchestrator.spawn(config).await?;
+    ///
+    /// network
+    ///     .restart_node_with_args("alice", vec!["-lparachain=trace"])
+    ///     .await?;
+    /// #   Ok(())
+    /// # }
+    /// ```
+    pub async fn restart_node_with_args(
+        &mut self,
+        name: impl Into<String>,
+        extra_args: Vec<impl Into<String>>,
+    ) -> Result<(), anyhow::Error> {
+        let name = name.into();
+        let extra_args: Vec<String> = extra_args.into_iter().map(|arg| arg.into()).collect();
+
+        let node = self.get_node_mut(&name)?;
+        let mut args: Vec<String> = node.args().into_iter().map(String::from).collect();
+        args.extend(extra_args.clone());
+        node.inner.restart_with_args(args, None).await?;
+        node.reset_client_cache().await;
+
+        node.extra_args.extend(extra_args.clone());
+        node.spec
+            .args
+            .extend(extra_args.iter().map(|arg| Arg::Flag(arg.clone())));
+
+        self.write_zombie_json().await?;
+        Ok(())
+    }
+
+    /// Detach the network, consuming it and leaving the nodes running.
+    ///
+    /// The latest state is written to `zombie.json` (in the returned base dir) so the network
+    /// can be re-attached later. The detached namespace is also returned, dropping it (or
+    /// stopping the process with ctrl-c) doesn't destroy the nodes.
+    ///
+    /// NOTE: this takes the network by value (instead of `&self`), so it can't be used (e.g.
+    /// destroyed) once detached.
+    ///
+    /// NOTE: the session of a running process can't be changed, so the native nodes are
+    /// restarted (with the same args and data dir) in their own session to outlive the
+    /// process. This waits until the restarted nodes are up again (bounded by the network
+    /// spawn timeout), the connections to them (e.g. clients) are lost. Their process ids are
+    /// recorded in `zombie.json` (`pids`), to stop them later with [`destroy_detached`].
+    pub async fn detach(self) -> Result<(PathBuf, DynNamespace), OrchestratorError> {
+        let mut pids = HashMap::new();
+        for node in self.nodes() {
+            pids.insert(node.name.clone(), node.inner.pid().await);
+        }
+
+        // detaching flags the namespace to skip the cleanup on drop/ctrl-c
+        self.ns.detach().await;
+
+        let mut restarted = vec![];
+        for node in self.nodes() {
+            if node.inner.pid().await != pids[&node.name] {
+                node.reset_client_cache().await;
+                restarted.push(node);
+            }
+        }
+
+        let timeout = self.initial_spec.global_settings.network_spawn_timeout();
+        tokio::time::timeout(
+            Duration::from_secs(timeout as u64),
+            futures::future::try_join_all(restarted.iter().map(|node| async move {
+                wait_ws_ready(node.ws_uri()).await.map_err(|err| {
+                    anyhow::anyhow!("Error waiting for node {} to be up: {err}", node.name())
+                })
+            })),
+        )
+        .await
+        .map_err(|_| OrchestratorError::GlobalTimeOut(timeout))??;
+
+        self.write_zombie_json().await?;
+
+        Ok((self.ns.base_dir().to_path_buf(), self.ns.clone()))
     }
 
     // Internal API
+    // Write the network state file (`zombie.json`) in the base dir
+    pub(crate) async fn write_zombie_json(&self) -> Result<(), OrchestratorError> {
+        let base_dir = self.ns.base_dir().to_string_lossy();
+        let scoped_fs = ScopedFilesystem::new(&self.filesystem, &base_dir);
+
+        let mut zombie_json = serde_json::to_value(self)?;
+        zombie_json["local_base_dir"] = serde_json::value::Value::String(base_dir.to_string());
+
+        // Record the process ids (native provider), to allow stopping detached nodes later
+        let mut pids = serde_json::Map::new();
+        for node in self.nodes() {
+            if let Some(pid) = node.inner.pid().await {
+                pids.insert(node.name.clone(), pid.into());
+            }
+        }
+        if !pids.is_empty() {
+            zombie_json["pids"] = serde_json::Value::Object(pids);
+        }
+
+        // Link the config recovered from the spec, to allow re-spawning the same network later
+        // (see `NetworkConfig::load_from_zombie_json`)
+        match self
+            .initial_spec
+            .to_config()
+            .map_err(|err| err.to_string())
+            .and_then(|config| config.dump_to_toml().map_err(|err| err.to_string()))
+        {
+            Ok(config_toml) => {
+                scoped_fs.write("config.toml", config_toml).await?;
+                zombie_json["config"] = serde_json::Value::String("config.toml".to_string());
+            },
+            Err(err) => warn!("⚠️ can't recover the network config from the spec: {err}"),
+        }
+
+        scoped_fs
+            .write("zombie.json", serde_json::to_string_pretty(&zombie_json)?)
+            .await?;
+        Ok(())
+    }
+
     pub(crate) fn add_running_node(&mut self, node: NetworkNode, para_id: Option<u32>) {
-        if let Some(para_id) = para_id {
+        let node_name = node.name.clone();
+        let location = if let Some(para_id) = para_id {
             if let Some(para) = self.parachains.get_mut(&para_id) {
-                para.collators.push(node.clone());
+                para.collators.push(node);
+                NodeLocation::Parachain(para_id, para.collators.len() - 1)
             } else {
                 // is the first node of the para, let create the entry
                 unreachable!()
             }
         } else {
-            self.relay.nodes.push(node.clone());
-        }
-        // TODO: we should hold a ref to the node in the vec in the future.
-        let node_name = node.name.clone();
-        self.nodes_by_name.insert(node_name, node);
+            self.relay.nodes.push(node);
+            NodeLocation::Relaychain(self.relay.nodes.len() - 1)
+        };
+        self.nodes_by_name.insert(node_name, location);
     }
 
     pub(crate) fn add_para(&mut self, para: Parachain) {
-        self.parachains.insert(para.para_id, para);
+        let para_id = para.para_id;
+        // drop the collators of a replaced parachain from the lookup
+        self.nodes_by_name.retain(
+            |_, location| !matches!(location, NodeLocation::Parachain(id, _) if *id == para_id),
+        );
+        for (index, collator) in para.collators.iter().enumerate() {
+            self.nodes_by_name.insert(
+                collator.name.clone(),
+                NodeLocation::Parachain(para_id, index),
+            );
+        }
+        self.parachains.insert(para_id, para);
+    }
+
+    fn node_by_location(&self, name: &str) -> Option<&NetworkNode> {
+        match self.nodes_by_name.get(name)? {
+            NodeLocation::Relaychain(index) => self.relay.nodes.get(*index),
+            NodeLocation::Parachain(para_id, index) => self
+                .parachains
+                .get(para_id)
+                .and_then(|para| para.collators.get(*index)),
+        }
+    }
+
+    pub(crate) fn set_spawn_timings(&mut self, timings: SpawnTimings) {
+        self.spawn_timings = Some(timings);
+    }
+
+    /// Resolve the relaychain rpc endpoint the collators of `para` should connect to. An explicit
+    /// url is used as-is, a referenced relaychain node (which should be already running) is
+    /// resolved to the address reachable from inside the namespace.
+    pub(crate) async fn relay_rpc_url(
+        &self,
+        para: &network_spec::parachain::ParachainSpec,
+    ) -> Result<Option<String>, anyhow::Error> {
+        if let Some(url) = &para.relay_rpc_url {
+            return Ok(Some(url.clone()));
+        }
+
+        let Some(node_name) = &para.relay_rpc_node else {
+            return Ok(None);
+        };
+
+        let node = self.get_node(node_name.as_str())?;
+        let ip = node.inner.ip().await?;
+        let port = if self.ns.capabilities().use_default_ports_in_cmd {
+            RPC_PORT
+        } else {
+            node.spec.rpc_port.0
+        };
+
+        // the tls termination (`rpc_scheme`) only exists at the external endpoint, the node
+        // itself only serves plain ws
+        Ok(Some(format!("ws://{}", SocketAddr::new(ip, port))))
     }
 
     pub fn name(&self) -> &str {
         self.ns.name()
     }
 
+    /// Get the parachain `para_id`, the returned reference borrows the network
+    /// (see [`Network::get_node`]).
     pub fn parachain(&self, para_id: u32) -> Option<&Parachain> {
         self.parachains.get(&para_id)
     }
 
+    /// All the parachains of the network, sorted by para id.
     pub fn parachains(&self) -> Vec<&Parachain> {
-        self.parachains.values().collect()
+        let mut parachains: Vec<&Parachain> = self.parachains.values().collect();
+        parachains.sort_by_key(|para| para.para_id);
+        parachains
+    }
+
+    /// Duration of each phase of the spawn, to find the bottlenecks (e.g. the raw
+    /// chain-spec generation). Set once the network is spawned.
+    pub fn spawn_timings(&self) -> Option<&SpawnTimings> {
+        self.spawn_timings.as_ref()
+    }
+
+    /// Paths of the artifacts generated for the network (chain-specs, genesis state/wasm of
+    /// the parachains and the `zombie.json` state file).
+    pub fn artifacts(&self) -> NetworkArtifacts {
+        let base_dir = self.ns.base_dir().to_path_buf();
+        let parachains = self
+            .parachains()
+            .into_iter()
+            .map(|para| ParaArtifacts {
+                id: para.para_id,
+                chain_spec: para.chain_spec_path.as_ref().map(|p| base_dir.join(p)),
+                genesis_state: para.genesis_state_path.as_ref().map(|p| base_dir.join(p)),
+                genesis_wasm: para.genesis_wasm_path.as_ref().map(|p| base_dir.join(p)),
+            })
+            .collect();
+
+        NetworkArtifacts {
+            zombie_json: base_dir.join("zombie.json"),
+            relay_chain_spec: base_dir.join(self.relay.chain_spec_path()),
+            parachains,
+            base_dir,
+        }
     }
 
     pub(crate) fn nodes_iter(&self) -> impl Iterator<Item = &NetworkNode> {
@@ -591,3 +1508,482 @@ impl<T: FileSystem> Network<T> {
         )
     }
 }
+
+/// Stop the native nodes of a network detached by a previous run (see [`Network::detach`]),
+/// from the process ids recorded in the `zombie.json` file of its `base_dir`. Nodes still
+/// running after the grace period are killed, the files in `base_dir` are kept.
+pub async fn destroy_detached(base_dir: impl AsRef<Path>) -> Result<(), OrchestratorError> {
+    // time to wait for the nodes to exit after `SIGTERM` before killing them
+    const DETACHED_NODES_STOP_GRACE: Duration = Duration::from_secs(10);
+
+    let path = base_dir.as_ref().join("zombie.json");
+    let contents = std::fs::read_to_string(&path).map_err(|err| {
+        anyhow::anyhow!("Can't read state file {}: {err}", path.to_string_lossy())
+    })?;
+    let zombie_json: serde_json::Value = serde_json::from_str(&contents)?;
+
+    let pids: HashMap<String, u32> = zombie_json
+        .get("pids")
+        .and_then(|pids| pids.as_object())
+        .map(|pids| {
+            pids.iter()
+                .filter_map(|(name, pid)| Some((name.clone(), pid.as_u64()? as u32)))
+                .collect()
+        })
+        .unwrap_or_default();
+
+    provider::destroy_detached_nodes(&pids, DETACHED_NODES_STOP_GRACE).await?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use async_trait::async_trait;
+    use configuration::{GlobalSettingsBuilder, NetworkConfigBuilder, RpcScheme};
+    use provider::{
+        types::{GenerateFilesOptions, ProviderCapabilities, SpawnNodeOptions},
+        DynNode, NativeProvider, Provider, ProviderNamespace,
+    };
+    use support::fs::local::LocalFileSystem;
+
+    use super::*;
+    use crate::network_spec::node::NodeSpec;
+
+    // namespace overriding the capabilities of the wrapped one
+    struct WithCapabilities {
+        inner: DynNamespace,
+        capabilities: ProviderCapabilities,
+    }
+
+    #[async_trait]
+    impl ProviderNamespace for WithCapabilities {
+        fn name(&self) -> &str {
+            self.inner.name()
+        }
+
+        fn base_dir(&self) -> &PathBuf {
+            self.inner.base_dir()
+        }
+
+        fn capabilities(&self) -> &ProviderCapabilities {
+            &self.capabilities
+        }
+
+        async fn nodes(&self) -> HashMap<String, DynNode> {
+            self.inner.nodes().await
+        }
+
+        async fn get_node_available_args_and_version(
+            &self,
+            options: (String, Option<String>),
+        ) -> Result<(String, Option<String>), ProviderError> {
+            self.inner
+                .get_node_available_args_and_version(options)
+                .await
+        }
+
+        async fn spawn_node(&self, options: &SpawnNodeOptions) -> Result<DynNode, ProviderError> {
+            self.inner.spawn_node(options).await
+        }
+
+        async fn generate_files(&self, options: GenerateFilesOptions) -> Result<(), ProviderError> {
+            self.inner.generate_files(options).await
+        }
+
+        async fn destroy(&self) -> Result<(), ProviderError> {
+            self.inner.destroy().await
+        }
+
+        async fn static_setup(&self) -> Result<(), ProviderError> {
+            self.inner.static_setup().await
+        }
+    }
+
+    async fn network_with_a_para() -> Network<LocalFileSystem> {
+        let config = NetworkConfigBuilder::new()
+            .with_relaychain(|r| {
+                r.with_chain("rococo-local")
+                    .with_default_command("polkadot")
+                    .with_node(|node| node.with_name("alice"))
+            })
+            .with_parachain(|p| {
+                p.with_id(2000)
+                    .with_default_command("polkadot-parachain")
+                    .with_collator(|c| c.with_name("collator"))
+            })
+            .build()
+            .unwrap();
+        let spec = NetworkSpec::from_config(&config).await.unwrap();
+        let ns = NativeProvider::new(LocalFileSystem)
+            .create_namespace()
+            .await
+            .unwrap();
+        let relay = Relaychain::new(
+            "rococo-local".into(),
+            "rococo_local_testnet".into(),
+            PathBuf::from("rococo-local.json"),
+        );
+        let mut network = Network::new_with_relay(relay, ns, LocalFileSystem, spec);
+        network.add_para(Parachain::new(2000));
+        network
+    }
+
+    // spawn a long running process (instead of a real node) in the network namespace
+    async fn spawn_node(network: &Network<LocalFileSystem>, name: &str) -> NetworkNode {
+        let inner = network
+            .ns
+            .spawn_node(&SpawnNodeOptions::new(name, "sleep").args(vec!["60"]))
+            .await
+            .unwrap();
+        let spec = NodeSpec {
+            name: name.to_string(),
+            ..Default::default()
+        };
+
+        NetworkNode::new(
+            name,
+            "ws://127.0.0.1:9944",
+            "http://127.0.0.1:9615",
+            spec,
+            inner,
+        )
+    }
+
+    async fn teardown(network: Network<LocalFileSystem>) {
+        for node in network.nodes() {
+            node.inner.destroy().await.unwrap();
+        }
+        network.ns.destroy().await.unwrap();
+        std::fs::remove_dir_all(network.ns.base_dir()).unwrap();
+    }
+
+    #[tokio::test]
+    async fn a_running_collator_should_be_only_listed_in_its_para() {
+        let mut network = network_with_a_para().await;
+        let alice = spawn_node(&network, "alice").await;
+        network.add_running_node(alice, None);
+        let collator = spawn_node(&network, "collator").await;
+        network.add_running_node(collator, Some(2000));
+
+        let names: Vec<&str> = network.nodes().iter().map(|node| node.name()).collect();
+        assert_eq!(names, vec!["alice", "collator"]);
+        assert_eq!(network.relay_nodes().len(), 1);
+        assert_eq!(network.collators(2000).unwrap().len(), 1);
+        assert_eq!(network.get_node("collator").unwrap().name(), "collator");
+        assert_eq!(network.get_node_mut("alice").unwrap().name(), "alice");
+        assert!(network.get_node("bob").is_err());
+
+        teardown(network).await;
+    }
+
+    #[tokio::test]
+    async fn relay_rpc_url_should_use_ws_for_the_internal_address_regardless_of_the_rpc_scheme() {
+        let mut network = network_with_a_para().await;
+        network.initial_spec.global_settings = GlobalSettingsBuilder::new()
+            .with_rpc_scheme(RpcScheme::Wss)
+            .build()
+            .unwrap();
+        let alice = spawn_node(&network, "alice").await;
+        network.add_running_node(alice, None);
+        let mut para = network.initial_spec.parachains[0].clone();
+        para.relay_rpc_node = Some("alice".into());
+
+        let url = network.relay_rpc_url(&para).await.unwrap();
+
+        assert_eq!(url, Some("ws://127.0.0.1:0".to_string()));
+        teardown(network).await;
+    }
+
+    #[tokio::test]
+    async fn shutdown_ordered_should_stop_the_collators_before_the_relaychain_nodes() {
+        let mut network = network_with_a_para().await;
+        let mut boot = spawn_node(&network, "boot").await;
+        boot.spec.is_bootnode = true;
+        network.add_running_node(boot, None);
+        let alice = spawn_node(&network, "alice").await;
+        network.add_running_node(alice, None);
+        let collator = spawn_node(&network, "collator").await;
+        network.add_running_node(collator, Some(2000));
+
+        let order: Vec<&str> = network
+            .shutdown_order()
+            .iter()
+            .map(|node| node.name())
+            .collect();
+        assert_eq!(order, vec!["collator", "alice", "boot"]);
+
+        let base_dir = network.ns.base_dir().clone();
+        network.shutdown_ordered().await.unwrap();
+        std::fs::remove_dir_all(base_dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn artifacts_should_return_the_paths_under_the_base_dir_sorted_by_para_id() {
+        let mut network = network_with_a_para().await;
+        let mut para = Parachain::with_chain_spec(1000, "asset-hub", "asset-hub.json");
+        para.genesis_state_path = Some(PathBuf::from("1000/genesis-state"));
+        para.genesis_wasm_path = Some(PathBuf::from("1000/genesis-wasm"));
+        network.add_para(para);
+
+        let artifacts = network.artifacts();
+        let base_dir = network.ns.base_dir();
+
+        assert_eq!(&artifacts.base_dir, base_dir);
+        assert_eq!(artifacts.zombie_json, base_dir.join("zombie.json"));
+        assert_eq!(
+            artifacts.relay_chain_spec,
+            base_dir.join("rococo-local.json")
+        );
+        let ids: Vec<u32> = artifacts.parachains.iter().map(|para| para.id).collect();
+        assert_eq!(ids, vec![1000, 2000]);
+        assert_eq!(
+            artifacts.parachains[0].chain_spec,
+            Some(base_dir.join("asset-hub.json"))
+        );
+        assert_eq!(
+            artifacts.parachains[0].genesis_state,
+            Some(base_dir.join("1000/genesis-state"))
+        );
+        assert_eq!(
+            artifacts.parachains[0].genesis_wasm,
+            Some(base_dir.join("1000/genesis-wasm"))
+        );
+        assert_eq!(artifacts.parachains[1].chain_spec, None);
+        assert_eq!(artifacts.parachains[1].genesis_state, None);
+        assert_eq!(artifacts.parachains[1].genesis_wasm, None);
+
+        teardown(network).await;
+    }
+
+    #[tokio::test]
+    async fn waits_with_cancel_should_return_early_once_the_token_is_cancelled() {
+        let mut network = network_with_a_para().await;
+        let alice = spawn_node(&network, "alice").await;
+        network.add_running_node(alice, None);
+        let alice = network.get_node("alice").unwrap();
+
+        let token = CancellationToken::new();
+        let canceller = token.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(500)).await;
+            canceller.cancel();
+        });
+
+        let started = std::time::Instant::now();
+        // the node never logs the line (nor exposes the metric), so only the cancellation ends
+        // the waits before the timeout
+        let err = alice
+            .wait_log_line_count_with_cancel("never logged", false, 1, 60_u64, token.clone())
+            .await
+            .unwrap_err();
+        assert_eq!(err.to_string(), "Cancelled, waiting to match 1 lines");
+
+        let err = alice
+            .wait_metric_with_cancel("block_height", |_| true, 60_u64, token)
+            .await
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Cancelled, waiting for metric block_height pass the predicate"
+        );
+        assert!(started.elapsed() < Duration::from_secs(10));
+
+        teardown(network).await;
+    }
+
+    #[tokio::test]
+    async fn adding_nodes_should_fail_if_the_provider_does_not_support_it() {
+        let mut network = network_with_a_para().await;
+        network.ns = Arc::new(WithCapabilities {
+            capabilities: network
+                .ns
+                .capabilities()
+                .clone()
+                .supports_runtime_node_addition(false),
+            inner: network.ns.clone(),
+        });
+
+        let err = network
+            .add_node("bob", AddNodeOptions::default())
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<OrchestratorError>(),
+            Some(OrchestratorError::UnsupportedByProvider(_))
+        ));
+        let err = network
+            .add_collator("collator2", AddCollatorOptions::default(), 2000)
+            .await
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Not supported by the provider: adding nodes to a running network"
+        );
+        assert!(network.nodes().is_empty());
+
+        teardown(network).await;
+    }
+
+    #[tokio::test]
+    async fn wait_until_parachains_progress_should_fail_if_a_para_has_no_collators() {
+        let network = network_with_a_para().await;
+
+        let err = network
+            .wait_until_parachains_progress(1_u32, 1_u64)
+            .await
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Can't read the progress of the parachains without collators: [2000]"
+        );
+
+        teardown(network).await;
+    }
+
+    #[tokio::test]
+    async fn restart_node_with_args_should_persist_the_extra_args() {
+        let mut network = network_with_a_para().await;
+        let alice = spawn_node(&network, "alice").await;
+        network.add_running_node(alice, None);
+
+        network
+            .restart_node_with_args("alice", vec!["30"])
+            .await
+            .unwrap();
+
+        let alice = network.get_node("alice").unwrap();
+        assert_eq!(alice.args(), vec!["60", "30"]);
+        assert!(alice.spec().args.contains(&Arg::Flag("30".into())));
+
+        // the updated args are also persisted in the network state
+        let zombie_json: serde_json::Value = serde_json::from_str(
+            &std::fs::read_to_string(network.ns.base_dir().join("zombie.json")).unwrap(),
+        )
+        .unwrap();
+        let spec_args = zombie_json
+            .pointer("/relay/nodes/0/spec/args")
+            .unwrap()
+            .as_array()
+            .unwrap();
+        assert!(spec_args.contains(&serde_json::json!("30")));
+
+        teardown(network).await;
+    }
+
+    // serve a successful response to every request (as a node that is up), returning its url
+    async fn ready_ws_server() -> String {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            while let Ok((mut stream, _)) = listener.accept().await {
+                let mut buffer = [0; 1024];
+                let _ = tokio::io::AsyncReadExt::read(&mut stream, &mut buffer).await;
+                let _ = tokio::io::AsyncWriteExt::write_all(
+                    &mut stream,
+                    b"HTTP/1.1 200 OK\r\ncontent-length: 0\r\n\r\n",
+                )
+                .await;
+            }
+        });
+
+        format!("ws://{addr}")
+    }
+
+    #[tokio::test]
+    async fn detach_should_return_the_base_dir_and_the_detached_namespace() {
+        let mut network = network_with_a_para().await;
+        let mut alice = spawn_node(&network, "alice").await;
+        alice.ws_uri = ready_ws_server().await;
+        let attached_pid = alice.inner.pid().await;
+        network.add_running_node(alice, None);
+
+        let (base_dir, ns) = network.detach().await.unwrap();
+
+        assert!(ns.is_detached().await);
+        assert_eq!(&base_dir, ns.base_dir());
+        // the native nodes are restarted in their own session
+        let alice = ns.nodes().await["alice"].clone();
+        assert_ne!(alice.pid().await, attached_pid);
+        let zombie_json: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(base_dir.join("zombie.json")).unwrap())
+                .unwrap();
+        assert_eq!(
+            zombie_json["pids"]["alice"].as_u64(),
+            alice.pid().await.map(u64::from)
+        );
+
+        for node in ns.nodes().await.values() {
+            node.destroy().await.unwrap();
+        }
+        std::fs::remove_dir_all(base_dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn destroy_detached_should_stop_the_recorded_nodes() {
+        // a process that is not our child (as the nodes detached by a previous run)
+        let output = std::process::Command::new("sh")
+            .args(["-c", "sleep 60 > /dev/null 2>&1 & echo $!"])
+            .output()
+            .unwrap();
+        let pid: u32 = String::from_utf8_lossy(&output.stdout)
+            .trim()
+            .parse()
+            .unwrap();
+        let base_dir = std::env::temp_dir().join(format!("zombie-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&base_dir).unwrap();
+        std::fs::write(
+            base_dir.join("zombie.json"),
+            serde_json::json!({ "pids": { "alice": pid } }).to_string(),
+        )
+        .unwrap();
+
+        destroy_detached(&base_dir).await.unwrap();
+
+        let mut running = true;
+        for _ in 0..50 {
+            running = std::process::Command::new("kill")
+                .args(["-0", &pid.to_string()])
+                .stderr(std::process::Stdio::null())
+                .status()
+                .unwrap()
+                .success();
+            if !running {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(100)).await;
+        }
+        assert!(!running);
+        std::fs::remove_dir_all(base_dir).unwrap();
+    }
+
+    #[test]
+    fn onboarding_timeout_error_should_report_the_last_error_if_the_lifecycle_was_never_read() {
+        let error =
+            onboarding_timeout_error(2000, 60, None, Some(anyhow::anyhow!("connection refused")));
+        assert_eq!(
+            error.to_string(),
+            "Timeout (60s), waiting for parachain 2000 to be onboarded, the lifecycle couldn't be read: connection refused"
+        );
+
+        // once read, the lifecycle is reported (even with a later error)
+        let error = onboarding_timeout_error(
+            2000,
+            60,
+            Some(Some("Onboarding".into())),
+            Some(anyhow::anyhow!("connection refused")),
+        );
+        assert_eq!(
+            error.to_string(),
+            "Timeout (60s), waiting for parachain 2000 to be onboarded, current lifecycle: Onboarding"
+        );
+
+        let error = onboarding_timeout_error(2000, 60, Some(None), None);
+        assert_eq!(
+            error.to_string(),
+            "Timeout (60s), waiting for parachain 2000 to be onboarded, current lifecycle: not registered"
+        );
+    }
+}
@@ -1,20 +1,28 @@
 use std::{sync::Arc, time::Duration};
 
 use anyhow::anyhow;
+use futures::StreamExt;
 use glob_match::glob_match;
 use prom_metrics_parser::MetricMap;
-use provider::DynNode;
+use provider::{constants::LOCALHOST, DynNode, LogStream};
 use regex::Regex;
-use serde::Serialize;
-use subxt::{backend::rpc::RpcClient, OnlineClient};
+use serde::{Deserialize, Serialize};
+use subxt::{
+    backend::{
+        legacy::LegacyRpcMethods,
+        rpc::{rpc_params, RpcClient},
+    },
+    OnlineClient, SubstrateConfig,
+};
 use support::net::{skip_err_while_waiting, wait_ws_ready};
 use thiserror::Error;
 use tokio::sync::RwLock;
+use tokio_util::sync::CancellationToken;
 use tracing::{debug, trace};
 
-use crate::network_spec::node::NodeSpec;
 #[cfg(feature = "pjs")]
 use crate::pjs_helper::{pjs_build_template, pjs_exec, PjsResult, ReturnValue};
+use crate::{network_spec::node::NodeSpec, shared::constants::PROMETHEUS_PORT};
 
 #[derive(Error, Debug)]
 pub enum NetworkNodeError {
@@ -22,6 +30,58 @@ pub enum NetworkNodeError {
     MetricNotFound(String),
 }
 
+/// Peers of a node, as reported by `system_health` and `system_peers`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PeerInfo {
+    /// Amount of connected peers.
+    pub peers_count: usize,
+    /// Whether the node is major syncing.
+    pub is_syncing: bool,
+    /// Ids of the connected peers (empty if `system_peers` is not exposed by the node).
+    pub peer_ids: Vec<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PeerEntry {
+    peer_id: String,
+}
+
+/// Sync progress of a node, as reported by `system_syncState` and `system_health`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SyncState {
+    /// Height of the block the sync started from (e.g. the restored db snapshot or, with
+    /// `warp` sync, the block where the state was downloaded).
+    pub starting_block: u64,
+    /// Height of the best block of the node.
+    pub current_block: u64,
+    /// Highest block height known by the node (from its peers).
+    pub highest_block: u64,
+    /// Whether the node is major syncing.
+    pub is_syncing: bool,
+}
+
+impl SyncState {
+    /// Percentage (0-100) of the blocks synced since the starting block.
+    pub fn progress(&self) -> u8 {
+        let total = self.highest_block.saturating_sub(self.starting_block);
+        if total == 0 {
+            return 100;
+        }
+
+        let synced = self.current_block.saturating_sub(self.starting_block);
+        (synced.min(total) * 100 / total) as u8
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SyncStateEntry {
+    starting_block: u64,
+    current_block: u64,
+    highest_block: Option<u64>,
+}
+
 #[derive(Clone, Serialize)]
 pub struct NetworkNode {
     #[serde(skip)]
@@ -32,8 +92,40 @@ pub struct NetworkNode {
     pub(crate) name: String,
     pub(crate) ws_uri: String,
     pub(crate) prometheus_uri: String,
+    pub(crate) p2p_port: u16,
+    // args appended at runtime (e.g. restart with extra args)
+    #[serde(skip)]
+    pub(crate) extra_args: Vec<String>,
     #[serde(skip)]
     metrics_cache: Arc<RwLock<MetricMap>>,
+    #[cfg(feature = "client")]
+    #[serde(skip)]
+    client_cache: Arc<RwLock<Option<OnlineClient<subxt::PolkadotConfig>>>>,
+}
+
+// Sort the nodes deterministically (validators first, then by name) regardless of the
+// spawn order.
+pub(crate) fn sort_nodes(nodes: &mut [&NetworkNode]) {
+    nodes.sort_by(|a, b| {
+        b.spec
+            .is_validator
+            .cmp(&a.spec.is_validator)
+            .then_with(|| a.name.cmp(&b.name))
+    });
+}
+
+// Serialize the nodes in a deterministic order, to keep the state file (`zombie.json`) stable
+// across runs.
+pub(crate) fn serialize_sorted_nodes<S>(
+    nodes: &[NetworkNode],
+    serializer: S,
+) -> Result<S::Ok, S::Error>
+where
+    S: serde::Serializer,
+{
+    let mut sorted: Vec<&NetworkNode> = nodes.iter().collect();
+    sort_nodes(&mut sorted);
+    serializer.collect_seq(sorted)
 }
 
 // #[derive(Clone, Debug)]
@@ -61,9 +153,13 @@ impl NetworkNode {
             name: name.into(),
             ws_uri: ws_uri.into(),
             prometheus_uri: prometheus_uri.into(),
+            p2p_port: spec.p2p_port.0,
             inner,
             spec,
+            extra_args: Default::default(),
             metrics_cache: Arc::new(Default::default()),
+            #[cfg(feature = "client")]
+            client_cache: Arc::new(Default::default()),
         }
     }
 
@@ -72,7 +168,9 @@ impl NetworkNode {
     }
 
     pub fn args(&self) -> Vec<&str> {
-        self.inner.args()
+        let mut args = self.inner.args();
+        args.extend(self.extra_args.iter().map(|arg| arg.as_str()));
+        args
     }
 
     pub fn spec(&self) -> &NodeSpec {
@@ -83,6 +181,16 @@ impl NetworkNode {
         &self.ws_uri
     }
 
+    /// Uri to scrape the node's prometheus metrics
+    pub fn prometheus_uri(&self) -> &str {
+        &self.prometheus_uri
+    }
+
+    /// Port assigned to the node for p2p connections
+    pub fn p2p_port(&self) -> u16 {
+        self.p2p_port
+    }
+
     // Subxt
 
     /// Get the rpc client for the node
@@ -90,6 +198,60 @@ impl NetworkNode {
         RpcClient::from_url(&self.ws_uri).await
     }
 
+    /// Get the current runtime `spec_version` of the node (through `state_getRuntimeVersion`)
+    pub async fn spec_version(&self) -> Result<u32, anyhow::Error> {
+        let rpc = LegacyRpcMethods::<SubstrateConfig>::new(self.rpc().await?);
+        let version = rpc.state_get_runtime_version(None).await?;
+        Ok(version.spec_version)
+    }
+
+    /// Get the peers of the node (through `system_health` and `system_peers`), useful to
+    /// debug connectivity issues (e.g. a collator not connected to the relaychain).
+    ///
+    /// `system_peers` is an unsafe rpc method, if it's not exposed the peer ids are empty.
+    pub async fn peers(&self) -> Result<PeerInfo, anyhow::Error> {
+        let client = self.rpc().await?;
+        let health = LegacyRpcMethods::<SubstrateConfig>::new(client.clone())
+            .system_health()
+            .await?;
+
+        let peer_ids = match client
+            .request::<Vec<PeerEntry>>("system_peers", rpc_params![])
+            .await
+        {
+            Ok(peers) => peers.into_iter().map(|peer| peer.peer_id).collect(),
+            Err(err) => {
+                debug!("can't get the peers of node {}: {err}", self.name);
+                vec![]
+            },
+        };
+
+        Ok(PeerInfo {
+            peers_count: health.peers,
+            is_syncing: health.is_syncing,
+            peer_ids,
+        })
+    }
+
+    /// Get the sync progress of the node (through `system_syncState` and `system_health`),
+    /// useful to check that a node started from a db snapshot or with `warp` sync didn't
+    /// sync from the genesis (the starting block should be greater than 0).
+    pub async fn sync_state(&self) -> Result<SyncState, anyhow::Error> {
+        let client = self.rpc().await?;
+        let health = LegacyRpcMethods::<SubstrateConfig>::new(client.clone())
+            .system_health()
+            .await?;
+        let state: SyncStateEntry = client.request("system_syncState", rpc_params![]).await?;
+
+        Ok(SyncState {
+            starting_block: state.starting_block,
+            current_block: state.current_block,
+            // not reported until the node knows about a better block from its peers
+            highest_block: state.highest_block.unwrap_or(state.current_block),
+            is_syncing: health.is_syncing,
+        })
+    }
+
     /// Get the [online client](subxt::client::OnlineClient) for the node
     #[deprecated = "Use `wait_client` instead."]
     pub async fn client<Config: subxt::Config>(
@@ -116,6 +278,41 @@ impl NetworkNode {
         }
     }
 
+    /// Get the (cached) [online client](subxt::client::OnlineClient) for the node.
+    ///
+    /// The client is created on the first call and reused afterwards. This doesn't wait
+    /// for the node, so callers should wait until the node is up first (e.g. with
+    /// [`NetworkNode::wait_client`]).
+    #[cfg(feature = "client")]
+    pub async fn cached_client(
+        &self,
+    ) -> Result<OnlineClient<subxt::PolkadotConfig>, anyhow::Error> {
+        if let Some(client) = self.client_cache.read().await.as_ref() {
+            return Ok(client.clone());
+        }
+
+        let mut cache = self.client_cache.write().await;
+        // other task could fill the cache while we were waiting for the lock
+        if let Some(client) = cache.as_ref() {
+            return Ok(client.clone());
+        }
+
+        let client = self
+            .try_client::<subxt::PolkadotConfig>()
+            .await
+            .map_err(|e| {
+                anyhow!(
+                    "Can't connect to node {} at {} (is the node up?), err: {}",
+                    self.name,
+                    self.ws_uri,
+                    e
+                )
+            })?;
+        *cache = Some(client.clone());
+
+        Ok(client)
+    }
+
     /// Wait until get the [online client](subxt::client::OnlineClient) for the node
     pub async fn wait_client<Config: subxt::Config>(
         &self,
@@ -145,25 +342,47 @@ impl NetworkNode {
     // Commands
 
     /// Pause the node, this is implemented by pausing the
-    /// actual process (e.g polkadot) with sending `SIGSTOP` signal
+    /// actual process (e.g polkadot) with sending `SIGSTOP` signal.
+    /// With the native provider and a `stop_grace` set in the global settings the
+    /// process is stopped cleanly instead (`SIGTERM`) and relaunched on resume.
     pub async fn pause(&self) -> Result<(), anyhow::Error> {
         self.inner.pause().await?;
         Ok(())
     }
 
+    /// Freeze the node, this is implemented by pausing the
+    /// actual process (e.g polkadot) with sending `SIGSTOP` signal
+    /// (regardless of the `stop_grace` setting).
+    pub async fn freeze(&self) -> Result<(), anyhow::Error> {
+        self.inner.freeze().await?;
+        Ok(())
+    }
+
     /// Resume the node, this is implemented by resuming the
     /// actual process (e.g polkadot) with sending `SIGCONT` signal
+    /// (or relaunching it if it was stopped by `pause`).
     pub async fn resume(&self) -> Result<(), anyhow::Error> {
         self.inner.resume().await?;
+        // the process could be relaunched, losing the connection of the cached client
+        self.reset_client_cache().await;
         Ok(())
     }
 
     /// Restart the node using the same `cmd`, `args` and `env` (and same isolated dir)
     pub async fn restart(&self, after: Option<Duration>) -> Result<(), anyhow::Error> {
         self.inner.restart(after).await?;
+        self.reset_client_cache().await;
         Ok(())
     }
 
+    // The connection of the cached client is lost when the node is restarted
+    pub(crate) async fn reset_client_cache(&self) {
+        #[cfg(feature = "client")]
+        {
+            *self.client_cache.write().await = None;
+        }
+    }
+
     // Metrics assertions
 
     /// Get metric value 'by name' from Prometheus (exposed by the node)
@@ -179,6 +398,14 @@ impl NetworkNode {
         self.metric(&metric_name, true).await
     }
 
+    /// Get the current value of a metric (see [`NetworkNode::reports`] for details on the
+    /// metric name), unlike `reports` a metric not exposed by the node is an error.
+    pub async fn metric_value(&self, metric_name: impl Into<String>) -> Result<f64, anyhow::Error> {
+        let metric_name = metric_name.into();
+        self.fetch_metrics().await?;
+        self.metric(&metric_name, false).await
+    }
+
     /// Assert on a metric value 'by name' from Prometheus (exposed by the node)
     /// metric name can be:
     /// with prefix (e.g: 'polkadot_')
@@ -217,6 +444,46 @@ impl NetworkNode {
         }
     }
 
+    /// Assert that a metric value pass the `predicate` within the timeout (secs), retrying
+    /// while the node is not reachable or the metric is not exposed yet. Returns the value
+    /// that passed the `predicate`, on timeout the error includes the last reported value.
+    /// See [`NetworkNode::reports`] description for details on metric name.
+    pub async fn assert_metric(
+        &self,
+        metric_name: impl Into<String>,
+        predicate: impl Fn(f64) -> bool,
+        timeout_secs: impl Into<u64>,
+    ) -> Result<f64, anyhow::Error> {
+        let metric_name = metric_name.into();
+        let secs = timeout_secs.into();
+        let mut last_value = None;
+
+        let res = tokio::time::timeout(Duration::from_secs(secs), async {
+            loop {
+                match self.metric_value(&metric_name).await {
+                    Ok(value) if predicate(value) => return Ok(value),
+                    Ok(value) => last_value = Some(value),
+                    Err(err) if !is_retryable_metric_err(&err) => return Err(err),
+                    Err(err) => trace!("🔎 metric {metric_name} not available yet: {err}"),
+                }
+
+                // sleep to not spam prometheus
+                tokio::time::sleep(Duration::from_secs(1)).await;
+            }
+        })
+        .await;
+
+        match res {
+            Ok(inner_res) => inner_res,
+            Err(_) => Err(anyhow!(
+                "Timeout ({secs}), metric {metric_name} didn't pass the predicate, last value: {}",
+                last_value
+                    .map(|value| value.to_string())
+                    .unwrap_or_else(|| "not reported".to_string())
+            )),
+        }
+    }
+
     // Wait methods for metrics
 
     /// Wait until a metric value pass the `predicate`
@@ -289,6 +556,24 @@ impl NetworkNode {
         }
     }
 
+    /// Wait until a metric value pass the `predicate`
+    /// with a timeout (secs), aborting early if the `token` is cancelled.
+    pub async fn wait_metric_with_cancel(
+        &self,
+        metric_name: impl Into<String>,
+        predicate: impl Fn(f64) -> bool,
+        timeout_secs: impl Into<u64>,
+        token: CancellationToken,
+    ) -> Result<(), anyhow::Error> {
+        let metric_name = metric_name.into();
+        tokio::select! {
+            res = self.wait_metric_with_timeout(&metric_name, predicate, timeout_secs) => res,
+            _ = token.cancelled() => Err(anyhow!(
+                "Cancelled, waiting for metric {metric_name} pass the predicate"
+            )),
+        }
+    }
+
     // Logs
 
     /// Get the logs of the node
@@ -297,7 +582,29 @@ impl NetworkNode {
         Ok(self.inner.logs().await?)
     }
 
-    /// Wait until a the number of matching log lines is reach
+    /// Stream the log lines of the node, from the first one and following the new ones
+    /// as they are written.
+    pub fn log_stream(&self) -> LogStream {
+        self.inner.log_stream()
+    }
+
+    /// Get the lines matching the `pattern` (regex) from the last `last_n` lines of the logs
+    pub async fn logs_matching(
+        &self,
+        pattern: &str,
+        last_n: usize,
+    ) -> Result<Vec<String>, anyhow::Error> {
+        let re = Regex::new(pattern)?;
+        let logs = self.inner.logs_tail(last_n).await?;
+
+        Ok(logs
+            .lines()
+            .filter(|line| re.is_match(line))
+            .map(|line| line.to_string())
+            .collect())
+    }
+
+    /// Wait until the number of matching log lines is reached
     pub async fn wait_log_line_count(
         &self,
         pattern: impl Into<String>,
@@ -315,10 +622,11 @@ impl NetworkNode {
 
         loop {
             let mut q = 0_usize;
-            let logs = self.logs().await?;
-            for line in logs.lines() {
+            let mut log_stream = self.inner.log_stream();
+            while let Some(line) = log_stream.next().await {
+                let line = line?;
                 trace!("line is {line}");
-                if match_fn(line) {
+                if match_fn(&line) {
                     trace!("pattern {pattern} match in line {line}");
                     q += 1;
                     if q >= count {
@@ -327,11 +635,12 @@ impl NetworkNode {
                 }
             }
 
+            // the stream ends if the node is stopped (e.g. restarted), start again
             tokio::time::sleep(Duration::from_secs(2)).await;
         }
     }
 
-    /// Wait until a the number of matching log lines is reach
+    /// Wait until the number of matching log lines is reached
     /// with timeout (secs)
     pub async fn wait_log_line_count_with_timeout(
         &self,
@@ -349,6 +658,22 @@ impl NetworkNode {
         .await?
     }
 
+    /// Wait until the number of matching log lines is reached
+    /// with timeout (secs), aborting early if the `token` is cancelled.
+    pub async fn wait_log_line_count_with_cancel(
+        &self,
+        substring: impl Into<String>,
+        is_glob: bool,
+        count: usize,
+        timeout_secs: impl Into<u64>,
+        token: CancellationToken,
+    ) -> Result<(), anyhow::Error> {
+        tokio::select! {
+            res = self.wait_log_line_count_with_timeout(substring, is_glob, count, timeout_secs) => res,
+            _ = token.cancelled() => Err(anyhow!("Cancelled, waiting to match {count} lines")),
+        }
+    }
+
     // TODO: impl
     // wait_event_count
     // wait_event_count_with_timeout
@@ -391,14 +716,41 @@ impl NetworkNode {
         self.pjs(content, args, user_types).await
     }
 
+    /// Scrape all the metrics exposed by the node in a single request (also refreshing
+    /// the cache used by the metrics assertions).
+    pub async fn metrics_snapshot(&self) -> Result<MetricMap, anyhow::Error> {
+        self.fetch_metrics().await?;
+        Ok(self.metrics_cache.read().await.clone())
+    }
+
     async fn fetch_metrics(&self) -> Result<(), anyhow::Error> {
-        let response = reqwest::get(&self.prometheus_uri).await?;
-        let metrics = prom_metrics_parser::parse(&response.text().await?)?;
+        let content = match reqwest::get(&self.prometheus_uri).await {
+            Ok(response) => response.text().await?,
+            Err(err) if err.is_connect() => self.scrape_through_port_forward().await?.ok_or(err)?,
+            Err(err) => Err(err)?,
+        };
+        let metrics = prom_metrics_parser::parse(&content)?;
         let mut cache = self.metrics_cache.write().await;
         *cache = metrics;
         Ok(())
     }
 
+    /// Scrape the metrics through a port-forward to the prometheus port, used when the
+    /// `prometheus_uri` is not reachable (e.g. k8s nodes exposed with the pod ip while the
+    /// network is used from outside the cluster). Returns `None` if the provider doesn't
+    /// support port-forwarding. The forward is cached by the provider node, so it's created
+    /// once and reused by the following scrapes.
+    async fn scrape_through_port_forward(&self) -> Result<Option<String>, anyhow::Error> {
+        let Some(local_port) = self.inner.create_port_forward(0, PROMETHEUS_PORT).await? else {
+            return Ok(None);
+        };
+
+        let uri = format!("http://{LOCALHOST}:{local_port}/metrics");
+        debug!("scraping {} metrics through port-forward {uri}", self.name);
+        let response = reqwest::get(uri).await?;
+        Ok(Some(response.text().await?))
+    }
+
     /// Query individual metric by name
     async fn metric(
         &self,
@@ -423,6 +775,18 @@ impl NetworkNode {
     }
 }
 
+// The node is not listening yet or the metric is not exposed yet, keep waiting
+fn is_retryable_metric_err(err: &anyhow::Error) -> bool {
+    if let Some(reqwest_err) = err.downcast_ref::<reqwest::Error>() {
+        return skip_err_while_waiting(reqwest_err);
+    }
+
+    matches!(
+        err.downcast_ref::<NetworkNodeError>(),
+        Some(NetworkNodeError::MetricNotFound(_))
+    )
+}
+
 impl std::fmt::Debug for NetworkNode {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("NetworkNode")
@@ -431,8 +795,172 @@ impl std::fmt::Debug for NetworkNode {
             .field("name", &self.name)
             .field("ws_uri", &self.ws_uri)
             .field("prometheus_uri", &self.prometheus_uri)
+            .field("p2p_port", &self.p2p_port)
             .finish()
     }
 }
 
 // TODO: mock and impl unit tests
+
+#[cfg(test)]
+mod tests {
+    use provider::{types::SpawnNodeOptions, NativeProvider, Provider};
+    use support::fs::local::LocalFileSystem;
+    use tokio::{
+        io::{AsyncReadExt, AsyncWriteExt},
+        net::TcpListener,
+    };
+
+    use super::*;
+
+    const METRICS: &str = r#"# HELP polkadot_block_height Block height info of the chain
+# TYPE polkadot_block_height gauge
+polkadot_block_height{status="best",chain="rococo_local_testnet"} 42
+polkadot_block_height{status="finalized",chain="rococo_local_testnet"} 40
+# HELP polkadot_node_is_active_validator Tracks if the validator is in the active set.
+# TYPE polkadot_node_is_active_validator gauge
+polkadot_node_is_active_validator{chain="rococo_local_testnet"} 1
+"#;
+
+    // serve the `METRICS` sample on each request, returns the prometheus uri
+    async fn serve_metrics() -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            while let Ok((mut stream, _)) = listener.accept().await {
+                let mut buf = [0; 1024];
+                let _ = stream.read(&mut buf).await;
+                let response = format!(
+                    "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{METRICS}",
+                    METRICS.len()
+                );
+                let _ = stream.write_all(response.as_bytes()).await;
+            }
+        });
+
+        format!("http://{addr}/metrics")
+    }
+
+    // node backed by a long running process, exposing the `METRICS` sample
+    async fn node_with_metrics() -> (NetworkNode, provider::DynNamespace) {
+        let ns = NativeProvider::new(LocalFileSystem)
+            .create_namespace()
+            .await
+            .unwrap();
+        let inner = ns
+            .spawn_node(&SpawnNodeOptions::new("alice", "sleep").args(vec!["60"]))
+            .await
+            .unwrap();
+        let node = NetworkNode::new(
+            "alice",
+            "ws://127.0.0.1:9944",
+            &serve_metrics().await,
+            NodeSpec::default(),
+            inner,
+        );
+
+        (node, ns)
+    }
+
+    async fn teardown(node: NetworkNode, ns: provider::DynNamespace) {
+        node.inner.destroy().await.unwrap();
+        ns.destroy().await.unwrap();
+        std::fs::remove_dir_all(ns.base_dir()).unwrap();
+    }
+
+    #[tokio::test]
+    async fn metric_value_should_lookup_the_metric_with_and_without_prefix_or_labels() {
+        let (node, ns) = node_with_metrics().await;
+
+        assert_eq!(
+            node.metric_value("polkadot_node_is_active_validator")
+                .await
+                .unwrap(),
+            1_f64
+        );
+        assert_eq!(
+            node.metric_value("node_is_active_validator{chain=\"rococo_local_testnet\"}")
+                .await
+                .unwrap(),
+            1_f64
+        );
+        assert_eq!(
+            node.metric_value("block_height{status=\"best\"}")
+                .await
+                .unwrap(),
+            42_f64
+        );
+        let err = node.metric_value("not_exposed").await.unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<NetworkNodeError>(),
+            Some(NetworkNodeError::MetricNotFound(name)) if name == "not_exposed"
+        ));
+
+        teardown(node, ns).await;
+    }
+
+    #[tokio::test]
+    async fn assert_metric_should_return_the_value_that_passed_the_predicate() {
+        let (node, ns) = node_with_metrics().await;
+
+        let value = node
+            .assert_metric("block_height{status=\"finalized\"}", |v| v >= 40_f64, 5_u64)
+            .await
+            .unwrap();
+        assert_eq!(value, 40_f64);
+
+        teardown(node, ns).await;
+    }
+
+    #[tokio::test]
+    async fn assert_metric_should_report_the_last_value_on_timeout() {
+        let (node, ns) = node_with_metrics().await;
+
+        let err = node
+            .assert_metric("block_height{status=\"best\"}", |v| v > 100_f64, 1_u64)
+            .await
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Timeout (1), metric block_height{status=\"best\"} didn't pass the predicate, last value: 42"
+        );
+
+        let err = node
+            .assert_metric("not_exposed", |_| true, 1_u64)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().ends_with("last value: not reported"));
+
+        teardown(node, ns).await;
+    }
+
+    fn sync_state(starting_block: u64, current_block: u64, highest_block: u64) -> SyncState {
+        SyncState {
+            starting_block,
+            current_block,
+            highest_block,
+            is_syncing: true,
+        }
+    }
+
+    #[test]
+    fn sync_state_progress_should_be_relative_to_the_starting_block() {
+        assert_eq!(sync_state(100, 100, 200).progress(), 0);
+        assert_eq!(sync_state(100, 150, 200).progress(), 50);
+        assert_eq!(sync_state(100, 200, 200).progress(), 100);
+    }
+
+    #[test]
+    fn sync_state_progress_should_be_complete_without_blocks_to_sync() {
+        assert_eq!(sync_state(0, 0, 0).progress(), 100);
+        // the highest block known is behind the starting one (e.g. no peers yet)
+        assert_eq!(sync_state(100, 100, 50).progress(), 100);
+    }
+
+    #[test]
+    fn sync_state_progress_should_be_capped_when_the_current_block_overshoots() {
+        assert_eq!(sync_state(0, 250, 200).progress(), 100);
+        // the current block is behind the starting one
+        assert_eq!(sync_state(100, 50, 200).progress(), 0);
+    }
+}
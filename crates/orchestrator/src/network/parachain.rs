@@ -10,12 +10,16 @@ use serde::Serialize;
 use subxt::{dynamic::Value, tx::TxStatus, OnlineClient, SubstrateConfig};
 use subxt_signer::{sr25519::Keypair, SecretUri};
 use support::{constants::THIS_IS_A_BUG, fs::FileSystem, net::wait_ws_ready};
-use tracing::info;
+use tracing::{debug, info};
 
-use super::{chain_upgrade::ChainUpgrade, node::NetworkNode};
+use super::{
+    chain_upgrade::ChainUpgrade,
+    node::{serialize_sorted_nodes, NetworkNode},
+};
 use crate::{
+    generators::para_artifact::decode_hex_artifact,
     network_spec::parachain::ParachainSpec,
-    shared::types::{RegisterParachainOptions, RuntimeUpgradeOptions},
+    shared::types::{RegisterParachainOptions, RegistrationMethod, RuntimeUpgradeOptions},
     ScopedFilesystem,
 };
 
@@ -25,6 +29,9 @@ pub struct Parachain {
     pub(crate) para_id: u32,
     pub(crate) chain_id: Option<String>,
     pub(crate) chain_spec_path: Option<PathBuf>,
+    pub(crate) genesis_state_path: Option<PathBuf>,
+    pub(crate) genesis_wasm_path: Option<PathBuf>,
+    #[serde(serialize_with = "serialize_sorted_nodes")]
     pub(crate) collators: Vec<NetworkNode>,
     pub(crate) files_to_inject: Vec<TransferedFile>,
 }
@@ -66,6 +73,8 @@ impl Parachain {
             para_id,
             chain_id: None,
             chain_spec_path: None,
+            genesis_state_path: None,
+            genesis_wasm_path: None,
             collators: Default::default(),
             files_to_inject: Default::default(),
         }
@@ -81,6 +90,8 @@ impl Parachain {
             chain: None,
             chain_id: Some(chain_id.into()),
             chain_spec_path: Some(chain_spec_path.as_ref().into()),
+            genesis_state_path: None,
+            genesis_wasm_path: None,
             collators: Default::default(),
             files_to_inject: Default::default(),
         }
@@ -94,7 +105,7 @@ impl Parachain {
         let mut para_files_to_inject = files_to_inject.to_owned();
 
         // parachain id is used for the keystore
-        let mut para = if let Some(chain_spec) = para.chain_spec.as_ref() {
+        let mut running_para = if let Some(chain_spec) = para.chain_spec.as_ref() {
             let id = chain_spec.read_chain_id(scoped_fs).await?;
 
             // add the spec to global files to inject
@@ -117,9 +128,11 @@ impl Parachain {
             Parachain::new(para.id)
         };
 
-        para.files_to_inject = para_files_to_inject;
+        running_para.files_to_inject = para_files_to_inject;
+        running_para.genesis_state_path = para.genesis_state.artifact_path().cloned();
+        running_para.genesis_wasm_path = para.genesis_wasm.artifact_path().cloned();
 
-        Ok(para)
+        Ok(running_para)
     }
 
     pub async fn register(
@@ -160,48 +173,112 @@ impl Parachain {
             })?;
         let api = OnlineClient::<SubstrateConfig>::from_url(options.node_ws_url).await?;
 
-        let schedule_para = subxt::dynamic::tx(
-            "ParasSudoWrapper",
-            "sudo_schedule_para_initialize",
-            vec![
-                Value::primitive(options.id.into()),
-                Value::named_composite([
-                    (
-                        "genesis_head",
-                        Value::from_bytes(hex::decode(&genesis_state[2..])?),
-                    ),
-                    (
-                        "validation_code",
-                        Value::from_bytes(hex::decode(&wasm_data[2..])?),
-                    ),
-                    ("para_kind", Value::bool(options.onboard_as_para)),
-                ]),
-            ],
-        );
+        let genesis_head =
+            decode_hex_artifact(&genesis_state).map_err(|err| anyhow!("genesis state {err}"))?;
+        let validation_code =
+            decode_hex_artifact(&wasm_data).map_err(|err| anyhow!("genesis wasm {err}"))?;
+
+        let has_pallet = |pallet: &str| api.metadata().pallet_by_name(pallet).is_some();
+        let method = match options.method {
+            RegistrationMethod::Auto if has_pallet("ParasSudoWrapper") => {
+                RegistrationMethod::ParasSudoWrapper
+            },
+            RegistrationMethod::Auto => RegistrationMethod::Registrar,
+            method => method,
+        };
+        debug!("Registering parachain {} using {:?}", options.id, method);
+
+        let calls = match method {
+            RegistrationMethod::Registrar => {
+                if !has_pallet("Registrar") {
+                    return Err(anyhow!(
+                        "Relaychain runtime doesn't include the 'Registrar' pallet, can't register para {}",
+                        options.id
+                    ));
+                }
+
+                let who = Value::from_bytes(sudo.public_key().0);
+                let mut calls = vec![subxt::dynamic::tx(
+                    "Registrar",
+                    "force_register",
+                    vec![
+                        who.clone(),
+                        Value::u128(0),
+                        Value::primitive(options.id.into()),
+                        Value::from_bytes(genesis_head),
+                        Value::from_bytes(validation_code),
+                    ],
+                )];
+
+                if options.onboard_as_para {
+                    if !has_pallet("Slots") {
+                        return Err(anyhow!(
+                            "Relaychain runtime doesn't include the 'Slots' pallet, can't onboard para {} as parachain",
+                            options.id
+                        ));
+                    }
+
+                    // lease the slot for (practically) the whole network lifetime
+                    calls.push(subxt::dynamic::tx(
+                        "Slots",
+                        "force_lease",
+                        vec![
+                            Value::primitive(options.id.into()),
+                            who,
+                            Value::u128(0),
+                            Value::u128(0),
+                            Value::u128(999),
+                        ],
+                    ));
+                }
+
+                calls
+            },
+            _ => vec![subxt::dynamic::tx(
+                "ParasSudoWrapper",
+                "sudo_schedule_para_initialize",
+                vec![
+                    Value::primitive(options.id.into()),
+                    Value::named_composite([
+                        ("genesis_head", Value::from_bytes(genesis_head)),
+                        ("validation_code", Value::from_bytes(validation_code)),
+                        ("para_kind", Value::bool(options.onboard_as_para)),
+                    ]),
+                ],
+            )],
+        };
 
-        let sudo_call = subxt::dynamic::tx("Sudo", "sudo", vec![schedule_para.into_value()]);
-
-        // TODO: uncomment below and fix the sign and submit (and follow afterwards until
-        // finalized block) to register the parachain
-        let mut tx = api
-            .tx()
-            .sign_and_submit_then_watch_default(&sudo_call, &sudo)
-            .await?;
-
-        // Below we use the low level API to replicate the `wait_for_in_block` behaviour
-        // which was removed in subxt 0.33.0. See https://github.com/paritytech/subxt/pull/1237.
-        while let Some(status) = tx.next().await {
-            match status? {
-                TxStatus::InBestBlock(tx_in_block) | TxStatus::InFinalizedBlock(tx_in_block) => {
-                    let _result = tx_in_block.wait_for_success().await?;
-                    info!("In block: {:#?}", tx_in_block.block_hash());
-                },
-                TxStatus::Error { message }
-                | TxStatus::Invalid { message }
-                | TxStatus::Dropped { message } => {
-                    return Err(anyhow::format_err!("Error submitting tx: {message}"));
-                },
-                _ => continue,
+        for call in calls {
+            let sudo_call = subxt::dynamic::tx("Sudo", "sudo", vec![call.into_value()]);
+
+            let mut tx = api
+                .tx()
+                .sign_and_submit_then_watch_default(&sudo_call, &sudo)
+                .await?;
+
+            // Below we use the low level API to replicate the `wait_for_in_block` behaviour
+            // which was removed in subxt 0.33.0. See https://github.com/paritytech/subxt/pull/1237.
+            while let Some(status) = tx.next().await {
+                match status? {
+                    TxStatus::InBestBlock(tx_in_block) => {
+                        let _result = tx_in_block.wait_for_success().await?;
+                        info!("In block: {:#?}", tx_in_block.block_hash());
+                        if !options.finalization {
+                            break;
+                        }
+                    },
+                    TxStatus::InFinalizedBlock(tx_in_block) => {
+                        let _result = tx_in_block.wait_for_success().await?;
+                        info!("In finalized block: {:#?}", tx_in_block.block_hash());
+                        break;
+                    },
+                    TxStatus::Error { message }
+                    | TxStatus::Invalid { message }
+                    | TxStatus::Dropped { message } => {
+                        return Err(anyhow::format_err!("Error submitting tx: {message}"));
+                    },
+                    _ => continue,
+                }
             }
         }
 
@@ -1,10 +1,10 @@
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use anyhow::anyhow;
 use async_trait::async_trait;
 use serde::Serialize;
 
-use super::node::NetworkNode;
+use super::node::{serialize_sorted_nodes, NetworkNode};
 use crate::{network::chain_upgrade::ChainUpgrade, shared::types::RuntimeUpgradeOptions};
 
 #[derive(Debug, Serialize)]
@@ -12,6 +12,7 @@ pub struct Relaychain {
     pub(crate) chain: String,
     pub(crate) chain_id: String,
     pub(crate) chain_spec_path: PathBuf,
+    #[serde(serialize_with = "serialize_sorted_nodes")]
     pub(crate) nodes: Vec<NetworkNode>,
 }
 
@@ -64,4 +65,9 @@ impl Relaychain {
     pub fn chain(&self) -> &str {
         &self.chain
     }
+
+    /// Path of the raw chain-spec (relative to the network base dir)
+    pub fn chain_spec_path(&self) -> &Path {
+        &self.chain_spec_path
+    }
 }
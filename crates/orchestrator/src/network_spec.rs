@@ -1,16 +1,33 @@
 use std::{
     collections::{hash_map::Entry, HashMap},
     sync::Arc,
+    time::Instant,
 };
 
-use configuration::{GlobalSettings, HrmpChannelConfig, NetworkConfig};
-use futures::future::try_join_all;
+use configuration::{
+    shared::{
+        chain_properties::{ChainProperties, ChainPropertiesBuilder},
+        resources::{Resources, ResourcesBuilder},
+        types::{BootnodeStrategy, LogTarget},
+    },
+    GlobalSettings, HrmpChannelConfig, ImagePolicy, NetworkConfig, NetworkConfigBuilder,
+    RegistrationStrategy, RpcScheme,
+};
+use futures::{future::try_join_all, FutureExt, StreamExt};
 use provider::{DynNamespace, ProviderError, ProviderNamespace};
 use serde::Serialize;
 use support::{constants::THIS_IS_A_BUG, fs::FileSystem};
-use tracing::debug;
-
-use crate::{errors::OrchestratorError, ScopedFilesystem};
+use tracing::{debug, info};
+
+use crate::{
+    errors::OrchestratorError,
+    generators,
+    shared::{
+        constants::{DEFAULT_MAX_NOMINATIONS, UNKNOWN_NODE_VERSION},
+        types::ChainDefaultContext,
+    },
+    ScopedFilesystem,
+};
 
 pub mod node;
 pub mod parachain;
@@ -38,7 +55,7 @@ impl NetworkSpec {
         network_config: &NetworkConfig,
     ) -> Result<NetworkSpec, OrchestratorError> {
         let mut errs = vec![];
-        let relaychain = RelaychainSpec::from_config(network_config.relaychain())?;
+        let mut relaychain = RelaychainSpec::from_config(network_config.relaychain())?;
         let mut parachains = vec![];
 
         // TODO: move to `fold` or map+fold
@@ -50,6 +67,63 @@ impl NetworkSpec {
         }
 
         if errs.is_empty() {
+            // the global spec builder image/command (if any) are used for all the generation
+            let global_settings = network_config.global_settings();
+            relaychain
+                .chain_spec
+                .apply_spec_builder_overrides(global_settings);
+            for chain_spec in parachains
+                .iter_mut()
+                .filter_map(|para| para.chain_spec.as_mut())
+            {
+                chain_spec.apply_spec_builder_overrides(global_settings);
+            }
+
+            // paras sharing the same chain would write (concurrently) the same `<chain>.json`,
+            // so in that case the chain-spec files are keyed by the para id
+            let mut chain_spec_names: HashMap<String, usize> = HashMap::new();
+            for chain_spec in parachains
+                .iter()
+                .filter_map(|para| para.chain_spec.as_ref())
+            {
+                *chain_spec_names
+                    .entry(chain_spec.chain_spec_name().to_string())
+                    .or_default() += 1;
+            }
+            for para in parachains.iter_mut() {
+                let para_id = para.id;
+                if let Some(chain_spec) = para.chain_spec.as_mut() {
+                    if chain_spec_names
+                        .get(chain_spec.chain_spec_name())
+                        .is_some_and(|count| *count > 1)
+                    {
+                        let name = format!("{}-{}", chain_spec.chain_spec_name(), para_id);
+                        chain_spec.set_chain_spec_name(name);
+                    }
+                }
+            }
+
+            if let Some(port_range) = network_config.global_settings().port_range() {
+                let mut port_generator =
+                    generators::PortRangeGenerator::new(port_range.base(), port_range.stride());
+
+                // ports are assigned in the order nodes are defined in the config
+                let relay_nodes = relaychain
+                    .nodes
+                    .iter_mut()
+                    .zip(network_config.relaychain().nodes());
+                let collators = parachains
+                    .iter_mut()
+                    .zip(network_config.parachains())
+                    .flat_map(|(para, para_config)| {
+                        para.collators.iter_mut().zip(para_config.collators())
+                    });
+
+                for (node, node_config) in relay_nodes.chain(collators) {
+                    node.assign_ports_from_range(node_config, &mut port_generator)?;
+                }
+            }
+
             Ok(NetworkSpec {
                 relaychain,
                 parachains,
@@ -70,6 +144,170 @@ impl NetworkSpec {
         }
     }
 
+    /// Reconstruct a [`NetworkConfig`] from the spec (inverse of [`NetworkSpec::from_config`]),
+    /// e.g. to dump a spec loaded from state back to toml with [`NetworkConfig::dump_to_toml`].
+    ///
+    /// Some values can't round-trip and are represented as follow:
+    /// - nodes ports are the resolved ones (random or from the `port_range`), and are set explicitly.
+    /// - chain-specs are represented by their location (`chain_spec_path`), the resolved
+    ///   `chain_spec_command` template isn't recovered so the default one is used.
+    /// - parachains `bootnodes_addresses` are not part of the spec and are lost.
+    pub fn to_config(&self) -> Result<NetworkConfig, OrchestratorError> {
+        let relaychain = &self.relaychain;
+        let Some((first, rest)) = relaychain.nodes.split_first() else {
+            return Err(OrchestratorError::InvalidConfig(
+                "Relaychain without nodes can't be converted to config".to_string(),
+            ));
+        };
+
+        if let Some(para) = self
+            .parachains
+            .iter()
+            .find(|para| para.collators.is_empty())
+        {
+            return Err(OrchestratorError::InvalidConfig(format!(
+                "Parachain {}, without collators can't be converted to config",
+                para.id
+            )));
+        }
+
+        let chain_context = ChainDefaultContext {
+            default_command: relaychain.default_command.as_ref(),
+            default_image: relaychain.default_image.as_ref(),
+            default_resources: relaychain.default_resources.as_ref(),
+            default_db_snapshot: relaychain.default_db_snapshot.as_ref(),
+            default_args: relaychain.default_args.iter().collect(),
+            default_log_targets: relaychain.default_log_targets.iter().collect(),
+        };
+
+        let mut builder = NetworkConfigBuilder::new().with_relaychain(|r| {
+            let mut r = r
+                .with_chain(relaychain.chain.clone())
+                .with_default_args(relaychain.default_args.clone())
+                .with_default_log_targets(log_target_pairs(&relaychain.default_log_targets))
+                .with_genesis_raw_entries(relaychain.genesis_raw_entries.clone());
+
+            if relaychain.bootnode_strategy != BootnodeStrategy::default() {
+                r = r.with_bootnode_strategy(relaychain.bootnode_strategy);
+            }
+            if let Some(command) = &relaychain.default_command {
+                r = r.with_default_command(command.clone());
+            }
+            if let Some(image) = &relaychain.default_image {
+                r = r.with_default_image(image.clone());
+            }
+            if let Some(resources) = &relaychain.default_resources {
+                r = r.with_default_resources(resources_builder(resources));
+            }
+            if let Some(db_snapshot) = &relaychain.default_db_snapshot {
+                r = r.with_default_db_snapshot(db_snapshot.clone());
+            }
+            if let Some(modifier) = relaychain.chain_spec_modifier.clone() {
+                r = r.with_chain_spec_modifier(move |spec| modifier.apply(spec));
+            }
+            if let Some(properties) = &relaychain.chain_properties {
+                r = r.with_chain_properties(chain_properties_builder(properties));
+            }
+            if let Some(location) = relaychain.chain_spec.get_asset_location() {
+                r = r.with_chain_spec_path(location.clone());
+            }
+            if let Some(wasm_override) = &relaychain.wasm_override {
+                r = r.with_wasm_override(wasm_override.clone());
+            }
+            if relaychain.random_nominators_count > 0 {
+                r = r.with_random_nominators_count(relaychain.random_nominators_count);
+            }
+            if relaychain.max_nominations != DEFAULT_MAX_NOMINATIONS {
+                r = r.with_max_nominations(relaychain.max_nominations);
+            }
+            if let Some(patch) = &relaychain.runtime_genesis_patch {
+                r = r.with_genesis_overrides(patch.clone());
+            }
+
+            let mut r = r.with_node(|node| first.to_config_builder(node, &chain_context));
+            for node_spec in rest {
+                r = r.with_node(|node| node_spec.to_config_builder(node, &chain_context));
+            }
+            r
+        });
+
+        for para in &self.parachains {
+            builder = builder.with_parachain(|p| para.to_config_builder(p));
+        }
+
+        for channel in &self.hrmp_channels {
+            builder = builder.with_hrmp_channel(|h| {
+                h.with_sender(channel.sender())
+                    .with_recipient(channel.recipient())
+                    .with_max_capacity(channel.max_capacity())
+                    .with_max_message_size(channel.max_message_size())
+            });
+        }
+
+        let settings = &self.global_settings;
+        let bootnodes_addresses: Vec<String> = settings
+            .bootnodes_addresses()
+            .iter()
+            .map(|addr| addr.to_string())
+            .collect();
+        builder = builder.with_global_settings(|g| {
+            let mut g = g
+                .with_bootnodes_addresses(bootnodes_addresses.iter().map(String::as_str).collect())
+                .with_network_spawn_timeout(settings.network_spawn_timeout())
+                .with_node_spawn_timeout(settings.node_spawn_timeout());
+
+            if settings.image_policy() != ImagePolicy::default() {
+                g = g.with_image_policy(settings.image_policy());
+            }
+            if settings.rpc_scheme() != RpcScheme::default() {
+                g = g.with_rpc_scheme(settings.rpc_scheme());
+            }
+            if let Some(local_ip) = settings.local_ip() {
+                g = g.with_local_ip(&local_ip.to_string());
+            }
+            if let Some(base_dir) = settings.base_dir() {
+                g = g.with_base_dir(base_dir);
+            }
+            if let Some(port_range) = settings.port_range() {
+                g = g.with_port_range(port_range.base(), port_range.stride());
+            }
+            if let Some(spawn_concurrency) = settings.spawn_concurrency() {
+                g = g.with_spawn_concurrency(spawn_concurrency);
+            }
+            if let Some(rpc_path_prefix) = settings.rpc_path_prefix() {
+                g = g.with_rpc_path_prefix(rpc_path_prefix);
+            }
+            if let Some(image) = settings.spec_builder_image() {
+                g = g.with_spec_builder_image(image.as_str());
+            }
+            if let Some(command) = settings.spec_builder_command() {
+                g = g.with_spec_builder_command(command);
+            }
+            if let Some(log_max_bytes) = settings.log_max_bytes() {
+                g = g.with_log_max_bytes(log_max_bytes);
+            }
+            if settings.log_max_files() != GlobalSettings::default().log_max_files() {
+                g = g.with_log_max_files(settings.log_max_files());
+            }
+            if let Some(namespace_prefix) = settings.namespace_prefix() {
+                g = g.with_namespace_prefix(namespace_prefix);
+            }
+            if let Some(stop_grace) = settings.stop_grace() {
+                g = g.with_stop_grace(stop_grace);
+            }
+            g
+        });
+
+        builder.build().map_err(|errs| {
+            OrchestratorError::InvalidConfig(
+                errs.into_iter()
+                    .map(|e| e.to_string())
+                    .collect::<Vec<String>>()
+                    .join("\n"),
+            )
+        })
+    }
+
     pub async fn populate_nodes_available_args(
         &mut self,
         ns: Arc<dyn ProviderNamespace + Send + Sync>,
@@ -91,46 +329,51 @@ impl NetworkSpec {
         Ok(())
     }
 
-    //
-    pub async fn node_available_args_output(
-        &self,
-        node_spec: &NodeSpec,
-        ns: Arc<dyn ProviderNamespace + Send + Sync>,
-    ) -> Result<String, ProviderError> {
-        // try to find a node that use the same combination of image/cmd
+    // find a node that use the same combination of image/cmd than `node_spec`
+    fn find_node_with_same_binary(&self, node_spec: &NodeSpec) -> Option<&NodeSpec> {
         let cmp_fn = |ad_hoc: &&NodeSpec| -> bool {
             ad_hoc.image == node_spec.image && ad_hoc.command == node_spec.command
         };
 
-        // check if we already had computed the args output for this cmd/[image]
-        let node = self.relaychain.nodes.iter().find(cmp_fn);
-        let node = if let Some(node) = node {
-            Some(node)
-        } else {
-            let node = self
-                .parachains
+        self.relaychain.nodes.iter().find(cmp_fn).or_else(|| {
+            self.parachains
                 .iter()
-                .find_map(|para| para.collators.iter().find(cmp_fn));
-
-            node
-        };
+                .find_map(|para| para.collators.iter().find(cmp_fn))
+        })
+    }
 
-        let output = if let Some(node) = node {
-            node.available_args_output.clone().expect(&format!(
+    /// Available args output and version of the `node_spec` binary, reusing the ones of the
+    /// nodes running the same cmd/[image] if any (`unknown` version if the binary doesn't
+    /// support `--version`).
+    pub async fn node_available_args_and_version(
+        &self,
+        node_spec: &NodeSpec,
+        ns: Arc<dyn ProviderNamespace + Send + Sync>,
+    ) -> Result<(String, String), ProviderError> {
+        if let Some(node) = self.find_node_with_same_binary(node_spec) {
+            let available_args_output = node.available_args_output.clone().expect(&format!(
                 "args_output should be set for running nodes {THIS_IS_A_BUG}"
-            ))
-        } else {
-            // we need to compute the args output
-            let image = node_spec
-                .image
-                .as_ref()
-                .map(|image| image.as_str().to_string());
-            let command = node_spec.command.as_str().to_string();
-
-            ns.get_node_available_args((command, image)).await?
-        };
+            ));
+            let version = node
+                .version
+                .clone()
+                .unwrap_or_else(|| UNKNOWN_NODE_VERSION.to_string());
+            return Ok((available_args_output, version));
+        }
 
-        Ok(output)
+        let image = node_spec
+            .image
+            .as_ref()
+            .map(|image| image.as_str().to_string());
+        let command = node_spec.command.as_str().to_string();
+        let (available_args_output, version) = ns
+            .get_node_available_args_and_version((command, image))
+            .await?;
+
+        Ok((
+            available_args_output,
+            version.unwrap_or_else(|| UNKNOWN_NODE_VERSION.to_string()),
+        ))
     }
 
     pub fn relaychain(&self) -> &RelaychainSpec {
@@ -153,44 +396,100 @@ impl NetworkSpec {
         self.global_settings = global_settings;
     }
 
+    /// Build the parachains artifacts (chain-spec, genesis state and wasm), running up to
+    /// `concurrency` parachains at the same time (each para artifacts are independent).
     pub async fn build_parachain_artifacts<'a, T: FileSystem>(
         &mut self,
         ns: DynNamespace,
         scoped_fs: &ScopedFilesystem<'a, T>,
         relaychain_id: &str,
         base_dir_exists: bool,
+        concurrency: usize,
     ) -> Result<(), anyhow::Error> {
-        for para in self.parachains.iter_mut() {
-            let chain_spec_raw_path = para.build_chain_spec(relaychain_id, &ns, scoped_fs).await?;
-            debug!("parachain chain-spec built!");
-
-            if base_dir_exists {
-                scoped_fs.create_dir_all(para.id.to_string()).await?;
-            } else {
-                scoped_fs.create_dir(para.id.to_string()).await?;
-            };
-
-            // create wasm/state
-            para.genesis_state
-                .build(
-                    chain_spec_raw_path.clone(),
-                    format!("{}/genesis-state", para.id),
-                    &ns,
-                    scoped_fs,
-                )
-                .await?;
-            debug!("parachain genesis state built!");
-            para.genesis_wasm
-                .build(
-                    chain_spec_raw_path,
-                    format!("{}/genesis-wasm", para.id),
-                    &ns,
-                    scoped_fs,
-                )
-                .await?;
-            debug!("parachain genesis wasm built!");
+        let start = Instant::now();
+        let paras_count = self.parachains.len();
+
+        let tasks: Vec<_> = self
+            .parachains
+            .iter_mut()
+            .map(|para| {
+                let para_id = para.id;
+                Self::build_para_artifacts(para, &ns, scoped_fs, relaychain_id, base_dir_exists)
+                    .map(move |result| (para_id, result))
+            })
+            .collect();
+        let results: Vec<(u32, Result<(), anyhow::Error>)> = futures::stream::iter(tasks)
+            .buffer_unordered(concurrency.max(1))
+            .collect()
+            .await;
+
+        let errors: Vec<String> = results
+            .into_iter()
+            .filter_map(|(para_id, result)| {
+                result.err().map(|err| format!("para {para_id}: {err}"))
+            })
+            .collect();
+
+        if !errors.is_empty() {
+            return Err(anyhow::anyhow!(
+                "Error building parachains artifacts:\n{}",
+                errors.join("\n")
+            ));
         }
 
+        info!(
+            "⏱️  artifacts of {paras_count} parachain(s) built in {:?} (concurrency: {concurrency})",
+            start.elapsed()
+        );
+
+        Ok(())
+    }
+
+    async fn build_para_artifacts<'a, T: FileSystem>(
+        para: &mut ParachainSpec,
+        ns: &DynNamespace,
+        scoped_fs: &ScopedFilesystem<'a, T>,
+        relaychain_id: &str,
+        base_dir_exists: bool,
+    ) -> Result<(), anyhow::Error> {
+        let chain_spec_raw_path = para.build_chain_spec(relaychain_id, ns, scoped_fs).await?;
+        debug!("parachain chain-spec built!");
+
+        if base_dir_exists {
+            scoped_fs.create_dir_all(para.id.to_string()).await?;
+        } else {
+            scoped_fs.create_dir(para.id.to_string()).await?;
+        };
+
+        // the para will never be registered, so the genesis artifacts are not needed
+        if para.registration_strategy == RegistrationStrategy::None {
+            debug!(
+                "parachain {} will not be registered, skipping genesis state/wasm",
+                para.id
+            );
+            return Ok(());
+        }
+
+        // create wasm/state
+        para.genesis_state
+            .build(
+                chain_spec_raw_path.clone(),
+                format!("{}/genesis-state", para.id),
+                ns,
+                scoped_fs,
+            )
+            .await?;
+        debug!("parachain genesis state built!");
+        para.genesis_wasm
+            .build(
+                chain_spec_raw_path,
+                format!("{}/genesis-wasm", para.id),
+                ns,
+                scoped_fs,
+            )
+            .await?;
+        debug!("parachain genesis wasm built!");
+
         Ok(())
     }
 
@@ -242,23 +541,24 @@ impl NetworkSpec {
     async fn retrieve_all_nodes_available_args_output(
         ns: Arc<dyn ProviderNamespace + Send + Sync>,
         image_command_to_nodes_mapping: &HashMap<(Option<String>, String), Vec<&mut NodeSpec>>,
-    ) -> Result<Vec<(Option<String>, String, String)>, OrchestratorError> {
+    ) -> Result<Vec<(Option<String>, String, String, String)>, OrchestratorError> {
         try_join_all(
             image_command_to_nodes_mapping
                 .keys()
                 .cloned()
                 .map(|(image, command)| async {
-                    // get node available args output from image/command
-                    let available_args = ns
-                        .get_node_available_args((command.clone(), image.clone()))
+                    // get node available args output and version from image/command
+                    let (available_args, version) = ns
+                        .get_node_available_args_and_version((command.clone(), image.clone()))
                         .await?;
+                    let version = version.unwrap_or_else(|| UNKNOWN_NODE_VERSION.to_string());
                     debug!(
-                        "retrieved available args for image: {:?}, command: {}",
+                        "retrieved available args and version ({version}) for image: {:?}, command: {}",
                         image, command
                     );
 
                     // map the result to include image and command
-                    Ok::<_, OrchestratorError>((image, command, available_args))
+                    Ok::<_, OrchestratorError>((image, command, available_args, version))
                 })
                 .collect::<Vec<_>>(),
         )
@@ -267,9 +567,9 @@ impl NetworkSpec {
 
     fn update_nodes_available_args_output(
         image_command_to_nodes_mapping: &mut HashMap<(Option<String>, String), Vec<&mut NodeSpec>>,
-        available_args_outputs: Vec<(Option<String>, String, String)>,
+        available_args_outputs: Vec<(Option<String>, String, String, String)>,
     ) {
-        for (image, command, available_args_output) in available_args_outputs {
+        for (image, command, available_args_output, version) in available_args_outputs {
             let nodes = image_command_to_nodes_mapping
                 .get_mut(&(image, command))
                 .expect(&format!(
@@ -278,11 +578,64 @@ impl NetworkSpec {
 
             for node in nodes {
                 node.available_args_output = Some(available_args_output.clone());
+                node.version = Some(version.clone());
             }
         }
     }
 }
 
+// Closure to set the `resources` into a [`ResourcesBuilder`], used to reconstruct the config.
+fn resources_builder(resources: &Resources) -> impl FnOnce(ResourcesBuilder) -> ResourcesBuilder {
+    let resources = resources.clone();
+    move |mut builder| {
+        if let Some(quantity) = resources.request_memory() {
+            builder = builder.with_request_memory(quantity.clone());
+        }
+        if let Some(quantity) = resources.request_cpu() {
+            builder = builder.with_request_cpu(quantity.clone());
+        }
+        if let Some(quantity) = resources.limit_memory() {
+            builder = builder.with_limit_memory(quantity.clone());
+        }
+        if let Some(quantity) = resources.limit_cpu() {
+            builder = builder.with_limit_cpu(quantity.clone());
+        }
+        if let Some(nofile) = resources.limit_nofile() {
+            builder = builder.with_limit_nofile(nofile);
+        }
+        builder
+    }
+}
+
+// The `(target, level)` pairs of the `log_targets`, used to reconstruct the config.
+fn log_target_pairs<'a>(
+    log_targets: impl IntoIterator<Item = &'a LogTarget>,
+) -> Vec<(&'a str, &'a str)> {
+    log_targets
+        .into_iter()
+        .map(|log_target| (log_target.target(), log_target.level()))
+        .collect()
+}
+
+// Closure to set the `properties` into a [`ChainPropertiesBuilder`], used to reconstruct the config.
+fn chain_properties_builder(
+    properties: &ChainProperties,
+) -> impl FnOnce(ChainPropertiesBuilder) -> ChainPropertiesBuilder {
+    let properties = properties.clone();
+    move |mut builder| {
+        if let Some(token_decimals) = properties.token_decimals() {
+            builder = builder.with_token_decimals(token_decimals.into());
+        }
+        if let Some(token_symbol) = properties.token_symbol() {
+            builder = builder.with_token_symbol(token_symbol);
+        }
+        if let Some(ss58_prefix) = properties.ss58_prefix() {
+            builder = builder.with_ss58_prefix(ss58_prefix.into());
+        }
+        builder
+    }
+}
+
 #[cfg(test)]
 mod tests {
 
@@ -324,4 +677,252 @@ mod tests {
         let para_100 = network_spec.parachains.first().unwrap();
         assert_eq!(para_100.id, 100);
     }
+
+    #[tokio::test]
+    async fn spec_builder_overrides_should_be_used_for_all_the_chain_specs() {
+        use configuration::NetworkConfigBuilder;
+
+        use super::*;
+
+        let config = NetworkConfigBuilder::new()
+            .with_relaychain(|r| {
+                r.with_chain("rococo-local")
+                    .with_default_command("polkadot")
+                    .with_default_image("docker.io/parity/polkadot:latest")
+                    .with_node(|node| node.with_name("alice"))
+            })
+            .with_parachain(|p| {
+                p.with_id(100)
+                    .with_chain("asset-hub-rococo-local")
+                    .with_default_command("polkadot-parachain")
+                    .with_collator(|c| c.with_name("collator1"))
+            })
+            .with_global_settings(|g| {
+                g.with_spec_builder_image("docker.io/parity/chain-spec-builder:latest")
+                    .with_spec_builder_command(
+                        "chain-spec-builder --chain {{chainName}} {{disableBootnodes}}",
+                    )
+            })
+            .build()
+            .unwrap();
+
+        let network_spec = NetworkSpec::from_config(&config).await.unwrap();
+        let para_chain_spec = network_spec.parachains[0].chain_spec.as_ref().unwrap();
+
+        for chain_spec in [&network_spec.relaychain.chain_spec, para_chain_spec] {
+            let chain_spec = serde_json::to_value(chain_spec).unwrap();
+            assert_eq!(
+                chain_spec["image"],
+                "docker.io/parity/chain-spec-builder:latest"
+            );
+            assert_eq!(
+                chain_spec["command"]["Remote"],
+                "chain-spec-builder --chain {{chainName}} --disable-default-bootnode"
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn paras_sharing_the_chain_should_key_the_chain_spec_by_para_id() {
+        use configuration::NetworkConfigBuilder;
+
+        use super::*;
+
+        let config = NetworkConfigBuilder::new()
+            .with_relaychain(|r| {
+                r.with_chain("rococo-local")
+                    .with_default_command("polkadot")
+                    .with_node(|node| node.with_name("alice"))
+            })
+            .with_parachain(|p| {
+                p.with_id(1000)
+                    .with_chain("asset-hub-rococo-local")
+                    .with_default_command("polkadot-parachain")
+                    .with_collator(|c| c.with_name("collator-1000"))
+            })
+            .with_parachain(|p| {
+                p.with_id(1001)
+                    .with_chain("asset-hub-rococo-local")
+                    .with_default_command("polkadot-parachain")
+                    .with_collator(|c| c.with_name("collator-1001"))
+            })
+            .with_parachain(|p| {
+                p.with_id(2000)
+                    .with_chain("other-chain")
+                    .with_default_command("polkadot-parachain")
+                    .with_collator(|c| c.with_name("collator-2000"))
+            })
+            .build()
+            .unwrap();
+
+        let network_spec = NetworkSpec::from_config(&config).await.unwrap();
+        let names: Vec<&str> = network_spec
+            .parachains
+            .iter()
+            .map(|para| para.chain_spec.as_ref().unwrap().chain_spec_name())
+            .collect();
+
+        assert_eq!(
+            names,
+            vec![
+                "asset-hub-rococo-local-1000",
+                "asset-hub-rococo-local-1001",
+                "other-chain"
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn build_parachain_artifacts_should_inject_the_raw_entries_in_the_para_chain_spec() {
+        use configuration::NetworkConfigBuilder;
+        use provider::Provider;
+        use support::fs::local::LocalFileSystem;
+
+        use super::*;
+
+        let temp_dir = tempfile::tempdir().unwrap();
+        let base_dir = temp_dir.path().to_string_lossy().to_string();
+        // already raw, so the chain-spec is used as is (without running the command)
+        let raw_spec = r#"{"name":"Para","id":"para","genesis":{"raw":{"top":{"0x3a636f6465":"0x00"},"childrenDefault":{}}}}"#;
+        let config = NetworkConfigBuilder::new()
+            .with_relaychain(|r| {
+                r.with_chain("rococo-local")
+                    .with_default_command("polkadot")
+                    .with_node(|node| node.with_name("alice"))
+            })
+            .with_parachain(|p| {
+                p.with_id(2000)
+                    .with_chain("para")
+                    .with_chain_spec_inline(raw_spec)
+                    .with_genesis_raw_entries(vec![
+                        ("0x3a636f6465", "0x01"),
+                        ("0x3a686561707061676573", "0x08"),
+                    ])
+                    .with_registration_strategy(RegistrationStrategy::None)
+                    .with_default_command("polkadot-parachain")
+                    .with_collator(|c| c.with_name("collator"))
+            })
+            .build()
+            .unwrap();
+        let mut network_spec = NetworkSpec::from_config(&config).await.unwrap();
+
+        let fs = LocalFileSystem;
+        let ns = provider::NativeProvider::new(fs.clone())
+            .create_namespace_with_base_dir(base_dir.as_ref())
+            .await
+            .unwrap();
+        let scoped_fs = ScopedFilesystem::new(&fs, &base_dir);
+        network_spec
+            .build_parachain_artifacts(ns, &scoped_fs, "rococo_local_testnet", true, 1)
+            .await
+            .unwrap();
+
+        let content = std::fs::read_to_string(format!("{base_dir}/para.json")).unwrap();
+        let chain_spec: serde_json::Value = serde_json::from_str(&content).unwrap();
+        let top = chain_spec.pointer("/genesis/raw/top").unwrap();
+        assert_eq!(top["0x3a636f6465"], "0x01");
+        assert_eq!(top["0x3a686561707061676573"], "0x08");
+    }
+
+    #[tokio::test]
+    async fn to_config_should_recover_the_network_config() {
+        use configuration::NetworkConfigBuilder;
+
+        use super::*;
+
+        let config = NetworkConfigBuilder::new()
+            .with_relaychain(|r| {
+                r.with_chain("rococo-local")
+                    .with_default_command("polkadot")
+                    .with_default_args(vec![("-lparachain", "debug").into()])
+                    .with_node(|node| node.with_name("alice"))
+                    .with_node(|node| {
+                        node.with_name("bob")
+                            .with_command("polkadot1")
+                            .validator(false)
+                    })
+            })
+            .with_parachain(|p| {
+                p.with_id(100)
+                    .with_default_command("adder-collator")
+                    .with_genesis_state_path("/tmp/genesis-state")
+                    .with_collator(|c| c.with_name("collator1"))
+            })
+            .with_parachain(|p| {
+                p.with_id(200)
+                    .with_default_command("adder-collator")
+                    .with_genesis_state_path("/tmp/genesis-state-200")
+                    .with_collator(|c| c.with_name("collator2"))
+            })
+            .with_hrmp_channel(|h| {
+                h.with_sender(100)
+                    .with_recipient(200)
+                    .with_max_capacity(8)
+                    .with_max_message_size(512)
+            })
+            .with_global_settings(|g| {
+                g.with_spawn_concurrency(4)
+                    .with_spec_builder_image("chain-spec-builder:pinned")
+                    .with_spec_builder_command("chain-spec-builder")
+                    .with_log_max_bytes(1024)
+                    .with_log_max_files(2)
+                    .with_namespace_prefix("ci-run")
+                    .with_stop_grace(10)
+            })
+            .build()
+            .unwrap();
+
+        let network_spec = NetworkSpec::from_config(&config).await.unwrap();
+        let recovered = network_spec.to_config().unwrap();
+
+        let relaychain = recovered.relaychain();
+        assert_eq!(relaychain.chain().as_str(), "rococo-local");
+        assert_eq!(relaychain.default_command().unwrap().as_str(), "polkadot");
+        let (alice, bob) = (relaychain.nodes()[0], relaychain.nodes()[1]);
+        assert_eq!(alice.name(), "alice");
+        assert_eq!(alice.command().unwrap().as_str(), "polkadot");
+        assert_eq!(alice.args(), relaychain.default_args());
+        assert_eq!(bob.command().unwrap().as_str(), "polkadot1");
+        assert!(!bob.is_validator());
+        // ports are the resolved ones
+        assert_eq!(
+            alice.rpc_port(),
+            Some(network_spec.relaychain.nodes[0].rpc_port.0)
+        );
+
+        let para = recovered.parachains()[0];
+        assert_eq!(para.id(), 100);
+        assert_eq!(
+            para.genesis_state_path().unwrap().to_string(),
+            "/tmp/genesis-state"
+        );
+        assert!(para.genesis_wasm_generator().is_none());
+        assert_eq!(para.collators()[0].name(), "collator1");
+
+        assert_eq!(recovered.hrmp_channels()[0].max_message_size(), 512);
+        let settings = recovered.global_settings();
+        assert_eq!(settings.spawn_concurrency(), Some(4));
+        assert_eq!(
+            settings.spec_builder_image().unwrap().as_str(),
+            "chain-spec-builder:pinned"
+        );
+        assert_eq!(settings.spec_builder_command(), Some("chain-spec-builder"));
+        assert_eq!(settings.log_max_bytes(), Some(1024));
+        assert_eq!(settings.log_max_files(), 2);
+        assert_eq!(settings.namespace_prefix(), Some("ci-run"));
+        assert_eq!(settings.stop_grace(), Some(10));
+
+        // values equal to the defaults are not set explicitly
+        let toml = recovered.dump_to_toml().unwrap();
+        for key in [
+            "rpc_external",
+            "prometheus_external",
+            "rpc_methods",
+            "bootnode_strategy",
+            "image_policy",
+            "rpc_scheme",
+        ] {
+            assert!(!toml.contains(key), "{key} should be skipped");
+        }
+    }
 }
@@ -1,13 +1,18 @@
+use std::{collections::HashMap, path::PathBuf};
+
 use configuration::shared::{
-    node::{EnvVar, NodeConfig},
+    helpers::merge_log_targets,
+    node::{self, EnvVar, InjectedFile, KeystoreKey, NodeConfig, NodeConfigBuilder},
     resources::Resources,
-    types::{Arg, AssetLocation, Command, Image},
+    types::{Arg, AssetLocation, Command, Image, LogTarget, RpcMethods, SessionKeyType, SyncMode},
 };
 use multiaddr::Multiaddr;
 use provider::types::Port;
 use serde::{Deserialize, Serialize};
 use support::constants::THIS_IS_A_BUG;
+use tracing::warn;
 
+use super::{log_target_pairs, resources_builder};
 use crate::{
     errors::OrchestratorError,
     generators,
@@ -21,6 +26,19 @@ use crate::{
 
 macros::create_add_options!(AddNodeSpecOpts {});
 
+/// The args only added to the node command if the binary supports them (checked with
+/// [`NodeSpec::supports_arg`]), so the only ones a `force_args` entry has effect on.
+pub(crate) const OPTIONAL_ARGS: &[&str] = &["--insecure-validator-i-know-what-i-do"];
+
+/// The forced args without effect, since they are not in [`OPTIONAL_ARGS`].
+fn ineffective_force_args(force_args: &[String]) -> Vec<&str> {
+    force_args
+        .iter()
+        .map(String::as_str)
+        .filter(|arg| !OPTIONAL_ARGS.contains(arg))
+        .collect()
+}
+
 macro_rules! impl_from_for_add_node_opts {
     ($struct:ident) => {
         impl From<$struct> for AddNodeSpecOpts {
@@ -68,12 +86,27 @@ pub struct NodeSpec {
     /// Optional subcommand for the node.
     pub(crate) subcommand: Option<Command>,
 
+    /// Ordered command parts, a wrapper (with its own args) followed by the node binary.
+    pub(crate) command_parts: Vec<String>,
+
     /// Arguments to use for node. Appended to default.
     pub(crate) args: Vec<Arg>,
 
+    /// Arguments for the embedded relaychain node of a cumulus collator (after `--`).
+    pub(crate) relay_args: Vec<Arg>,
+
+    /// Log targets (chain defaults merged with the node ones), compiled into the `-l` flag.
+    pub(crate) log_targets: Vec<LogTarget>,
+
+    /// Flags assumed as supported, even if missing in the available arguments.
+    pub(crate) force_args: Vec<String>,
+
     // The help command output containing the available arguments.
     pub(crate) available_args_output: Option<String>,
 
+    /// Version of the node binary (`<command> --version`), `unknown` if not supported.
+    pub(crate) version: Option<String>,
+
     /// Wether the node is a validator.
     pub(crate) is_validator: bool,
 
@@ -83,6 +116,9 @@ pub struct NodeSpec {
     /// Whether the node is a bootnode.
     pub(crate) is_bootnode: bool,
 
+    /// Whether the node is a dedicated bootnode (never part of the validator/authority sets).
+    pub(crate) is_dedicated_bootnode: bool,
+
     /// Node initial balance present in genesis.
     pub(crate) initial_balance: u128,
 
@@ -110,8 +146,40 @@ pub struct NodeSpec {
     /// libp2p cert hash to use with `webrtc` transport.
     pub(crate) p2p_cert_hash: Option<String>,
 
+    /// Bind the rpc on all the interfaces.
+    pub(crate) rpc_external: bool,
+
+    /// Bind prometheus on all the interfaces.
+    pub(crate) prometheus_external: bool,
+
+    /// RPC methods exposed by the node.
+    pub(crate) rpc_methods: RpcMethods,
+
+    /// P2P listen address, the port is replaced by the p2p one.
+    pub(crate) listen_addr: Option<Multiaddr>,
+
+    /// Account used in the genesis session keys, derived from the chain if not set.
+    pub(crate) session_key_type: Option<SessionKeyType>,
+
+    /// Syncing mode (`--sync`), the node default if not set.
+    pub(crate) sync_mode: Option<SyncMode>,
+
     /// Database snapshot. Override the default.
     pub(crate) db_snapshot: Option<AssetLocation>,
+
+    /// Custom keys to pre-populate the keystore with.
+    pub(crate) keystore: Vec<KeystoreKey>,
+
+    /// Addresses of the custom keys marked as authorities (by key type), used in the
+    /// chain-spec instead of the derived ones.
+    #[serde(default)]
+    pub(crate) authority_keys: HashMap<String, String>,
+
+    /// Files to inject in the node before starting it.
+    pub(crate) injected_files: Vec<InjectedFile>,
+
+    /// Data dir (`--base-path`) to use instead of the derived one.
+    pub(crate) data_dir_override: Option<PathBuf>,
 }
 
 impl NodeSpec {
@@ -122,9 +190,30 @@ impl NodeSpec {
         // Check first if the image is set at node level, then try with the default
         let image = node_config.image().or(chain_context.default_image).cloned();
 
-        // Check first if the command is set at node level, then try with the default
+        // Check first if the command is set at node level (or as the last of the command parts),
+        // then try with the default
+        let command_parts: Vec<String> = node_config
+            .command_parts()
+            .into_iter()
+            .map(str::to_string)
+            .collect();
         let command = if let Some(cmd) = node_config.command() {
+            if let Some(binary) = command_parts
+                .last()
+                .filter(|binary| *binary != cmd.as_str())
+            {
+                warn!(
+                    "⚠️ node '{}': command '{}' is used instead of the last command part '{binary}'",
+                    node_config.name(),
+                    cmd.as_str()
+                );
+            }
             cmd.clone()
+        } else if let Some(cmd) = command_parts
+            .last()
+            .and_then(|binary| Command::try_from(binary.as_str()).ok())
+        {
+            cmd
         } else if let Some(cmd) = chain_context.default_command {
             cmd.clone()
         } else {
@@ -137,8 +226,8 @@ impl NodeSpec {
         let subcommand = node_config.subcommand().cloned();
 
         // If `args` is set at `node` level use them
-        // otherwise use the default_args (can be empty).
-        let args: Vec<Arg> = if node_config.args().is_empty() {
+        // otherwise use the default_args (can be empty), unless the node opted out.
+        let args: Vec<Arg> = if node_config.args().is_empty() && !node_config.no_default_args() {
             chain_context
                 .default_args
                 .iter()
@@ -155,6 +244,29 @@ impl NodeSpec {
         let accounts = generators::generate_node_keys(&seed)?;
         let accounts = NodeAccounts { seed, accounts };
 
+        let authority_keys = node_config
+            .keystore_keys()
+            .into_iter()
+            .filter(|key| key.authority)
+            .map(|key| {
+                generators::address_from_public(&key.key_type, &key.public)
+                    .map(|address| (key.key_type.clone(), address))
+            })
+            .collect::<Result<_, _>>()?;
+
+        let force_args: Vec<String> = node_config
+            .force_args()
+            .into_iter()
+            .map(String::from)
+            .collect();
+        for arg in ineffective_force_args(&force_args) {
+            warn!(
+                "⚠️ node '{}': forced arg '{arg}' has no effect, only {} are added depending on the available args",
+                node_config.name(),
+                OPTIONAL_ARGS.join(", ")
+            );
+        }
+
         let db_snapshot = match (node_config.db_snapshot(), chain_context.default_db_snapshot) {
             (Some(db_snapshot), _) => Some(db_snapshot),
             (None, Some(db_snapshot)) => Some(db_snapshot),
@@ -168,11 +280,25 @@ impl NodeSpec {
             image,
             command,
             subcommand,
+            command_parts,
             args,
+            relay_args: node_config.relay_args().into_iter().cloned().collect(),
+            log_targets: merge_log_targets(
+                chain_context
+                    .default_log_targets
+                    .iter()
+                    .map(|log_target| (*log_target).clone())
+                    .collect(),
+                node_config.log_targets().into_iter().cloned().collect(),
+            ),
+            force_args,
             available_args_output: None,
-            is_validator: node_config.is_validator(),
-            is_invulnerable: node_config.is_invulnerable(),
-            is_bootnode: node_config.is_bootnode(),
+            version: None,
+            // a dedicated bootnode is excluded from the validator/authority sets
+            is_validator: node_config.is_validator() && !node_config.is_dedicated_bootnode(),
+            is_invulnerable: node_config.is_invulnerable() && !node_config.is_dedicated_bootnode(),
+            is_bootnode: node_config.is_bootnode() || node_config.is_dedicated_bootnode(),
+            is_dedicated_bootnode: node_config.is_dedicated_bootnode(),
             initial_balance: node_config.initial_balance(),
             env: node_config.env().into_iter().cloned().collect(),
             bootnodes_addresses: node_config
@@ -182,7 +308,17 @@ impl NodeSpec {
                 .collect(),
             resources: node_config.resources().cloned(),
             p2p_cert_hash: node_config.p2p_cert_hash().map(str::to_string),
+            rpc_external: node_config.rpc_external(),
+            prometheus_external: node_config.prometheus_external(),
+            rpc_methods: node_config.rpc_methods(),
+            listen_addr: node_config.listen_addr().cloned(),
+            session_key_type: node_config.session_key_type(),
+            sync_mode: node_config.sync_mode(),
             db_snapshot: db_snapshot.cloned(),
+            keystore: node_config.keystore_keys().into_iter().cloned().collect(),
+            authority_keys,
+            injected_files: node_config.injected_files().into_iter().cloned().collect(),
+            data_dir_override: node_config.data_dir().map(PathBuf::from),
             accounts,
             ws_port: generators::generate_node_port(node_config.ws_port())?,
             rpc_port: generators::generate_node_port(node_config.rpc_port())?,
@@ -191,6 +327,154 @@ impl NodeSpec {
         })
     }
 
+    /// Version of the node binary (`<command> --version`), `unknown` if not supported.
+    pub fn version(&self) -> Option<&str> {
+        self.version.as_deref()
+    }
+
+    // Re-assign the ports not explicitly set in the `node_config` drawing from the
+    // `port_generator` range. The ws_port is not exposed, so is kept as is.
+    pub(crate) fn assign_ports_from_range(
+        &mut self,
+        node_config: &NodeConfig,
+        port_generator: &mut generators::PortRangeGenerator,
+    ) -> Result<(), OrchestratorError> {
+        if node_config.rpc_port().is_none() {
+            self.rpc_port = port_generator.generate()?;
+        }
+        if node_config.prometheus_port().is_none() {
+            self.prometheus_port = port_generator.generate()?;
+        }
+        if node_config.p2p_port().is_none() {
+            self.p2p_port = port_generator.generate()?;
+        }
+
+        Ok(())
+    }
+
+    // Set the node fields into the `builder` (inverse of [`NodeSpec::from_config`]), values
+    // inherited from the chain defaults (`chain_context`) are not set at node level.
+    pub(crate) fn to_config_builder(
+        &self,
+        builder: NodeConfigBuilder<node::Initial>,
+        chain_context: &ChainDefaultContext,
+    ) -> NodeConfigBuilder<node::Buildable> {
+        let bootnodes_addresses: Vec<String> = self
+            .bootnodes_addresses
+            .iter()
+            .map(|addr| addr.to_string())
+            .collect();
+
+        let mut builder = builder
+            .with_name(self.name.as_str())
+            .validator(self.is_validator)
+            .invulnerable(self.is_invulnerable)
+            .bootnode(self.is_bootnode)
+            .dedicated_bootnode(self.is_dedicated_bootnode)
+            .with_initial_balance(self.initial_balance)
+            .with_env(self.env.clone())
+            .with_bootnodes_addresses(bootnodes_addresses.iter().map(String::as_str).collect())
+            .with_ws_port(self.ws_port.0)
+            .with_rpc_port(self.rpc_port.0)
+            .with_prometheus_port(self.prometheus_port.0)
+            .with_p2p_port(self.p2p_port.0);
+
+        if !self.command_parts.is_empty() {
+            builder = builder.with_command_parts(self.command_parts.clone());
+        }
+
+        if chain_context.default_command != Some(&self.command) {
+            builder = builder.with_command(self.command.clone());
+        }
+
+        if let Some(subcommand) = &self.subcommand {
+            builder = builder.with_subcommand(subcommand.clone());
+        }
+
+        if let Some(image) = &self.image {
+            if chain_context.default_image != Some(image) {
+                builder = builder.with_image(image.clone());
+            }
+        }
+
+        if self.args.is_empty() && !chain_context.default_args.is_empty() {
+            builder = builder.with_no_default_args();
+        } else if self.args.iter().collect::<Vec<_>>() != chain_context.default_args {
+            builder = builder.with_args(self.args.clone());
+        }
+
+        if !self.relay_args.is_empty() {
+            builder = builder.with_relay_args(self.relay_args.clone());
+        }
+
+        if self.log_targets.iter().collect::<Vec<_>>() != chain_context.default_log_targets {
+            builder = builder.with_log_targets(log_target_pairs(&self.log_targets));
+        }
+
+        if !self.force_args.is_empty() {
+            builder = builder.with_force_args(self.force_args.clone());
+        }
+
+        if let Some(resources) = &self.resources {
+            if chain_context.default_resources != Some(resources) {
+                builder = builder.with_resources(resources_builder(resources));
+            }
+        }
+
+        if let Some(p2p_cert_hash) = &self.p2p_cert_hash {
+            builder = builder.with_p2p_cert_hash(p2p_cert_hash.as_str());
+        }
+
+        // external interfaces are the default
+        if !self.rpc_external {
+            builder = builder.with_rpc_external(false);
+        }
+
+        if !self.prometheus_external {
+            builder = builder.with_prometheus_external(false);
+        }
+
+        if self.rpc_methods != RpcMethods::default() {
+            builder = builder.with_rpc_methods(self.rpc_methods);
+        }
+
+        if let Some(listen_addr) = &self.listen_addr {
+            builder = builder.with_listen_addr(listen_addr.to_string().as_str());
+        }
+
+        if let Some(session_key_type) = self.session_key_type {
+            builder = builder.with_session_key_type(session_key_type);
+        }
+
+        if let Some(sync_mode) = self.sync_mode {
+            builder = builder.with_sync_mode(sync_mode);
+        }
+
+        if let Some(db_snapshot) = &self.db_snapshot {
+            if chain_context.default_db_snapshot != Some(db_snapshot) {
+                builder = builder.with_db_snapshot(db_snapshot.clone());
+            }
+        }
+
+        for key in &self.keystore {
+            builder = if key.authority {
+                builder.with_keystore_authority_key(&key.key_type, &key.seed, &key.public)
+            } else {
+                builder.with_keystore_key(&key.key_type, &key.seed, &key.public)
+            };
+        }
+
+        for file in &self.injected_files {
+            builder = builder.with_injected_file(&file.local_path, &file.remote_path);
+        }
+
+        if let Some(data_dir) = &self.data_dir_override {
+            builder = builder.with_data_dir(data_dir.clone());
+        }
+
+        builder
+    }
+
     pub fn from_ad_hoc(
         name: impl Into<String>,
         options: AddNodeSpecOpts,
@@ -248,17 +532,37 @@ impl NodeSpec {
             image,
             command,
             subcommand,
+            command_parts: vec![],
             args,
+            relay_args: vec![],
+            log_targets: chain_context
+                .default_log_targets
+                .iter()
+                .map(|log_target| (*log_target).clone())
+                .collect(),
+            force_args: vec![],
             available_args_output: None,
+            version: None,
             is_validator: options.is_validator,
             is_invulnerable: false,
             is_bootnode: false,
+            is_dedicated_bootnode: false,
             initial_balance: 0,
             env: options.env,
             bootnodes_addresses: vec![],
             resources: None,
             p2p_cert_hash: None,
+            rpc_external: true,
+            prometheus_external: true,
+            rpc_methods: RpcMethods::default(),
+            listen_addr: None,
+            session_key_type: None,
+            sync_mode: None,
             db_snapshot: None,
+            keystore: vec![],
+            authority_keys: Default::default(),
+            injected_files: vec![],
+            data_dir_override: None,
             accounts,
             // should be deprecated now!
             ws_port: generators::generate_node_port(None)?,
@@ -269,15 +573,92 @@ impl NodeSpec {
     }
 
     pub(crate) fn supports_arg(&self, arg: impl AsRef<str>) -> bool {
-        self.available_args_output
+        let arg = arg.as_ref();
+        let available = self
+            .available_args_output
             .as_ref()
             .expect(&format!(
                 "available args should be present at this point {THIS_IS_A_BUG}"
             ))
-            .contains(arg.as_ref())
+            .contains(arg);
+
+        if !available && self.force_args.iter().any(|forced| forced == arg) {
+            warn!(
+                "node '{}': '{arg}' is not in the available args of '{}', forced without validation",
+                self.name,
+                self.command.as_str()
+            );
+            return true;
+        }
+
+        available
     }
 
     pub fn command(&self) -> &str {
         self.command.as_str()
     }
+
+    /// Run the generated `program` and `args` through the wrapper set in the command parts (if
+    /// any), the first part is launched and the rest is prepended to the node command.
+    pub(crate) fn wrap_command(&self, program: String, args: Vec<String>) -> (String, Vec<String>) {
+        match self.command_parts.split_last() {
+            Some((_, [wrapper, wrapper_args @ ..])) => {
+                let mut wrapped_args = wrapper_args.to_vec();
+                wrapped_args.push(program);
+                wrapped_args.extend(args);
+                (wrapper.clone(), wrapped_args)
+            },
+            _ => (program, args),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(args: &[&str]) -> Vec<String> {
+        args.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    #[test]
+    fn ineffective_force_args_should_return_the_args_not_added_conditionally() {
+        let force_args = args(&["--insecure-validator-i-know-what-i-do", "--validator"]);
+
+        assert_eq!(ineffective_force_args(&force_args), vec!["--validator"]);
+        assert!(ineffective_force_args(&[]).is_empty());
+    }
+
+    #[test]
+    fn wrap_command_should_run_the_command_through_the_wrapper() {
+        let node = NodeSpec {
+            command_parts: args(&["taskset", "-c", "0-3", "polkadot"]),
+            ..Default::default()
+        };
+
+        let (program, wrapped_args) =
+            node.wrap_command("/bin/polkadot".into(), args(&["--chain", "rococo-local"]));
+
+        assert_eq!(program, "taskset");
+        assert_eq!(
+            wrapped_args,
+            args(&["-c", "0-3", "/bin/polkadot", "--chain", "rococo-local"])
+        );
+    }
+
+    #[test]
+    fn wrap_command_should_keep_the_command_without_wrapper() {
+        for command_parts in [vec![], args(&["polkadot"])] {
+            let node = NodeSpec {
+                command_parts,
+                ..Default::default()
+            };
+
+            let (program, wrapped_args) =
+                node.wrap_command("polkadot".into(), args(&["--chain", "rococo-local"]));
+
+            assert_eq!(program, "polkadot");
+            assert_eq!(wrapped_args, args(&["--chain", "rococo-local"]));
+        }
+    }
 }
@@ -1,16 +1,17 @@
 use std::{collections::HashMap, path::PathBuf};
 
 use configuration::{
-    shared::resources::Resources,
-    types::{Arg, AssetLocation, Command, Image},
-    ParachainConfig, RegistrationStrategy,
+    para_states,
+    shared::{chain_properties::ChainProperties, resources::Resources},
+    types::{Arg, AssetLocation, BootnodeStrategy, ChainSpecModifier, Command, Image, LogTarget},
+    ParachainConfig, ParachainConfigBuilder, RegistrationStrategy,
 };
 use provider::DynNamespace;
 use serde::Serialize;
-use support::{fs::FileSystem, replacer::apply_replacements};
+use support::{constants::THIS_IS_A_BUG, fs::FileSystem, replacer::apply_replacements};
 use tracing::debug;
 
-use super::node::NodeSpec;
+use super::{chain_properties_builder, log_target_pairs, node::NodeSpec, resources_builder};
 use crate::{
     errors::OrchestratorError,
     generators::{
@@ -43,15 +44,41 @@ pub struct ParachainSpec {
     /// Default arguments to use in nodes. Can be overridden on each node.
     pub(crate) default_args: Vec<Arg>,
 
+    /// Default log targets of the nodes. Merged with the node ones.
+    pub(crate) default_log_targets: Vec<LogTarget>,
+
     /// Chain-spec, only needed by cumulus based paras
     pub(crate) chain_spec: Option<ChainSpec>,
 
+    /// Runtime (wasm) to override in the raw chain-spec.
+    pub(crate) wasm_override: Option<AssetLocation>,
+
+    /// Raw storage entries (key/value) to inject in the raw chain-spec.
+    pub(crate) genesis_raw_entries: Vec<(String, String)>,
+
+    /// Strategy used to select the bootnodes.
+    pub(crate) bootnode_strategy: BootnodeStrategy,
+
+    /// Closure to post-process the generated (plain) chain-spec.
+    #[serde(skip)]
+    pub(crate) chain_spec_modifier: Option<ChainSpecModifier>,
+
+    /// Properties to set in the chain-spec (e.g. `tokenDecimals`).
+    pub(crate) chain_properties: Option<ChainProperties>,
+
     /// Registration strategy to use
     pub(crate) registration_strategy: RegistrationStrategy,
 
     /// Onboard as parachain or parathread
     pub(crate) onboard_as_parachain: bool,
 
+    /// Seed of the account used to register the parachain (with an extrinsic)
+    #[serde(skip)]
+    pub(crate) registration_seed: Option<[u8; 32]>,
+
+    /// Wait until the registration extrinsic is finalized
+    pub(crate) wait_for_finalization: bool,
+
     /// Is the parachain cumulus-based
     pub(crate) is_cumulus_based: bool,
 
@@ -70,12 +97,23 @@ pub struct ParachainSpec {
     /// Genesis overrides as JSON value.
     pub(crate) genesis_overrides: Option<serde_json::Value>,
 
+    /// External relaychain rpc endpoint used by the collators (cumulus only).
+    pub(crate) relay_rpc_url: Option<String>,
+
+    /// Name of the relaychain node the collators use as rpc endpoint (cumulus only).
+    pub(crate) relay_rpc_node: Option<String>,
+
     /// Collators to spawn
     pub(crate) collators: Vec<NodeSpec>,
 }
 
 impl ParachainSpec {
     pub fn from_config(config: &ParachainConfig) -> Result<ParachainSpec, OrchestratorError> {
+        // Commands set from env vars are resolved at spawn time
+        let config = &config
+            .with_resolved_commands()
+            .map_err(|err| OrchestratorError::InvalidConfig(err.to_string()))?;
+
         let main_cmd = if let Some(cmd) = config.default_command() {
             cmd
         } else if let Some(first_node) = config.collators().first() {
@@ -143,6 +181,7 @@ impl ParachainSpec {
             default_resources: config.default_resources(),
             default_db_snapshot: config.default_db_snapshot(),
             default_args: config.default_args(),
+            default_log_targets: config.default_log_targets(),
         };
 
         // We want to track the errors for all the nodes and report them ones
@@ -191,6 +230,19 @@ impl ParachainSpec {
             .image(main_image.clone())
         };
 
+        let registration_seed = config
+            .registration_seed()
+            .map(|seed| {
+                hex::decode(seed.trim_start_matches("0x"))
+                    .ok()
+                    .and_then(|bytes| <[u8; 32]>::try_from(bytes).ok())
+                    .ok_or(OrchestratorError::InvalidConfig(format!(
+                        "Parachain {}, invalid registration_seed",
+                        config.id()
+                    )))
+            })
+            .transpose()?;
+
         let para_spec = ParachainSpec {
             id: config.id(),
             default_command: config.default_command().cloned(),
@@ -198,24 +250,167 @@ impl ParachainSpec {
             default_resources: config.default_resources().cloned(),
             default_db_snapshot: config.default_db_snapshot().cloned(),
             default_args: config.default_args().into_iter().cloned().collect(),
+            default_log_targets: config.default_log_targets().into_iter().cloned().collect(),
             chain_spec,
+            wasm_override: config.wasm_override().cloned(),
+            genesis_raw_entries: config
+                .genesis_raw_entries()
+                .into_iter()
+                .map(|(key, value)| (key.to_string(), value.to_string()))
+                .collect(),
+            bootnode_strategy: config.bootnode_strategy(),
+            chain_spec_modifier: config.chain_spec_modifier().cloned(),
+            chain_properties: config.chain_properties().cloned(),
             registration_strategy: config
                 .registration_strategy()
                 .unwrap_or(&RegistrationStrategy::InGenesis)
                 .clone(),
             onboard_as_parachain: config.onboard_as_parachain(),
+            registration_seed,
+            wait_for_finalization: config.wait_for_finalization(),
             is_cumulus_based: config.is_cumulus_based(),
             is_evm_based: config.is_evm_based(),
             initial_balance: config.initial_balance(),
             genesis_state,
             genesis_wasm,
             genesis_overrides: config.genesis_overrides().cloned(),
+            relay_rpc_url: config.relay_rpc_url().map(|url| url.to_string()),
+            relay_rpc_node: config.relay_rpc_node().map(str::to_string),
             collators,
         };
 
         Ok(para_spec)
     }
 
+    // Set the parachain fields (and collators) into the `builder`, inverse of
+    // [`ParachainSpec::from_config`]. The parachain should have at least one collator.
+    pub(crate) fn to_config_builder(
+        &self,
+        builder: ParachainConfigBuilder<para_states::Initial, para_states::Bootstrap>,
+    ) -> ParachainConfigBuilder<para_states::WithAtLeastOneCollator, para_states::Bootstrap> {
+        let (first, rest) = self.collators.split_first().expect(&format!(
+            "parachain {} should have at least one collator {THIS_IS_A_BUG}",
+            self.id
+        ));
+
+        let mut builder = builder
+            .with_id(self.id)
+            .with_registration_strategy(self.registration_strategy.clone())
+            .onboard_as_parachain(self.onboard_as_parachain)
+            .wait_for_finalization(self.wait_for_finalization)
+            .with_initial_balance(self.initial_balance)
+            .with_default_args(self.default_args.clone())
+            .with_default_log_targets(log_target_pairs(&self.default_log_targets))
+            .with_genesis_raw_entries(self.genesis_raw_entries.clone())
+            .cumulus_based(self.is_cumulus_based)
+            .evm_based(self.is_evm_based);
+
+        if self.bootnode_strategy != BootnodeStrategy::default() {
+            builder = builder.with_bootnode_strategy(self.bootnode_strategy);
+        }
+
+        if let Some(chain_spec) = &self.chain_spec {
+            if let Some(chain_name) = chain_spec.chain_name().filter(|name| !name.is_empty()) {
+                builder = builder.with_chain(chain_name);
+            }
+            if let Some(location) = chain_spec.get_asset_location() {
+                builder = builder.with_chain_spec_path(location.clone());
+            }
+        }
+
+        if let Some(url) = &self.relay_rpc_url {
+            builder = builder.with_relay_rpc_url(url);
+        }
+
+        if let Some(node_name) = &self.relay_rpc_node {
+            builder = builder.with_relay_rpc_node(node_name.as_str());
+        }
+
+        if let Some(seed) = &self.registration_seed {
+            builder = builder.with_registration_seed(format!("0x{}", hex::encode(seed)));
+        }
+
+        if let Some(command) = &self.default_command {
+            builder = builder.with_default_command(command.clone());
+        }
+
+        if let Some(image) = &self.default_image {
+            builder = builder.with_default_image(image.clone());
+        }
+
+        if let Some(resources) = &self.default_resources {
+            builder = builder.with_default_resources(resources_builder(resources));
+        }
+
+        if let Some(db_snapshot) = &self.default_db_snapshot {
+            builder = builder.with_default_db_snapshot(db_snapshot.clone());
+        }
+
+        if let Some(modifier) = self.chain_spec_modifier.clone() {
+            builder = builder.with_chain_spec_modifier(move |spec| modifier.apply(spec));
+        }
+
+        if let Some(properties) = &self.chain_properties {
+            builder = builder.with_chain_properties(chain_properties_builder(properties));
+        }
+
+        // generators using the main command are the default ones
+        let main_cmd = self
+            .default_command
+            .as_ref()
+            .unwrap_or(&first.command)
+            .as_str();
+
+        builder = match self.genesis_state.build_option() {
+            ParaArtifactBuildOption::Path(path) => builder.with_genesis_state_path(path.as_str()),
+            ParaArtifactBuildOption::Command(cmd) if cmd != main_cmd => {
+                builder.with_genesis_state_generator(cmd.as_str())
+            },
+            ParaArtifactBuildOption::CommandWithCustomArgs(cmd) => {
+                builder.with_genesis_state_generator(cmd.clone())
+            },
+            ParaArtifactBuildOption::Command(_) => builder,
+        };
+
+        builder = match self.genesis_wasm.build_option() {
+            ParaArtifactBuildOption::Path(path) => builder.with_genesis_wasm_path(path.as_str()),
+            ParaArtifactBuildOption::Command(cmd) if cmd != main_cmd => {
+                builder.with_genesis_wasm_generator(cmd.as_str())
+            },
+            ParaArtifactBuildOption::CommandWithCustomArgs(cmd) => {
+                builder.with_genesis_wasm_generator(cmd.cmd().clone())
+            },
+            ParaArtifactBuildOption::Command(_) => builder,
+        };
+
+        if let Some(genesis_overrides) = &self.genesis_overrides {
+            builder = builder.with_genesis_overrides(genesis_overrides.clone());
+        }
+
+        if let Some(wasm_override) = &self.wasm_override {
+            builder = builder.with_wasm_override(wasm_override.clone());
+        }
+
+        let chain_context = ChainDefaultContext {
+            default_command: self.default_command.as_ref(),
+            default_image: self.default_image.as_ref(),
+            default_resources: self.default_resources.as_ref(),
+            default_db_snapshot: self.default_db_snapshot.as_ref(),
+            default_args: self.default_args.iter().collect(),
+            default_log_targets: self.default_log_targets.iter().collect(),
+        };
+
+        let mut builder =
+            builder.with_collator(|collator| first.to_config_builder(collator, &chain_context));
+        for collator_spec in rest {
+            builder = builder.with_collator(|collator| {
+                collator_spec.to_config_builder(collator, &chain_context)
+            });
+        }
+
+        builder
+    }
+
     pub fn registration_strategy(&self) -> &RegistrationStrategy {
         &self.registration_strategy
     }
@@ -250,6 +445,10 @@ impl ParachainSpec {
         self.chain_spec.as_mut()
     }
 
+    pub fn collators(&self) -> &[NodeSpec] {
+        &self.collators
+    }
+
     /// Build parachain chain-spec
     ///
     /// This function customize the chain-spec (if is possible) and build the raw version
@@ -275,6 +474,18 @@ impl ParachainSpec {
             debug!("parachain chain-spec customized!");
             chain_spec.build_raw(ns, scoped_fs).await?;
             debug!("parachain chain-spec raw built!");
+
+            if let Some(wasm_override) = cloned.wasm_override.as_ref() {
+                chain_spec.override_code(scoped_fs, wasm_override).await?;
+                debug!("parachain chain-spec code overridden!");
+            }
+
+            if !cloned.genesis_raw_entries.is_empty() {
+                chain_spec
+                    .override_raw_entries(scoped_fs, &cloned.genesis_raw_entries)
+                    .await?;
+                debug!("parachain chain-spec raw entries injected!");
+            }
             let chain_spec_raw_path =
                 chain_spec
                     .raw_path()
@@ -2,8 +2,12 @@ use std::collections::HashMap;
 
 use configuration::{
     shared::{
+        chain_properties::ChainProperties,
         resources::Resources,
-        types::{Arg, AssetLocation, Chain, Command, Image},
+        types::{
+            Arg, AssetLocation, BootnodeStrategy, Chain, ChainSpecModifier, Command, Image,
+            LogTarget,
+        },
     },
     RelaychainConfig,
 };
@@ -14,7 +18,10 @@ use super::node::NodeSpec;
 use crate::{
     errors::OrchestratorError,
     generators::chain_spec::{ChainSpec, Context},
-    shared::{constants::DEFAULT_CHAIN_SPEC_TPL_COMMAND, types::ChainDefaultContext},
+    shared::{
+        constants::{DEFAULT_CHAIN_SPEC_TPL_COMMAND, DEFAULT_MAX_NOMINATIONS},
+        types::ChainDefaultContext,
+    },
 };
 
 /// A relaychain configuration spec
@@ -38,9 +45,28 @@ pub struct RelaychainSpec {
     /// Default arguments to use in nodes. Can be overridden on each node.
     pub(crate) default_args: Vec<Arg>,
 
+    /// Default log targets of the nodes. Merged with the node ones.
+    pub(crate) default_log_targets: Vec<LogTarget>,
+
     // chain_spec_path: Option<AssetLocation>,
     pub(crate) chain_spec: ChainSpec,
 
+    /// Runtime (wasm) to override in the raw chain-spec.
+    pub(crate) wasm_override: Option<AssetLocation>,
+
+    /// Raw storage entries (key/value) to inject in the raw chain-spec.
+    pub(crate) genesis_raw_entries: Vec<(String, String)>,
+
+    /// Strategy used to select the bootnodes.
+    pub(crate) bootnode_strategy: BootnodeStrategy,
+
+    /// Closure to post-process the generated (plain) chain-spec.
+    #[serde(skip)]
+    pub(crate) chain_spec_modifier: Option<ChainSpecModifier>,
+
+    /// Properties to set in the chain-spec (e.g. `tokenDecimals`).
+    pub(crate) chain_properties: Option<ChainProperties>,
+
     /// Set the count of nominators to generator (used with PoS networks).
     pub(crate) random_nominators_count: u32,
 
@@ -56,6 +82,11 @@ pub struct RelaychainSpec {
 
 impl RelaychainSpec {
     pub fn from_config(config: &RelaychainConfig) -> Result<RelaychainSpec, OrchestratorError> {
+        // Commands set from env vars are resolved at spawn time
+        let config = &config
+            .with_resolved_commands()
+            .map_err(|err| OrchestratorError::InvalidConfig(err.to_string()))?;
+
         // Relaychain main command to use, in order:
         // set as `default_command` or
         // use the command of the first node.
@@ -103,6 +134,7 @@ impl RelaychainSpec {
             default_resources: config.default_resources(),
             default_db_snapshot: config.default_db_snapshot(),
             default_args: config.default_args(),
+            default_log_targets: config.default_log_targets(),
         };
 
         let (nodes, mut errs) = config
@@ -129,9 +161,19 @@ impl RelaychainSpec {
             default_resources: config.default_resources().cloned(),
             default_db_snapshot: config.default_db_snapshot().cloned(),
             default_args: config.default_args().into_iter().cloned().collect(),
+            default_log_targets: config.default_log_targets().into_iter().cloned().collect(),
             chain_spec,
+            wasm_override: config.wasm_override().cloned(),
+            genesis_raw_entries: config
+                .genesis_raw_entries()
+                .into_iter()
+                .map(|(key, value)| (key.to_string(), value.to_string()))
+                .collect(),
+            bootnode_strategy: config.bootnode_strategy(),
+            chain_spec_modifier: config.chain_spec_modifier().cloned(),
+            chain_properties: config.chain_properties().cloned(),
             random_nominators_count: config.random_nominators_count().unwrap_or(0),
-            max_nominations: config.max_nominations().unwrap_or(24),
+            max_nominations: config.max_nominations().unwrap_or(DEFAULT_MAX_NOMINATIONS),
             runtime_genesis_patch: config.runtime_genesis_patch().cloned(),
             nodes,
         })
@@ -144,4 +186,8 @@ impl RelaychainSpec {
     pub fn chain_spec_mut(&mut self) -> &mut ChainSpec {
         &mut self.chain_spec
     }
+
+    pub fn nodes(&self) -> &[NodeSpec] {
+        &self.nodes
+    }
 }
@@ -9,3 +9,11 @@ pub const P2P_PORT: u16 = 30333;
 // default command template to build chain-spec
 pub const DEFAULT_CHAIN_SPEC_TPL_COMMAND: &str =
     "{{mainCommand}} build-spec --chain {{chainName}} {{disableBootnodes}}";
+// token (in the node args) replaced by the node base dir, to reference the injected files
+pub const NODE_BASE_DIR_TOKEN: &str = "nodeBaseDir";
+// directory (inside the base_dir) to cache the remote assets
+pub const ASSETS_CACHE_DIR: &str = "assets-cache";
+// default max nominations (used with PoS networks)
+pub const DEFAULT_MAX_NOMINATIONS: u8 = 24;
+// version stored for the node binaries that don't support `--version`
+pub const UNKNOWN_NODE_VERSION: &str = "unknown";
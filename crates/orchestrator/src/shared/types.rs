@@ -3,11 +3,12 @@ use std::{
     net::TcpListener,
     path::PathBuf,
     sync::{Arc, RwLock},
+    time::Duration,
 };
 
 use configuration::shared::{
     resources::Resources,
-    types::{Arg, AssetLocation, Command, Image, Port},
+    types::{Arg, AssetLocation, Command, Image, LogTarget, Port},
 };
 use serde::{Deserialize, Serialize};
 
@@ -60,6 +61,19 @@ pub struct ChainDefaultContext<'a> {
     pub default_resources: Option<&'a Resources>,
     pub default_db_snapshot: Option<&'a AssetLocation>,
     pub default_args: Vec<&'a Arg>,
+    pub default_log_targets: Vec<&'a LogTarget>,
+}
+
+/// The extrinsics used to register a parachain (always wrapped in `Sudo.sudo`).
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum RegistrationMethod {
+    /// Use `ParasSudoWrapper` if the relaychain runtime includes it, `Registrar` otherwise.
+    #[default]
+    Auto,
+    /// `ParasSudoWrapper.sudo_schedule_para_initialize`
+    ParasSudoWrapper,
+    /// `Registrar.force_register` (and `Slots.force_lease` to onboard as parachain)
+    Registrar,
 }
 
 #[derive(Debug, Clone)]
@@ -71,6 +85,7 @@ pub struct RegisterParachainOptions {
     pub onboard_as_para: bool,
     pub seed: Option<[u8; 32]>,
     pub finalization: bool,
+    pub method: RegistrationMethod,
 }
 
 pub struct RuntimeUpgradeOptions {
@@ -91,6 +106,79 @@ impl RuntimeUpgradeOptions {
         }
     }
 }
+/// Report of the artifacts generated by [`crate::Orchestrator::spawn_dry_run`].
+/// All the paths (except `base_dir`) are relative to the `base_dir`.
+#[derive(Debug, Clone, Serialize)]
+pub struct DryRunReport {
+    pub base_dir: PathBuf,
+    /// Raw chain-spec of the relaychain
+    pub relay_chain_spec: PathBuf,
+    pub parachains: Vec<DryRunParaReport>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DryRunParaReport {
+    pub id: u32,
+    /// Raw chain-spec of the parachain (IFF the para is cumulus based)
+    pub chain_spec: Option<PathBuf>,
+    pub genesis_state: Option<PathBuf>,
+    pub genesis_wasm: Option<PathBuf>,
+}
+
+/// Paths of the artifacts generated for a running network, returned by
+/// [`crate::network::Network::artifacts`]. All the paths are absolute.
+#[derive(Debug, Clone, Serialize)]
+pub struct NetworkArtifacts {
+    pub base_dir: PathBuf,
+    /// Network state file
+    pub zombie_json: PathBuf,
+    /// Raw chain-spec of the relaychain
+    pub relay_chain_spec: PathBuf,
+    /// Artifacts of the parachains, sorted by para id
+    pub parachains: Vec<ParaArtifacts>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ParaArtifacts {
+    pub id: u32,
+    /// Raw chain-spec of the parachain (IFF the para is cumulus based)
+    pub chain_spec: Option<PathBuf>,
+    pub genesis_state: Option<PathBuf>,
+    pub genesis_wasm: Option<PathBuf>,
+}
+
+/// Duration of each phase of the network spawn, see [`crate::network::Network::spawn_timings`].
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct SpawnTimings {
+    /// Build the relaychain chain-spec (plain, customized and raw)
+    pub relay_chain_spec: Duration,
+    /// Build the parachains artifacts (chain-spec, genesis state/wasm)
+    pub para_artifacts: Duration,
+    /// Spawn the relaychain nodes
+    pub relay_nodes: Duration,
+    /// Spawn the parachains nodes
+    pub para_nodes: Duration,
+    /// Register the parachains with an extrinsic
+    pub registration: Duration,
+    /// Whole spawn, including the phases above
+    pub total: Duration,
+}
+
+impl std::fmt::Display for SpawnTimings {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "relay chain-spec: {:?}, para artifacts: {:?}, relay nodes: {:?}, para nodes: {:?}, registration: {:?} (total: {:?})",
+            self.relay_chain_spec,
+            self.para_artifacts,
+            self.relay_nodes,
+            self.para_nodes,
+            self.registration,
+            self.total
+        )
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct ParachainGenesisArgs {
     pub genesis_head: String,
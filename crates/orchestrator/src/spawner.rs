@@ -1,20 +1,21 @@
-use std::{collections::HashMap, path::PathBuf};
+use std::{collections::HashMap, net::SocketAddr, path::PathBuf, time::Duration};
 
 use anyhow::Context;
+use configuration::GlobalSettings;
 use provider::{
     constants::{LOCALHOST, NODE_CONFIG_DIR, NODE_DATA_DIR, NODE_RELAY_DATA_DIR, P2P_PORT},
     shared::helpers::running_in_ci,
-    types::{SpawnNodeOptions, TransferedFile},
+    types::{LogRotation, SpawnNodeOptions, TransferedFile},
     DynNamespace,
 };
-use support::{constants::THIS_IS_A_BUG, fs::FileSystem};
+use support::{constants::THIS_IS_A_BUG, fs::FileSystem, replacer::apply_replacements};
 use tracing::info;
 
 use crate::{
     generators,
     network::node::NetworkNode,
     network_spec::{node::NodeSpec, parachain::ParachainSpec},
-    shared::constants::{PROMETHEUS_PORT, RPC_PORT},
+    shared::constants::{NODE_BASE_DIR_TOKEN, PROMETHEUS_PORT, RPC_PORT},
     ScopedFilesystem, ZombieRole,
 };
 
@@ -39,6 +40,10 @@ pub struct SpawnNodeCtx<'a, T: FileSystem> {
     /// Flag to wait node is ready or not
     /// Ready state means we can query Prometheus internal server
     pub(crate) wait_ready: bool,
+    /// Ref to the network global settings (e.g. used to build the endpoints)
+    pub(crate) global_settings: &'a GlobalSettings,
+    /// Relaychain rpc endpoint used by the collators instead of an embedded full node
+    pub(crate) relay_rpc_url: Option<&'a str>,
 }
 
 pub async fn spawn_node<'a, T>(
@@ -50,37 +55,59 @@ where
     T: FileSystem,
 {
     let mut created_paths = vec![];
+    let node_files_path = if let Some(para) = ctx.parachain {
+        para.id.to_string()
+    } else {
+        node.name.clone()
+    };
+    let mut key_filenames = vec![];
+
+    // Collators connected to an external relaychain rpc don't need the relaychain chain-spec
+    if ctx.relay_rpc_url.is_some() {
+        let relay_chain_spec = PathBuf::from(format!("/cfg/{}.json", ctx.chain));
+        files_to_inject.retain(|file| file.remote_path != relay_chain_spec);
+    }
+
     // Create and inject the keystore IFF
     // - The node is validator in the relaychain
     // - The node is collator (encoded as validator) and the parachain is cumulus_based
     // (parachain_id) should be set then.
     if node.is_validator && (ctx.parachain.is_none() || ctx.parachain_id.is_some()) {
         // Generate keystore for node
-        let node_files_path = if let Some(para) = ctx.parachain {
-            para.id.to_string()
-        } else {
-            node.name.clone()
-        };
         let asset_hub_polkadot = ctx
             .parachain_id
             .map(|id| id.starts_with("asset-hub-polkadot"))
             .unwrap_or_default();
-        let key_filenames = generators::generate_node_keystore(
-            &node.accounts,
-            &node_files_path,
-            ctx.scoped_fs,
-            asset_hub_polkadot,
-        )
-        .await
-        .unwrap();
+        key_filenames.extend(
+            generators::generate_node_keystore(
+                &node.accounts,
+                &node_files_path,
+                ctx.scoped_fs,
+                asset_hub_polkadot,
+            )
+            .await
+            .unwrap(),
+        );
+    }
+
+    // Custom keys are always injected, even if the node is not a validator
+    if !node.keystore.is_empty() {
+        key_filenames.extend(
+            generators::generate_custom_keystore(&node.keystore, &node_files_path, ctx.scoped_fs)
+                .await?,
+        );
+    }
+
+    let remote_keystore_chain_id = if let Some(id) = ctx.parachain_id {
+        id
+    } else {
+        ctx.chain_id
+    };
+    let has_keystore = !key_filenames.is_empty();
 
+    if has_keystore {
         // Paths returned are relative to the base dir, we need to convert into
         // fullpaths to inject them in the nodes.
-        let remote_keystore_chain_id = if let Some(id) = ctx.parachain_id {
-            id
-        } else {
-            ctx.chain_id
-        };
 
         for key_filename in key_filenames {
             let f = TransferedFile::new(
@@ -104,6 +131,17 @@ where
         )));
     }
 
+    // Custom files (e.g. a node key) are injected as-is, creating their parent dir if needed
+    for file in &node.injected_files {
+        if let Some(parent) = file.remote_path.parent() {
+            created_paths.push(parent.to_path_buf());
+        }
+        files_to_inject.push(TransferedFile::new(
+            file.local_path.clone(),
+            file.remote_path.clone(),
+        ));
+    }
+
     let base_dir = format!("{}/{}", ctx.ns.base_dir().to_string_lossy(), &node.name);
 
     let (cfg_path, data_path, relay_data_path) = if !ctx.ns.capabilities().prefix_with_full_path {
@@ -119,20 +157,43 @@ where
         (cfg_path, data_path, relay_data_path)
     };
 
+    // the keys are injected in the derived data dir, so if the data dir is overridden
+    // we need to point the node to them
+    let keystore_path = match &node.data_dir_override {
+        Some(_) if has_keystore => Some(format!(
+            "{data_path}/chains/{remote_keystore_chain_id}/keystore"
+        )),
+        _ => None,
+    };
+    let data_path = match &node.data_dir_override {
+        Some(data_dir) => data_dir.to_string_lossy().to_string(),
+        None => data_path,
+    };
+
     let gen_opts = generators::GenCmdOptions {
         relay_chain_name: ctx.chain,
         cfg_path: &cfg_path,               // TODO: get from provider/ns
         data_path: &data_path,             // TODO: get from provider
         relay_data_path: &relay_data_path, // TODO: get from provider
-        use_wrapper: false,                // TODO: get from provider
+        keystore_path: keystore_path.as_deref(),
+        use_wrapper: false, // TODO: get from provider
         bootnode_addr: ctx.bootnodes_addr.clone(),
         // IFF the provider require an image (e.g k8s) we should use the default ports in the cmd.
         use_default_ports_in_cmd: ctx.ns.capabilities().use_default_ports_in_cmd,
+        relay_rpc_url: ctx.relay_rpc_url,
+    };
+
+    let role = if node.is_dedicated_bootnode && ctx.parachain.is_none() {
+        ZombieRole::Bootnode
+    } else {
+        ctx.role.clone()
     };
 
-    let (program, args) = match ctx.role {
+    // p2p port of the full node embedded in cumulus collators
+    let mut full_p2p_port = None;
+    let (program, args) = match role {
         // Collator should be `non-cumulus` one (e.g adder/undying)
-        ZombieRole::Node | ZombieRole::Collator => {
+        ZombieRole::Node | ZombieRole::Bootnode | ZombieRole::Collator => {
             let maybe_para_id = ctx.parachain.map(|para| para.id);
 
             generators::generate_node_command(node, gen_opts, maybe_para_id)
@@ -142,12 +203,25 @@ where
                 "parachain must be part of the context {THIS_IS_A_BUG}"
             ));
             let full_p2p = generators::generate_node_port(None)?;
+            full_p2p_port = Some(full_p2p.0);
             generators::generate_node_command_cumulus(node, gen_opts, para.id, full_p2p.0)
         },
         _ => unreachable!(), /* TODO: do we need those?
-                              * ZombieRole::Bootnode => todo!(),
                               * ZombieRole::Companion => todo!(), */
     };
+    // the injected files are referenced from the node base dir (empty inside a container)
+    let node_base_dir = if ctx.ns.capabilities().prefix_with_full_path {
+        base_dir.as_str()
+    } else {
+        ""
+    };
+    let replacements = HashMap::from([(NODE_BASE_DIR_TOKEN, node_base_dir)]);
+    let args = args
+        .iter()
+        .map(|arg| apply_replacements(arg, &replacements))
+        .collect();
+
+    let (program, args) = node.wrap_command(program, args);
 
     info!(
         "🚀 {}, spawning.... with command: {} {}",
@@ -158,17 +232,23 @@ where
 
     let ports = if ctx.ns.capabilities().use_default_ports_in_cmd {
         // should use default ports to as internal
-        [
+        HashMap::from([
             (P2P_PORT, node.p2p_port.0),
             (RPC_PORT, node.rpc_port.0),
             (PROMETHEUS_PORT, node.prometheus_port.0),
-        ]
+        ])
     } else {
+        // ports are used directly in the cmd (e.g native)
         [
-            (P2P_PORT, P2P_PORT),
-            (RPC_PORT, RPC_PORT),
-            (PROMETHEUS_PORT, PROMETHEUS_PORT),
+            node.p2p_port.0,
+            node.rpc_port.0,
+            node.prometheus_port.0,
+            node.ws_port.0,
         ]
+        .into_iter()
+        .chain(full_p2p_port)
+        .map(|port| (port, port))
+        .collect()
     };
 
     let spawn_ops = SpawnNodeOptions::new(node.name.clone(), program)
@@ -181,7 +261,20 @@ where
         .injected_files(files_to_inject)
         .created_paths(created_paths)
         .db_snapshot(node.db_snapshot.clone())
-        .port_mapping(HashMap::from(ports));
+        .port_mapping(ports)
+        .log_rotation(
+            ctx.global_settings
+                .log_max_bytes()
+                .map(|max_bytes| LogRotation {
+                    max_bytes,
+                    max_files: ctx.global_settings.log_max_files(),
+                }),
+        )
+        .stop_grace(
+            ctx.global_settings
+                .stop_grace()
+                .map(|secs| Duration::from_secs(secs.into())),
+        );
 
     let spawn_ops = if let Some(image) = node.image.as_ref() {
         spawn_ops.image(image.as_str())
@@ -189,6 +282,14 @@ where
         spawn_ops
     };
 
+    // only forward the resources to providers that can enforce them
+    let spawn_ops = match node.resources.as_ref() {
+        Some(resources) if ctx.ns.capabilities().has_resources => {
+            spawn_ops.resources(resources.clone())
+        },
+        _ => spawn_ops,
+    };
+
     // Drops the port parking listeners before spawn
     node.ws_port.drop_listener();
     node.p2p_port.drop_listener();
@@ -206,8 +307,13 @@ where
 
     let (rpc_port_external, prometheus_port_external);
 
-    // Create port-forward iff we are  in CI and with k8s provider
-    if running_in_ci() && ctx.ns.capabilities().use_default_ports_in_cmd {
+    // Use the node ip iff we are in CI with k8s provider and the endpoints are bound externally,
+    // else the endpoints are only reachable through a port-forward.
+    if running_in_ci()
+        && ctx.ns.capabilities().use_default_ports_in_cmd
+        && node.rpc_external
+        && node.prometheus_external
+    {
         // running kubernets in ci require to use ip and default port
         (rpc_port_external, prometheus_port_external) = (RPC_PORT, PROMETHEUS_PORT);
         ip_to_use = running_node.ip().await?;
@@ -225,8 +331,19 @@ where
         );
     }
 
-    let ws_uri = format!("ws://{}:{}", ip_to_use, rpc_port_external);
-    let prometheus_uri = format!("http://{}:{}/metrics", ip_to_use, prometheus_port_external);
+    let rpc_scheme = ctx.global_settings.rpc_scheme();
+    let path_prefix = ctx.global_settings.rpc_path_prefix().unwrap_or_default();
+    // `SocketAddr` wraps IPv6 addresses in brackets, as required in the uris
+    let ws_uri = format!(
+        "{}://{}{path_prefix}",
+        rpc_scheme.ws_scheme(),
+        SocketAddr::new(ip_to_use, rpc_port_external)
+    );
+    let prometheus_uri = format!(
+        "{}://{}{path_prefix}/metrics",
+        rpc_scheme.http_scheme(),
+        SocketAddr::new(ip_to_use, prometheus_port_external)
+    );
     info!("🚀 {}, should be running now", node.name);
     info!(
         "💻 {}: direct link https://polkadot.js.org/apps/?rpc={ws_uri}#/explorer",
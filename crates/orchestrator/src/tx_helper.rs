@@ -1,3 +1,4 @@
 // pub mod register_para;
 // pub mod validator_actions;
+pub mod hrmp;
 pub mod runtime_upgrade;
@@ -0,0 +1,136 @@
+use anyhow::anyhow;
+use subxt::{
+    dynamic::Value,
+    ext::scale_value::{Composite, ValueDef},
+    tx::TxStatus,
+    utils::H256,
+    OnlineClient, SubstrateConfig,
+};
+use subxt_signer::sr25519::Keypair;
+use tracing::{debug, info};
+
+use crate::network::node::NetworkNode;
+
+/// Open an HRMP channel between `sender` and `recipient`, returning the hash of the
+/// block where the extrinsic was included.
+///
+/// This call 'Hrmp.force_open_hrmp_channel' wrapped in 'Sudo.sudo'
+pub async fn open_channel(
+    node: &NetworkNode,
+    sender: u32,
+    recipient: u32,
+    max_capacity: u32,
+    max_message_size: u32,
+    sudo: &Keypair,
+) -> Result<H256, anyhow::Error> {
+    debug!(
+        "Opening hrmp channel {sender} -> {recipient}, using node: {} with endpoint {}",
+        node.name, node.ws_uri
+    );
+    let api: OnlineClient<SubstrateConfig> = node.wait_client().await?;
+
+    for pallet in ["Hrmp", "Sudo"] {
+        if api.metadata().pallet_by_name(pallet).is_none() {
+            return Err(anyhow!(
+                "Relaychain runtime doesn't include the '{pallet}' pallet, can't open hrmp channel"
+            ));
+        }
+    }
+
+    let open_channel = subxt::dynamic::tx(
+        "Hrmp",
+        "force_open_hrmp_channel",
+        vec![
+            Value::primitive(sender.into()),
+            Value::primitive(recipient.into()),
+            Value::primitive(max_capacity.into()),
+            Value::primitive(max_message_size.into()),
+        ],
+    );
+
+    let sudo_call = subxt::dynamic::tx("Sudo", "sudo", vec![open_channel.into_value()]);
+
+    let mut tx = api
+        .tx()
+        .sign_and_submit_then_watch_default(&sudo_call, sudo)
+        .await?;
+
+    while let Some(status) = tx.next().await {
+        match status? {
+            TxStatus::InBestBlock(tx_in_block) | TxStatus::InFinalizedBlock(tx_in_block) => {
+                let events = tx_in_block.wait_for_success().await?;
+                // the extrinsic succeeds even if the sudo call fails, check the `Sudid` result
+                for event in events.iter() {
+                    let event = event?;
+                    if event.pallet_name() == "Sudo" && event.variant_name() == "Sudid" {
+                        if let Some(err) = sudo_dispatch_error(&event.field_values()?) {
+                            return Err(anyhow!(
+                                "Sudo call to open the hrmp channel {sender} -> {recipient} failed: {err}"
+                            ));
+                        }
+                    }
+                }
+
+                info!(
+                    "Hrmp channel {sender} -> {recipient} opened in block: {:#?}",
+                    tx_in_block.block_hash()
+                );
+                return Ok(tx_in_block.block_hash());
+            },
+            TxStatus::Error { message }
+            | TxStatus::Invalid { message }
+            | TxStatus::Dropped { message } => {
+                return Err(anyhow!("Error submitting tx: {message}"));
+            },
+            _ => continue,
+        }
+    }
+
+    Err(anyhow!(
+        "Tx stream ended before the hrmp channel {sender} -> {recipient} was included"
+    ))
+}
+
+// Get the dispatch error of the `Sudo::Sudid` event fields (`sudo_result`), if any.
+fn sudo_dispatch_error(fields: &Composite<u32>) -> Option<String> {
+    let Composite::Named(fields) = fields else {
+        return None;
+    };
+
+    fields
+        .iter()
+        .find(|(name, _)| name == "sudo_result")
+        .and_then(|(_, result)| match &result.value {
+            ValueDef::Variant(variant) if variant.name == "Err" => Some(variant.values.to_string()),
+            _ => None,
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sudid_fields(result: Value) -> Composite<u32> {
+        Composite::named([("sudo_result", result.map_context(|_| 0))])
+    }
+
+    #[test]
+    fn sudo_dispatch_error_should_return_the_error_of_a_failed_sudo_call() {
+        let fields = sudid_fields(Value::unnamed_variant(
+            "Err",
+            [Value::unnamed_variant("BadOrigin", [])],
+        ));
+
+        assert_eq!(
+            sudo_dispatch_error(&fields),
+            Some("(BadOrigin ())".to_string())
+        );
+    }
+
+    #[test]
+    fn sudo_dispatch_error_should_return_none_for_a_successful_sudo_call() {
+        let fields = sudid_fields(Value::unnamed_variant("Ok", [Value::unnamed_composite([])]));
+
+        assert_eq!(sudo_dispatch_error(&fields), None);
+    }
+}
@@ -0,0 +1,45 @@
+//! Compare `parse` against `parse_filtered` (looking for a couple of metrics, the common
+//! case when waiting for a metric) over the metrics in `testing/metrics.txt`.
+
+use std::{
+    collections::HashSet,
+    hint::black_box,
+    time::{Duration, Instant},
+};
+
+const METRICS: &str = include_str!("../testing/metrics.txt");
+const ITERATIONS: u32 = 200;
+
+fn bench(name: &str, f: impl Fn()) -> Duration {
+    // warm up
+    for _ in 0..ITERATIONS / 10 {
+        f();
+    }
+
+    let start = Instant::now();
+    for _ in 0..ITERATIONS {
+        f();
+    }
+    let per_iter = start.elapsed() / ITERATIONS;
+
+    println!("{name:<16} {per_iter:>12?}/iter ({ITERATIONS} iterations)");
+    per_iter
+}
+
+fn main() {
+    let wanted = HashSet::from(["block_height", "polkadot_node_is_active_validator"]);
+
+    let full = bench("parse", || {
+        black_box(zombienet_prom_metrics_parser::parse(black_box(METRICS)).unwrap());
+    });
+    let filtered = bench("parse_filtered", || {
+        black_box(
+            zombienet_prom_metrics_parser::parse_filtered(black_box(METRICS), &wanted).unwrap(),
+        );
+    });
+
+    println!(
+        "parse_filtered speedup: {:.2}x",
+        full.as_secs_f64() / filtered.as_secs_f64()
+    );
+}
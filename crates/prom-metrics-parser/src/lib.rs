@@ -1,4 +1,7 @@
-use std::{collections::HashMap, num::ParseFloatError};
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    num::ParseFloatError,
+};
 
 use pest::Parser;
 use pest_derive::Parser;
@@ -25,7 +28,55 @@ pub struct MetricsParser;
 pub type MetricMap = HashMap<String, f64>;
 
 pub fn parse(input: &str) -> Result<MetricMap, ParserError> {
-    let mut metric_map: MetricMap = Default::default();
+    parse_inner(input, None)
+}
+
+/// Parse the metrics but only materialize the entries whose base key
+/// (with or without prefix) is in `wanted`, skipping the labels/value
+/// construction for everything else.
+pub fn parse_filtered(input: &str, wanted: &HashSet<&str>) -> Result<MetricMap, ParserError> {
+    parse_inner(input, Some(wanted))
+}
+
+/// A metric sample, with the original metric name and the labels map.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Sample {
+    pub name: String,
+    pub labels: BTreeMap<String, String>,
+    pub value: f64,
+}
+
+/// Parse the metrics keeping the structure of each sample (name, labels and value),
+/// useful to filter the samples by any label.
+pub fn parse_structured(input: &str) -> Result<Vec<Sample>, ParserError> {
+    let samples = parse_samples(input, None)?
+        .into_iter()
+        .map(|sample| Sample {
+            name: sample.name.to_string(),
+            labels: sample
+                .labels
+                .into_iter()
+                .map(|(key, value)| (key.to_string(), value.to_string()))
+                .collect(),
+            value: sample.value,
+        })
+        .collect();
+
+    Ok(samples)
+}
+
+/// A sample borrowing from the input, keeping the labels in the original order.
+struct RawSample<'a> {
+    name: &'a str,
+    labels: Vec<(&'a str, &'a str)>,
+    value: f64,
+}
+
+fn parse_samples<'a>(
+    input: &'a str,
+    wanted: Option<&HashSet<&str>>,
+) -> Result<Vec<RawSample<'a>>, ParserError> {
+    let mut samples = vec![];
     let mut pairs = MetricsParser::parse(Rule::statement, input)
         .map_err(|e| ParserError::ParseError(Box::new(e)))?;
 
@@ -42,6 +93,17 @@ pub fn parse(input: &str) -> Result<MetricMap, ParserError> {
                         continue;
                     },
                     Rule::promstmt => {
+                        if let Some(wanted) = wanted {
+                            // SAFETY: a prom statement always starts with the key
+                            let key = value.clone().into_inner().next().unwrap().as_str();
+                            let key_without_prefix = key.split_once('_').map(|(_, k)| k);
+                            if !wanted.contains(key)
+                                && !key_without_prefix.is_some_and(|k| wanted.contains(k))
+                            {
+                                continue;
+                            }
+                        }
+
                         let mut key: &str = "";
                         let mut labels: Vec<(&str, &str)> = Vec::new();
                         let mut val: f64 = 0_f64;
@@ -81,46 +143,11 @@ pub fn parse(input: &str) -> Result<MetricMap, ParserError> {
                             }
                         }
 
-                        // we should store to make it compatible with zombienet v1:
-                        // key_without_prefix
-                        // key_without_prefix_and_without_chain
-                        // key_with_prefix_with_chain
-                        // key_with_prefix_and_without_chain
-                        let key_with_out_prefix =
-                            key.split('_').collect::<Vec<&str>>()[1..].join("_");
-                        let (labels_without_chain, labels_with_chain) =
-                            labels.iter().fold((vec![], vec![]), |mut acc, item| {
-                                if item.0.eq("chain") {
-                                    acc.1.push(format!("{}=\"{}\"", item.0, item.1));
-                                } else {
-                                    acc.0.push(format!("{}=\"{}\"", item.0, item.1));
-                                    acc.1.push(format!("{}=\"{}\"", item.0, item.1));
-                                }
-                                acc
-                            });
-
-                        let labels_with_chain_str = if labels_with_chain.is_empty() {
-                            String::from("")
-                        } else {
-                            format!("{{{}}}", labels_with_chain.join(","))
-                        };
-
-                        let labels_without_chain_str = if labels_without_chain.is_empty() {
-                            String::from("")
-                        } else {
-                            format!("{{{}}}", labels_without_chain.join(","))
-                        };
-
-                        metric_map.insert(format!("{}{}", key, labels_without_chain_str), val);
-                        metric_map.insert(
-                            format!("{}{}", key_with_out_prefix, labels_without_chain_str),
-                            val,
-                        );
-                        metric_map.insert(format!("{}{}", key, labels_with_chain_str), val);
-                        metric_map.insert(
-                            format!("{}{}", key_with_out_prefix, labels_with_chain_str),
-                            val,
-                        );
+                        samples.push(RawSample {
+                            name: key,
+                            labels,
+                            value: val,
+                        });
                     },
                     _ => {},
                 }
@@ -128,6 +155,59 @@ pub fn parse(input: &str) -> Result<MetricMap, ParserError> {
         }
     }
 
+    Ok(samples)
+}
+
+fn parse_inner(input: &str, wanted: Option<&HashSet<&str>>) -> Result<MetricMap, ParserError> {
+    let mut metric_map: MetricMap = Default::default();
+
+    for RawSample {
+        name: key,
+        labels,
+        value: val,
+    } in parse_samples(input, wanted)?
+    {
+        // we should store to make it compatible with zombienet v1:
+        // key_without_prefix
+        // key_without_prefix_and_without_chain
+        // key_with_prefix_with_chain
+        // key_with_prefix_and_without_chain
+        let key_with_out_prefix = key.split('_').collect::<Vec<&str>>()[1..].join("_");
+        let (labels_without_chain, labels_with_chain) =
+            labels.iter().fold((vec![], vec![]), |mut acc, item| {
+                if item.0.eq("chain") {
+                    acc.1.push(format!("{}=\"{}\"", item.0, item.1));
+                } else {
+                    acc.0.push(format!("{}=\"{}\"", item.0, item.1));
+                    acc.1.push(format!("{}=\"{}\"", item.0, item.1));
+                }
+                acc
+            });
+
+        let labels_with_chain_str = if labels_with_chain.is_empty() {
+            String::from("")
+        } else {
+            format!("{{{}}}", labels_with_chain.join(","))
+        };
+
+        let labels_without_chain_str = if labels_without_chain.is_empty() {
+            String::from("")
+        } else {
+            format!("{{{}}}", labels_without_chain.join(","))
+        };
+
+        metric_map.insert(format!("{}{}", key, labels_without_chain_str), val);
+        metric_map.insert(
+            format!("{}{}", key_with_out_prefix, labels_without_chain_str),
+            val,
+        );
+        metric_map.insert(format!("{}{}", key, labels_with_chain_str), val);
+        metric_map.insert(
+            format!("{}{}", key_with_out_prefix, labels_with_chain_str),
+            val,
+        );
+    }
+
     Ok(metric_map)
 }
 
@@ -165,6 +245,71 @@ mod tests {
         assert_eq!(metrics.get("node_is_active_validator").unwrap(), &1_f64);
     }
 
+    #[test]
+    fn parse_filtered_metrics_works() {
+        let metrics_raw = fs::read_to_string("./testing/metrics.txt").unwrap();
+        let wanted = HashSet::from(["node_is_active_validator"]);
+        let metrics = parse_filtered(&metrics_raw, &wanted).unwrap();
+
+        // all the key variants are present for the wanted metric
+        assert_eq!(metrics.len(), 4);
+        assert_eq!(
+            metrics
+                .get("polkadot_node_is_active_validator{chain=\"rococo_local_testnet\"}")
+                .unwrap(),
+            &1_f64
+        );
+        assert_eq!(
+            metrics.get("polkadot_node_is_active_validator").unwrap(),
+            &1_f64
+        );
+        assert_eq!(
+            metrics
+                .get("node_is_active_validator{chain=\"rococo_local_testnet\"}")
+                .unwrap(),
+            &1_f64
+        );
+        assert_eq!(metrics.get("node_is_active_validator").unwrap(), &1_f64);
+
+        // same values as the full parse
+        let full = parse(&metrics_raw).unwrap();
+        for (k, v) in metrics.iter() {
+            assert_eq!(full.get(k).unwrap(), v);
+        }
+    }
+
+    #[test]
+    fn parse_filtered_with_prefix_works() {
+        let metrics_raw = fs::read_to_string("./testing/metrics.txt").unwrap();
+        let wanted = HashSet::from(["polkadot_node_is_active_validator", "not_present"]);
+        let metrics = parse_filtered(&metrics_raw, &wanted).unwrap();
+
+        assert_eq!(metrics.len(), 4);
+        assert_eq!(metrics.get("node_is_active_validator").unwrap(), &1_f64);
+    }
+
+    #[test]
+    fn parse_structured_works() {
+        let metrics_raw = fs::read_to_string("./testing/metrics.txt").unwrap();
+        let samples = parse_structured(&metrics_raw).unwrap();
+
+        let sample = samples
+            .iter()
+            .find(|sample| sample.name == "polkadot_node_is_active_validator")
+            .unwrap();
+        assert_eq!(
+            sample.labels,
+            BTreeMap::from([("chain".to_string(), "rococo_local_testnet".to_string())])
+        );
+        assert_eq!(sample.value, 1_f64);
+
+        // every sample is reachable through the flattened keys
+        let metrics = parse(&metrics_raw).unwrap();
+        for sample in samples.iter().filter(|sample| sample.labels.is_empty()) {
+            assert_eq!(metrics.get(&sample.name).unwrap(), &sample.value);
+        }
+    }
+
     #[test]
     fn parse_invalid_metrics_str_should_fail() {
         let metrics_raw = r"
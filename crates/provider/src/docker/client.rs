@@ -1,12 +1,16 @@
 use std::{collections::HashMap, path::Path};
 
 use anyhow::anyhow;
+use configuration::shared::resources::Resources;
 use futures::future::try_join_all;
 use serde::{Deserialize, Deserializer};
 use tokio::process::Command;
-use tracing::{info, trace};
+use tracing::{info, trace, warn};
 
-use crate::types::{ExecutionResult, Port};
+use crate::{
+    shared::helpers::{bytes_from_quantity, split_quantity},
+    types::{ExecutionResult, Port},
+};
 
 #[derive(thiserror::Error, Debug)]
 #[error(transparent)]
@@ -30,6 +34,11 @@ pub struct ContainerRunOptions {
     port_mapping: HashMap<Port, Port>,
     rm: bool,
     detach: bool,
+    cpus: Option<String>,
+    memory: Option<String>,
+    memory_reservation: Option<String>,
+    restart_policy: Option<String>,
+    network: Option<String>,
 }
 
 enum Container {
@@ -118,6 +127,11 @@ impl ContainerRunOptions {
             port_mapping: HashMap::default(),
             rm: false,
             detach: true, // add -d flag by default
+            cpus: None,
+            memory: None,
+            memory_reservation: None,
+            restart_policy: None,
+            network: None,
         }
     }
 
@@ -176,6 +190,73 @@ impl ContainerRunOptions {
         self.detach = choice;
         self
     }
+
+    /// Map the (k8s style) resources to `--cpus`, `--memory` and `--memory-reservation`.
+    /// Docker doesn't have a cpu request equivalent, so `request_cpu` is ignored.
+    pub fn resources(mut self, resources: &Resources) -> Self {
+        if let Some(limit_cpu) = resources.limit_cpu() {
+            self.cpus = cpus_from_quantity(limit_cpu.as_str());
+            if self.cpus.is_none() {
+                warn!(
+                    "⚠️  can't map cpu limit {} to docker, ignoring",
+                    limit_cpu.as_str()
+                );
+            }
+        }
+
+        if let Some(limit_memory) = resources.limit_memory() {
+            self.memory = bytes_from_quantity(limit_memory.as_str()).map(|b| b.to_string());
+            if self.memory.is_none() {
+                warn!(
+                    "⚠️  can't map memory limit {} to docker, ignoring",
+                    limit_memory.as_str()
+                );
+            }
+        }
+
+        if let Some(request_memory) = resources.request_memory() {
+            self.memory_reservation =
+                bytes_from_quantity(request_memory.as_str()).map(|b| b.to_string());
+            if self.memory_reservation.is_none() {
+                warn!(
+                    "⚠️  can't map memory request {} to docker, ignoring",
+                    request_memory.as_str()
+                );
+            }
+        }
+
+        self
+    }
+
+    /// Set the container restart policy (e.g `on-failure:3`).
+    pub fn restart_policy<S>(mut self, restart_policy: S) -> Self
+    where
+        S: Into<String> + std::fmt::Debug + Send + Clone,
+    {
+        self.restart_policy = Some(restart_policy.into());
+        self
+    }
+
+    /// Attach the container to the `network` (instead of the default bridge one).
+    pub fn network<S>(mut self, network: S) -> Self
+    where
+        S: Into<String> + std::fmt::Debug + Send + Clone,
+    {
+        self.network = Some(network.into());
+        self
+    }
+}
+
+/// Convert a k8s cpu quantity to the docker `--cpus` format (e.g `500m` -> `0.5`).
+fn cpus_from_quantity(quantity: &str) -> Option<String> {
+    let (value, suffix) = split_quantity(quantity)?;
+    let cpus = match suffix {
+        "" => value,
+        "m" => value / 1000_f64,
+        _ => return None,
+    };
+
+    Some(cpus.to_string())
 }
 
 impl DockerClient {
@@ -239,6 +320,44 @@ impl DockerClient {
         Ok(())
     }
 
+    pub async fn create_network(&self, name: &str) -> Result<()> {
+        let result = self
+            .client_command()
+            .args(["network", "create", name])
+            .output()
+            .await
+            .map_err(|err| anyhow!("Failed to create network '{name}': {err}"))?;
+
+        if !result.status.success() {
+            return Err(anyhow!(
+                "Failed to create network '{name}': {}",
+                String::from_utf8_lossy(&result.stderr)
+            )
+            .into());
+        }
+
+        Ok(())
+    }
+
+    pub async fn network_rm(&self, name: &str) -> Result<()> {
+        let result = self
+            .client_command()
+            .args(["network", "rm", name])
+            .output()
+            .await
+            .map_err(|err| anyhow!("Failed to remove network '{name}': {err}"))?;
+
+        if !result.status.success() {
+            return Err(anyhow!(
+                "Failed to remove network '{name}': {}",
+                String::from_utf8_lossy(&result.stderr)
+            )
+            .into());
+        }
+
+        Ok(())
+    }
+
     pub async fn container_run(&self, options: ContainerRunOptions) -> Result<String> {
         let mut cmd = self.client_command();
         cmd.args(["run", "--platform", "linux/amd64"]);
@@ -447,30 +566,40 @@ impl DockerClient {
         Ok(())
     }
 
-    pub async fn container_ip(&self, container_name: &str) -> Result<String> {
-        let ip = if self.using_podman {
-            "127.0.0.1".into()
-        } else {
-            let mut cmd = tokio::process::Command::new("docker");
-            cmd.args(vec![
-                "inspect",
-                "-f",
-                "{{ .NetworkSettings.IPAddress }}",
-                container_name,
-            ]);
-
-            trace!("CMD: {cmd:?}");
-
-            let res = cmd
-                .output()
-                .await
-                .map_err(|err| anyhow!("Failed to get docker container ip,  output: {err}"))?;
-
-            String::from_utf8(res.stdout)
-                .map_err(|err| anyhow!("Failed to get docker container ip,  output: {err}"))?
-                .trim()
-                .into()
-        };
+    /// Get the ip of the container in the `network`, reachable from the other containers
+    /// attached to it.
+    pub async fn container_ip(&self, container_name: &str, network: &str) -> Result<String> {
+        let template =
+            format!("{{{{ (index .NetworkSettings.Networks \"{network}\").IPAddress }}}}");
+        let mut cmd = self.client_command();
+        cmd.args(["inspect", "-f", template.as_str(), container_name]);
+
+        trace!("CMD: {cmd:?}");
+
+        let res = cmd
+            .output()
+            .await
+            .map_err(|err| anyhow!("Failed to get container ip,  output: {err}"))?;
+
+        if !res.status.success() {
+            return Err(anyhow!(
+                "Failed to get container ip: {}",
+                String::from_utf8_lossy(&res.stderr)
+            )
+            .into());
+        }
+
+        let ip: String = String::from_utf8(res.stdout)
+            .map_err(|err| anyhow!("Failed to get container ip,  output: {err}"))?
+            .trim()
+            .into();
+
+        if ip.is_empty() || ip == "<no value>" {
+            return Err(anyhow!(
+                "Container '{container_name}' is not attached to the network '{network}'"
+            )
+            .into());
+        }
 
         trace!("IP: {ip}");
         Ok(ip)
@@ -559,6 +688,26 @@ impl DockerClient {
             cmd.args(["--name", name]);
         }
 
+        if let Some(cpus) = options.cpus.as_ref() {
+            cmd.args(["--cpus", cpus]);
+        }
+
+        if let Some(memory) = options.memory.as_ref() {
+            cmd.args(["--memory", memory]);
+        }
+
+        if let Some(memory_reservation) = options.memory_reservation.as_ref() {
+            cmd.args(["--memory-reservation", memory_reservation]);
+        }
+
+        if let Some(restart_policy) = options.restart_policy.as_ref() {
+            cmd.args(["--restart", restart_policy]);
+        }
+
+        if let Some(network) = options.network.as_ref() {
+            cmd.args(["--network", network]);
+        }
+
         cmd.arg(&options.image);
 
         for arg in &options.command {
@@ -566,3 +715,43 @@ impl DockerClient {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cpus_from_quantity_works() {
+        assert_eq!(cpus_from_quantity("500m"), Some("0.5".to_string()));
+        assert_eq!(cpus_from_quantity("2"), Some("2".to_string()));
+        assert_eq!(cpus_from_quantity("1.5"), Some("1.5".to_string()));
+        assert_eq!(cpus_from_quantity("2Gi"), None);
+    }
+
+    #[test]
+    fn apply_cmd_options_should_attach_the_container_to_the_network() {
+        let mut cmd = Command::new("docker");
+        let options = ContainerRunOptions::new("polkadot:latest", vec!["polkadot"])
+            .name("zombie-alice")
+            .network("zombie-ns");
+
+        DockerClient::apply_cmd_options(&mut cmd, &options);
+
+        let args: Vec<_> = cmd
+            .as_std()
+            .get_args()
+            .map(|arg| arg.to_string_lossy().to_string())
+            .collect();
+        assert_eq!(
+            args,
+            vec![
+                "--name",
+                "zombie-alice",
+                "--network",
+                "zombie-ns",
+                "polkadot:latest",
+                "polkadot"
+            ]
+        );
+    }
+}
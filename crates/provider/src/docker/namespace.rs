@@ -1,6 +1,6 @@
 use std::{
     collections::HashMap,
-    path::{Path, PathBuf},
+    path::PathBuf,
     sync::{Arc, Weak},
     thread,
 };
@@ -18,11 +18,11 @@ use super::{
     DockerProvider,
 };
 use crate::{
-    constants::NAMESPACE_PREFIX,
     docker::node::DockerNodeOptions,
+    shared::helpers::{generate_namespace_name, parse_version_output},
     types::{
-        GenerateFileCommand, GenerateFilesOptions, ProviderCapabilities, RunCommandOptions,
-        SpawnNodeOptions,
+        CreateNamespaceOptions, GenerateFileCommand, GenerateFilesOptions, ProviderCapabilities,
+        RunCommandOptions, SpawnNodeOptions,
     },
     DynNode, ProviderError, ProviderNamespace, ProviderNode,
 };
@@ -39,6 +39,7 @@ where
     capabilities: ProviderCapabilities,
     docker_client: DockerClient,
     filesystem: FS,
+    restart_policy: Option<String>,
     delete_on_drop: Arc<Mutex<bool>>,
     pub(super) nodes: RwLock<HashMap<String, Arc<DockerNode<FS>>>>,
 }
@@ -53,10 +54,11 @@ where
         capabilities: &ProviderCapabilities,
         docker_client: &DockerClient,
         filesystem: &FS,
-        custom_base_dir: Option<&Path>,
+        options: &CreateNamespaceOptions,
+        restart_policy: Option<&String>,
     ) -> Result<Arc<Self>, ProviderError> {
-        let name = format!("{}{}", NAMESPACE_PREFIX, Uuid::new_v4());
-        let base_dir = if let Some(custom_base_dir) = custom_base_dir {
+        let name = generate_namespace_name(options.name_prefix.as_deref());
+        let base_dir = if let Some(custom_base_dir) = options.base_dir.as_deref() {
             if !filesystem.exists(custom_base_dir).await {
                 filesystem.create_dir(custom_base_dir).await?;
             } else {
@@ -80,6 +82,7 @@ where
             capabilities: capabilities.clone(),
             filesystem: filesystem.clone(),
             docker_client: docker_client.clone(),
+            restart_policy: restart_policy.cloned(),
             nodes: RwLock::new(HashMap::new()),
             delete_on_drop: Arc::new(Mutex::new(true)),
         });
@@ -92,6 +95,11 @@ where
     async fn initialize(&self) -> Result<(), ProviderError> {
         // let ns_scripts_shared =  PathBuf::from_iter([&self.base_dir, &PathBuf::from("shared-scripts")]);
         // self.filesystem.create_dir(&ns_scripts_shared).await?;
+        // nodes are attached to a dedicated network (named as the ns) to reach each other by ip
+        self.docker_client
+            .create_network(&self.name)
+            .await
+            .map_err(|err| ProviderError::CreateNamespaceFailed(self.name.clone(), err.into()))?;
         self.initialize_zombie_scripts_volume().await?;
         self.initialize_helper_binaries_volume().await?;
 
@@ -262,7 +270,7 @@ where
     }
 
     async fn is_detached(&self) -> bool {
-        self.delete_on_drop().await
+        !self.delete_on_drop().await
     }
 
     async fn nodes(&self) -> HashMap<String, DynNode> {
@@ -274,10 +282,10 @@ where
             .collect()
     }
 
-    async fn get_node_available_args(
+    async fn get_node_available_args_and_version(
         &self,
         (command, image): (String, Option<String>),
-    ) -> Result<String, ProviderError> {
+    ) -> Result<(String, Option<String>), ProviderError> {
         let node_image = image.expect(&format!("image should be present when getting node available args with docker provider {THIS_IS_A_BUG}"));
 
         let temp_node = self
@@ -289,14 +297,23 @@ where
 
         let available_args_output = temp_node
             .run_command(RunCommandOptions::new(command.clone()).args(vec!["--help"]))
-            .await?
-            .map_err(|(_exit, status)| {
-                ProviderError::NodeAvailableArgsError(node_image, command, status)
-            })?;
+            .await?;
+        let version_output = temp_node
+            .run_command(RunCommandOptions::new(command.clone()).args(vec!["--version"]))
+            .await?;
 
         temp_node.destroy().await?;
 
-        Ok(available_args_output)
+        let available_args_output = available_args_output.map_err(|(_exit, status)| {
+            ProviderError::NodeAvailableArgsError(node_image, command, status)
+        })?;
+
+        Ok((
+            available_args_output,
+            version_output
+                .ok()
+                .and_then(|output| parse_version_output(&output)),
+        ))
     }
 
     async fn spawn_node(&self, options: &SpawnNodeOptions) -> Result<DynNode, ProviderError> {
@@ -305,6 +322,19 @@ where
             return Err(ProviderError::DuplicatedNodeName(options.name.clone()));
         }
 
+        // the container ports are published from the container interface, so a rpc bound
+        // to the loopback (`rpc_external = false`) is not reachable from the host
+        let rpc_external = options
+            .args
+            .iter()
+            .any(|arg| arg == "--rpc-external" || arg == "--unsafe-rpc-external");
+        if options.port_mapping.is_some() && !rpc_external {
+            warn!(
+                "⚠️  rpc of node {} is not bound externally, it will not be reachable from the host",
+                options.name
+            );
+        }
+
         let node = DockerNode::new(DockerNodeOptions {
             namespace: &self.weak,
             namespace_base_dir: &self.base_dir,
@@ -319,6 +349,8 @@ where
             container_name: format!("{}-{}", self.name, options.name),
             filesystem: &self.filesystem,
             port_mapping: options.port_mapping.as_ref().unwrap_or(&HashMap::default()),
+            resources: options.resources.as_ref(),
+            restart_policy: self.restart_policy.as_ref(),
         })
         .await?;
 
@@ -394,6 +426,11 @@ where
             .await
             .map_err(|err| ProviderError::DeleteNamespaceFailed(self.name.clone(), err.into()))?;
 
+        self.docker_client
+            .network_rm(&self.name)
+            .await
+            .map_err(|err| ProviderError::DeleteNamespaceFailed(self.name.clone(), err.into()))?;
+
         if let Some(provider) = self.provider.upgrade() {
             provider.namespaces.write().await.remove(&self.name);
         }
@@ -418,6 +455,7 @@ where
                     rt.block_on(async move {
                         trace!("🧟 deleting ns {ns_name} from cluster");
                         let _ = client.namespaced_containers_rm(&ns_name).await;
+                        let _ = client.network_rm(&ns_name).await;
                         trace!("✅ deleted");
                     });
                 });
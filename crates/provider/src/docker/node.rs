@@ -2,17 +2,22 @@ use std::{
     collections::HashMap,
     net::IpAddr,
     path::{Component, Path, PathBuf},
+    process::Stdio,
     sync::{Arc, Weak},
     time::Duration,
 };
 
 use anyhow::anyhow;
 use async_trait::async_trait;
-use configuration::types::AssetLocation;
-use futures::future::try_join_all;
+use configuration::{shared::resources::Resources, types::AssetLocation};
 use support::{constants::THIS_IS_A_BUG, fs::FileSystem};
-use tokio::{time::sleep, try_join};
-use tracing::debug;
+use tokio::{
+    io::{AsyncBufReadExt, BufReader},
+    process::Command,
+    time::sleep,
+    try_join,
+};
+use tracing::{debug, warn};
 
 use super::{
     client::{ContainerRunOptions, DockerClient},
@@ -20,8 +25,9 @@ use super::{
 };
 use crate::{
     constants::{NODE_CONFIG_DIR, NODE_DATA_DIR, NODE_RELAY_DATA_DIR, NODE_SCRIPTS_DIR},
+    shared::helpers::{build_files_archive, extract_files_archive_cmd},
     types::{ExecutionResult, Port, RunCommandOptions, RunScriptOptions, TransferedFile},
-    ProviderError, ProviderNamespace, ProviderNode,
+    LogStream, ProviderError, ProviderNamespace, ProviderNode,
 };
 
 pub(super) struct DockerNodeOptions<'a, FS>
@@ -41,6 +47,8 @@ where
     pub(super) container_name: String,
     pub(super) filesystem: &'a FS,
     pub(super) port_mapping: &'a HashMap<Port, Port>,
+    pub(super) resources: Option<&'a Resources>,
+    pub(super) restart_policy: Option<&'a String>,
 }
 
 pub struct DockerNode<FS>
@@ -62,7 +70,8 @@ where
     docker_client: DockerClient,
     container_name: String,
     port_mapping: HashMap<Port, Port>,
-    #[allow(dead_code)]
+    resources: Option<Resources>,
+    restart_policy: Option<String>,
     filesystem: FS,
 }
 
@@ -114,6 +123,8 @@ where
             docker_client: options.docker_client.clone(),
             container_name: options.container_name,
             port_mapping: options.port_mapping.clone(),
+            resources: options.resources.cloned(),
+            restart_policy: options.restart_policy.cloned(),
         });
 
         node.initialize_docker().await?;
@@ -132,36 +143,51 @@ where
     async fn initialize_docker(&self) -> Result<(), ProviderError> {
         let command = [vec![self.program.to_string()], self.args.to_vec()].concat();
 
+        let mut run_options = ContainerRunOptions::new(&self.image, command)
+            .name(&self.container_name)
+            .env(self.env.clone())
+            .volume_mounts(HashMap::from([
+                (
+                    format!("{}-zombie-wrapper", self.namespace_name(),),
+                    "/scripts".to_string(),
+                ),
+                (
+                    format!("{}-helper-binaries", self.namespace_name()),
+                    "/helpers".to_string(),
+                ),
+                (
+                    self.config_dir.to_string_lossy().into_owned(),
+                    "/cfg".to_string(),
+                ),
+                (
+                    self.data_dir.to_string_lossy().into_owned(),
+                    "/data".to_string(),
+                ),
+                (
+                    self.relay_data_dir.to_string_lossy().into_owned(),
+                    "/relay-data".to_string(),
+                ),
+            ]))
+            .entrypoint("/scripts/zombie-wrapper.sh")
+            .port_mapping(&self.port_mapping)
+            .network(self.namespace_name());
+
+        if let Some(resources) = self.resources.as_ref() {
+            run_options = run_options.resources(resources);
+        }
+
+        if let Some(restart_policy) = self.restart_policy.as_ref() {
+            // the wrapper needs to exit when the node dies for the policy to take effect
+            let env = [
+                self.env.clone(),
+                vec![("ZOMBIE_EXIT_WITH_CHILD".to_string(), "1".to_string())],
+            ]
+            .concat();
+            run_options = run_options.env(env).restart_policy(restart_policy);
+        }
+
         self.docker_client
-            .container_run(
-                ContainerRunOptions::new(&self.image, command)
-                    .name(&self.container_name)
-                    .env(self.env.clone())
-                    .volume_mounts(HashMap::from([
-                        (
-                            format!("{}-zombie-wrapper", self.namespace_name(),),
-                            "/scripts".to_string(),
-                        ),
-                        (
-                            format!("{}-helper-binaries", self.namespace_name()),
-                            "/helpers".to_string(),
-                        ),
-                        (
-                            self.config_dir.to_string_lossy().into_owned(),
-                            "/cfg".to_string(),
-                        ),
-                        (
-                            self.data_dir.to_string_lossy().into_owned(),
-                            "/data".to_string(),
-                        ),
-                        (
-                            self.relay_data_dir.to_string_lossy().into_owned(),
-                            "/relay-data".to_string(),
-                        ),
-                    ]))
-                    .entrypoint("/scripts/zombie-wrapper.sh")
-                    .port_mapping(&self.port_mapping),
-            )
+            .container_run(run_options)
             .await
             .map_err(|err| ProviderError::NodeSpawningFailed(self.name.clone(), err.into()))?;
 
@@ -226,14 +252,18 @@ where
         &self,
         startup_files: &[TransferedFile],
     ) -> Result<(), ProviderError> {
-        try_join_all(
-            startup_files
-                .iter()
-                .map(|file| self.send_file(&file.local_path, &file.remote_path, &file.mode)),
-        )
-        .await?;
+        let files: Vec<(PathBuf, PathBuf, String)> = startup_files
+            .iter()
+            .map(|file| {
+                (
+                    file.local_path.clone(),
+                    file.remote_path.clone(),
+                    file.mode.clone(),
+                )
+            })
+            .collect();
 
-        Ok(())
+        self.send_files(&files).await
     }
 
     pub(super) async fn start(&self) -> Result<(), ProviderError> {
@@ -361,6 +391,56 @@ where
         todo!()
     }
 
+    fn log_stream(&self) -> LogStream {
+        // the node logs are written to both stdout/stderr of the container
+        let name = self.name.clone();
+        let mut child = match Command::new(self.docker_client.client_binary())
+            .args(["logs", "-f", &self.container_name])
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true)
+            .spawn()
+        {
+            Ok(child) => child,
+            Err(err) => {
+                return Box::pin(futures::stream::once(async move {
+                    Err(ProviderError::GetLogsFailed(name, err.into()))
+                }))
+            },
+        };
+
+        let stdout = child.stdout.take().map(|out| BufReader::new(out).lines());
+        let stderr = child.stderr.take().map(|err| BufReader::new(err).lines());
+
+        Box::pin(futures::stream::try_unfold(
+            (child, stdout, stderr),
+            move |(child, mut stdout, mut stderr)| {
+                let name = name.clone();
+                async move {
+                    loop {
+                        let (line, from_stdout) = tokio::select! {
+                            line = async { stdout.as_mut()?.next_line().await.transpose() }, if stdout.is_some() => (line, true),
+                            line = async { stderr.as_mut()?.next_line().await.transpose() }, if stderr.is_some() => (line, false),
+                            else => return Ok(None),
+                        };
+
+                        match line {
+                            Some(line) => {
+                                let line = line.map_err(|err| {
+                                    ProviderError::GetLogsFailed(name, err.into())
+                                })?;
+                                return Ok(Some((line, (child, stdout, stderr))));
+                            },
+                            // the stream is closed
+                            None if from_stdout => stdout = None,
+                            None => stderr = None,
+                        }
+                    }
+                }
+            },
+        ))
+    }
+
     async fn dump_logs(&self, _local_dest: PathBuf) -> Result<(), ProviderError> {
         todo!()
     }
@@ -455,6 +535,66 @@ where
         Ok(())
     }
 
+    async fn send_files(&self, files: &[(PathBuf, PathBuf, String)]) -> Result<(), ProviderError> {
+        let id = uuid::Uuid::new_v4();
+        let local_archive_path = self.base_dir.join(format!("files-{id}.tar"));
+        let remote_archive_path = format!("/tmp/zombie-files-{id}.tar");
+        let send_files_err = |err: anyhow::Error| {
+            ProviderError::SendFile(
+                local_archive_path.to_string_lossy().to_string(),
+                self.name.clone(),
+                err,
+            )
+        };
+
+        let mut contents = vec![];
+        for (local_file_path, remote_file_path, mode) in files {
+            let content = self.filesystem.read(local_file_path).await?;
+            contents.push((content, remote_file_path.clone(), mode.clone()));
+        }
+
+        debug!(
+            "sending {} files for {} in a single archive",
+            contents.len(),
+            self.name
+        );
+
+        let archive = build_files_archive(&contents).map_err(send_files_err)?;
+        self.filesystem.write(&local_archive_path, archive).await?;
+
+        let transfer = async {
+            self.docker_client
+                .container_cp(
+                    &self.container_name,
+                    &local_archive_path,
+                    Path::new(&remote_archive_path),
+                )
+                .await
+                .map_err(|err| send_files_err(err.into()))?;
+
+            self.run_command(extract_files_archive_cmd(
+                &remote_archive_path,
+                &format!("/tmp/zombie-files-{id}"),
+            ))
+            .await?
+            .map_err(|(status, err)| {
+                send_files_err(anyhow!(
+                    "error extracting files archive: status {status}: {err}"
+                ))
+            })?;
+
+            Ok(())
+        }
+        .await;
+
+        // the local archive is only needed for the transfer
+        if let Err(err) = self.filesystem.remove_file(&local_archive_path).await {
+            warn!("⚠️ Failed to remove the files archive {local_archive_path:?}: {err}");
+        }
+
+        transfer
+    }
+
     async fn receive_file(
         &self,
         _remote_src: &Path,
@@ -466,7 +606,7 @@ where
     async fn ip(&self) -> Result<IpAddr, ProviderError> {
         let ip = self
             .docker_client
-            .container_ip(&self.container_name)
+            .container_ip(&self.container_name, &self.namespace_name())
             .await
             .map_err(|err| {
                 ProviderError::InvalidConfig(format!("Error getting container ip, err: {err}"))
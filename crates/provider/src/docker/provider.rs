@@ -1,6 +1,6 @@
 use std::{
     collections::HashMap,
-    path::{Path, PathBuf},
+    path::PathBuf,
     sync::{Arc, Weak},
 };
 
@@ -10,10 +10,13 @@ use tokio::sync::RwLock;
 
 use super::{client::DockerClient, namespace::DockerNamespace};
 use crate::{
-    types::ProviderCapabilities, DynNamespace, Provider, ProviderError, ProviderNamespace,
+    shared::helpers::destroy_attached_namespaces,
+    types::{CreateNamespaceOptions, ProviderCapabilities},
+    DynNamespace, Provider, ProviderError, ProviderNamespace,
 };
 
 const PROVIDER_NAME: &str = "docker";
+const DEFAULT_MAX_SPAWN_CONCURRENCY: usize = 20;
 
 pub struct DockerProvider<FS>
 where
@@ -24,6 +27,7 @@ where
     tmp_dir: PathBuf,
     docker_client: DockerClient,
     filesystem: FS,
+    restart_policy: Option<String>,
     pub(super) namespaces: RwLock<HashMap<String, Arc<DockerNamespace<FS>>>>,
 }
 
@@ -32,31 +36,39 @@ where
     FS: FileSystem + Send + Sync + Clone + 'static,
 {
     pub async fn new(filesystem: FS) -> Arc<Self> {
+        Self::new_with_restart_policy(filesystem, None).await
+    }
+
+    /// Create the provider setting a `--restart=on-failure:<max_retries>` policy
+    /// to all the spawned node containers, so crashed nodes are restarted by docker.
+    pub async fn with_restart_on_failure(filesystem: FS, max_retries: u32) -> Arc<Self> {
+        Self::new_with_restart_policy(filesystem, Some(format!("on-failure:{max_retries}"))).await
+    }
+
+    async fn new_with_restart_policy(filesystem: FS, restart_policy: Option<String>) -> Arc<Self> {
         let docker_client = DockerClient::new().await.unwrap();
 
         let provider = Arc::new_cyclic(|weak| DockerProvider {
             weak: weak.clone(),
             capabilities: ProviderCapabilities {
                 requires_image: true,
-                has_resources: false,
+                has_resources: true,
                 prefix_with_full_path: false,
                 use_default_ports_in_cmd: true,
+                max_spawn_concurrency: DEFAULT_MAX_SPAWN_CONCURRENCY,
+                supports_runtime_node_addition: true,
             },
             tmp_dir: std::env::temp_dir(),
             docker_client,
             filesystem,
+            restart_policy,
             namespaces: RwLock::new(HashMap::new()),
         });
 
         let cloned_provider = provider.clone();
         tokio::spawn(async move {
             tokio::signal::ctrl_c().await.unwrap();
-            for (_, ns) in cloned_provider.namespaces().await {
-                if ns.is_detached().await {
-                    // best effort
-                    let _ = ns.destroy().await;
-                }
-            }
+            destroy_attached_namespaces(cloned_provider.namespaces().await).await;
 
             // exit the process (130, SIGINT)
             std::process::exit(130)
@@ -93,28 +105,9 @@ where
             .collect()
     }
 
-    async fn create_namespace(&self) -> Result<DynNamespace, ProviderError> {
-        let namespace = DockerNamespace::new(
-            &self.weak,
-            &self.tmp_dir,
-            &self.capabilities,
-            &self.docker_client,
-            &self.filesystem,
-            None,
-        )
-        .await?;
-
-        self.namespaces
-            .write()
-            .await
-            .insert(namespace.name().to_string(), namespace.clone());
-
-        Ok(namespace)
-    }
-
-    async fn create_namespace_with_base_dir(
+    async fn create_namespace_with_options(
         &self,
-        base_dir: &Path,
+        options: &CreateNamespaceOptions,
     ) -> Result<DynNamespace, ProviderError> {
         let namespace = DockerNamespace::new(
             &self.weak,
@@ -122,7 +115,8 @@ where
             &self.capabilities,
             &self.docker_client,
             &self.filesystem,
-            Some(base_dir),
+            options,
+            self.restart_policy.as_ref(),
         )
         .await?;
 
@@ -4,7 +4,7 @@ use std::{
 };
 
 use anyhow::anyhow;
-use futures::{StreamExt, TryStreamExt};
+use futures::{AsyncBufReadExt, Stream, StreamExt, TryStreamExt};
 use k8s_openapi::api::core::v1::{
     ConfigMap, Namespace, Pod, PodSpec, PodStatus, Service, ServiceSpec,
 };
@@ -175,13 +175,19 @@ impl KubernetesClient {
         Ok(pod)
     }
 
-    pub(super) async fn pod_logs(&self, namespace: &str, name: &str) -> Result<String> {
+    pub(super) async fn pod_logs(
+        &self,
+        namespace: &str,
+        name: &str,
+        tail_lines: Option<i64>,
+    ) -> Result<String> {
         Api::<Pod>::namespaced(self.inner.clone(), namespace)
             .logs(
                 name,
                 &LogParams {
                     pretty: true,
                     timestamps: true,
+                    tail_lines,
                     ..Default::default()
                 },
             )
@@ -189,6 +195,30 @@ impl KubernetesClient {
             .map_err(|err| Error::from(anyhow!("error while getting logs for pod {name}: {err}")))
     }
 
+    /// Stream the pod logs lines (from the start), following the new ones.
+    pub(super) async fn pod_logs_stream(
+        &self,
+        namespace: &str,
+        name: &str,
+    ) -> Result<impl Stream<Item = std::io::Result<String>>> {
+        let reader = Api::<Pod>::namespaced(self.inner.clone(), namespace)
+            .log_stream(
+                name,
+                &LogParams {
+                    follow: true,
+                    pretty: true,
+                    timestamps: true,
+                    ..Default::default()
+                },
+            )
+            .await
+            .map_err(|err| {
+                Error::from(anyhow!("error while streaming logs for pod {name}: {err}"))
+            })?;
+
+        Ok(Box::pin(reader).lines())
+    }
+
     pub(super) async fn pod_status(&self, namespace: &str, name: &str) -> Result<PodStatus> {
         let pod = Api::<Pod>::namespaced(self.inner.clone(), namespace)
             .get(name)
@@ -1,7 +1,7 @@
 use std::{
     collections::{BTreeMap, HashMap},
     env,
-    path::{Path, PathBuf},
+    path::PathBuf,
     sync::{Arc, Weak},
 };
 
@@ -20,12 +20,11 @@ use uuid::Uuid;
 
 use super::{client::KubernetesClient, node::KubernetesNode};
 use crate::{
-    constants::NAMESPACE_PREFIX,
     kubernetes::node::KubernetesNodeOptions,
-    shared::helpers::running_in_ci,
+    shared::helpers::{generate_namespace_name, parse_version_output, running_in_ci},
     types::{
-        GenerateFileCommand, GenerateFilesOptions, ProviderCapabilities, RunCommandOptions,
-        SpawnNodeOptions,
+        CreateNamespaceOptions, GenerateFileCommand, GenerateFilesOptions, ProviderCapabilities,
+        RunCommandOptions, SpawnNodeOptions,
     },
     DynNode, KubernetesProvider, ProviderError, ProviderNamespace, ProviderNode,
 };
@@ -43,6 +42,7 @@ where
     capabilities: ProviderCapabilities,
     k8s_client: KubernetesClient,
     filesystem: FS,
+    file_server_transfer_attempts: u32,
     file_server_fw_task: RwLock<Option<tokio::task::JoinHandle<()>>>,
     delete_on_drop: Arc<Mutex<bool>>,
     pub(super) file_server_port: RwLock<Option<u16>>,
@@ -59,10 +59,11 @@ where
         capabilities: &ProviderCapabilities,
         k8s_client: &KubernetesClient,
         filesystem: &FS,
-        custom_base_dir: Option<&Path>,
+        file_server_transfer_attempts: u32,
+        options: &CreateNamespaceOptions,
     ) -> Result<Arc<Self>, ProviderError> {
-        let name = format!("{}{}", NAMESPACE_PREFIX, Uuid::new_v4());
-        let base_dir = if let Some(custom_base_dir) = custom_base_dir {
+        let name = generate_namespace_name(options.name_prefix.as_deref());
+        let base_dir = if let Some(custom_base_dir) = options.base_dir.as_deref() {
             if !filesystem.exists(custom_base_dir).await {
                 filesystem.create_dir(custom_base_dir).await?;
             } else {
@@ -86,6 +87,7 @@ where
             capabilities: capabilities.clone(),
             filesystem: filesystem.clone(),
             k8s_client: k8s_client.clone(),
+            file_server_transfer_attempts,
             file_server_port: RwLock::new(None),
             file_server_fw_task: RwLock::new(None),
             nodes: RwLock::new(HashMap::new()),
@@ -214,7 +216,7 @@ where
                 }]),
                 startup_probe: Some(Probe {
                     http_get: Some(HTTPGetAction {
-                        path: Some("/".to_string()),
+                        path: Some("/healthz".to_string()),
                         port: IntOrString::Int(80),
                         ..Default::default()
                     }),
@@ -383,7 +385,7 @@ where
     }
 
     async fn is_detached(&self) -> bool {
-        self.delete_on_drop().await
+        !self.delete_on_drop().await
     }
 
     async fn nodes(&self) -> HashMap<String, DynNode> {
@@ -395,13 +397,12 @@ where
             .collect()
     }
 
-    async fn get_node_available_args(
+    async fn get_node_available_args_and_version(
         &self,
         (command, image): (String, Option<String>),
-    ) -> Result<String, ProviderError> {
+    ) -> Result<(String, Option<String>), ProviderError> {
         let node_image = image.expect(&format!("image should be present when getting node available args with kubernetes provider {THIS_IS_A_BUG}"));
 
-        // run dummy command in new pod
         let temp_node = self
             .spawn_node(
                 &SpawnNodeOptions::new(format!("temp-{}", Uuid::new_v4()), "cat".to_string())
@@ -411,14 +412,23 @@ where
 
         let available_args_output = temp_node
             .run_command(RunCommandOptions::new(command.clone()).args(vec!["--help"]))
-            .await?
-            .map_err(|(_exit, status)| {
-                ProviderError::NodeAvailableArgsError(node_image, command, status)
-            })?;
+            .await?;
+        let version_output = temp_node
+            .run_command(RunCommandOptions::new(command.clone()).args(vec!["--version"]))
+            .await?;
 
         temp_node.destroy().await?;
 
-        Ok(available_args_output)
+        let available_args_output = available_args_output.map_err(|(_exit, status)| {
+            ProviderError::NodeAvailableArgsError(node_image, command, status)
+        })?;
+
+        Ok((
+            available_args_output,
+            version_output
+                .ok()
+                .and_then(|output| parse_version_output(&output)),
+        ))
     }
 
     async fn spawn_node(&self, options: &SpawnNodeOptions) -> Result<DynNode, ProviderError> {
@@ -440,6 +450,7 @@ where
             db_snapshot: options.db_snapshot.as_ref(),
             k8s_client: &self.k8s_client,
             filesystem: &self.filesystem,
+            file_server_transfer_attempts: self.file_server_transfer_attempts,
         })
         .await?;
 
@@ -10,7 +10,7 @@ use std::{
 use anyhow::anyhow;
 use async_trait::async_trait;
 use configuration::{shared::resources::Resources, types::AssetLocation};
-use futures::future::try_join_all;
+use futures::TryStreamExt;
 use k8s_openapi::api::core::v1::{ServicePort, ServiceSpec};
 use sha2::Digest;
 use support::{constants::THIS_IS_A_BUG, fs::FileSystem};
@@ -23,11 +23,12 @@ use super::{
 };
 use crate::{
     constants::{
-        NODE_CONFIG_DIR, NODE_DATA_DIR, NODE_RELAY_DATA_DIR, NODE_SCRIPTS_DIR, P2P_PORT,
-        PROMETHEUS_PORT, RPC_HTTP_PORT, RPC_WS_PORT,
+        CONTENT_SHA256_HEADER, NODE_CONFIG_DIR, NODE_DATA_DIR, NODE_RELAY_DATA_DIR,
+        NODE_SCRIPTS_DIR, P2P_PORT, PROMETHEUS_PORT, RPC_HTTP_PORT, RPC_WS_PORT,
     },
+    shared::helpers::{build_files_archive, extract_files_archive_cmd},
     types::{ExecutionResult, RunCommandOptions, RunScriptOptions, TransferedFile},
-    ProviderError, ProviderNamespace, ProviderNode,
+    LogStream, ProviderError, ProviderNamespace, ProviderNode,
 };
 
 pub(super) struct KubernetesNodeOptions<'a, FS>
@@ -46,10 +47,58 @@ where
     pub(super) db_snapshot: Option<&'a AssetLocation>,
     pub(super) k8s_client: &'a KubernetesClient,
     pub(super) filesystem: &'a FS,
+    pub(super) file_server_transfer_attempts: u32,
 }
 
 type FwdInfo = (u16, JoinHandle<()>);
 
+/// Delay before the first retry of a file server transfer, doubled on each new attempt.
+const FILE_SERVER_RETRY_BASE_DELAY: Duration = Duration::from_millis(500);
+/// Max delay between file server transfer attempts.
+const FILE_SERVER_RETRY_MAX_DELAY: Duration = Duration::from_secs(30);
+/// Exit code returned by `curl --fail` when the server responds with an http error.
+const CURL_HTTP_ERROR_EXIT_CODE: i32 = 22;
+
+/// Error of a single transfer attempt against the file server.
+enum TransferError {
+    /// Connection/IO error, the transfer can be retried.
+    Transient(ProviderError),
+    /// The request was rejected (e.g. 4xx), retrying will not help.
+    Permanent(ProviderError),
+}
+
+/// Run the `transfer` up to `attempts` times, with an exponential backoff between attempts,
+/// as long as it fails with a [`TransferError::Transient`] error.
+async fn with_transfer_retries<T, F, Fut>(
+    what: &str,
+    attempts: u32,
+    mut transfer: F,
+) -> Result<T, ProviderError>
+where
+    F: FnMut() -> Fut,
+    Fut: std::future::Future<Output = Result<T, TransferError>>,
+{
+    let mut attempt = 1;
+    loop {
+        match transfer().await {
+            Ok(res) => return Ok(res),
+            Err(TransferError::Transient(err)) if attempt < attempts => {
+                let delay = FILE_SERVER_RETRY_BASE_DELAY
+                    .saturating_mul(2_u32.saturating_pow(attempt - 1))
+                    .min(FILE_SERVER_RETRY_MAX_DELAY);
+                warn!(
+                    "⚠️ {what} failed (attempt {attempt}/{attempts}), retrying in {delay:?}: {err}"
+                );
+                sleep(delay).await;
+                attempt += 1;
+            },
+            Err(TransferError::Transient(err)) | Err(TransferError::Permanent(err)) => {
+                return Err(err)
+            },
+        }
+    }
+}
+
 pub(super) struct KubernetesNode<FS>
 where
     FS: FileSystem + Send + Sync + Clone,
@@ -70,6 +119,7 @@ where
     k8s_client: KubernetesClient,
     http_client: reqwest::Client,
     filesystem: FS,
+    file_server_transfer_attempts: u32,
     port_fwds: RwLock<HashMap<u16, FwdInfo>>,
 }
 
@@ -121,6 +171,7 @@ where
             filesystem: filesystem.clone(),
             k8s_client: options.k8s_client.clone(),
             http_client: reqwest::Client::new(),
+            file_server_transfer_attempts: options.file_server_transfer_attempts,
             port_fwds: Default::default(),
         });
 
@@ -238,6 +289,11 @@ where
                 let (url, _) = self.upload_to_fileserver(filepath).await?;
                 url
             },
+            AssetLocation::InlineJson(_) => {
+                return Err(ProviderError::InvalidConfig(
+                    "db_snapshot can't be an inline json".to_string(),
+                ));
+            },
         };
 
         // we need to get the snapshot from a public access
@@ -275,14 +331,18 @@ where
         &self,
         startup_files: &[TransferedFile],
     ) -> Result<(), ProviderError> {
-        try_join_all(
-            startup_files
-                .iter()
-                .map(|file| self.send_file(&file.local_path, &file.remote_path, &file.mode)),
-        )
-        .await?;
+        let files: Vec<(PathBuf, PathBuf, String)> = startup_files
+            .iter()
+            .map(|file| {
+                (
+                    file.local_path.clone(),
+                    file.remote_path.clone(),
+                    file.mode.clone(),
+                )
+            })
+            .collect();
 
-        Ok(())
+        self.send_files(&files).await
     }
 
     pub(super) async fn start(&self) -> Result<(), ProviderError> {
@@ -361,35 +421,88 @@ where
 
         let data = self.filesystem.read(location).await?;
         let content_hashed = hex::encode(sha2::Sha256::digest(&data));
+
+        let url = with_transfer_retries(
+            &format!("upload of {}", location.to_string_lossy()),
+            self.file_server_transfer_attempts,
+            || self.try_upload_to_fileserver(location, &file_name, &data, &content_hashed),
+        )
+        .await?;
+
+        Ok((url, content_hashed))
+    }
+
+    // Single upload attempt, each attempt sends the whole file again.
+    async fn try_upload_to_fileserver(
+        &self,
+        location: &Path,
+        file_name: &str,
+        data: &[u8],
+        content_hashed: &str,
+    ) -> Result<Url, TransferError> {
+        let upload_err = |err: anyhow::Error| {
+            ProviderError::UploadFile(location.to_string_lossy().to_string(), err)
+        };
+        // only connection/IO errors are worth a retry
+        let request_err = |err: reqwest::Error| {
+            if err.is_builder() {
+                TransferError::Permanent(upload_err(err.into()))
+            } else {
+                TransferError::Transient(upload_err(err.into()))
+            }
+        };
+
         let req = self
             .http_client
             .head(format!(
                 "http://{}/{content_hashed}__{file_name}",
-                self.file_server_local_host().await?
+                self.file_server_local_host()
+                    .await
+                    .map_err(TransferError::Permanent)?
             ))
             .build()
-            .map_err(|err| {
-                ProviderError::UploadFile(location.to_string_lossy().to_string(), err.into())
-            })?;
+            .map_err(request_err)?;
 
         let url = req.url().clone();
-        let res = self.http_client.execute(req).await.map_err(|err| {
-            ProviderError::UploadFile(location.to_string_lossy().to_string(), err.into())
-        })?;
-
-        if res.status() != reqwest::StatusCode::OK {
+        let res = self.http_client.execute(req).await.map_err(request_err)?;
+
+        // the file server returns the checksum of the served file, so we can detect
+        // corrupted files and upload them again
+        let already_uploaded = res.status() == reqwest::StatusCode::OK
+            && res
+                .headers()
+                .get(CONTENT_SHA256_HEADER)
+                .and_then(|value| value.to_str().ok())
+                .map(|hash| hash == content_hashed)
+                .unwrap_or(true);
+
+        if !already_uploaded {
             // we need to upload the file
-            self.http_client
+            let res = self
+                .http_client
                 .post(url.as_ref())
-                .body(data)
+                .header(CONTENT_SHA256_HEADER, content_hashed)
+                .body(data.to_vec())
                 .send()
                 .await
-                .map_err(|err| {
-                    ProviderError::UploadFile(location.to_string_lossy().to_string(), err.into())
-                })?;
+                .map_err(request_err)?;
+
+            let status = res.status();
+            if !status.is_success() {
+                let err = upload_err(anyhow!(
+                    "file server returned {}: {}",
+                    status,
+                    res.text().await.unwrap_or_default()
+                ));
+                return Err(if status.is_server_error() {
+                    TransferError::Transient(err)
+                } else {
+                    TransferError::Permanent(err)
+                });
+            }
         }
 
-        Ok((url, content_hashed))
+        Ok(url)
     }
 
     async fn file_server_local_host(&self) -> Result<String, ProviderError> {
@@ -410,6 +523,29 @@ where
         remote_file_path: &Path,
         hash: Option<&str>,
     ) -> Result<(), ProviderError> {
+        with_transfer_retries(
+            &format!(
+                "download of {} in node {}",
+                remote_file_path.to_string_lossy(),
+                self.name
+            ),
+            self.file_server_transfer_attempts,
+            || self.try_download_file(url, remote_file_path, hash),
+        )
+        .await
+    }
+
+    // Single download attempt, each attempt downloads the whole file again.
+    async fn try_download_file(
+        &self,
+        url: &str,
+        remote_file_path: &Path,
+        hash: Option<&str>,
+    ) -> Result<(), TransferError> {
+        let download_err = |err: anyhow::Error| {
+            ProviderError::DownloadFile(remote_file_path.to_string_lossy().to_string(), err)
+        };
+
         let r = self
             .k8s_client
             .pod_exec(
@@ -417,6 +553,7 @@ where
                 &self.name,
                 vec![
                     "/cfg/curl",
+                    "--fail",
                     url,
                     "--output",
                     &remote_file_path.to_string_lossy(),
@@ -424,19 +561,27 @@ where
             )
             .await
             .map_err(|err| {
-                ProviderError::DownloadFile(
-                    remote_file_path.to_string_lossy().to_string(),
-                    anyhow!(format!("node: {}, err: {}", self.name(), err)),
-                )
+                TransferError::Transient(download_err(anyhow!(format!(
+                    "node: {}, err: {}",
+                    self.name(),
+                    err
+                ))))
             })?;
 
         trace!("download url {} result: {:?}", url, r);
 
-        if r.is_err() {
-            return Err(ProviderError::DownloadFile(
-                remote_file_path.to_string_lossy().to_string(),
-                anyhow!(format!("node: {}, err downloading file", self.name())),
-            ));
+        if let Err((status, output)) = r {
+            let err = download_err(anyhow!(format!(
+                "node: {}, err downloading file: {status} {output}",
+                self.name()
+            )));
+            // with `--fail` curl exits with a dedicated code for http errors (e.g. 4xx),
+            // any other failure is a connection/IO one.
+            return Err(if status.code() == Some(CURL_HTTP_ERROR_EXIT_CODE) {
+                TransferError::Permanent(err)
+            } else {
+                TransferError::Transient(err)
+            });
         }
 
         if let Some(hash) = hash {
@@ -454,28 +599,28 @@ where
                 )
                 .await
                 .map_err(|err| {
-                    ProviderError::DownloadFile(
-                        remote_file_path.to_string_lossy().to_string(),
-                        anyhow!(format!("node: {}, err: {}", self.name(), err)),
-                    )
+                    TransferError::Transient(download_err(anyhow!(format!(
+                        "node: {}, err: {}",
+                        self.name(),
+                        err
+                    ))))
                 })?;
 
+            // an invalid hash means the file was corrupted during the transfer,
+            // so we can download it again.
             if let Ok(output) = res {
                 if !output.contains(hash) {
-                    return Err(ProviderError::DownloadFile(
-                        remote_file_path.to_string_lossy().to_string(),
-                        anyhow!(format!("node: {}, invalid sha256sum hash: {hash} for file, output was {output}", self.name())),
-                    ));
+                    return Err(TransferError::Transient(download_err(anyhow!(format!(
+                        "node: {}, invalid sha256sum hash: {hash} for file, output was {output}",
+                        self.name()
+                    )))));
                 }
             } else {
-                return Err(ProviderError::DownloadFile(
-                    remote_file_path.to_string_lossy().to_string(),
-                    anyhow!(format!(
-                        "node: {}, err calculating sha256sum for file {:?}",
-                        self.name(),
-                        res
-                    )),
-                ));
+                return Err(TransferError::Permanent(download_err(anyhow!(format!(
+                    "node: {}, err calculating sha256sum for file {:?}",
+                    self.name(),
+                    res
+                )))));
             }
         }
 
@@ -533,11 +678,41 @@ where
     // TODO: handle log rotation as we do in v1
     async fn logs(&self) -> Result<String, ProviderError> {
         self.k8s_client
-            .pod_logs(&self.namespace_name(), &self.name)
+            .pod_logs(&self.namespace_name(), &self.name, None)
+            .await
+            .map_err(|err| ProviderError::GetLogsFailed(self.name.to_string(), err.into()))
+    }
+
+    async fn logs_tail(&self, lines: usize) -> Result<String, ProviderError> {
+        self.k8s_client
+            .pod_logs(&self.namespace_name(), &self.name, Some(lines as i64))
             .await
             .map_err(|err| ProviderError::GetLogsFailed(self.name.to_string(), err.into()))
     }
 
+    fn log_stream(&self) -> LogStream {
+        let (k8s_client, namespace, name) = (
+            self.k8s_client.clone(),
+            self.namespace_name(),
+            self.name.clone(),
+        );
+
+        Box::pin(
+            futures::stream::once(async move {
+                let pod_name = name.clone();
+                let map_err =
+                    move |err: anyhow::Error| ProviderError::GetLogsFailed(name.clone(), err);
+                let lines = k8s_client
+                    .pod_logs_stream(&namespace, &pod_name)
+                    .await
+                    .map_err(|err| map_err(err.into()))?;
+
+                Ok::<_, ProviderError>(lines.map_err(move |err| map_err(err.into())))
+            })
+            .try_flatten(),
+        )
+    }
+
     async fn dump_logs(&self, local_dest: PathBuf) -> Result<(), ProviderError> {
         let logs = self.logs().await?;
 
@@ -646,31 +821,10 @@ where
         let _ = url.set_host(Some("fileserver"));
         let _ = url.set_port(Some(80));
 
-        // Sometimes downloading the file fails (the file is corrupted)
-        // Add at most 5 retries
-        let mut last_err = None;
-        for i in 0..5 {
-            if i > 0 {
-                warn!("retrying number {i} download file {:?}", remote_file_path);
-                tokio::time::sleep(Duration::from_secs(i)).await;
-            }
-
-            let res = self
-                .download_file(url.as_ref(), remote_file_path, Some(&hash))
-                .await;
-
-            last_err = res.err();
-
-            if last_err.is_none() {
-                // ready to continue
-                break;
-            }
-        }
-
-        if last_err.is_some() {
-            // SECURITY: should be some.
-            return Err(last_err.unwrap());
-        }
+        // Sometimes downloading the file fails (e.g. the file is corrupted),
+        // transient errors are retried in `download_file`
+        self.download_file(url.as_ref(), remote_file_path, Some(&hash))
+            .await?;
 
         let _ = self
             .k8s_client
@@ -691,6 +845,65 @@ where
         Ok(())
     }
 
+    async fn send_files(&self, files: &[(PathBuf, PathBuf, String)]) -> Result<(), ProviderError> {
+        let id = uuid::Uuid::new_v4();
+        let local_archive_path = self.base_dir.join(format!("files-{id}.tar"));
+        let remote_archive_path = format!("/tmp/zombie-files-{id}.tar");
+        let send_files_err = |err: anyhow::Error| {
+            ProviderError::SendFile(
+                self.name.clone(),
+                local_archive_path.to_string_lossy().to_string(),
+                err,
+            )
+        };
+
+        let mut contents = vec![];
+        for (local_file_path, remote_file_path, mode) in files {
+            let content = self.filesystem.read(local_file_path).await?;
+            contents.push((content, remote_file_path.clone(), mode.clone()));
+        }
+
+        debug!(
+            "sending {} files for {} in a single archive",
+            contents.len(),
+            self.name
+        );
+
+        let archive = build_files_archive(&contents).map_err(send_files_err)?;
+        self.filesystem.write(&local_archive_path, archive).await?;
+
+        let transfer = async {
+            // we need to override the url to use inside the pod
+            let (mut url, hash) = self.upload_to_fileserver(&local_archive_path).await?;
+            let _ = url.set_host(Some("fileserver"));
+            let _ = url.set_port(Some(80));
+
+            self.download_file(url.as_ref(), Path::new(&remote_archive_path), Some(&hash))
+                .await?;
+
+            self.run_command(extract_files_archive_cmd(
+                &remote_archive_path,
+                &format!("/tmp/zombie-files-{id}"),
+            ))
+            .await?
+            .map_err(|(status, err)| {
+                send_files_err(anyhow!(
+                    "error extracting files archive: status {status}: {err}"
+                ))
+            })?;
+
+            Ok(())
+        }
+        .await;
+
+        // the local archive is only needed for the transfer
+        if let Err(err) = self.filesystem.remove_file(&local_archive_path).await {
+            warn!("⚠️ Failed to remove the files archive {local_archive_path:?}: {err}");
+        }
+
+        transfer
+    }
+
     async fn receive_file(
         &self,
         _remote_src: &Path,
@@ -796,3 +1009,94 @@ where
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{
+        atomic::{AtomicU32, Ordering},
+        Arc,
+    };
+
+    use tokio::time::Instant;
+
+    use super::*;
+
+    fn transfer_err() -> ProviderError {
+        ProviderError::FileServerSetupError(anyhow!("connection refused"))
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn transfer_retries_should_retry_transient_errors_with_backoff() {
+        let calls = Arc::new(AtomicU32::new(0));
+        let start = Instant::now();
+
+        let res: Result<(), _> = with_transfer_retries("upload", 4, || {
+            let calls = calls.clone();
+            async move {
+                calls.fetch_add(1, Ordering::SeqCst);
+                Err(TransferError::Transient(transfer_err()))
+            }
+        })
+        .await;
+
+        assert!(res.is_err());
+        assert_eq!(calls.load(Ordering::SeqCst), 4);
+        // 500ms + 1s + 2s between the 4 attempts
+        assert_eq!(start.elapsed(), Duration::from_millis(3500));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn transfer_retries_should_return_once_the_transfer_succeeds() {
+        let calls = Arc::new(AtomicU32::new(0));
+        let start = Instant::now();
+
+        let res = with_transfer_retries("download", 5, || {
+            let calls = calls.clone();
+            async move {
+                if calls.fetch_add(1, Ordering::SeqCst) < 2 {
+                    Err(TransferError::Transient(transfer_err()))
+                } else {
+                    Ok(42)
+                }
+            }
+        })
+        .await;
+
+        assert_eq!(res.unwrap(), 42);
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+        assert_eq!(start.elapsed(), Duration::from_millis(1500));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn transfer_retries_should_not_retry_permanent_errors() {
+        let calls = Arc::new(AtomicU32::new(0));
+        let start = Instant::now();
+
+        let res: Result<(), _> = with_transfer_retries("upload", 5, || {
+            let calls = calls.clone();
+            async move {
+                calls.fetch_add(1, Ordering::SeqCst);
+                Err(TransferError::Permanent(transfer_err()))
+            }
+        })
+        .await;
+
+        assert!(res.is_err());
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+        assert_eq!(start.elapsed(), Duration::ZERO);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn transfer_retries_should_cap_the_backoff_delay() {
+        let start = Instant::now();
+
+        let res: Result<(), _> = with_transfer_retries("upload", 9, || async {
+            Err(TransferError::Transient(transfer_err()))
+        })
+        .await;
+
+        assert!(res.is_err());
+        // 0.5 + 1 + 2 + 4 + 8 + 16 and then capped to 30s twice
+        assert_eq!(start.elapsed(), Duration::from_millis(91500));
+    }
+}
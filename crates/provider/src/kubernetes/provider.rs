@@ -1,6 +1,6 @@
 use std::{
     collections::HashMap,
-    path::{Path, PathBuf},
+    path::PathBuf,
     sync::{Arc, Weak},
 };
 
@@ -10,10 +10,13 @@ use tokio::sync::RwLock;
 
 use super::{client::KubernetesClient, namespace::KubernetesNamespace};
 use crate::{
-    types::ProviderCapabilities, DynNamespace, Provider, ProviderError, ProviderNamespace,
+    types::{CreateNamespaceOptions, ProviderCapabilities},
+    DynNamespace, Provider, ProviderError, ProviderNamespace,
 };
 
 const PROVIDER_NAME: &str = "k8s";
+const DEFAULT_MAX_SPAWN_CONCURRENCY: usize = 100;
+const DEFAULT_FILE_SERVER_TRANSFER_ATTEMPTS: u32 = 5;
 
 pub struct KubernetesProvider<FS>
 where
@@ -24,6 +27,7 @@ where
     tmp_dir: PathBuf,
     k8s_client: KubernetesClient,
     filesystem: FS,
+    file_server_transfer_attempts: u32,
     pub(super) namespaces: RwLock<HashMap<String, Arc<KubernetesNamespace<FS>>>>,
 }
 
@@ -32,6 +36,14 @@ where
     FS: FileSystem + Send + Sync + Clone,
 {
     pub async fn new(filesystem: FS) -> Arc<Self> {
+        Self::with_file_server_transfer_attempts(filesystem, DEFAULT_FILE_SERVER_TRANSFER_ATTEMPTS)
+            .await
+    }
+
+    /// Create the provider setting the max number of `attempts` for each transfer (upload
+    /// or download) against the file server. Only transient (connection/IO) errors are
+    /// retried, using an exponential backoff between attempts.
+    pub async fn with_file_server_transfer_attempts(filesystem: FS, attempts: u32) -> Arc<Self> {
         let k8s_client = KubernetesClient::new().await.unwrap();
 
         Arc::new_cyclic(|weak| KubernetesProvider {
@@ -41,10 +53,13 @@ where
                 has_resources: true,
                 prefix_with_full_path: false,
                 use_default_ports_in_cmd: true,
+                max_spawn_concurrency: DEFAULT_MAX_SPAWN_CONCURRENCY,
+                supports_runtime_node_addition: true,
             },
             tmp_dir: std::env::temp_dir(),
             k8s_client,
             filesystem,
+            file_server_transfer_attempts: attempts.max(1),
             namespaces: RwLock::new(HashMap::new()),
         })
     }
@@ -77,28 +92,9 @@ where
             .collect()
     }
 
-    async fn create_namespace(&self) -> Result<DynNamespace, ProviderError> {
-        let namespace = KubernetesNamespace::new(
-            &self.weak,
-            &self.tmp_dir,
-            &self.capabilities,
-            &self.k8s_client,
-            &self.filesystem,
-            None,
-        )
-        .await?;
-
-        self.namespaces
-            .write()
-            .await
-            .insert(namespace.name().to_string(), namespace.clone());
-
-        Ok(namespace)
-    }
-
-    async fn create_namespace_with_base_dir(
+    async fn create_namespace_with_options(
         &self,
-        base_dir: &Path,
+        options: &CreateNamespaceOptions,
     ) -> Result<DynNamespace, ProviderError> {
         let namespace = KubernetesNamespace::new(
             &self.weak,
@@ -106,7 +102,8 @@ where
             &self.capabilities,
             &self.k8s_client,
             &self.filesystem,
-            Some(base_dir),
+            self.file_server_transfer_attempts,
+            options,
         )
         .await?;
 
@@ -8,16 +8,18 @@ use std::{
     collections::HashMap,
     net::IpAddr,
     path::{Path, PathBuf},
+    pin::Pin,
     sync::Arc,
     time::Duration,
 };
 
 use async_trait::async_trait;
+use futures::Stream;
 use shared::{
     constants::LOCALHOST,
     types::{
-        ExecutionResult, GenerateFilesOptions, ProviderCapabilities, RunCommandOptions,
-        RunScriptOptions, SpawnNodeOptions,
+        CreateNamespaceOptions, ExecutionResult, GenerateFilesOptions, ProviderCapabilities,
+        RunCommandOptions, RunScriptOptions, SpawnNodeOptions,
     },
 };
 use support::fs::FileSystemError;
@@ -85,6 +87,9 @@ pub enum ProviderError {
     #[error("Failed to destroy node '{0}': {1}")]
     DestroyNodeFailed(String, anyhow::Error),
 
+    #[error("Failed to detach node '{0}': {1}")]
+    DetachNodeFailed(String, anyhow::Error),
+
     #[error("Failed to get logs for node '{0}': {1}")]
     GetLogsFailed(String, anyhow::Error),
 
@@ -111,6 +116,9 @@ pub enum ProviderError {
 
     #[error("Failed to delete namespace '{0}': {1}")]
     DeleteNamespaceFailed(String, anyhow::Error),
+
+    #[error("Unsupported: {0}")]
+    Unsupported(String),
 }
 
 #[async_trait]
@@ -121,11 +129,22 @@ pub trait Provider {
 
     async fn namespaces(&self) -> HashMap<String, DynNamespace>;
 
-    async fn create_namespace(&self) -> Result<DynNamespace, ProviderError>;
+    async fn create_namespace(&self) -> Result<DynNamespace, ProviderError> {
+        self.create_namespace_with_options(&CreateNamespaceOptions::new())
+            .await
+    }
 
     async fn create_namespace_with_base_dir(
         &self,
         base_dir: &Path,
+    ) -> Result<DynNamespace, ProviderError> {
+        self.create_namespace_with_options(&CreateNamespaceOptions::new().base_dir(base_dir))
+            .await
+    }
+
+    async fn create_namespace_with_options(
+        &self,
+        options: &CreateNamespaceOptions,
     ) -> Result<DynNamespace, ProviderError>;
 }
 
@@ -154,7 +173,17 @@ pub trait ProviderNamespace {
     async fn get_node_available_args(
         &self,
         options: (String, Option<String>),
-    ) -> Result<String, ProviderError>;
+    ) -> Result<String, ProviderError> {
+        Ok(self.get_node_available_args_and_version(options).await?.0)
+    }
+
+    /// Available args (`<command> --help`) and version (`<command> --version`) of the node
+    /// binary, both read from the same temporary node. The version is `None` if the binary
+    /// doesn't support the flag.
+    async fn get_node_available_args_and_version(
+        &self,
+        options: (String, Option<String>),
+    ) -> Result<(String, Option<String>), ProviderError>;
 
     async fn spawn_node(&self, options: &SpawnNodeOptions) -> Result<DynNode, ProviderError>;
 
@@ -193,6 +222,16 @@ pub trait ProviderNode {
 
     async fn logs(&self) -> Result<String, ProviderError>;
 
+    // Return the last `lines` lines of the logs, by default filtering the full logs
+    async fn logs_tail(&self, lines: usize) -> Result<String, ProviderError> {
+        Ok(shared::helpers::tail_lines(&self.logs().await?, lines))
+    }
+
+    // Stream the log lines, starting from the first one and following the new ones as
+    // they are written. The stream ends if the node stops (e.g. destroyed or restarted
+    // for the docker/k8s providers).
+    fn log_stream(&self) -> LogStream;
+
     async fn dump_logs(&self, local_dest: PathBuf) -> Result<(), ProviderError>;
 
     // By default return localhost, should be overrided for k8s
@@ -224,23 +263,62 @@ pub trait ProviderNode {
         mode: &str,
     ) -> Result<(), ProviderError>;
 
+    // Send many files `(local_path, remote_path, mode)` to the node.
+    // By default is just a loop over `send_file`, should be overrided to use a
+    // single transfer (docker/k8s provider)
+    async fn send_files(&self, files: &[(PathBuf, PathBuf, String)]) -> Result<(), ProviderError> {
+        for (local_file_path, remote_file_path, mode) in files {
+            self.send_file(local_file_path, remote_file_path, mode)
+                .await?;
+        }
+
+        Ok(())
+    }
+
     async fn receive_file(
         &self,
         remote_file_path: &Path,
         local_file_path: &Path,
     ) -> Result<(), ProviderError>;
 
+    // Return the id of the process running the node, if any.
+    // None by default (docker/k8s provider)
+    async fn pid(&self) -> Option<u32> {
+        None
+    }
+
     async fn pause(&self) -> Result<(), ProviderError>;
 
+    // Freeze the node process in place (e.g. `SIGSTOP`), resumed with `resume`.
+    // Same as `pause` by default (docker/k8s provider)
+    async fn freeze(&self) -> Result<(), ProviderError> {
+        self.pause().await
+    }
+
     async fn resume(&self) -> Result<(), ProviderError>;
 
     async fn restart(&self, after: Option<Duration>) -> Result<(), ProviderError>;
 
+    // Restart the node replacing the process args on relaunch.
+    // Not supported by default (docker/k8s provider)
+    async fn restart_with_args(
+        &self,
+        _args: Vec<String>,
+        _after: Option<Duration>,
+    ) -> Result<(), ProviderError> {
+        Err(ProviderError::RestartNodeFailed(
+            self.name().to_string(),
+            anyhow::anyhow!("restart with custom args is not supported by this provider"),
+        ))
+    }
+
     async fn destroy(&self) -> Result<(), ProviderError>;
 }
 
 pub type DynNode = Arc<dyn ProviderNode + Send + Sync>;
 
+pub type LogStream = Pin<Box<dyn Stream<Item = Result<String, ProviderError>> + Send>>;
+
 // re-export
 pub use docker::*;
 pub use kubernetes::*;
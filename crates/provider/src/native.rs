@@ -2,4 +2,4 @@ mod namespace;
 mod node;
 mod provider;
 
-pub use provider::NativeProvider;
+pub use provider::{destroy_detached_nodes, NativeProvider};
@@ -1,5 +1,6 @@
 use std::{
     collections::HashMap,
+    net::TcpListener,
     path::{Path, PathBuf},
     sync::{Arc, Weak},
 };
@@ -13,7 +14,8 @@ use uuid::Uuid;
 
 use super::node::{NativeNode, NativeNodeOptions};
 use crate::{
-    constants::NAMESPACE_PREFIX,
+    constants::LOCALHOST,
+    shared::helpers::{generate_namespace_name, parse_version_output},
     types::{
         GenerateFileCommand, GenerateFilesOptions, ProviderCapabilities, RunCommandOptions,
         SpawnNodeOptions,
@@ -32,6 +34,7 @@ where
     capabilities: ProviderCapabilities,
     filesystem: FS,
     pub(super) nodes: RwLock<HashMap<String, Arc<NativeNode<FS>>>>,
+    detached: RwLock<bool>,
 }
 
 impl<FS> NativeNamespace<FS>
@@ -44,8 +47,9 @@ where
         capabilities: &ProviderCapabilities,
         filesystem: &FS,
         custom_base_dir: Option<&Path>,
+        name_prefix: Option<&str>,
     ) -> Result<Arc<Self>, ProviderError> {
-        let name = format!("{}{}", NAMESPACE_PREFIX, Uuid::new_v4());
+        let name = generate_namespace_name(name_prefix);
         let base_dir = if let Some(custom_base_dir) = custom_base_dir {
             if !filesystem.exists(custom_base_dir).await {
                 filesystem.create_dir(custom_base_dir).await?;
@@ -70,6 +74,7 @@ where
             capabilities: capabilities.clone(),
             filesystem: filesystem.clone(),
             nodes: RwLock::new(HashMap::new()),
+            detached: RwLock::new(false),
         }))
     }
 }
@@ -100,10 +105,29 @@ where
             .collect()
     }
 
-    async fn get_node_available_args(
+    async fn detach(&self) {
+        let mut detached = self.detached.write().await;
+        if *detached {
+            return;
+        }
+
+        for node in self.nodes.read().await.values() {
+            if let Err(err) = node.detach().await {
+                warn!("⚠️ {err}");
+            }
+        }
+
+        *detached = true;
+    }
+
+    async fn is_detached(&self) -> bool {
+        *self.detached.read().await
+    }
+
+    async fn get_node_available_args_and_version(
         &self,
         (command, _image): (String, Option<String>),
-    ) -> Result<String, ProviderError> {
+    ) -> Result<(String, Option<String>), ProviderError> {
         let temp_node = self
             .spawn_node(
                 &SpawnNodeOptions::new(format!("temp-{}", Uuid::new_v4()), "bash".to_string())
@@ -113,14 +137,23 @@ where
 
         let available_args_output = temp_node
             .run_command(RunCommandOptions::new(command.clone()).args(vec!["--help"]))
-            .await?
-            .map_err(|(_exit, status)| {
-                ProviderError::NodeAvailableArgsError("".to_string(), command, status)
-            })?;
+            .await?;
+        let version_output = temp_node
+            .run_command(RunCommandOptions::new(command.clone()).args(vec!["--version"]))
+            .await?;
 
         temp_node.destroy().await?;
 
-        Ok(available_args_output)
+        let available_args_output = available_args_output.map_err(|(_exit, status)| {
+            ProviderError::NodeAvailableArgsError("".to_string(), command, status)
+        })?;
+
+        Ok((
+            available_args_output,
+            version_output
+                .ok()
+                .and_then(|output| parse_version_output(&output)),
+        ))
     }
 
     async fn spawn_node(&self, options: &SpawnNodeOptions) -> Result<DynNode, ProviderError> {
@@ -128,6 +161,19 @@ where
             return Err(ProviderError::DuplicatedNodeName(options.name.clone()));
         }
 
+        // Fail fast if a port assigned to the node is already taken (e.g by a leftover
+        // process from a previous run), instead of timing out waiting for the node.
+        if let Some(port_mapping) = options.port_mapping.as_ref() {
+            for port in port_mapping.values() {
+                if *port != 0 && TcpListener::bind((LOCALHOST, *port)).is_err() {
+                    return Err(ProviderError::InvalidConfig(format!(
+                        "port {port} assigned to node '{}' is already in use",
+                        options.name
+                    )));
+                }
+            }
+        }
+
         let node = NativeNode::new(NativeNodeOptions {
             namespace: &self.weak,
             namespace_base_dir: &self.base_dir,
@@ -138,6 +184,10 @@ where
             startup_files: &options.injected_files,
             created_paths: &options.created_paths,
             db_snapshot: options.db_snapshot.as_ref(),
+            resources: options.resources.as_ref(),
+            log_rotation: options.log_rotation,
+            stop_grace: options.stop_grace,
+            detached: *self.detached.read().await,
             filesystem: &self.filesystem,
         })
         .await?;
@@ -214,7 +264,9 @@ where
     }
 
     async fn destroy(&self) -> Result<(), ProviderError> {
-        for node in self.nodes.read().await.values() {
+        // the nodes remove themselves from the namespace, so don't hold the lock
+        let nodes: Vec<_> = self.nodes.read().await.values().cloned().collect();
+        for node in nodes {
             node.destroy().await?;
         }
 
@@ -225,3 +277,136 @@ where
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use nix::{
+        sys::{
+            signal::{kill, Signal},
+            wait::{waitpid, WaitPidFlag, WaitStatus},
+        },
+        unistd::{getpgid, getpgrp, getsid, Pid},
+    };
+    use support::fs::local::LocalFileSystem;
+
+    use super::*;
+    use crate::Provider;
+
+    #[tokio::test]
+    async fn spawn_node_should_fail_if_a_port_is_already_in_use() {
+        let ns = NativeProvider::new(LocalFileSystem)
+            .create_namespace()
+            .await
+            .unwrap();
+        let listener = TcpListener::bind((LOCALHOST, 0)).unwrap();
+        let port = listener.local_addr().unwrap().port();
+
+        let err = ns
+            .spawn_node(
+                &SpawnNodeOptions::new("alice", "sleep")
+                    .args(vec!["60"])
+                    .port_mapping(HashMap::from([(port, port)])),
+            )
+            .await
+            .err()
+            .unwrap();
+
+        assert_eq!(
+            err.to_string(),
+            format!("Invalid network configuration field port {port} assigned to node 'alice' is already in use")
+        );
+        assert!(ns.nodes().await.is_empty());
+        ns.destroy().await.unwrap();
+    }
+
+    #[cfg(target_os = "linux")]
+    #[tokio::test]
+    async fn spawn_node_should_apply_the_resources_limits() {
+        use configuration::shared::resources::ResourcesBuilder;
+
+        let ns = NativeProvider::new(LocalFileSystem)
+            .create_namespace()
+            .await
+            .unwrap();
+        let resources = ResourcesBuilder::new()
+            .with_limit_nofile(128)
+            .with_limit_address_space("512Mi")
+            .build()
+            .unwrap();
+
+        let node = ns
+            .spawn_node(
+                &SpawnNodeOptions::new("alice", "sh")
+                    .args(vec!["-c", "ulimit -n; ulimit -v; sleep 60"])
+                    .resources(resources),
+            )
+            .await
+            .unwrap();
+
+        let mut logs = String::new();
+        for _ in 0..50 {
+            logs = node.logs().await.unwrap();
+            if logs.lines().count() >= 2 {
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+        }
+        // `ulimit -v` is in KiB
+        assert_eq!(logs.lines().collect::<Vec<_>>(), vec!["128", "524288"]);
+
+        node.destroy().await.unwrap();
+        ns.destroy().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn spawn_node_should_keep_the_node_in_the_process_group_of_the_parent() {
+        let ns = NativeProvider::new(LocalFileSystem)
+            .create_namespace()
+            .await
+            .unwrap();
+        let node = ns
+            .spawn_node(&SpawnNodeOptions::new("alice", "sleep").args(vec!["60"]))
+            .await
+            .unwrap();
+        let pid = Pid::from_raw(node.pid().await.unwrap() as i32);
+
+        // signals sent to our process group (e.g. ctrl-c) also reach the node
+        assert_eq!(getpgid(Some(pid)).unwrap(), getpgrp());
+
+        node.destroy().await.unwrap();
+        ns.destroy().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn detach_should_keep_the_nodes_running_after_the_namespace_is_dropped() {
+        let provider = NativeProvider::new(LocalFileSystem);
+        let ns = provider.create_namespace().await.unwrap();
+        let node = ns
+            .spawn_node(&SpawnNodeOptions::new("alice", "sleep").args(vec!["60"]))
+            .await
+            .unwrap();
+        let attached_pid = node.pid().await.unwrap();
+        let base_dir = ns.base_dir().clone();
+
+        ns.detach().await;
+        // the process is relaunched as the leader of its own session
+        let pid = node.pid().await.unwrap();
+        assert_ne!(pid, attached_pid);
+        let pid = Pid::from_raw(pid as i32);
+        assert_eq!(getsid(Some(pid)).unwrap(), pid);
+
+        drop(node);
+        drop(ns);
+        provider.namespaces.write().await.clear();
+        tokio::time::sleep(std::time::Duration::from_millis(200)).await;
+
+        // still running (not killed and waiting to be reaped)
+        assert_eq!(
+            waitpid(pid, Some(WaitPidFlag::WNOHANG)).unwrap(),
+            WaitStatus::StillAlive
+        );
+
+        kill(pid, Signal::SIGKILL).unwrap();
+        std::fs::remove_dir_all(base_dir).unwrap();
+    }
+}
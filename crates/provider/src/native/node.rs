@@ -1,15 +1,18 @@
 use std::{
-    collections::HashMap,
+    collections::{HashMap, VecDeque},
     env,
     path::{Path, PathBuf},
     process::Stdio,
-    sync::{Arc, Weak},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Weak,
+    },
     time::Duration,
 };
 
 use anyhow::anyhow;
 use async_trait::async_trait;
-use configuration::types::AssetLocation;
+use configuration::{shared::resources::Resources, types::AssetLocation};
 use flate2::read::GzDecoder;
 use futures::future::try_join_all;
 use nix::{
@@ -17,26 +20,26 @@ use nix::{
     unistd::Pid,
 };
 use sha2::Digest;
-use support::{constants::THIS_IS_A_BUG, fs::FileSystem};
+use support::{
+    constants::THIS_IS_A_BUG,
+    fs::{FileSystem, FileSystemError},
+};
 use tar::Archive;
 use tokio::{
-    io::{AsyncRead, AsyncReadExt, BufReader},
-    process::{Child, ChildStderr, ChildStdout, Command},
-    sync::{
-        mpsc::{self, Sender},
-        RwLock,
-    },
+    process::{Child, Command},
+    sync::RwLock,
     task::JoinHandle,
     time::sleep,
     try_join,
 };
-use tracing::trace;
+use tracing::{trace, warn};
 
 use super::namespace::NativeNamespace;
 use crate::{
     constants::{NODE_CONFIG_DIR, NODE_DATA_DIR, NODE_RELAY_DATA_DIR, NODE_SCRIPTS_DIR},
-    types::{ExecutionResult, RunCommandOptions, RunScriptOptions, TransferedFile},
-    ProviderError, ProviderNamespace, ProviderNode,
+    shared::helpers::{bytes_from_quantity, tail_lines},
+    types::{ExecutionResult, LogRotation, RunCommandOptions, RunScriptOptions, TransferedFile},
+    LogStream, ProviderError, ProviderNamespace, ProviderNode,
 };
 
 pub(super) struct NativeNodeOptions<'a, FS>
@@ -52,9 +55,236 @@ where
     pub(super) startup_files: &'a [TransferedFile],
     pub(super) created_paths: &'a [PathBuf],
     pub(super) db_snapshot: Option<&'a AssetLocation>,
+    pub(super) resources: Option<&'a Resources>,
+    pub(super) log_rotation: Option<LogRotation>,
+    pub(super) stop_grace: Option<Duration>,
+    // spawn the process already detached (e.g. added to a detached namespace)
+    pub(super) detached: bool,
     pub(super) filesystem: &'a FS,
 }
 
+/// Limits applied to the node process (through `setrlimit`) before `exec`.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+struct ProcessLimits {
+    /// Max number of open file descriptors (`RLIMIT_NOFILE`).
+    nofile: Option<u64>,
+    /// Max size of the process virtual address space in bytes (`RLIMIT_AS`), only set
+    /// if explicitly opted in (`limits.address_space`).
+    address_space: Option<u64>,
+}
+
+impl ProcessLimits {
+    fn from_resources(resources: &Resources) -> Self {
+        // the processes reserve many GiB of virtual memory, so the memory limit can't be
+        // mapped to `RLIMIT_AS` (the node would fail at startup).
+        if let Some(memory) = resources.limit_memory() {
+            warn!(
+                "⚠️ memory limit ({}) is ignored by the native provider, use `limits.address_space` to bound the process virtual memory",
+                memory.as_str()
+            );
+        }
+
+        Self {
+            nofile: resources.limit_nofile(),
+            address_space: resources
+                .limit_address_space()
+                .and_then(|quantity| bytes_from_quantity(quantity.as_str())),
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.nofile.is_none() && self.address_space.is_none()
+    }
+}
+
+/// Apply the limits in the spawned child, before `exec`.
+#[cfg(target_os = "linux")]
+fn apply_process_limits(command: &mut Command, limits: ProcessLimits) -> Result<(), ProviderError> {
+    use nix::sys::resource::{setrlimit, Resource};
+
+    if limits.is_empty() {
+        return Ok(());
+    }
+
+    // SAFETY: the closure only calls `setrlimit`, which is async-signal-safe.
+    unsafe {
+        command.pre_exec(move || {
+            if let Some(nofile) = limits.nofile {
+                setrlimit(Resource::RLIMIT_NOFILE, nofile, nofile)?;
+            }
+
+            if let Some(address_space) = limits.address_space {
+                setrlimit(Resource::RLIMIT_AS, address_space, address_space)?;
+            }
+
+            Ok(())
+        });
+    }
+
+    Ok(())
+}
+
+/// Start the child in a new session (`setsid`) before `exec`, so it doesn't receive the
+/// signals (e.g. `SIGHUP`/`SIGINT`) sent to the session/process group of the parent.
+/// Only used for detached nodes, the others stay in the process group of the parent.
+fn apply_new_session(command: &mut Command) {
+    // SAFETY: the closure only calls `setsid`, which is async-signal-safe.
+    unsafe {
+        command.pre_exec(|| {
+            nix::unistd::setsid()?;
+            Ok(())
+        });
+    }
+}
+
+/// Process limits are best-effort and only supported on Linux.
+#[cfg(not(target_os = "linux"))]
+fn apply_process_limits(
+    _command: &mut Command,
+    limits: ProcessLimits,
+) -> Result<(), ProviderError> {
+    if limits.is_empty() {
+        Ok(())
+    } else {
+        Err(ProviderError::Unsupported(
+            "process resource limits are only supported on linux".to_string(),
+        ))
+    }
+}
+
+/// Rotate the log, copying `<log>` to `<log>.1` (and moving `<log>.N` to `<log>.N+1`),
+/// keeping the last `max_files` rotated files.
+async fn rotate_log<FS>(
+    filesystem: &FS,
+    log_path: &Path,
+    max_files: usize,
+) -> Result<(), FileSystemError>
+where
+    FS: FileSystem,
+{
+    if max_files == 0 {
+        return filesystem.write(log_path, "").await;
+    }
+
+    let rotated = |index: usize| PathBuf::from(format!("{}.{index}", log_path.to_string_lossy()));
+    for index in (1..max_files).rev() {
+        if filesystem.exists(rotated(index)).await {
+            filesystem
+                .rename(rotated(index), rotated(index + 1))
+                .await?;
+        }
+    }
+
+    // the process keeps the log open (in append mode) and can't reopen it (it also survives
+    // a detach), so a renamed log would still be written. Copy and truncate it in place instead.
+    // NOTE: the lines written between the copy and the truncation are lost
+    filesystem.copy(log_path, rotated(1)).await?;
+    filesystem.write(log_path, "").await
+}
+
+/// Read the last `lines` lines of the log file, reading (through the `filesystem`) growing
+/// windows from the end of the file until enough lines are found.
+async fn read_log_tail<FS>(
+    filesystem: &FS,
+    log_path: &Path,
+    lines: usize,
+) -> Result<String, FileSystemError>
+where
+    FS: FileSystem,
+{
+    const WINDOW_SIZE: u64 = 64 * 1024;
+
+    let size = filesystem.file_size(log_path).await?;
+    let mut window = WINDOW_SIZE;
+    loop {
+        let offset = size.saturating_sub(window);
+        let contents = filesystem.read_from(log_path, offset).await?;
+        // the first line of the window can be partial, so one more line is needed
+        let newlines = contents.iter().filter(|byte| **byte == b'\n').count();
+
+        if offset == 0 || newlines > lines {
+            return Ok(tail_lines(&String::from_utf8_lossy(&contents), lines));
+        }
+        window *= 2;
+    }
+}
+
+/// Tail the log file (through the `filesystem`), polling for new content when we reach the end
+/// and following the file across truncations and rotations (counted by `rotations`).
+fn tail_log_file<FS>(
+    filesystem: FS,
+    name: String,
+    log_path: PathBuf,
+    rotations: Arc<AtomicUsize>,
+) -> LogStream
+where
+    FS: FileSystem + Send + Sync + Clone + 'static,
+{
+    const POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+    let seen_rotations = rotations.load(Ordering::Acquire);
+    Box::pin(futures::stream::try_unfold(
+        (0_u64, seen_rotations, vec![], VecDeque::new()),
+        move |(mut position, mut seen_rotations, mut partial, mut lines)| {
+            let (filesystem, log_path, name, rotations) = (
+                filesystem.clone(),
+                log_path.clone(),
+                name.clone(),
+                rotations.clone(),
+            );
+            let map_err =
+                move |err: FileSystemError| ProviderError::GetLogsFailed(name.clone(), err.into());
+
+            async move {
+                loop {
+                    if let Some(line) = lines.pop_front() {
+                        return Ok(Some((line, (position, seen_rotations, partial, lines))));
+                    }
+
+                    // loaded before reading, so reaching the end after a rotation means
+                    // everything written to the rotated file was read
+                    let current_rotations = rotations.load(Ordering::Acquire);
+                    let contents = filesystem
+                        .read_from(&log_path, position)
+                        .await
+                        .map_err(map_err.clone())?;
+
+                    if !contents.is_empty() {
+                        position += contents.len() as u64;
+                        partial.extend(contents);
+                        while let Some(end) = partial.iter().position(|byte| *byte == b'\n') {
+                            let line: Vec<u8> = partial.drain(..=end).collect();
+                            lines.push_back(
+                                String::from_utf8_lossy(&line)
+                                    .trim_end_matches(['\r', '\n'])
+                                    .to_string(),
+                            );
+                        }
+                        continue;
+                    }
+
+                    // nothing to read from the last byte read means the file is shorter
+                    let truncated = position > 0
+                        && filesystem
+                            .read_from(&log_path, position - 1)
+                            .await
+                            .map_err(map_err.clone())?
+                            .is_empty();
+
+                    if truncated || current_rotations != seen_rotations {
+                        // start again from the beginning
+                        seen_rotations = rotations.load(Ordering::Acquire);
+                        position = 0;
+                        partial.clear();
+                    } else {
+                        sleep(POLL_INTERVAL).await;
+                    }
+                }
+            }
+        },
+    ))
+}
+
 pub(super) struct NativeNode<FS>
 where
     FS: FileSystem + Send + Sync + Clone,
@@ -63,17 +293,29 @@ where
     name: String,
     program: String,
     args: Vec<String>,
+    // args to use (instead of `args`) when the process is relaunched
+    args_override: RwLock<Option<Vec<String>>>,
     env: Vec<(String, String)>,
+    limits: ProcessLimits,
     base_dir: PathBuf,
     config_dir: PathBuf,
     data_dir: PathBuf,
     relay_data_dir: PathBuf,
     scripts_dir: PathBuf,
     log_path: PathBuf,
+    // NOTE: the log is rotated by the controlling process, so it isn't rotated
+    // once the controlling process of a detached node exits
+    log_rotation: Option<LogRotation>,
+    // number of rotations done, used by the log streams to follow the new file
+    log_rotations: Arc<AtomicUsize>,
+    // time to wait for the process to exit after `SIGTERM` before killing it
+    stop_grace: Option<Duration>,
+    // the process writes the log directly to `log_path`, so it can keep writing it
+    // after the exit of the controlling process (see `detach`)
     process: RwLock<Option<Child>>,
-    stdout_reading_task: RwLock<Option<JoinHandle<()>>>,
-    stderr_reading_task: RwLock<Option<JoinHandle<()>>>,
-    log_writing_task: RwLock<Option<JoinHandle<()>>>,
+    // detached processes run in their own session and are not killed on drop
+    detached: RwLock<bool>,
+    log_rotation_task: RwLock<Option<JoinHandle<()>>>,
     filesystem: FS,
 }
 
@@ -113,17 +355,24 @@ where
             name: options.name.to_string(),
             program: options.program.to_string(),
             args: options.args.to_vec(),
+            args_override: RwLock::new(None),
             env: options.env.to_vec(),
+            limits: options
+                .resources
+                .map(ProcessLimits::from_resources)
+                .unwrap_or_default(),
             base_dir,
             config_dir,
             data_dir,
             relay_data_dir,
             scripts_dir,
             log_path,
+            log_rotation: options.log_rotation,
+            log_rotations: Arc::new(AtomicUsize::new(0)),
+            stop_grace: options.stop_grace,
             process: RwLock::new(None),
-            stdout_reading_task: RwLock::new(None),
-            stderr_reading_task: RwLock::new(None),
-            log_writing_task: RwLock::new(None),
+            detached: RwLock::new(options.detached),
+            log_rotation_task: RwLock::new(None),
             filesystem: filesystem.clone(),
         });
 
@@ -134,9 +383,7 @@ where
             node.initialize_db_snapshot(db_snap).await?;
         }
 
-        let (stdout, stderr) = node.initialize_process().await?;
-
-        node.initialize_log_writing(stdout, stderr).await;
+        node.initialize_process().await?;
 
         Ok(node)
     }
@@ -183,6 +430,7 @@ where
             AssetLocation::FilePath(filepath) => {
                 hex::encode(sha2::Sha256::digest(filepath.to_string_lossy().to_string()))
             },
+            AssetLocation::InlineJson(content) => hex::encode(sha2::Sha256::digest(content)),
         };
 
         let full_path = format!("{}/{}.tgz", ns_base_dir, hashed_location);
@@ -221,97 +469,104 @@ where
             AssetLocation::FilePath(filepath) => {
                 self.filesystem.copy(filepath, full_path).await?;
             },
+            AssetLocation::InlineJson(_) => {
+                return Err(ProviderError::InvalidConfig(
+                    "db_snapshot can't be an inline json".to_string(),
+                ));
+            },
         };
 
         Ok(())
     }
 
-    async fn initialize_process(&self) -> Result<(ChildStdout, ChildStderr), ProviderError> {
+    // Spawn the process, writing the outputs directly to the log file. Detached processes are
+    // spawned in their own session, so they keep running after the controlling process exits.
+    async fn initialize_process(&self) -> Result<(), ProviderError> {
         let filtered_env: HashMap<String, String> = env::vars()
             .filter(|(k, _)| k == "TZ" || k == "LANG" || k == "PATH")
             .collect();
 
-        let mut process = Command::new(&self.program)
-            .args(&self.args)
+        let args = self
+            .args_override
+            .read()
+            .await
+            .clone()
+            .unwrap_or_else(|| self.args.clone());
+
+        let detached = *self.detached.read().await;
+        let mut command = Command::new(&self.program);
+        command
+            .args(&args)
             .env_clear()
             .envs(&filtered_env) // minimal environment
             .envs(self.env.to_vec())
             .stdin(Stdio::null())
-            .stdout(Stdio::piped())
-            .stderr(Stdio::piped())
-            .kill_on_drop(true)
-            .current_dir(&self.base_dir)
-            .spawn()
+            .current_dir(&self.base_dir);
+        apply_process_limits(&mut command, self.limits)?;
+
+        let log_file = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.log_path)
             .map_err(|err| ProviderError::NodeSpawningFailed(self.name.to_string(), err.into()))?;
-        let stdout = process
-            .stdout
-            .take()
-            .expect(&format!("infaillible, stdout is piped {THIS_IS_A_BUG}"));
-        let stderr = process
-            .stderr
-            .take()
-            .expect(&format!("infaillible, stderr is piped {THIS_IS_A_BUG}"));
+        let log_file_err = log_file
+            .try_clone()
+            .map_err(|err| ProviderError::NodeSpawningFailed(self.name.to_string(), err.into()))?;
+
+        command
+            .stdout(Stdio::from(log_file))
+            .stderr(Stdio::from(log_file_err))
+            .kill_on_drop(!detached);
+        if detached {
+            apply_new_session(&mut command);
+        }
 
+        let process = command
+            .spawn()
+            .map_err(|err| ProviderError::NodeSpawningFailed(self.name.to_string(), err.into()))?;
         self.process.write().await.replace(process);
 
-        Ok((stdout, stderr))
-    }
+        self.initialize_log_rotation().await;
 
-    async fn initialize_log_writing(&self, stdout: ChildStdout, stderr: ChildStderr) {
-        let (stdout_tx, mut rx) = mpsc::channel(10);
-        let stderr_tx = stdout_tx.clone();
+        Ok(())
+    }
 
-        self.stdout_reading_task
-            .write()
-            .await
-            .replace(self.create_stream_polling_task(stdout, stdout_tx));
-        self.stderr_reading_task
-            .write()
-            .await
-            .replace(self.create_stream_polling_task(stderr, stderr_tx));
+    // Rotate the log once it exceeds the max size. The process keeps the log open (in append
+    // mode), so the log is copied and truncated in place (see `rotate_log`).
+    async fn initialize_log_rotation(&self) {
+        let Some(rotation) = self.log_rotation else {
+            return;
+        };
 
         let filesystem = self.filesystem.clone();
         let log_path = self.log_path.clone();
+        let log_rotations = self.log_rotations.clone();
 
-        self.log_writing_task
+        self.log_rotation_task
             .write()
             .await
             .replace(tokio::spawn(async move {
                 loop {
-                    while let Some(Ok(data)) = rx.recv().await {
-                        // TODO: find a better way instead of ignoring error ?
-                        let _ = filesystem.append(&log_path, data).await;
-                    }
                     sleep(Duration::from_millis(250)).await;
-                }
-            }));
-    }
 
-    fn create_stream_polling_task(
-        &self,
-        stream: impl AsyncRead + Unpin + Send + 'static,
-        tx: Sender<Result<Vec<u8>, std::io::Error>>,
-    ) -> JoinHandle<()> {
-        tokio::spawn(async move {
-            let mut reader = BufReader::new(stream);
-            let mut buffer = vec![0u8; 1024];
-
-            loop {
-                match reader.read(&mut buffer).await {
-                    Ok(0) => {
-                        let _ = tx.send(Ok(Vec::new())).await;
-                        break;
-                    },
-                    Ok(n) => {
-                        let _ = tx.send(Ok(buffer[..n].to_vec())).await;
-                    },
-                    Err(e) => {
-                        let _ = tx.send(Err(e)).await;
-                        break;
-                    },
+                    // only the content past the cap is read
+                    let exceeded = filesystem
+                        .read_from(&log_path, rotation.max_bytes)
+                        .await
+                        .map(|contents| !contents.is_empty())
+                        .unwrap_or_default();
+
+                    if exceeded {
+                        if let Err(err) =
+                            rotate_log(&filesystem, &log_path, rotation.max_files).await
+                        {
+                            warn!("⚠️ Failed to rotate log {log_path:?}: {err}");
+                        } else {
+                            log_rotations.fetch_add(1, Ordering::Release);
+                        }
+                    }
                 }
-            }
-        })
+            }));
     }
 
     async fn process_id(&self) -> Result<Pid, ProviderError> {
@@ -326,37 +581,39 @@ where
         Ok(Pid::from_raw(raw_pid as i32))
     }
 
+    // Stop the node process (a noop if it's already stopped) and the log tasks.
     async fn abort(&self) -> anyhow::Result<()> {
-        self.log_writing_task
-            .write()
-            .await
-            .take()
-            .ok_or_else(|| anyhow!("no log writing task was attached for the node"))?
-            .abort();
+        let Some(mut process) = self.process.write().await.take() else {
+            return Ok(());
+        };
 
-        self.stdout_reading_task
-            .write()
-            .await
-            .take()
-            .ok_or_else(|| anyhow!("no stdout reading task was attached for the node"))?
-            .abort();
+        stop_process(&mut process, self.stop_grace).await?;
 
-        self.stderr_reading_task
-            .write()
-            .await
-            .take()
-            .ok_or_else(|| anyhow!("no stderr reading task was attached for the node"))?
-            .abort();
+        if let Some(task) = self.log_rotation_task.write().await.take() {
+            task.abort();
+        }
 
-        self.process
-            .write()
+        Ok(())
+    }
+
+    /// Relaunch the process in a new session, so the node keeps running after the node is
+    /// dropped and the controlling process exits.
+    ///
+    /// NOTE: the session of a running process can't be changed from the outside, so the
+    /// running process is stopped and relaunched (with the same args and data dir).
+    pub(super) async fn detach(&self) -> Result<(), ProviderError> {
+        if *self.detached.read().await {
+            return Ok(());
+        }
+
+        self.abort()
             .await
-            .take()
-            .ok_or_else(|| anyhow!("no process was attached for the node"))?
-            .kill()
-            .await?;
+            .map_err(|err| ProviderError::DetachNodeFailed(self.name.clone(), err))?;
+        *self.detached.write().await = true;
 
-        Ok(())
+        self.initialize_process()
+            .await
+            .map_err(|err| ProviderError::DetachNodeFailed(self.name.clone(), err.into()))
     }
 
     fn namespace_base_dir(&self) -> String {
@@ -425,6 +682,21 @@ where
         Ok(self.filesystem.read_to_string(&self.log_path).await?)
     }
 
+    async fn logs_tail(&self, lines: usize) -> Result<String, ProviderError> {
+        read_log_tail(&self.filesystem, &self.log_path, lines)
+            .await
+            .map_err(|err| ProviderError::GetLogsFailed(self.name.clone(), err.into()))
+    }
+
+    fn log_stream(&self) -> LogStream {
+        tail_log_file(
+            self.filesystem.clone(),
+            self.name.clone(),
+            self.log_path.clone(),
+            self.log_rotations.clone(),
+        )
+    }
+
     async fn dump_logs(&self, local_dest: PathBuf) -> Result<(), ProviderError> {
         Ok(self.filesystem.copy(&self.log_path, local_dest).await?)
     }
@@ -546,7 +818,27 @@ where
         Ok(())
     }
 
+    async fn pid(&self) -> Option<u32> {
+        self.process
+            .read()
+            .await
+            .as_ref()
+            .and_then(|process| process.id())
+    }
+
     async fn pause(&self) -> Result<(), ProviderError> {
+        // with a grace period the process is stopped cleanly and relaunched on `resume`
+        if self.stop_grace.is_some() {
+            return self
+                .abort()
+                .await
+                .map_err(|err| ProviderError::PauseNodeFailed(self.name.clone(), err));
+        }
+
+        self.freeze().await
+    }
+
+    async fn freeze(&self) -> Result<(), ProviderError> {
         let process_id = self.process_id().await?;
 
         kill(process_id, Signal::SIGSTOP)
@@ -556,6 +848,14 @@ where
     }
 
     async fn resume(&self) -> Result<(), ProviderError> {
+        // stopped by `pause`, relaunch the process
+        if self.process.read().await.is_none() {
+            return self
+                .initialize_process()
+                .await
+                .map_err(|err| ProviderError::ResumeNodeFailed(self.name.clone(), err.into()));
+        }
+
         let process_id = self.process_id().await?;
 
         nix::sys::signal::kill(process_id, Signal::SIGCONT)
@@ -573,14 +873,18 @@ where
             .await
             .map_err(|err| ProviderError::RestartNodeFailed(self.name.clone(), err))?;
 
-        let (stdout, stderr) = self
-            .initialize_process()
+        self.initialize_process()
             .await
-            .map_err(|err| ProviderError::RestartNodeFailed(self.name.clone(), err.into()))?;
-
-        self.initialize_log_writing(stdout, stderr).await;
+            .map_err(|err| ProviderError::RestartNodeFailed(self.name.clone(), err.into()))
+    }
 
-        Ok(())
+    async fn restart_with_args(
+        &self,
+        args: Vec<String>,
+        after: Option<Duration>,
+    ) -> Result<(), ProviderError> {
+        self.args_override.write().await.replace(args);
+        self.restart(after).await
     }
 
     async fn destroy(&self) -> Result<(), ProviderError> {
@@ -595,3 +899,194 @@ where
         Ok(())
     }
 }
+
+impl<FS> Drop for NativeNode<FS>
+where
+    FS: FileSystem + Send + Sync + Clone,
+{
+    fn drop(&mut self) {
+        if let Some(task) = self.log_rotation_task.get_mut().take() {
+            task.abort();
+        }
+    }
+}
+
+/// Stop the `process`, sending `SIGTERM` and waiting up to the `grace` period for it to exit
+/// before killing it. Without grace period the process is killed right away.
+async fn stop_process(process: &mut Child, grace: Option<Duration>) -> anyhow::Result<()> {
+    if let (Some(grace), Some(raw_pid)) = (grace, process.id()) {
+        let pid = Pid::from_raw(raw_pid as i32);
+        kill(pid, Signal::SIGTERM)?;
+        // a frozen (`SIGSTOP`) process only handles the signal once resumed
+        let _ = kill(pid, Signal::SIGCONT);
+
+        if let Ok(Ok(_)) = tokio::time::timeout(grace, process.wait()).await {
+            return Ok(());
+        }
+
+        warn!("⚠️  process {raw_pid} still running after {grace:?}, killing it");
+    }
+
+    process.kill().await?;
+
+    Ok(())
+}
+
+#[cfg(all(test, target_os = "linux"))]
+mod tests {
+    use futures::StreamExt;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn nofile_limit_is_applied_to_the_child_process() {
+        let mut command = Command::new("sh");
+        command.args(["-c", "ulimit -n"]);
+        apply_process_limits(
+            &mut command,
+            ProcessLimits {
+                nofile: Some(128),
+                address_space: None,
+            },
+        )
+        .unwrap();
+
+        let output = command.output().await.unwrap();
+
+        assert!(output.status.success());
+        assert_eq!(String::from_utf8_lossy(&output.stdout).trim(), "128");
+    }
+
+    #[tokio::test]
+    async fn process_is_stopped_gracefully_within_the_grace_period() {
+        let mut process = Command::new("sh")
+            .args(["-c", "trap 'exit 3' TERM; while true; do sleep 0.1; done"])
+            .spawn()
+            .unwrap();
+        // let the shell install the trap
+        sleep(Duration::from_millis(200)).await;
+
+        stop_process(&mut process, Some(Duration::from_secs(5)))
+            .await
+            .unwrap();
+
+        // exited through the trap, not killed
+        assert_eq!(process.wait().await.unwrap().code(), Some(3));
+    }
+
+    #[tokio::test]
+    async fn process_is_killed_after_the_grace_period() {
+        let mut process = Command::new("sh")
+            .args(["-c", "trap '' TERM; while true; do sleep 0.1; done"])
+            .spawn()
+            .unwrap();
+        sleep(Duration::from_millis(200)).await;
+
+        stop_process(&mut process, Some(Duration::from_millis(300)))
+            .await
+            .unwrap();
+
+        assert_eq!(process.wait().await.unwrap().code(), None);
+    }
+
+    #[tokio::test]
+    async fn new_session_is_created_for_the_child_process() {
+        let mut command = Command::new("sh");
+        command.args(["-c", "ps -o sid= -p $$"]);
+        apply_new_session(&mut command);
+
+        let child = command.stdout(Stdio::piped()).spawn().unwrap();
+        let pid = child.id().unwrap();
+        let output = child.wait_with_output().await.unwrap();
+
+        assert!(output.status.success());
+        // the child is the leader of its own session
+        assert_eq!(
+            String::from_utf8_lossy(&output.stdout).trim(),
+            pid.to_string()
+        );
+    }
+
+    async fn next_lines(stream: &mut LogStream, count: usize) -> Vec<String> {
+        tokio::time::timeout(Duration::from_secs(5), async {
+            let mut lines = vec![];
+            while lines.len() < count {
+                lines.push(stream.next().await.unwrap().unwrap());
+            }
+            lines
+        })
+        .await
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn log_tail_should_only_return_the_last_lines() {
+        let fs = support::fs::in_memory::InMemoryFileSystem::new(HashMap::from([
+            ("/".into(), support::fs::in_memory::InMemoryFile::dir()),
+            (
+                "/alice.log".into(),
+                support::fs::in_memory::InMemoryFile::file(""),
+            ),
+        ]));
+        // bigger than a window, so the end is read in more than one step
+        let contents = (1..=20_000)
+            .map(|line| format!("line {line}\n"))
+            .collect::<String>();
+        fs.append("/alice.log", contents).await.unwrap();
+
+        let tail = read_log_tail(&fs, Path::new("/alice.log"), 2)
+            .await
+            .unwrap();
+        assert_eq!(tail, "line 19999\nline 20000");
+
+        let tail = read_log_tail(&fs, Path::new("/alice.log"), 15_000)
+            .await
+            .unwrap();
+        assert_eq!(tail.lines().count(), 15_000);
+        assert_eq!(tail.lines().next(), Some("line 5001"));
+
+        let tail = read_log_tail(&fs, Path::new("/alice.log"), 30_000)
+            .await
+            .unwrap();
+        assert_eq!(tail.lines().count(), 20_000);
+    }
+
+    #[tokio::test]
+    async fn log_exceeding_the_cap_is_rotated_and_the_stream_keeps_following_it() {
+        let test_dir = PathBuf::from(format!("/tmp/unit_test_{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir(&test_dir).unwrap();
+        let fs = support::fs::local::LocalFileSystem;
+        let log_path = test_dir.join("alice.log");
+        fs.append(&log_path, "line 1\nline 2\n").await.unwrap();
+
+        let rotations = Arc::new(AtomicUsize::new(0));
+        let mut stream = tail_log_file(
+            fs.clone(),
+            "alice".into(),
+            log_path.clone(),
+            rotations.clone(),
+        );
+        assert_eq!(stream.next().await.unwrap().unwrap(), "line 1");
+
+        // rotate (keeping only one rotated file) and write to the new file
+        rotate_log(&fs, &log_path, 1).await.unwrap();
+        rotations.fetch_add(1, Ordering::Release);
+        fs.append(&log_path, "line 3\n").await.unwrap();
+
+        // the stream finishes the rotated file and then follows the new one
+        assert_eq!(next_lines(&mut stream, 2).await, vec!["line 2", "line 3"]);
+
+        rotate_log(&fs, &log_path, 1).await.unwrap();
+        rotations.fetch_add(1, Ordering::Release);
+        fs.append(&log_path, "line 4\n").await.unwrap();
+
+        assert_eq!(next_lines(&mut stream, 1).await, vec!["line 4"]);
+        assert_eq!(
+            std::fs::read_to_string(test_dir.join("alice.log.1")).unwrap(),
+            "line 3\n"
+        );
+        assert!(!test_dir.join("alice.log.2").exists());
+
+        std::fs::remove_dir_all(test_dir).unwrap();
+    }
+}
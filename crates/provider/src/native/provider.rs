@@ -1,19 +1,29 @@
 use std::{
     collections::HashMap,
-    path::{Path, PathBuf},
+    path::PathBuf,
     sync::{Arc, Weak},
+    time::Duration,
 };
 
 use async_trait::async_trait;
+use nix::{
+    errno::Errno,
+    sys::signal::{kill, Signal},
+    unistd::Pid,
+};
 use support::fs::FileSystem;
-use tokio::sync::RwLock;
+use tokio::{sync::RwLock, time::Instant};
+use tracing::warn;
 
 use super::namespace::NativeNamespace;
 use crate::{
-    types::ProviderCapabilities, DynNamespace, Provider, ProviderError, ProviderNamespace,
+    types::{CreateNamespaceOptions, ProviderCapabilities},
+    DynNamespace, Provider, ProviderError, ProviderNamespace,
 };
 
 const PROVIDER_NAME: &str = "native";
+// used when the available parallelism of the machine can not be determined
+const DEFAULT_MAX_SPAWN_CONCURRENCY: usize = 4;
 
 pub struct NativeProvider<FS>
 where
@@ -34,10 +44,16 @@ where
         Arc::new_cyclic(|weak| NativeProvider {
             weak: weak.clone(),
             capabilities: ProviderCapabilities {
-                has_resources: false,
+                // the resources limits are applied to the node process
+                has_resources: true,
                 requires_image: false,
                 prefix_with_full_path: true,
                 use_default_ports_in_cmd: false,
+                // processes run in the local machine, bound the tasks to the cpus
+                max_spawn_concurrency: std::thread::available_parallelism()
+                    .map(|n| n.get())
+                    .unwrap_or(DEFAULT_MAX_SPAWN_CONCURRENCY),
+                supports_runtime_node_addition: true,
             },
             // NOTE: temp_dir in linux return `/tmp` but on mac something like
             //  `/var/folders/rz/1cyx7hfj31qgb98d8_cg7jwh0000gn/T/`, having
@@ -55,6 +71,48 @@ where
     }
 }
 
+/// Stop the nodes detached by a previous run, from their recorded process ids by name (e.g.
+/// `pids` in `zombie.json`). `SIGTERM` is sent to each process and the ones still running
+/// after the `grace` period are killed, processes that already exited are skipped.
+///
+/// NOTE: the system can reuse the id of an exited process, so this should only be used for
+/// nodes known to be still running.
+pub async fn destroy_detached_nodes(
+    pids: &HashMap<String, u32>,
+    grace: Duration,
+) -> Result<(), ProviderError> {
+    let mut running = vec![];
+    for (name, raw_pid) in pids {
+        let pid = Pid::from_raw(*raw_pid as i32);
+        match kill(pid, Signal::SIGTERM) {
+            Ok(()) => {
+                // a frozen (`SIGSTOP`) process only handles the signal once resumed
+                let _ = kill(pid, Signal::SIGCONT);
+                running.push((name, pid));
+            },
+            Err(Errno::ESRCH) => {},
+            Err(err) => return Err(ProviderError::DestroyNodeFailed(name.clone(), err.into())),
+        }
+    }
+
+    // the processes are not our children, so poll until they exit
+    let deadline = Instant::now() + grace;
+    while !running.is_empty() && Instant::now() < deadline {
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        running.retain(|(_, pid)| kill(*pid, None).is_ok());
+    }
+
+    for (name, pid) in running {
+        warn!("⚠️  node {name} (pid {pid}) still running after {grace:?}, killing it");
+        match kill(pid, Signal::SIGKILL) {
+            Ok(()) | Err(Errno::ESRCH) => {},
+            Err(err) => return Err(ProviderError::DestroyNodeFailed(name.clone(), err.into())),
+        }
+    }
+
+    Ok(())
+}
+
 #[async_trait]
 impl<FS> Provider for NativeProvider<FS>
 where
@@ -77,13 +135,17 @@ where
             .collect()
     }
 
-    async fn create_namespace(&self) -> Result<DynNamespace, ProviderError> {
+    async fn create_namespace_with_options(
+        &self,
+        options: &CreateNamespaceOptions,
+    ) -> Result<DynNamespace, ProviderError> {
         let namespace = NativeNamespace::new(
             &self.weak,
             &self.tmp_dir,
             &self.capabilities,
             &self.filesystem,
-            None,
+            options.base_dir.as_deref(),
+            options.name_prefix.as_deref(),
         )
         .await?;
 
@@ -94,25 +156,52 @@ where
 
         Ok(namespace)
     }
+}
 
-    async fn create_namespace_with_base_dir(
-        &self,
-        base_dir: &Path,
-    ) -> Result<DynNamespace, ProviderError> {
-        let namespace = NativeNamespace::new(
-            &self.weak,
-            &self.tmp_dir,
-            &self.capabilities,
-            &self.filesystem,
-            Some(base_dir),
-        )
-        .await?;
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-        self.namespaces
-            .write()
+    // spawn a process that is not our child (as the nodes detached by a previous run)
+    fn spawn_orphan_process(script: &str) -> u32 {
+        let output = std::process::Command::new("sh")
+            .args(["-c", &format!("{script} > /dev/null 2>&1 & echo $!")])
+            .output()
+            .unwrap();
+
+        String::from_utf8_lossy(&output.stdout)
+            .trim()
+            .parse()
+            .unwrap()
+    }
+
+    async fn wait_exit(pid: u32) -> bool {
+        for _ in 0..50 {
+            if kill(Pid::from_raw(pid as i32), None).is_err() {
+                return true;
+            }
+            tokio::time::sleep(Duration::from_millis(100)).await;
+        }
+
+        false
+    }
+
+    #[tokio::test]
+    async fn destroy_detached_nodes_should_stop_the_recorded_processes() {
+        let alice = spawn_orphan_process("sleep 60");
+        // ignores `SIGTERM`, so it's killed after the grace period
+        let bob = spawn_orphan_process("trap '' TERM; while true; do sleep 0.1; done");
+        let pids = HashMap::from([("alice".to_string(), alice), ("bob".to_string(), bob)]);
+
+        destroy_detached_nodes(&pids, Duration::from_millis(500))
             .await
-            .insert(namespace.name().to_string(), namespace.clone());
+            .unwrap();
 
-        Ok(namespace)
+        assert!(wait_exit(alice).await);
+        assert!(wait_exit(bob).await);
+        // the processes that already exited are skipped
+        destroy_detached_nodes(&pids, Duration::from_millis(500))
+            .await
+            .unwrap();
     }
 }
@@ -20,3 +20,5 @@ pub const PROMETHEUS_PORT: u16 = 9615;
 pub const RPC_WS_PORT: u16 = 9944;
 /// The remote port HTTP to access the RPC
 pub const RPC_HTTP_PORT: u16 = 9933;
+/// Header used by the file server to check the integrity (sha256) of the files
+pub const CONTENT_SHA256_HEADER: &str = "x-content-sha256";
@@ -1,13 +1,140 @@
-use std::env;
+use std::{collections::HashMap, env, path::PathBuf};
+
+use anyhow::anyhow;
+use uuid::Uuid;
+
+use super::{constants::NAMESPACE_PREFIX, types::RunCommandOptions};
+use crate::DynNamespace;
 
 /// Check if we are running in `CI` by checking the 'RUN_IN_CI' env var
 pub fn running_in_ci() -> bool {
     env::var("RUN_IN_CI").unwrap_or_default() == "1"
 }
 
+/// Build a tar archive (in memory) with the files `(content, remote_path, mode)`.
+/// Entries are stored relative to `/`, so extracting the archive in a directory
+/// reproduces the remote layout under it.
+pub(crate) fn build_files_archive(
+    files: &[(Vec<u8>, PathBuf, String)],
+) -> Result<Vec<u8>, anyhow::Error> {
+    let mut builder = tar::Builder::new(Vec::new());
+
+    for (content, remote_path, mode) in files {
+        let mode = u32::from_str_radix(mode, 8).map_err(|_| {
+            anyhow!(
+                "invalid mode '{mode}' for file {}",
+                remote_path.to_string_lossy()
+            )
+        })?;
+        let entry_path = remote_path.strip_prefix("/").unwrap_or(remote_path);
+
+        let mut header = tar::Header::new_gnu();
+        header.set_size(content.len() as u64);
+        header.set_mode(mode);
+        builder.append_data(&mut header, entry_path, content.as_slice())?;
+    }
+
+    Ok(builder.into_inner()?)
+}
+
+/// Command to extract the (already transfered) `archive` into a temp dir and then move
+/// the files to their final location, cleaning up after.
+pub(crate) fn extract_files_archive_cmd(archive: &str, tmp_dir: &str) -> RunCommandOptions {
+    RunCommandOptions::new("mkdir").args([
+        "-p",
+        tmp_dir,
+        "&&",
+        "tar",
+        "-xf",
+        archive,
+        "-C",
+        tmp_dir,
+        "&&",
+        "cp",
+        "-a",
+        &format!("{tmp_dir}/."),
+        "/",
+        "&&",
+        "rm",
+        "-rf",
+        tmp_dir,
+        archive,
+    ])
+}
+
+/// Generate a random namespace name, prefixed with `prefix` (or [`NAMESPACE_PREFIX`]).
+pub(crate) fn generate_namespace_name(prefix: Option<&str>) -> String {
+    match prefix {
+        Some(prefix) => format!("{prefix}-{}", Uuid::new_v4()),
+        None => format!("{NAMESPACE_PREFIX}{}", Uuid::new_v4()),
+    }
+}
+
+/// Keep only the last `lines` lines of `content`.
+pub(crate) fn tail_lines(content: &str, lines: usize) -> String {
+    let skip = content.lines().count().saturating_sub(lines);
+    content.lines().skip(skip).collect::<Vec<_>>().join("\n")
+}
+
+/// Parse the `<command> --version` output (e.g. `polkadot 1.7.0-97df9dd6554`),
+/// keeping the first non empty line.
+pub(crate) fn parse_version_output(output: &str) -> Option<String> {
+    output
+        .lines()
+        .map(str::trim)
+        .find(|line| !line.is_empty())
+        .map(str::to_string)
+}
+
+/// Split a k8s quantity (e.g `500m`, `2Gi`) in the numeric part and the suffix.
+pub(crate) fn split_quantity(quantity: &str) -> Option<(f64, &str)> {
+    let idx = quantity
+        .find(|c: char| c.is_ascii_alphabetic())
+        .unwrap_or(quantity.len());
+    let (value, suffix) = quantity.split_at(idx);
+    value.parse::<f64>().ok().map(|value| (value, suffix))
+}
+
+/// Convert a k8s memory quantity to bytes (e.g `1Ki` -> `1024`).
+pub(crate) fn bytes_from_quantity(quantity: &str) -> Option<u64> {
+    let (value, suffix) = split_quantity(quantity)?;
+    let multiplier: f64 = match suffix {
+        "" => 1_f64,
+        "m" => 0.001,
+        "K" => 1e3,
+        "M" => 1e6,
+        "G" => 1e9,
+        "T" => 1e12,
+        "P" => 1e15,
+        "E" => 1e18,
+        "Ki" => 1024_f64,
+        "Mi" => 1024_f64.powi(2),
+        "Gi" => 1024_f64.powi(3),
+        "Ti" => 1024_f64.powi(4),
+        "Pi" => 1024_f64.powi(5),
+        "Ei" => 1024_f64.powi(6),
+        _ => return None,
+    };
+
+    Some((value * multiplier).ceil() as u64)
+}
+
+/// Destroy (best effort) the namespaces that are not detached,
+/// detached namespaces are kept running.
+pub(crate) async fn destroy_attached_namespaces(namespaces: HashMap<String, DynNamespace>) {
+    for (_, ns) in namespaces {
+        if !ns.is_detached().await {
+            let _ = ns.destroy().await;
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
+    use support::fs::local::LocalFileSystem;
+
     use super::*;
+    use crate::{NativeProvider, Provider};
 
     #[test]
     fn check_runing_in_ci_env_var() {
@@ -18,4 +145,104 @@ mod tests {
         // reset
         env::set_var("RUN_IN_CI", "");
     }
+
+    #[test]
+    fn generate_namespace_name_works() {
+        assert!(generate_namespace_name(None).starts_with("zombie-"));
+
+        let name = generate_namespace_name(Some("ci-1234"));
+        assert!(name.starts_with("ci-1234-"));
+        assert!(Uuid::parse_str(name.trim_start_matches("ci-1234-")).is_ok());
+    }
+
+    #[test]
+    fn tail_lines_works() {
+        let content = "line 1\nline 2\nline 3\n";
+
+        assert_eq!(tail_lines(content, 2), "line 2\nline 3");
+        assert_eq!(tail_lines(content, 10), "line 1\nline 2\nline 3");
+        assert_eq!(tail_lines(content, 0), "");
+    }
+
+    #[test]
+    fn parse_version_output_works() {
+        assert_eq!(
+            parse_version_output("\npolkadot 1.7.0-97df9dd6554\n"),
+            Some("polkadot 1.7.0-97df9dd6554".to_string())
+        );
+        assert_eq!(parse_version_output("  \n"), None);
+    }
+
+    #[test]
+    fn bytes_from_quantity_works() {
+        assert_eq!(bytes_from_quantity("1024"), Some(1024));
+        assert_eq!(bytes_from_quantity("1K"), Some(1000));
+        assert_eq!(bytes_from_quantity("1Ki"), Some(1024));
+        assert_eq!(bytes_from_quantity("2Gi"), Some(2 * 1024 * 1024 * 1024));
+        assert_eq!(bytes_from_quantity("1.5M"), Some(1_500_000));
+        assert_eq!(bytes_from_quantity("invalid"), None);
+    }
+
+    #[test]
+    fn build_files_archive_works() {
+        let files = vec![
+            (
+                b"chain spec".to_vec(),
+                PathBuf::from("/cfg/rococo-local.json"),
+                "0644".to_string(),
+            ),
+            (
+                b"#!/bin/bash".to_vec(),
+                PathBuf::from("/scripts/run.sh"),
+                "0755".to_string(),
+            ),
+        ];
+
+        let archive = build_files_archive(&files).unwrap();
+        let mut archive = tar::Archive::new(archive.as_slice());
+        let entries: Vec<(PathBuf, u32)> = archive
+            .entries()
+            .unwrap()
+            .map(|entry| {
+                let entry = entry.unwrap();
+                (
+                    entry.path().unwrap().to_path_buf(),
+                    entry.header().mode().unwrap(),
+                )
+            })
+            .collect();
+
+        assert_eq!(
+            entries,
+            vec![
+                (PathBuf::from("cfg/rococo-local.json"), 0o644),
+                (PathBuf::from("scripts/run.sh"), 0o755),
+            ]
+        );
+    }
+
+    #[test]
+    fn build_files_archive_with_invalid_mode_should_fails() {
+        let files = vec![(vec![], PathBuf::from("/cfg/file"), "rw-r--r--".to_string())];
+
+        assert!(build_files_archive(&files).is_err());
+    }
+
+    #[tokio::test]
+    async fn destroy_attached_namespaces_should_keep_the_detached_ones() {
+        let provider = NativeProvider::new(LocalFileSystem);
+        let attached = provider.create_namespace().await.unwrap();
+        let detached = provider.create_namespace().await.unwrap();
+        detached.detach().await;
+
+        destroy_attached_namespaces(provider.namespaces().await).await;
+
+        let namespaces = provider.namespaces().await;
+        assert_eq!(namespaces.len(), 1);
+        assert!(namespaces.contains_key(detached.name()));
+
+        for ns in [attached, detached] {
+            std::fs::remove_dir_all(ns.base_dir()).unwrap();
+        }
+    }
 }
@@ -121,25 +121,49 @@ resume() {
 }
 
 
+# if the container was restarted (e.g. by a docker restart policy) the pid file
+# of the previous run is still there, so start the process right away.
+if [ -f $ZOMBIE_CMD_PID ]; then
+    start
+fi
+
+# keep the pipe open (read-write), so reading doesn't block waiting for a writer
+exec 3<>$pipe
+
+# the loop below polls the pipe every second, stop tracing so the node logs aren't
+# flooded with the trace of each iteration
+set +x
+
 # keep listening from the pipe
-while read line <$pipe
-echo "read line: ${line}"
+while true
 do
-    if [[ "$line" == "start" ]]; then
-        start
-    elif [[ "$line" == "quit" ]]; then
-        break
-    elif [[ "$line" =~ "restart" ]]; then
-        # check if we have timeout between restart
-        if [[ $line =~ [^0-9]+([0-9]+) ]]; then
-            restart "${BASH_REMATCH[1]}"
-        else
-            restart 0
-        fi;
-    elif [[ "$line" == "pause" ]]; then
-        pause
-    elif [[ "$line" == "resume" ]]; then
-        resume
+    if read -t 1 line <&3; then
+        echo "read line: ${line}"
+        if [[ "$line" == "start" ]]; then
+            start
+        elif [[ "$line" == "quit" ]]; then
+            break
+        elif [[ "$line" =~ "restart" ]]; then
+            # check if we have timeout between restart
+            if [[ $line =~ [^0-9]+([0-9]+) ]]; then
+                restart "${BASH_REMATCH[1]}"
+            else
+                restart 0
+            fi;
+        elif [[ "$line" == "pause" ]]; then
+            pause
+        elif [[ "$line" == "resume" ]]; then
+            resume
+        fi
+    fi
+
+    # exit with the status of the process when it dies, so the container exits and
+    # the restart policy (e.g. docker `--restart=on-failure`) can take effect
+    if [[ -n "${ZOMBIE_EXIT_WITH_CHILD:-}" && -n "${child_pid}" ]] && ! $LS /proc/$child_pid > /dev/null 2>&1; then
+        status=0
+        wait "$child_pid" || status=$?
+        echo "child process exited with status ${status}, quiting..."
+        exit $status
     fi
 done
 
@@ -2,6 +2,7 @@ use std::{
     collections::HashMap,
     path::{Path, PathBuf},
     process::ExitStatus,
+    time::Duration,
 };
 
 use configuration::{shared::resources::Resources, types::AssetLocation};
@@ -11,18 +12,79 @@ pub type Port = u16;
 
 pub type ExecutionResult = Result<String, (ExitStatus, String)>;
 
+/// The capabilities of a provider.
+///
+/// NOTE: capabilities can be added over time, providers outside of this crate should build it
+/// with the setters or `..Default::default()` to fill the fields they don't care about.
 #[derive(Debug, Clone, PartialEq)]
 pub struct ProviderCapabilities {
     // default ports internal
     /// Ensure that we have an image for each node (k8s/podman/docker)
     pub requires_image: bool,
-    /// Allow to customize the resources through manifest (k8s).
+    /// Allow to customize the resources through manifest (k8s), docker run options or
+    /// process limits (native).
     pub has_resources: bool,
     /// Used in native to prefix filepath with fullpath
     pub prefix_with_full_path: bool,
     /// Use default ports in node cmd/args.
     /// NOTE: generally used in k8s/dockers since the images expose those ports.
     pub use_default_ports_in_cmd: bool,
+    /// Max amount of concurrent spawning tasks the provider can handle.
+    pub max_spawn_concurrency: usize,
+    /// Allow to spawn nodes in the namespace once the network is running
+    /// (e.g. `add_node`/`add_collator`).
+    pub supports_runtime_node_addition: bool,
+}
+
+impl Default for ProviderCapabilities {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ProviderCapabilities {
+    /// Capabilities of a provider without image nor resources support, that doesn't bound
+    /// the spawn concurrency and allows to add nodes to a running network.
+    pub fn new() -> Self {
+        Self {
+            requires_image: false,
+            has_resources: false,
+            prefix_with_full_path: false,
+            use_default_ports_in_cmd: false,
+            max_spawn_concurrency: usize::MAX,
+            supports_runtime_node_addition: true,
+        }
+    }
+
+    pub fn requires_image(mut self, requires_image: bool) -> Self {
+        self.requires_image = requires_image;
+        self
+    }
+
+    pub fn has_resources(mut self, has_resources: bool) -> Self {
+        self.has_resources = has_resources;
+        self
+    }
+
+    pub fn prefix_with_full_path(mut self, prefix_with_full_path: bool) -> Self {
+        self.prefix_with_full_path = prefix_with_full_path;
+        self
+    }
+
+    pub fn use_default_ports_in_cmd(mut self, use_default_ports_in_cmd: bool) -> Self {
+        self.use_default_ports_in_cmd = use_default_ports_in_cmd;
+        self
+    }
+
+    pub fn max_spawn_concurrency(mut self, max_spawn_concurrency: usize) -> Self {
+        self.max_spawn_concurrency = max_spawn_concurrency;
+        self
+    }
+
+    pub fn supports_runtime_node_addition(mut self, supports_runtime_node_addition: bool) -> Self {
+        self.supports_runtime_node_addition = supports_runtime_node_addition;
+        self
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -50,6 +112,19 @@ pub struct SpawnNodeOptions {
     /// Could be a local or remote asset
     pub db_snapshot: Option<AssetLocation>,
     pub port_mapping: Option<HashMap<Port, Port>>,
+    /// Rotation to apply to the node log (IFF is supported by the provider)
+    pub log_rotation: Option<LogRotation>,
+    /// Grace period between `SIGTERM` and `SIGKILL` when stopping the node
+    /// (IFF is supported by the provider)
+    pub stop_grace: Option<Duration>,
+}
+
+/// Rotate the node log once it exceeds `max_bytes`, keeping the last `max_files` rotated files
+/// (`<node>.log.1` being the most recent).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LogRotation {
+    pub max_bytes: u64,
+    pub max_files: usize,
 }
 
 impl SpawnNodeOptions {
@@ -68,6 +143,8 @@ impl SpawnNodeOptions {
             created_paths: vec![],
             db_snapshot: None,
             port_mapping: None,
+            log_rotation: None,
+            stop_grace: None,
         }
     }
 
@@ -134,6 +211,46 @@ impl SpawnNodeOptions {
         self.port_mapping = Some(ports);
         self
     }
+
+    pub fn log_rotation(mut self, log_rotation: Option<LogRotation>) -> Self {
+        self.log_rotation = log_rotation;
+        self
+    }
+
+    pub fn stop_grace(mut self, stop_grace: Option<Duration>) -> Self {
+        self.stop_grace = stop_grace;
+        self
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct CreateNamespaceOptions {
+    /// Directory to use as base dir (instead of a random tmp one)
+    pub base_dir: Option<PathBuf>,
+    /// Prefix of the namespace name (`<prefix>-<random>`), default to `zombie`
+    pub name_prefix: Option<String>,
+}
+
+impl CreateNamespaceOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn base_dir<P>(mut self, base_dir: P) -> Self
+    where
+        P: AsRef<Path>,
+    {
+        self.base_dir = Some(base_dir.as_ref().into());
+        self
+    }
+
+    pub fn name_prefix<S>(mut self, name_prefix: S) -> Self
+    where
+        S: AsRef<str>,
+    {
+        self.name_prefix = Some(name_prefix.as_ref().to_string());
+        self
+    }
 }
 
 #[derive(Debug)]
@@ -4,8 +4,9 @@ pub use configuration::{NetworkConfig, NetworkConfigBuilder, RegistrationStrateg
 pub use orchestrator::pjs_helper::PjsResult;
 pub use orchestrator::{
     errors::OrchestratorError,
-    network::{node::NetworkNode, Network},
-    AddCollatorOptions, AddNodeOptions, Orchestrator,
+    network::{destroy_detached, node::NetworkNode, Network},
+    shared::types::{NetworkArtifacts, ParaArtifacts, SpawnTimings},
+    AddCollatorOptions, AddNodeOptions, CancellationToken, LogStream, Orchestrator,
 };
 
 // Helpers used for interact with the network
@@ -16,7 +17,7 @@ pub mod tx_helper {
 }
 
 use provider::{DockerProvider, KubernetesProvider, NativeProvider};
-pub use support::fs::local::LocalFileSystem;
+pub use support::fs::{local::LocalFileSystem, FileSystem};
 
 pub mod environment;
 pub const PROVIDERS: [&str; 3] = ["k8s", "native", "docker"];
@@ -42,26 +43,70 @@ pub trait NetworkConfigExt {
     async fn spawn_native(self) -> Result<Network<LocalFileSystem>, OrchestratorError>;
     async fn spawn_k8s(self) -> Result<Network<LocalFileSystem>, OrchestratorError>;
     async fn spawn_docker(self) -> Result<Network<LocalFileSystem>, OrchestratorError>;
+
+    /// Spawns a network using the native provider and a custom [`FileSystem`]
+    /// (e.g. an in-memory one), used for all the orchestrator and provider file operations.
+    async fn spawn_native_with_fs<FS>(
+        self,
+        filesystem: FS,
+    ) -> Result<Network<FS>, OrchestratorError>
+    where
+        FS: FileSystem + Send + Sync + Clone + 'static;
+    /// Same as [`NetworkConfigExt::spawn_native_with_fs`] but using the k8s provider.
+    async fn spawn_k8s_with_fs<FS>(self, filesystem: FS) -> Result<Network<FS>, OrchestratorError>
+    where
+        FS: FileSystem + Send + Sync + Clone + 'static;
+    /// Same as [`NetworkConfigExt::spawn_native_with_fs`] but using the docker provider.
+    async fn spawn_docker_with_fs<FS>(
+        self,
+        filesystem: FS,
+    ) -> Result<Network<FS>, OrchestratorError>
+    where
+        FS: FileSystem + Send + Sync + Clone + 'static;
 }
 
 #[async_trait]
 impl NetworkConfigExt for NetworkConfig {
     async fn spawn_native(self) -> Result<Network<LocalFileSystem>, OrchestratorError> {
-        let filesystem = LocalFileSystem;
+        self.spawn_native_with_fs(LocalFileSystem).await
+    }
+
+    async fn spawn_k8s(self) -> Result<Network<LocalFileSystem>, OrchestratorError> {
+        self.spawn_k8s_with_fs(LocalFileSystem).await
+    }
+
+    async fn spawn_docker(self) -> Result<Network<LocalFileSystem>, OrchestratorError> {
+        self.spawn_docker_with_fs(LocalFileSystem).await
+    }
+
+    async fn spawn_native_with_fs<FS>(
+        self,
+        filesystem: FS,
+    ) -> Result<Network<FS>, OrchestratorError>
+    where
+        FS: FileSystem + Send + Sync + Clone + 'static,
+    {
         let provider = NativeProvider::new(filesystem.clone());
         let orchestrator = Orchestrator::new(filesystem, provider);
         orchestrator.spawn(self).await
     }
 
-    async fn spawn_k8s(self) -> Result<Network<LocalFileSystem>, OrchestratorError> {
-        let filesystem = LocalFileSystem;
+    async fn spawn_k8s_with_fs<FS>(self, filesystem: FS) -> Result<Network<FS>, OrchestratorError>
+    where
+        FS: FileSystem + Send + Sync + Clone + 'static,
+    {
         let provider = KubernetesProvider::new(filesystem.clone()).await;
         let orchestrator = Orchestrator::new(filesystem, provider);
         orchestrator.spawn(self).await
     }
 
-    async fn spawn_docker(self) -> Result<Network<LocalFileSystem>, OrchestratorError> {
-        let filesystem = LocalFileSystem;
+    async fn spawn_docker_with_fs<FS>(
+        self,
+        filesystem: FS,
+    ) -> Result<Network<FS>, OrchestratorError>
+    where
+        FS: FileSystem + Send + Sync + Clone + 'static,
+    {
         let provider = DockerProvider::new(filesystem.clone()).await;
         let orchestrator = Orchestrator::new(filesystem, provider);
         orchestrator.spawn(self).await
@@ -7,6 +7,7 @@ pub const NO_ERR_DEF_BUILDER: &str = "should have no errors for default builder
 pub const RW_FAILED: &str = "should be able to read/write - failed ";
 pub const DEFAULT_TYPESTATE: &str = "'default' overriding should be ensured by typestate ";
 pub const VALIDATION_CHECK: &str = "validation failed ";
+pub const SCHEMA_SHOULD_SERIALIZE: &str = "the json schema should be serializable ";
 
 pub const PREFIX_CANT_BE_NONE: &str = "name prefix can't be None if a value exists ";
 
@@ -31,6 +31,17 @@ pub trait FileSystem {
     where
         P: AsRef<Path> + Send;
 
+    /// Read the contents of the file from the `offset` (in bytes) to the end,
+    /// an empty vec is returned if the file is shorter than the `offset`.
+    async fn read_from<P>(&self, path: P, offset: u64) -> FileSystemResult<Vec<u8>>
+    where
+        P: AsRef<Path> + Send;
+
+    /// Return the size (in bytes) of the file.
+    async fn file_size<P>(&self, path: P) -> FileSystemResult<u64>
+    where
+        P: AsRef<Path> + Send;
+
     async fn read_to_string<P>(&self, path: P) -> FileSystemResult<String>
     where
         P: AsRef<Path> + Send;
@@ -50,6 +61,15 @@ pub trait FileSystem {
         P1: AsRef<Path> + Send,
         P2: AsRef<Path> + Send;
 
+    async fn rename<P1, P2>(&self, from: P1, to: P2) -> FileSystemResult<()>
+    where
+        P1: AsRef<Path> + Send,
+        P2: AsRef<Path> + Send;
+
+    async fn remove_file<P>(&self, path: P) -> FileSystemResult<()>
+    where
+        P: AsRef<Path> + Send;
+
     async fn set_mode<P>(&self, path: P, perm: u32) -> FileSystemResult<()>
     where
         P: AsRef<Path> + Send;
@@ -152,6 +152,23 @@ impl FileSystem for InMemoryFileSystem {
         }
     }
 
+    async fn read_from<P>(&self, path: P, offset: u64) -> FileSystemResult<Vec<u8>>
+    where
+        P: AsRef<Path> + Send,
+    {
+        let contents = self.read(path).await?;
+        let offset = (offset as usize).min(contents.len());
+
+        Ok(contents[offset..].to_vec())
+    }
+
+    async fn file_size<P>(&self, path: P) -> FileSystemResult<u64>
+    where
+        P: AsRef<Path> + Send,
+    {
+        Ok(self.read(path).await?.len() as u64)
+    }
+
     async fn read_to_string<P>(&self, path: P) -> FileSystemResult<String>
     where
         P: AsRef<Path> + Send,
@@ -222,6 +239,37 @@ impl FileSystem for InMemoryFileSystem {
         self.write(to_ref, content).await
     }
 
+    async fn rename<P1, P2>(&self, from: P1, to: P2) -> FileSystemResult<()>
+    where
+        P1: AsRef<Path> + Send,
+        P2: AsRef<Path> + Send,
+    {
+        let from_ref = from.as_ref();
+        self.copy(from_ref, to).await?;
+        self.files.write().await.remove(from_ref.as_os_str());
+
+        Ok(())
+    }
+
+    async fn remove_file<P>(&self, path: P) -> FileSystemResult<()>
+    where
+        P: AsRef<Path> + Send,
+    {
+        let os_path = path.as_ref().as_os_str();
+        let mut files = self.files.write().await;
+
+        match files.get(os_path) {
+            Some(InMemoryFile::File { .. }) => {
+                files.remove(os_path);
+                Ok(())
+            },
+            Some(InMemoryFile::Directory { .. }) => {
+                Err(anyhow!("file {:?} is a directory", os_path).into())
+            },
+            None => Err(anyhow!("file {:?} not found", os_path).into()),
+        }
+    }
+
     async fn set_mode<P>(&self, path: P, mode: u32) -> FileSystemResult<()>
     where
         P: AsRef<Path> + Send,
@@ -483,6 +531,51 @@ mod tests {
         assert_eq!(err.to_string(), "file \"/myfile\" is a directory");
     }
 
+    #[tokio::test]
+    async fn read_from_should_return_the_file_content_from_the_offset() {
+        let fs = InMemoryFileSystem::new(HashMap::from([(
+            OsString::from_str("/myfile").unwrap(),
+            InMemoryFile::file("content"),
+        )]));
+
+        let content = fs.read_from("/myfile", 3).await.unwrap();
+
+        assert_eq!(content, "tent".as_bytes().to_vec());
+    }
+
+    #[tokio::test]
+    async fn read_from_should_return_an_empty_content_if_the_offset_is_past_the_end() {
+        let fs = InMemoryFileSystem::new(HashMap::from([(
+            OsString::from_str("/myfile").unwrap(),
+            InMemoryFile::file("content"),
+        )]));
+
+        let content = fs.read_from("/myfile", 10).await.unwrap();
+
+        assert!(content.is_empty());
+    }
+
+    #[tokio::test]
+    async fn read_from_should_return_an_error_if_file_doesnt_exists() {
+        let fs = InMemoryFileSystem::new(HashMap::new());
+
+        let err = fs.read_from("/myfile", 0).await.unwrap_err();
+
+        assert_eq!(err.to_string(), "file \"/myfile\" not found");
+    }
+
+    #[tokio::test]
+    async fn file_size_should_return_the_size_of_the_file() {
+        let fs = InMemoryFileSystem::new(HashMap::from([(
+            OsString::from_str("/myfile").unwrap(),
+            InMemoryFile::file("content"),
+        )]));
+
+        let size = fs.file_size("/myfile").await.unwrap();
+
+        assert_eq!(size, 7);
+    }
+
     #[tokio::test]
     async fn read_to_string_should_return_the_file_content_as_a_string() {
         let fs = InMemoryFileSystem::new(HashMap::from([(
@@ -827,6 +920,67 @@ mod tests {
         assert_eq!(err.to_string(), "ancestor \"/mypath\" is not a directory");
     }
 
+    #[tokio::test]
+    async fn rename_should_move_the_source_file_to_the_destination() {
+        let fs = InMemoryFileSystem::new(HashMap::from([
+            (OsString::from_str("/").unwrap(), InMemoryFile::dir()),
+            (
+                OsString::from_str("/myfile").unwrap(),
+                InMemoryFile::file("my new file content"),
+            ),
+            (
+                OsString::from_str("/myfile.1").unwrap(),
+                InMemoryFile::file("my file content"),
+            ),
+        ]));
+
+        fs.rename("/myfile", "/myfile.1").await.unwrap();
+
+        assert_eq!(fs.files.read().await.len(), 2);
+        assert!(!fs.exists("/myfile").await);
+        assert!(
+            matches!(fs.files.read().await.get(&OsString::from_str("/myfile.1").unwrap()).unwrap(), InMemoryFile::File { contents, .. } if contents == "my new file content".as_bytes())
+        );
+    }
+
+    #[tokio::test]
+    async fn remove_file_should_remove_the_file() {
+        let fs = InMemoryFileSystem::new(HashMap::from([
+            (OsString::from_str("/").unwrap(), InMemoryFile::dir()),
+            (
+                OsString::from_str("/myfile").unwrap(),
+                InMemoryFile::file("my file content"),
+            ),
+        ]));
+
+        fs.remove_file("/myfile").await.unwrap();
+
+        assert_eq!(fs.files.read().await.len(), 1);
+        assert!(!fs.exists("/myfile").await);
+    }
+
+    #[tokio::test]
+    async fn remove_file_should_return_an_error_if_file_doesnt_exists() {
+        let fs = InMemoryFileSystem::new(HashMap::new());
+
+        let err = fs.remove_file("/myfile").await.unwrap_err();
+
+        assert_eq!(err.to_string(), "file \"/myfile\" not found");
+    }
+
+    #[tokio::test]
+    async fn remove_file_should_return_an_error_if_file_is_a_directory() {
+        let fs = InMemoryFileSystem::new(HashMap::from([(
+            OsString::from_str("/mydir").unwrap(),
+            InMemoryFile::dir(),
+        )]));
+
+        let err = fs.remove_file("/mydir").await.unwrap_err();
+
+        assert_eq!(err.to_string(), "file \"/mydir\" is a directory");
+        assert!(fs.exists("/mydir").await);
+    }
+
     #[tokio::test]
     async fn set_mode_should_update_the_file_mode_at_path() {
         let fs = InMemoryFileSystem::new(HashMap::from([
@@ -1,7 +1,7 @@
-use std::{fs::Permissions, os::unix::fs::PermissionsExt, path::Path};
+use std::{fs::Permissions, io::SeekFrom, os::unix::fs::PermissionsExt, path::Path};
 
 use async_trait::async_trait;
-use tokio::io::AsyncWriteExt;
+use tokio::io::{AsyncReadExt, AsyncSeekExt, AsyncWriteExt};
 
 use super::{FileSystem, FileSystemError, FileSystemResult};
 
@@ -31,6 +31,34 @@ impl FileSystem for LocalFileSystem {
         tokio::fs::read(path).await.map_err(Into::into)
     }
 
+    async fn read_from<P>(&self, path: P, offset: u64) -> FileSystemResult<Vec<u8>>
+    where
+        P: AsRef<Path> + Send,
+    {
+        let mut file = tokio::fs::File::open(path)
+            .await
+            .map_err(Into::<FileSystemError>::into)?;
+        file.seek(SeekFrom::Start(offset))
+            .await
+            .map_err(Into::<FileSystemError>::into)?;
+
+        let mut contents = vec![];
+        file.read_to_end(&mut contents)
+            .await
+            .and(Ok(contents))
+            .map_err(Into::into)
+    }
+
+    async fn file_size<P>(&self, path: P) -> FileSystemResult<u64>
+    where
+        P: AsRef<Path> + Send,
+    {
+        tokio::fs::metadata(path)
+            .await
+            .map(|metadata| metadata.len())
+            .map_err(Into::into)
+    }
+
     async fn read_to_string<P>(&self, path: P) -> FileSystemResult<String>
     where
         P: AsRef<Path> + Send,
@@ -77,6 +105,21 @@ impl FileSystem for LocalFileSystem {
             .map_err(Into::into)
     }
 
+    async fn rename<P1, P2>(&self, from: P1, to: P2) -> FileSystemResult<()>
+    where
+        P1: AsRef<Path> + Send,
+        P2: AsRef<Path> + Send,
+    {
+        tokio::fs::rename(from, to).await.map_err(Into::into)
+    }
+
+    async fn remove_file<P>(&self, path: P) -> FileSystemResult<()>
+    where
+        P: AsRef<Path> + Send,
+    {
+        tokio::fs::remove_file(path).await.map_err(Into::into)
+    }
+
     async fn set_mode<P>(&self, path: P, mode: u32) -> FileSystemResult<()>
     where
         P: AsRef<Path> + Send,
@@ -193,6 +236,44 @@ mod tests {
         teardown(test_dir);
     }
 
+    #[tokio::test]
+    async fn read_from_should_return_the_contents_of_the_file_at_path_from_the_offset() {
+        let test_dir = setup();
+        let fs = LocalFileSystem;
+
+        let file_path = format!("{test_dir}/myfile");
+        std::fs::write(&file_path, b"Test").unwrap();
+
+        assert_eq!(fs.read_from(&file_path, 2).await.unwrap(), b"st");
+        assert!(fs.read_from(&file_path, 10).await.unwrap().is_empty());
+        teardown(test_dir);
+    }
+
+    #[tokio::test]
+    async fn read_from_should_bubble_up_error_if_some_happens() {
+        let test_dir = setup();
+        let fs = LocalFileSystem;
+
+        let file_path = format!("{test_dir}/myfile");
+        // intentionally forget to create file to force error
+        let err = fs.read_from(file_path, 0).await.unwrap_err();
+
+        assert_eq!(err.to_string(), "No such file or directory (os error 2)");
+        teardown(test_dir);
+    }
+
+    #[tokio::test]
+    async fn file_size_should_return_the_size_of_the_file_at_path() {
+        let test_dir = setup();
+        let fs = LocalFileSystem;
+
+        let file_path = format!("{test_dir}/myfile");
+        std::fs::write(&file_path, b"Test").unwrap();
+
+        assert_eq!(fs.file_size(&file_path).await.unwrap(), 4);
+        teardown(test_dir);
+    }
+
     #[tokio::test]
     async fn read_to_string_should_return_the_contents_of_the_file_at_path_as_string() {
         let test_dir = setup();
@@ -341,6 +422,48 @@ mod tests {
         teardown(test_dir);
     }
 
+    #[tokio::test]
+    async fn rename_should_move_the_source_to_destination() {
+        let test_dir = setup();
+        let fs = LocalFileSystem;
+
+        let from_path = format!("{test_dir}/myfile");
+        std::fs::write(&from_path, "Test").unwrap();
+        let to_path = format!("{test_dir}/myfile.1");
+        std::fs::write(&to_path, "Some content").unwrap();
+        fs.rename(&from_path, &to_path).await.unwrap();
+
+        assert!(!Path::new(&from_path).exists());
+        assert_eq!(std::fs::read_to_string(to_path).unwrap(), "Test");
+        teardown(test_dir);
+    }
+
+    #[tokio::test]
+    async fn remove_file_should_remove_the_file_at_path() {
+        let test_dir = setup();
+        let fs = LocalFileSystem;
+
+        let file_path = format!("{test_dir}/myfile");
+        std::fs::write(&file_path, "Test").unwrap();
+        fs.remove_file(&file_path).await.unwrap();
+
+        assert!(!Path::new(&file_path).exists());
+        teardown(test_dir);
+    }
+
+    #[tokio::test]
+    async fn remove_file_should_bubble_up_error_if_some_happens() {
+        let test_dir = setup();
+        let fs = LocalFileSystem;
+
+        let file_path = format!("{test_dir}/myfile");
+        // intentionally forget to create file to force error
+        let err = fs.remove_file(file_path).await.unwrap_err();
+
+        assert_eq!(err.to_string(), "No such file or directory (os error 2)");
+        teardown(test_dir);
+    }
+
     #[tokio::test]
     async fn set_mode_should_update_the_file_mode_at_path() {
         let test_dir = setup();
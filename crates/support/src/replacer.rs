@@ -1,9 +1,17 @@
 use std::collections::HashMap;
 
+use lazy_static::lazy_static;
 use regex::{Captures, Regex};
 
 use crate::constants::{THIS_IS_A_BUG, VALID_REGEX};
 
+lazy_static! {
+    /// The `{{ZOMBIE:<node>:<field>}}` token, capturing the node name and the field.
+    pub static ref ZOMBIE_TOKEN_RE: Regex =
+        Regex::new(r#"\{\{ZOMBIE:([a-zA-Z0-9_-]+):([a-zA-Z0-9_]+)\}\}"#)
+            .unwrap_or_else(|_| panic!("{} {}", VALID_REGEX, THIS_IS_A_BUG));
+}
+
 pub fn apply_replacements(text: &str, replacements: &HashMap<&str, &str>) -> String {
     let re = Regex::new(r#"\{\{([a-zA-Z0-9_]*)\}\}"#)
         .unwrap_or_else(|_| panic!("{} {}", VALID_REGEX, THIS_IS_A_BUG));
@@ -19,6 +27,23 @@ pub fn apply_replacements(text: &str, replacements: &HashMap<&str, &str>) -> Str
     augmented_text.to_string()
 }
 
+/// Replace the `{{ZOMBIE:<node>:<field>}}` tokens in `text` with the `field` value of the
+/// running `node` from `nodes`. Tokens of unknown nodes or fields are kept untouched.
+pub fn apply_running_network_replacements(
+    text: &str,
+    nodes: &HashMap<String, HashMap<&str, String>>,
+) -> String {
+    let augmented_text = ZOMBIE_TOKEN_RE.replace_all(text, |caps: &Captures| {
+        if let Some(value) = nodes.get(&caps[1]).and_then(|fields| fields.get(&caps[2])) {
+            value.to_string()
+        } else {
+            caps[0].to_string()
+        }
+    });
+
+    augmented_text.to_string()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -68,4 +93,22 @@ mod tests {
         let res = apply_replacements(text, &replacements);
         assert_eq!(text.to_string(), res);
     }
+
+    #[test]
+    fn replace_running_network_tokens_should_works() {
+        let text = "--bootnodes={{ZOMBIE:alice:multiaddr}} --rpc={{ZOMBIE:bob:ws_uri}} {{ZOMBIE:alice:unknown}}";
+        let nodes = HashMap::from([(
+            "alice".to_string(),
+            HashMap::from([(
+                "multiaddr",
+                "/ip4/127.0.0.1/tcp/30333/ws/p2p/12D3".to_string(),
+            )]),
+        )]);
+
+        let res = apply_running_network_replacements(text, &nodes);
+        assert_eq!(
+            "--bootnodes=/ip4/127.0.0.1/tcp/30333/ws/p2p/12D3 --rpc={{ZOMBIE:bob:ws_uri}} {{ZOMBIE:alice:unknown}}",
+            res
+        );
+    }
 }